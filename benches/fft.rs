@@ -0,0 +1,240 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::{izip, Itertools};
+use phantom_zone::{
+    Complex64, Decomposer, DefaultDecomposer, FftBackendF64, ModInit, ModularOpsU64, Ntt,
+    NttBackendU64, NttInit, ShoupMatrixFMA,
+};
+use rand::{thread_rng, Rng};
+use rand_distr::Uniform;
+
+fn decompose_r(r: &[u64], decomp_r: &mut [Vec<u64>], decomposer: &DefaultDecomposer<u64>) {
+    r.iter().enumerate().for_each(|(i, v)| {
+        decomposer
+            .decompose_iter(v)
+            .enumerate()
+            .for_each(|(j, el)| decomp_r[j][i] = el);
+    });
+}
+
+/// RLWE x RGSW product with NTT as in blind rotation. Decomposes each
+/// polynomial of `rlwe_in` and writes the product with `key_a` and `key_b` to
+/// `rlwe_out`. `key_a` and `key_b` are in evaluation domain and `key_a_shoup`
+/// and `key_b_shoup` are their shoup representations.
+fn rlwe_x_rgsw_ntt(
+    rlwe_out: &mut [Vec<u64>],
+    rlwe_in: &[Vec<u64>],
+    key: (&[Vec<u64>], &[Vec<u64>]),
+    key_shoup: (&[Vec<u64>], &[Vec<u64>]),
+    decomp: &mut [Vec<u64>],
+    decomposer: &DefaultDecomposer<u64>,
+    ntt: &NttBackendU64,
+    modop: &ModularOpsU64<u64>,
+) {
+    let d = decomp.len() / rlwe_in.len();
+    izip!(rlwe_in.iter(), decomp.chunks_mut(d)).for_each(|(r, decomp)| {
+        decompose_r(r, decomp, decomposer);
+        decomp.iter_mut().for_each(|r| ntt.forward_lazy(r));
+    });
+
+    rlwe_out.iter_mut().for_each(|r| r.fill(0));
+    let (rlwe_a, rlwe_b) = rlwe_out.split_at_mut(1);
+    modop.shoup_matrix_fma(&mut rlwe_a[0], key.0, key_shoup.0, decomp);
+    modop.shoup_matrix_fma(&mut rlwe_b[0], key.1, key_shoup.1, decomp);
+    rlwe_out.iter_mut().for_each(|r| ntt.backward(r));
+}
+
+/// Same as `rlwe_x_rgsw_ntt` with FFT. `key_a` and `key_b` are in Fourier
+/// domain in split representation.
+fn rlwe_x_rgsw_fft(
+    rlwe_out: &mut [Vec<u64>],
+    rlwe_in: &[Vec<u64>],
+    key: (&[Vec<Vec<Complex64>>], &[Vec<Vec<Complex64>>]),
+    decomp: &mut [Vec<u64>],
+    decomp_fourier: &mut [Complex64],
+    acc: &mut [Vec<Vec<Complex64>>],
+    decomposer: &DefaultDecomposer<u64>,
+    fft: &FftBackendF64,
+) {
+    let d = decomp.len() / rlwe_in.len();
+    acc.iter_mut()
+        .flatten()
+        .for_each(|r| r.fill(Complex64::default()));
+    izip!(rlwe_in.iter(), decomp.chunks_mut(d)).for_each(|(r, decomp)| {
+        decompose_r(r, decomp, decomposer);
+    });
+    izip!(decomp.iter(), key.0.iter(), key.1.iter()).for_each(|(r, k_a, k_b)| {
+        fft.forward(r, decomp_fourier);
+        fft.fma_split(&mut acc[0], decomp_fourier, k_a);
+        fft.fma_split(&mut acc[1], decomp_fourier, k_b);
+    });
+    izip!(acc.iter_mut(), rlwe_out.iter_mut()).for_each(|(acc, r)| fft.backward_split(acc, r));
+}
+
+fn benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft");
+    // rlwe_q of bool parameters
+    for prime in [18014398509404161] {
+        for ring_size in [1 << 11] {
+            let mut rng = thread_rng();
+            let dist = Uniform::new(0, prime);
+            let a = (&mut rng).sample_iter(dist).take(ring_size).collect_vec();
+
+            let ntt = NttBackendU64::new(&prime, ring_size);
+            let fft: FftBackendF64 = NttInit::new(&prime, ring_size);
+            let modop = ModularOpsU64::new(prime);
+
+            group.bench_function(
+                BenchmarkId::new("ntt_forward", format!("q={prime}/N={ring_size}")),
+                |b| {
+                    b.iter_batched_ref(
+                        || a.clone(),
+                        |a| black_box(ntt.forward_lazy(a)),
+                        criterion::BatchSize::PerIteration,
+                    )
+                },
+            );
+
+            group.bench_function(
+                BenchmarkId::new("fft_forward", format!("q={prime}/N={ring_size}")),
+                |b| {
+                    b.iter_batched_ref(
+                        || vec![Complex64::default(); ring_size >> 1],
+                        |out| black_box(fft.forward(&a, out)),
+                        criterion::BatchSize::PerIteration,
+                    )
+                },
+            );
+
+            group.bench_function(
+                BenchmarkId::new(
+                    "fft_forward_split",
+                    format!("q={prime}/N={ring_size}/limbs={}", fft.limbs()),
+                ),
+                |b| {
+                    b.iter_batched_ref(
+                        || vec![vec![Complex64::default(); ring_size >> 1]; fft.limbs()],
+                        |out| black_box(fft.forward_split(&a, out)),
+                        criterion::BatchSize::PerIteration,
+                    )
+                },
+            );
+
+            // RLWE x RGSW and RLWE automorphism decomposition parameters of bool
+            // parameters. Key switching in RLWE automorphism is RLWE x RGSW with only
+            // part A of RLWE ciphertext.
+            for (name, logb, parts) in [("rlwe_x_rgsw", 17, 2), ("rlwe_auto", 24, 1)] {
+                let decomposer = DefaultDecomposer::new(prime, logb, 1);
+
+                let rlwe = (0..parts)
+                    .map(|_| (&mut rng).sample_iter(dist).take(ring_size).collect_vec())
+                    .collect_vec();
+                // parts A and B of key polynomials multiplied with decomposed polynomials
+                let key = (0..2)
+                    .map(|_| {
+                        (0..parts)
+                            .map(|_| (&mut rng).sample_iter(dist).take(ring_size).collect_vec())
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                let key_shoup = key
+                    .iter()
+                    .map(|k| {
+                        k.iter()
+                            .map(|r| {
+                                r.iter()
+                                    .map(|v| ((*v as u128 * (1u128 << 64)) / prime as u128) as u64)
+                                    .collect_vec()
+                            })
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                let key_fourier = key
+                    .iter()
+                    .map(|k| {
+                        k.iter()
+                            .map(|r| {
+                                let mut out =
+                                    vec![vec![Complex64::default(); ring_size >> 1]; fft.limbs()];
+                                fft.forward_split(r, &mut out);
+                                out
+                            })
+                            .collect_vec()
+                    })
+                    .collect_vec();
+
+                group.bench_function(
+                    BenchmarkId::new(
+                        format!("{name}_ntt"),
+                        format!("q={prime}/N={ring_size}/logB={logb}"),
+                    ),
+                    |b| {
+                        b.iter_batched_ref(
+                            || {
+                                (
+                                    vec![vec![0u64; ring_size]; 2],
+                                    vec![vec![0u64; ring_size]; parts],
+                                )
+                            },
+                            |(rlwe_out, decomp)| {
+                                black_box(rlwe_x_rgsw_ntt(
+                                    rlwe_out,
+                                    &rlwe,
+                                    (&key[0], &key[1]),
+                                    (&key_shoup[0], &key_shoup[1]),
+                                    decomp,
+                                    &decomposer,
+                                    &ntt,
+                                    &modop,
+                                ))
+                            },
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+
+                group.bench_function(
+                    BenchmarkId::new(
+                        format!("{name}_fft"),
+                        format!("q={prime}/N={ring_size}/logB={logb}/limbs={}", fft.limbs()),
+                    ),
+                    |b| {
+                        b.iter_batched_ref(
+                            || {
+                                (
+                                    vec![vec![0u64; ring_size]; 2],
+                                    vec![vec![0u64; ring_size]; parts],
+                                    vec![Complex64::default(); ring_size >> 1],
+                                    vec![
+                                        vec![
+                                            vec![Complex64::default(); ring_size >> 1];
+                                            fft.limbs()
+                                        ];
+                                        2
+                                    ],
+                                )
+                            },
+                            |(rlwe_out, decomp, decomp_fourier, acc)| {
+                                black_box(rlwe_x_rgsw_fft(
+                                    rlwe_out,
+                                    &rlwe,
+                                    (&key_fourier[0], &key_fourier[1]),
+                                    decomp,
+                                    decomp_fourier,
+                                    acc,
+                                    &decomposer,
+                                    &fft,
+                                ))
+                            },
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(fft, benchmark);
+criterion_main!(fft);
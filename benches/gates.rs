@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::Itertools;
+use phantom_zone::*;
+use rand::{thread_rng, Rng, RngCore};
+
+/// Blind rotation backend of the runtime server key
+const BACKEND: &str = if cfg!(feature = "fft") { "fft" } else { "ntt" };
+
+/// Runs 2 party interactive protocol, sets the server key and returns
+/// encryptions of random u8s with the collective public key
+fn setup(count: usize) -> Vec<FheUint8> {
+    set_parameter_set(ParameterSelector::InteractiveLTE2Party);
+    let mut seed = [0u8; 32];
+    thread_rng().fill_bytes(&mut seed);
+    set_common_reference_seed(seed);
+
+    let no_of_parties = 2;
+    let cks = (0..no_of_parties).map(|_| gen_client_key()).collect_vec();
+    let pk_shares = cks.iter().map(collective_pk_share).collect_vec();
+    let pk = aggregate_public_key_shares(&pk_shares);
+    let server_key_shares = cks
+        .iter()
+        .enumerate()
+        .map(|(user_id, k)| collective_server_key_share(k, user_id, no_of_parties, &pk))
+        .collect_vec();
+    aggregate_server_key_shares(&server_key_shares).set_server_key();
+
+    let m = (0..count).map(|_| thread_rng().gen::<u8>()).collect_vec();
+    let batched: BatchedFheUint8 = pk.encrypt(m.as_slice());
+    batched.extract_all()
+}
+
+/// Gate latency with NTT or, with `fft` feature, f64 FFT blind rotation.
+/// Compare with `cargo bench --bench gates --features interactive_mp` and
+/// `cargo bench --bench gates --features interactive_mp,fft`.
+fn benchmark(c: &mut Criterion) {
+    let cts = setup(4);
+    // bootstrapped FheBools
+    let a = cts[0].lt(&cts[1]);
+    let b = cts[2].lt(&cts[3]);
+
+    let mut group = c.benchmark_group("gates");
+    group.sample_size(20);
+    group.bench_function(BenchmarkId::new("and", BACKEND), |bench| {
+        bench.iter(|| black_box(&a & &b))
+    });
+    group.bench_function(BenchmarkId::new("or", BACKEND), |bench| {
+        bench.iter(|| black_box(&a | &b))
+    });
+    group.bench_function(BenchmarkId::new("u8_add", BACKEND), |bench| {
+        bench.iter(|| black_box(&cts[0] + &cts[1]))
+    });
+    group.finish();
+}
+
+criterion_group!(gates, benchmark);
+criterion_main!(gates);
@@ -1,9 +1,9 @@
-use bin_rs::{
-    ArithmeticLazyOps, ArithmeticOps, Decomposer, DefaultDecomposer, ModInit, ModularOpsU64,
-    ShoupMatrixFMA, VectorOps,
-};
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use itertools::{izip, Itertools};
+use phantom_zone::{
+    ArithmeticLazyOps, ArithmeticOps, Decomposer, DefaultDecomposer, ModInit, ModularOpsU64,
+    ShoupMatrixFMA, SimdLevel, VectorOps,
+};
 use rand::{thread_rng, Rng};
 use rand_distr::Uniform;
 
@@ -121,26 +121,50 @@ fn benchmark(c: &mut Criterion) {
                 },
             );
 
-            group.bench_function(
-                BenchmarkId::new(
-                    "matrix_shoup_fma_lazy",
-                    format!("q={prime}/N={ring_size}/d={d}"),
-                ),
-                |b| {
-                    b.iter_batched_ref(
-                        || (vec![0u64; ring_size]),
-                        |(out)| {
-                            black_box(modop.shoup_matrix_fma(
-                                out,
-                                &a0_matrix,
-                                &a0_shoup_matrix,
-                                &a1_matrix,
-                            ))
-                        },
-                        criterion::BatchSize::PerIteration,
-                    )
-                },
-            );
+            let levels = [
+                SimdLevel::Scalar,
+                SimdLevel::Avx2,
+                SimdLevel::Avx512,
+                SimdLevel::Avx512Ifma,
+            ];
+            for level in levels.into_iter().filter(|l| *l <= SimdLevel::detect()) {
+                let modop = ModularOpsU64::new(prime).with_simd_level(level);
+
+                group.bench_function(
+                    BenchmarkId::new(
+                        "matrix_shoup_fma_lazy",
+                        format!("q={prime}/N={ring_size}/d={d}/simd={level:?}"),
+                    ),
+                    |b| {
+                        b.iter_batched_ref(
+                            || (vec![0u64; ring_size]),
+                            |(out)| {
+                                black_box(modop.shoup_matrix_fma(
+                                    out,
+                                    &a0_matrix,
+                                    &a0_shoup_matrix,
+                                    &a1_matrix,
+                                ))
+                            },
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+
+                group.bench_function(
+                    BenchmarkId::new(
+                        "elwise_add_mut",
+                        format!("q={prime}/N={ring_size}/simd={level:?}"),
+                    ),
+                    |b| {
+                        b.iter_batched_ref(
+                            || (a0.clone()),
+                            |(out)| black_box(modop.elwise_add_mut(out, &a1)),
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+            }
         }
     }
 
@@ -1,6 +1,6 @@
-use bin_rs::{Ntt, NttBackendU64, NttInit};
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use itertools::Itertools;
+use phantom_zone::{Ntt, NttBackendU64, NttBackendU64Simd, NttInit, SimdLevel};
 use rand::{thread_rng, Rng};
 use rand_distr::Uniform;
 
@@ -147,5 +147,79 @@ fn benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(ntt, benchmark);
+fn benchmark_simd(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ntt_simd");
+    // 55
+    for prime in [36028797017456641] {
+        // rlwe_n of bool parameters
+        for ring_size in [1 << 11] {
+            let mut rng = thread_rng();
+            let a = (&mut rng)
+                .sample_iter(Uniform::new(0, prime))
+                .take(ring_size)
+                .collect_vec();
+
+            let scalar: Box<dyn Ntt<Element = u64>> =
+                Box::new(NttBackendU64::new(&prime, ring_size));
+            let mut backends = vec![("scalar".to_string(), scalar)];
+            for level in [SimdLevel::Avx2, SimdLevel::Avx512]
+                .into_iter()
+                .filter(|l| *l <= SimdLevel::detect())
+            {
+                let ntt = NttBackendU64Simd::new(&prime, ring_size).with_simd_level(level);
+                backends.push((format!("{level:?}"), Box::new(ntt)));
+            }
+
+            for (name, ntt) in backends.iter() {
+                group.bench_function(
+                    BenchmarkId::new("forward", format!("q={prime}/N={ring_size}/{name}")),
+                    |b| {
+                        b.iter_batched_ref(
+                            || a.clone(),
+                            |mut a| black_box(ntt.forward(&mut a)),
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+
+                group.bench_function(
+                    BenchmarkId::new("forward_lazy", format!("q={prime}/N={ring_size}/{name}")),
+                    |b| {
+                        b.iter_batched_ref(
+                            || a.clone(),
+                            |mut a| black_box(ntt.forward_lazy(&mut a)),
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+
+                group.bench_function(
+                    BenchmarkId::new("backward", format!("q={prime}/N={ring_size}/{name}")),
+                    |b| {
+                        b.iter_batched_ref(
+                            || a.clone(),
+                            |mut a| black_box(ntt.backward(&mut a)),
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+
+                group.bench_function(
+                    BenchmarkId::new("backward_lazy", format!("q={prime}/N={ring_size}/{name}")),
+                    |b| {
+                        b.iter_batched_ref(
+                            || a.clone(),
+                            |mut a| black_box(ntt.backward_lazy(&mut a)),
+                            criterion::BatchSize::PerIteration,
+                        )
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(ntt, benchmark, benchmark_simd);
 criterion_main!(ntt);
@@ -128,17 +128,11 @@ fn client_encrypt_job_criteria(jc: JobCriteria, ck: ClientKeys) -> ClientEncrypt
 
 fn server_extract_job_criteria(id: usize, data: ClientEncryptedData) -> FheJobCriteria {
     let bool_enc_ks = data.bool_enc.key_switch(id);
-    let in_market = FheBool {
-        data: bool_enc_ks.extract(0),
-    };
-    let position = FheBool {
-        data: bool_enc_ks.extract(1),
-    };
+    let in_market = FheBool::new(bool_enc_ks.extract(0));
+    let position = FheBool::new(bool_enc_ks.extract(1));
     let mut criteria: [FheBool; NUM_CRITERIA] = Default::default();
     for i in 0..NUM_CRITERIA {
-        criteria[i] = FheBool {
-            data: bool_enc_ks.extract(i + 2),
-        };
+        criteria[i] = FheBool::new(bool_enc_ks.extract(i + 2));
     }
 
     let salary = data
@@ -4,10 +4,13 @@ use crate::{utils::log2, Row};
 
 mod modulus_u64;
 mod power_of_2;
+pub(crate) mod simd;
 mod word_size;
 
 pub use modulus_u64::ModularOpsU64;
-pub(crate) use power_of_2::ModulusPowerOf2;
+pub(crate) use power_of_2::{ModulusPowerOf2, PowerOf2Word};
+pub use simd::SimdLevel;
+pub use word_size::WordSizeModulus;
 
 pub trait Modulus {
     type Element;
@@ -68,13 +71,12 @@ impl Modulus for u64 {
         }
     }
     fn map_element_from_i64(&self, v: i64) -> Self::Element {
-        let v_u64 = v.abs().to_u64().unwrap();
+        // branchless since v may be secret
+        let sign = v >> 63;
+        let v_u64 = ((v ^ sign) - sign) as u64;
         assert!(v_u64 <= self.largest_unsigned_value());
-        if v < 0 {
-            self - v_u64
-        } else {
-            v_u64
-        }
+        let mask = sign as u64;
+        (v_u64 & !mask) | (self.wrapping_sub(v_u64) & mask)
     }
     fn q(&self) -> Option<Self::Element> {
         Some(*self)
@@ -2,6 +2,7 @@ use itertools::izip;
 use num_traits::WrappingMul;
 
 use super::{
+    simd::{self, SimdLevel},
     ArithmeticLazyOps, ArithmeticOps, GetModulus, ModInit, Modulus, ShoupMatrixFMA, VectorOps,
 };
 use crate::RowMut;
@@ -13,6 +14,8 @@ pub struct ModularOpsU64<T> {
     barrett_mu: u128,
     barrett_alpha: usize,
     modulus: T,
+    /// SIMD level of vector ops
+    simd: SimdLevel,
 }
 
 impl<T> ModInit for ModularOpsU64<T>
@@ -38,42 +41,50 @@ where
             barrett_alpha: alpha as usize,
             barrett_mu: mu,
             modulus,
+            simd: Self::max_simd_level(q),
         }
     }
 }
 
 impl<T> ModularOpsU64<T> {
+    /// SIMD kernels require 6q < 2^63
+    fn max_simd_level(q: u64) -> SimdLevel {
+        if q < (1 << 60) {
+            SimdLevel::detect()
+        } else {
+            SimdLevel::Scalar
+        }
+    }
+
+    /// Returns `self` with vector ops using SIMD `level`. Level is capped to
+    /// the highest level supported by the CPU and the modulus.
+    pub fn with_simd_level(mut self, level: SimdLevel) -> Self {
+        self.simd = level.min(Self::max_simd_level(self.q));
+        self
+    }
+
     fn add_mod_fast(&self, a: u64, b: u64) -> u64 {
         debug_assert!(a < self.q);
         debug_assert!(b < self.q);
 
-        let mut o = a + b;
-        if o >= self.q {
-            o -= self.q;
-        }
-        o
+        let o = a + b;
+        o.min(o.wrapping_sub(self.q))
     }
 
     fn add_mod_fast_lazy(&self, a: u64, b: u64) -> u64 {
         debug_assert!(a < self.q_twice);
         debug_assert!(b < self.q_twice);
 
-        let mut o = a + b;
-        if o >= self.q_twice {
-            o -= self.q_twice;
-        }
-        o
+        let o = a + b;
+        o.min(o.wrapping_sub(self.q_twice))
     }
 
     fn sub_mod_fast(&self, a: u64, b: u64) -> u64 {
         debug_assert!(a < self.q);
         debug_assert!(b < self.q);
 
-        if a >= b {
-            a - b
-        } else {
-            (self.q + a) - b
-        }
+        let o = a.wrapping_sub(b);
+        o.min(o.wrapping_add(self.q))
     }
 
     // returns (a * b)  % q
@@ -119,13 +130,9 @@ impl<T> ModularOpsU64<T> {
         // ab - k*p
         let tmp = k * (self.q as u128);
 
-        let mut out = (ab - tmp) as u64;
+        let out = (ab - tmp) as u64;
 
-        if out >= self.q {
-            out -= self.q;
-        }
-
-        return out;
+        out.min(out.wrapping_sub(self.q))
     }
 }
 
@@ -167,13 +174,15 @@ impl<T> VectorOps for ModularOpsU64<T> {
     type Element = u64;
 
     fn elwise_add_mut(&self, a: &mut [Self::Element], b: &[Self::Element]) {
-        izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| {
+        let done = simd::add_mod_mut(self.simd, a, b, self.q);
+        izip!(a[done..].iter_mut(), b[done..].iter()).for_each(|(ai, bi)| {
             *ai = self.add_mod_fast(*ai, *bi);
         });
     }
 
     fn elwise_sub_mut(&self, a: &mut [Self::Element], b: &[Self::Element]) {
-        izip!(a.iter_mut(), b.iter()).for_each(|(ai, bi)| {
+        let done = simd::sub_mod_mut(self.simd, a, b, self.q);
+        izip!(a[done..].iter_mut(), b[done..].iter()).for_each(|(ai, bi)| {
             *ai = self.sub_mod_fast(*ai, *bi);
         });
     }
@@ -185,7 +194,8 @@ impl<T> VectorOps for ModularOpsU64<T> {
     }
 
     fn elwise_neg_mut(&self, a: &mut [Self::Element]) {
-        a.iter_mut().for_each(|ai| *ai = self.q - *ai);
+        let done = simd::neg_mod_mut(self.simd, a, self.q);
+        a[done..].iter_mut().for_each(|ai| *ai = self.q - *ai);
     }
 
     fn elwise_scalar_mul(&self, out: &mut [Self::Element], a: &[Self::Element], b: &Self::Element) {
@@ -242,22 +252,26 @@ impl<R: RowMut<Element = u64>, T> ShoupMatrixFMA<R> for ModularOpsU64<T> {
         let q_twice = self.q << 1;
 
         izip!(a.iter(), a_shoup.iter(), b.iter()).for_each(|(a_row, a_shoup_row, b_row)| {
+            let done = simd::shoup_fma_mut(
+                self.simd,
+                out,
+                a_row.as_ref(),
+                a_shoup_row.as_ref(),
+                b_row.as_ref(),
+                q,
+            );
             izip!(
-                out.as_mut().iter_mut(),
-                a_row.as_ref().iter(),
-                a_shoup_row.as_ref().iter(),
-                b_row.as_ref().iter()
+                out[done..].iter_mut(),
+                a_row.as_ref()[done..].iter(),
+                a_shoup_row.as_ref()[done..].iter(),
+                b_row.as_ref()[done..].iter()
             )
             .for_each(|(o, a0, a0_shoup, b0)| {
                 let quotient = ((*a0_shoup as u128 * *b0 as u128) >> 64) as u64;
                 let mut v = (a0.wrapping_mul(b0)).wrapping_add(*o);
                 v = v.wrapping_sub(q.wrapping_mul(quotient));
 
-                if v >= q_twice {
-                    v -= q_twice;
-                }
-
-                *o = v;
+                *o = v.min(v.wrapping_sub(q_twice));
             });
         });
     }
@@ -334,4 +348,60 @@ mod tests {
 
         assert_eq!(out_expected, out_shoup_fma);
     }
+
+    #[test]
+    fn add_sub_mul_are_correct() {
+        let mut rng = thread_rng();
+        let q = 36028797017456641u64;
+        let mod_op = ModularOpsU64::new(q);
+        let dist = Uniform::new(0, q);
+        for _ in 0..10000 {
+            let a = rng.sample(dist);
+            let b = rng.sample(dist);
+            assert_eq!(
+                mod_op.add(&a, &b) as u128,
+                (a as u128 + b as u128) % q as u128
+            );
+            assert_eq!(
+                mod_op.sub(&a, &b) as u128,
+                (q as u128 + a as u128 - b as u128) % q as u128
+            );
+            assert_eq!(
+                mod_op.mul(&a, &b) as u128,
+                (a as u128 * b as u128) % q as u128
+            );
+        }
+        assert_eq!(mod_op.sub(&0, &(q - 1)), 1);
+        assert_eq!(mod_op.add(&(q - 1), &1), 0);
+    }
+
+    // Run with `cargo test --release ct_ -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn ct_modular_ops() {
+        let q = 36028797017456641u64;
+        let mod_op = ModularOpsU64::new(q);
+        let mut rng = thread_rng();
+        let dist = Uniform::new(0, q);
+        crate::utils::tests::dudect(
+            1_000_000,
+            |class| {
+                let mut v = [1u64; 64];
+                if class == 1 {
+                    v.iter_mut().for_each(|v| *v = rng.sample(dist));
+                }
+                v
+            },
+            |v| {
+                v.iter().tuple_windows().for_each(|(a, b)| {
+                    let a = std::hint::black_box(*a);
+                    let b = std::hint::black_box(*b);
+                    std::hint::black_box(mod_op.add(&a, &b));
+                    std::hint::black_box(mod_op.sub(&a, &b));
+                    std::hint::black_box(mod_op.mul(&a, &b));
+                });
+            },
+        )
+        .assert_constant_time("ModularOpsU64");
+    }
 }
@@ -1,62 +1,119 @@
 use itertools::izip;
+use num_traits::{One, PrimInt, WrappingAdd, WrappingMul, WrappingSub, Zero};
 
 use crate::{ArithmeticOps, ModInit, VectorOps};
 
-use super::{GetModulus, Modulus};
+use super::{
+    simd::{self, SimdLevel},
+    GetModulus, Modulus,
+};
 
-pub(crate) struct ModulusPowerOf2<T> {
+/// Unsigned word that stores elements mod power of 2 modulus. Small moduli
+/// (ex, LWE modulus) can use narrower words to halve memory and double
+/// throughput of vector ops.
+pub(crate) trait PowerOf2Word: PrimInt + WrappingAdd + WrappingSub + WrappingMul {
+    /// Sets a = a + b * c mod (mask + 1) with SIMD `level` for a prefix of
+    /// `a` and returns length of the prefix. Remaining elements must be
+    /// processed by the caller.
+    #[inline]
+    fn fma_scalar_simd(
+        _level: SimdLevel,
+        _a: &mut [Self],
+        _b: &[Self],
+        _c: Self,
+        _mask: Self,
+    ) -> usize {
+        0
+    }
+}
+
+impl PowerOf2Word for u64 {
+    #[inline]
+    fn fma_scalar_simd(level: SimdLevel, a: &mut [u64], b: &[u64], c: u64, mask: u64) -> usize {
+        simd::fma_scalar_pow2_mut(level, a, b, c, mask)
+    }
+}
+
+impl PowerOf2Word for u32 {
+    #[inline]
+    fn fma_scalar_simd(level: SimdLevel, a: &mut [u32], b: &[u32], c: u32, mask: u32) -> usize {
+        simd::fma_scalar_pow2_u32_mut(level, a, b, c, mask)
+    }
+}
+
+impl PowerOf2Word for u16 {}
+
+pub(crate) struct ModulusPowerOf2<T: Modulus> {
     modulus: T,
     /// Modulus mask: (1 << q) - 1
-    mask: u64,
+    mask: T::Element,
+    /// SIMD level of vector ops
+    simd: SimdLevel,
+}
+
+impl<T: Modulus> ModulusPowerOf2<T> {
+    /// Returns `self` with vector ops using SIMD `level`. Level is capped to
+    /// the highest level supported by the CPU.
+    #[cfg(test)]
+    pub(crate) fn with_simd_level(mut self, level: SimdLevel) -> Self {
+        self.simd = level.min(SimdLevel::detect());
+        self
+    }
 }
 
-impl<T> ArithmeticOps for ModulusPowerOf2<T> {
-    type Element = u64;
+impl<T: Modulus> ArithmeticOps for ModulusPowerOf2<T>
+where
+    T::Element: PowerOf2Word,
+{
+    type Element = T::Element;
     #[inline]
     fn add(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
-        (a.wrapping_add(*b)) & self.mask
+        (a.wrapping_add(b)) & self.mask
     }
     #[inline]
     fn sub(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
-        (a.wrapping_sub(*b)) & self.mask
+        (a.wrapping_sub(b)) & self.mask
     }
     #[inline]
     fn mul(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
-        (a.wrapping_mul(*b)) & self.mask
+        (a.wrapping_mul(b)) & self.mask
     }
     #[inline]
     fn neg(&self, a: &Self::Element) -> Self::Element {
-        (0u64.wrapping_sub(*a)) & self.mask
+        (T::Element::zero().wrapping_sub(a)) & self.mask
     }
 }
 
-impl<T> VectorOps for ModulusPowerOf2<T> {
-    type Element = u64;
+impl<T: Modulus> VectorOps for ModulusPowerOf2<T>
+where
+    T::Element: PowerOf2Word,
+{
+    type Element = T::Element;
 
     #[inline]
     fn elwise_add_mut(&self, a: &mut [Self::Element], b: &[Self::Element]) {
-        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| *a0 = (a0.wrapping_add(*b0)) & self.mask);
+        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| *a0 = (a0.wrapping_add(b0)) & self.mask);
     }
 
     #[inline]
     fn elwise_mul_mut(&self, a: &mut [Self::Element], b: &[Self::Element]) {
-        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| *a0 = (a0.wrapping_mul(*b0)) & self.mask);
+        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| *a0 = (a0.wrapping_mul(b0)) & self.mask);
     }
 
     #[inline]
     fn elwise_neg_mut(&self, a: &mut [Self::Element]) {
         a.iter_mut()
-            .for_each(|a0| *a0 = 0u64.wrapping_sub(*a0) & self.mask);
+            .for_each(|a0| *a0 = T::Element::zero().wrapping_sub(a0) & self.mask);
     }
     #[inline]
     fn elwise_sub_mut(&self, a: &mut [Self::Element], b: &[Self::Element]) {
-        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| *a0 = (a0.wrapping_sub(*b0)) & self.mask);
+        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| *a0 = (a0.wrapping_sub(b0)) & self.mask);
     }
 
     #[inline]
     fn elwise_fma_mut(&self, a: &mut [Self::Element], b: &[Self::Element], c: &[Self::Element]) {
         izip!(a.iter_mut(), b.iter(), c.iter()).for_each(|(a0, b0, c0)| {
-            *a0 = a0.wrapping_add(b0.wrapping_mul(*c0)) & self.mask;
+            *a0 = a0.wrapping_add(&b0.wrapping_mul(c0)) & self.mask;
         });
     }
 
@@ -67,44 +124,52 @@ impl<T> VectorOps for ModulusPowerOf2<T> {
         b: &[Self::Element],
         c: &Self::Element,
     ) {
-        izip!(a.iter_mut(), b.iter()).for_each(|(a0, b0)| {
-            *a0 = a0.wrapping_add(b0.wrapping_mul(*c)) & self.mask;
+        let done = T::Element::fma_scalar_simd(self.simd, a, b, *c, self.mask);
+        izip!(a[done..].iter_mut(), b[done..].iter()).for_each(|(a0, b0)| {
+            *a0 = a0.wrapping_add(&b0.wrapping_mul(c)) & self.mask;
         });
     }
     #[inline]
     fn elwise_scalar_mul_mut(&self, a: &mut [Self::Element], b: &Self::Element) {
         a.iter_mut()
-            .for_each(|a0| *a0 = a0.wrapping_mul(*b) & self.mask)
+            .for_each(|a0| *a0 = a0.wrapping_mul(b) & self.mask)
     }
 
     #[inline]
     fn elwise_mul(&self, out: &mut [Self::Element], a: &[Self::Element], b: &[Self::Element]) {
         izip!(out.iter_mut(), a.iter(), b.iter()).for_each(|(o0, a0, b0)| {
-            *o0 = a0.wrapping_mul(*b0) & self.mask;
+            *o0 = a0.wrapping_mul(b0) & self.mask;
         });
     }
 
     #[inline]
     fn elwise_scalar_mul(&self, out: &mut [Self::Element], a: &[Self::Element], b: &Self::Element) {
         izip!(out.iter_mut(), a.iter()).for_each(|(o0, a0)| {
-            *o0 = a0.wrapping_mul(*b) & self.mask;
+            *o0 = a0.wrapping_mul(b) & self.mask;
         });
     }
 }
 
-impl<T: Modulus<Element = u64>> ModInit for ModulusPowerOf2<T> {
+impl<T: Modulus> ModInit for ModulusPowerOf2<T>
+where
+    T::Element: PowerOf2Word,
+{
     type M = T;
     fn new(modulus: Self::M) -> Self {
         assert!(!modulus.is_native());
-        assert!(modulus.q().unwrap().is_power_of_two());
         let q = modulus.q().unwrap();
-        let mask = q - 1;
-        Self { modulus, mask }
+        assert!(q.count_ones() == 1);
+        let mask = q - T::Element::one();
+        Self {
+            modulus,
+            mask,
+            simd: SimdLevel::detect(),
+        }
     }
 }
 
-impl<T: Modulus<Element = u64>> GetModulus for ModulusPowerOf2<T> {
-    type Element = u64;
+impl<T: Modulus> GetModulus for ModulusPowerOf2<T> {
+    type Element = T::Element;
     type M = T;
     fn modulus(&self) -> &Self::M {
         &self.modulus
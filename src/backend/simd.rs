@@ -0,0 +1,859 @@
+use std::sync::OnceLock;
+
+/// SIMD instruction set used by vector ops of modulus backends.
+///
+/// Levels are ordered. A backend never uses a level higher than the one
+/// detected on the running CPU, [SimdLevel::detect].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimdLevel {
+    /// Portable scalar code
+    Scalar,
+    /// AVX2
+    Avx2,
+    /// AVX-512F and AVX-512DQ
+    Avx512,
+    /// AVX-512F, AVX-512DQ and AVX-512IFMA. IFMA operates on 52 bit limbs
+    /// and is only used for LWE moduli <= 2^52. RLWE moduli are larger.
+    Avx512Ifma,
+}
+
+impl SimdLevel {
+    /// Highest level supported by the running CPU. Detected once and cached.
+    pub fn detect() -> SimdLevel {
+        static LEVEL: OnceLock<SimdLevel> = OnceLock::new();
+        *LEVEL.get_or_init(|| {
+            #[cfg(target_arch = "x86_64")]
+            {
+                if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512dq") {
+                    if is_x86_feature_detected!("avx512ifma") {
+                        return SimdLevel::Avx512Ifma;
+                    }
+                    return SimdLevel::Avx512;
+                }
+                if is_x86_feature_detected!("avx2") {
+                    return SimdLevel::Avx2;
+                }
+            }
+            SimdLevel::Scalar
+        })
+    }
+}
+
+// Kernels below process a prefix of the input whose length is a multiple of
+// the no. of lanes and return the length of the prefix. Callers process the
+// remaining tail with scalar code. All kernels must be called with level <=
+// SimdLevel::detect().
+
+/// Sets a[i] = a[i] + b[i] mod q for a[i], b[i] in [0, q). Requires q < 2^62
+pub(super) fn add_mod_mut(level: SimdLevel, a: &mut [u64], b: &[u64], q: u64) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe { avx512::add_mod_mut(a, b, q) },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { avx2::add_mod_mut(a, b, q) },
+        _ => 0,
+    }
+}
+
+/// Sets a[i] = a[i] - b[i] mod q for a[i], b[i] in [0, q). Requires q < 2^62
+pub(super) fn sub_mod_mut(level: SimdLevel, a: &mut [u64], b: &[u64], q: u64) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe { avx512::sub_mod_mut(a, b, q) },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { avx2::sub_mod_mut(a, b, q) },
+        _ => 0,
+    }
+}
+
+/// Sets a[i] = q - a[i]
+pub(super) fn neg_mod_mut(level: SimdLevel, a: &mut [u64], q: u64) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe { avx512::neg_mod_mut(a, q) },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { avx2::neg_mod_mut(a, q) },
+        _ => 0,
+    }
+}
+
+/// Sets out[i] = out[i] + a[i] * b[i] mod q in range [0, 2q) with Shoup's
+/// multiplication, where a_shoup[i] = (a[i] * 2^64) / q. Requires out[i] in
+/// [0, 2q) and q < 2^60.
+pub(super) fn shoup_fma_mut(
+    level: SimdLevel,
+    out: &mut [u64],
+    a: &[u64],
+    a_shoup: &[u64],
+    b: &[u64],
+    q: u64,
+) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe {
+            avx512::shoup_fma_mut(out, a, a_shoup, b, q)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { avx2::shoup_fma_mut(out, a, a_shoup, b, q) },
+        _ => 0,
+    }
+}
+
+/// Sets a[i] = (a[i] + b[i] * c) & mask, that is the FMA modulo power of two
+/// modulus mask + 1.
+///
+/// 52 bit IFMA multiply-add is used if the modulus is <= 2^52 and 32 bit
+/// multiplication if the modulus is <= 2^32. Low bits of the product only
+/// depend on low bits of the operands, hence the inputs need not be reduced.
+pub(super) fn fma_scalar_pow2_mut(
+    level: SimdLevel,
+    a: &mut [u64],
+    b: &[u64],
+    c: u64,
+    mask: u64,
+) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512Ifma if mask < (1 << 52) => unsafe {
+            avx512::fma_scalar_pow2_mut_ifma(a, b, c, mask)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe {
+            if mask <= u32::MAX as u64 {
+                avx512::fma_scalar_pow2_mut::<true>(a, b, c, mask)
+            } else {
+                avx512::fma_scalar_pow2_mut::<false>(a, b, c, mask)
+            }
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe {
+            if mask <= u32::MAX as u64 {
+                avx2::fma_scalar_pow2_mut::<true>(a, b, c, mask)
+            } else {
+                avx2::fma_scalar_pow2_mut::<false>(a, b, c, mask)
+            }
+        },
+        _ => 0,
+    }
+}
+
+/// Same as `fma_scalar_pow2_mut` for elements stored in u32, for which the
+/// modulus is <= 2^32. Lanes are twice as many as with u64.
+pub(super) fn fma_scalar_pow2_u32_mut(
+    level: SimdLevel,
+    a: &mut [u32],
+    b: &[u32],
+    c: u32,
+    mask: u32,
+) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe {
+            avx512::fma_scalar_pow2_u32_mut(a, b, c, mask)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { avx2::fma_scalar_pow2_u32_mut(a, b, c, mask) },
+        _ => 0,
+    }
+}
+
+/// Forward NTT butterflies x[i]' = x[i] + w * y[i] and y[i]' = x[i] - w * y[i]
+/// with a single twiddle `w` and its Shoup representation `w_shoup`. Inputs
+/// and outputs are in [0, 4q). Requires q < 2^60.
+pub(crate) fn ntt_forward_butterfly_mut(
+    level: SimdLevel,
+    x: &mut [u64],
+    y: &mut [u64],
+    w: u64,
+    w_shoup: u64,
+    q: u64,
+) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe {
+            avx512::ntt_forward_butterfly_mut(x, y, w, w_shoup, q)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { avx2::ntt_forward_butterfly_mut(x, y, w, w_shoup, q) },
+        _ => 0,
+    }
+}
+
+/// Inverse NTT butterflies x[i]' = x[i] + y[i] and y[i]' = w * (x[i] - y[i])
+/// with a single twiddle `w` and its Shoup representation `w_shoup`. Inputs
+/// and outputs are in [0, 2q). Requires q < 2^60.
+pub(crate) fn ntt_inverse_butterfly_mut(
+    level: SimdLevel,
+    x: &mut [u64],
+    y: &mut [u64],
+    w: u64,
+    w_shoup: u64,
+    q: u64,
+) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe {
+            avx512::ntt_inverse_butterfly_mut::<false, false>(x, y, w, w_shoup, 0, 0, q)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe {
+            avx2::ntt_inverse_butterfly_mut::<false, false>(x, y, w, w_shoup, 0, 0, q)
+        },
+        _ => 0,
+    }
+}
+
+/// Same as [ntt_inverse_butterfly_mut] for the last layer of inverse NTT.
+/// Additionally multiplies outputs by n^{-1}. Outputs are in [0, q) if
+/// `REDUCE` is true, otherwise in [0, 2q).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn ntt_inverse_last_butterfly_mut<const REDUCE: bool>(
+    level: SimdLevel,
+    x: &mut [u64],
+    y: &mut [u64],
+    w: u64,
+    w_shoup: u64,
+    n_inv: u64,
+    n_inv_shoup: u64,
+    q: u64,
+) -> usize {
+    match level {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 | SimdLevel::Avx512Ifma => unsafe {
+            avx512::ntt_inverse_butterfly_mut::<true, REDUCE>(
+                x,
+                y,
+                w,
+                w_shoup,
+                n_inv,
+                n_inv_shoup,
+                q,
+            )
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe {
+            avx2::ntt_inverse_butterfly_mut::<true, REDUCE>(x, y, w, w_shoup, n_inv, n_inv_shoup, q)
+        },
+        _ => 0,
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    const LANES: usize = 4;
+
+    /// Approximates high 64 bits of 64x64 bit product from three 32x32 bit
+    /// products. Carries of the low products are ignored, hence the output is
+    /// smaller than the high bits by at most 2.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mul_hi_approx(a: __m256i, b: __m256i) -> __m256i {
+        let a_hi = _mm256_srli_epi64::<32>(a);
+        let b_hi = _mm256_srli_epi64::<32>(b);
+        let lohi = _mm256_mul_epu32(a, b_hi);
+        let hilo = _mm256_mul_epu32(a_hi, b);
+        let hihi = _mm256_mul_epu32(a_hi, b_hi);
+        _mm256_add_epi64(
+            hihi,
+            _mm256_add_epi64(_mm256_srli_epi64::<32>(lohi), _mm256_srli_epi64::<32>(hilo)),
+        )
+    }
+
+    /// Low 64 bits of 64x64 bit product from three 32x32 bit products
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mul_lo(a: __m256i, b: __m256i) -> __m256i {
+        let lolo = _mm256_mul_epu32(a, b);
+        let cross = _mm256_add_epi64(
+            _mm256_mul_epu32(a, _mm256_srli_epi64::<32>(b)),
+            _mm256_mul_epu32(_mm256_srli_epi64::<32>(a), b),
+        );
+        _mm256_add_epi64(lolo, _mm256_slli_epi64::<32>(cross))
+    }
+
+    /// Returns v if v >= 0 else v + q, where v is interpreted as signed
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add_if_negative(v: __m256i, q: __m256i) -> __m256i {
+        let negative = _mm256_cmpgt_epi64(_mm256_setzero_si256(), v);
+        _mm256_add_epi64(v, _mm256_and_si256(q, negative))
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn add_mod_mut(a: &mut [u64], b: &[u64], q: u64) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let q = _mm256_set1_epi64x(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m256i>();
+            let x = _mm256_loadu_si256(a_ptr);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i).cast());
+            let v = _mm256_sub_epi64(_mm256_add_epi64(x, y), q);
+            _mm256_storeu_si256(a_ptr, add_if_negative(v, q));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn sub_mod_mut(a: &mut [u64], b: &[u64], q: u64) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let q = _mm256_set1_epi64x(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m256i>();
+            let x = _mm256_loadu_si256(a_ptr);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i).cast());
+            _mm256_storeu_si256(a_ptr, add_if_negative(_mm256_sub_epi64(x, y), q));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn neg_mod_mut(a: &mut [u64], q: u64) -> usize {
+        let n = a.len() / LANES * LANES;
+        let q = _mm256_set1_epi64x(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m256i>();
+            let x = _mm256_loadu_si256(a_ptr);
+            _mm256_storeu_si256(a_ptr, _mm256_sub_epi64(q, x));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn shoup_fma_mut(
+        out: &mut [u64],
+        a: &[u64],
+        a_shoup: &[u64],
+        b: &[u64],
+        q: u64,
+    ) -> usize {
+        let n = out.len().min(a.len()).min(a_shoup.len()).min(b.len()) / LANES * LANES;
+        let q_twice = _mm256_set1_epi64x((q << 1) as i64);
+        let q_four = _mm256_set1_epi64x((q << 2) as i64);
+        let q = _mm256_set1_epi64x(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let out_ptr = out.as_mut_ptr().add(i).cast::<__m256i>();
+            let o = _mm256_loadu_si256(out_ptr);
+            let x = _mm256_loadu_si256(a.as_ptr().add(i).cast());
+            let x_shoup = _mm256_loadu_si256(a_shoup.as_ptr().add(i).cast());
+            let y = _mm256_loadu_si256(b.as_ptr().add(i).cast());
+
+            let quotient = mul_hi_approx(x_shoup, y);
+            let v = _mm256_add_epi64(mul_lo(x, y), o);
+            let v = _mm256_sub_epi64(v, mul_lo(q, quotient));
+
+            // quotient is smaller by at most 2, hence v is in [0, 6q)
+            let v = add_if_negative(_mm256_sub_epi64(v, q_four), q_four);
+            let v = add_if_negative(_mm256_sub_epi64(v, q_twice), q_twice);
+            _mm256_storeu_si256(out_ptr, v);
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn fma_scalar_pow2_mut<const NARROW: bool>(
+        a: &mut [u64],
+        b: &[u64],
+        c: u64,
+        mask: u64,
+    ) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let c = _mm256_set1_epi64x(c as i64);
+        let mask = _mm256_set1_epi64x(mask as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m256i>();
+            let x = _mm256_loadu_si256(a_ptr);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i).cast());
+            let yc = if NARROW {
+                _mm256_mul_epu32(y, c)
+            } else {
+                mul_lo(y, c)
+            };
+            _mm256_storeu_si256(a_ptr, _mm256_and_si256(_mm256_add_epi64(x, yc), mask));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn fma_scalar_pow2_u32_mut(
+        a: &mut [u32],
+        b: &[u32],
+        c: u32,
+        mask: u32,
+    ) -> usize {
+        let n = a.len().min(b.len()) / (2 * LANES) * (2 * LANES);
+        let c = _mm256_set1_epi32(c as i32);
+        let mask = _mm256_set1_epi32(mask as i32);
+        for i in (0..n).step_by(2 * LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m256i>();
+            let x = _mm256_loadu_si256(a_ptr);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i).cast());
+            let yc = _mm256_mullo_epi32(y, c);
+            _mm256_storeu_si256(a_ptr, _mm256_and_si256(_mm256_add_epi32(x, yc), mask));
+        }
+        n
+    }
+
+    /// Returns a * b mod q in range [0, 2q) with Shoup's multiplication, where
+    /// b_shoup = (b * 2^64) / q
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn shoup_mul(a: __m256i, b: __m256i, b_shoup: __m256i, q: __m256i) -> __m256i {
+        let quotient = mul_hi_approx(b_shoup, a);
+        let v = _mm256_sub_epi64(mul_lo(a, b), mul_lo(q, quotient));
+        // quotient is smaller by at most 2, hence v is in [0, 4q)
+        let q_twice = _mm256_add_epi64(q, q);
+        add_if_negative(_mm256_sub_epi64(v, q_twice), q_twice)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn ntt_forward_butterfly_mut(
+        x: &mut [u64],
+        y: &mut [u64],
+        w: u64,
+        w_shoup: u64,
+        q: u64,
+    ) -> usize {
+        let n = x.len().min(y.len()) / LANES * LANES;
+        let q_twice = _mm256_set1_epi64x((q << 1) as i64);
+        let w = _mm256_set1_epi64x(w as i64);
+        let w_shoup = _mm256_set1_epi64x(w_shoup as i64);
+        let q = _mm256_set1_epi64x(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let x_ptr = x.as_mut_ptr().add(i).cast::<__m256i>();
+            let y_ptr = y.as_mut_ptr().add(i).cast::<__m256i>();
+            let a = _mm256_loadu_si256(x_ptr);
+            let b = _mm256_loadu_si256(y_ptr);
+
+            let a = add_if_negative(_mm256_sub_epi64(a, q_twice), q_twice);
+            let t = shoup_mul(b, w, w_shoup, q);
+
+            _mm256_storeu_si256(x_ptr, _mm256_add_epi64(a, t));
+            _mm256_storeu_si256(y_ptr, _mm256_sub_epi64(_mm256_add_epi64(a, q_twice), t));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn ntt_inverse_butterfly_mut<const LAST: bool, const REDUCE: bool>(
+        x: &mut [u64],
+        y: &mut [u64],
+        w: u64,
+        w_shoup: u64,
+        n_inv: u64,
+        n_inv_shoup: u64,
+        q: u64,
+    ) -> usize {
+        let n = x.len().min(y.len()) / LANES * LANES;
+        let q_twice = _mm256_set1_epi64x((q << 1) as i64);
+        let w = _mm256_set1_epi64x(w as i64);
+        let w_shoup = _mm256_set1_epi64x(w_shoup as i64);
+        let n_inv = _mm256_set1_epi64x(n_inv as i64);
+        let n_inv_shoup = _mm256_set1_epi64x(n_inv_shoup as i64);
+        let q = _mm256_set1_epi64x(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let x_ptr = x.as_mut_ptr().add(i).cast::<__m256i>();
+            let y_ptr = y.as_mut_ptr().add(i).cast::<__m256i>();
+            let a = _mm256_loadu_si256(x_ptr);
+            let b = _mm256_loadu_si256(y_ptr);
+
+            let mut ox =
+                add_if_negative(_mm256_sub_epi64(_mm256_add_epi64(a, b), q_twice), q_twice);
+            let mut oy = shoup_mul(
+                _mm256_sub_epi64(_mm256_add_epi64(a, q_twice), b),
+                w,
+                w_shoup,
+                q,
+            );
+
+            if LAST {
+                ox = shoup_mul(ox, n_inv, n_inv_shoup, q);
+                oy = shoup_mul(oy, n_inv, n_inv_shoup, q);
+                if REDUCE {
+                    ox = add_if_negative(_mm256_sub_epi64(ox, q), q);
+                    oy = add_if_negative(_mm256_sub_epi64(oy, q), q);
+                }
+            }
+
+            _mm256_storeu_si256(x_ptr, ox);
+            _mm256_storeu_si256(y_ptr, oy);
+        }
+        n
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx512 {
+    use std::arch::x86_64::*;
+
+    const LANES: usize = 8;
+
+    /// Approximates high 64 bits of 64x64 bit product from three 32x32 bit
+    /// products. Carries of the low products are ignored, hence the output is
+    /// smaller than the high bits by at most 2.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    unsafe fn mul_hi_approx(a: __m512i, b: __m512i) -> __m512i {
+        let a_hi = _mm512_srli_epi64::<32>(a);
+        let b_hi = _mm512_srli_epi64::<32>(b);
+        let lohi = _mm512_mul_epu32(a, b_hi);
+        let hilo = _mm512_mul_epu32(a_hi, b);
+        let hihi = _mm512_mul_epu32(a_hi, b_hi);
+        _mm512_add_epi64(
+            hihi,
+            _mm512_add_epi64(_mm512_srli_epi64::<32>(lohi), _mm512_srli_epi64::<32>(hilo)),
+        )
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn add_mod_mut(a: &mut [u64], b: &[u64], q: u64) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let q = _mm512_set1_epi64(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m512i>();
+            let x = _mm512_loadu_si512(a_ptr);
+            let y = _mm512_loadu_si512(b.as_ptr().add(i).cast());
+            let v = _mm512_add_epi64(x, y);
+            _mm512_storeu_si512(a_ptr, _mm512_min_epu64(v, _mm512_sub_epi64(v, q)));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn sub_mod_mut(a: &mut [u64], b: &[u64], q: u64) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let q = _mm512_set1_epi64(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m512i>();
+            let x = _mm512_loadu_si512(a_ptr);
+            let y = _mm512_loadu_si512(b.as_ptr().add(i).cast());
+            let v = _mm512_sub_epi64(x, y);
+            _mm512_storeu_si512(a_ptr, _mm512_min_epu64(v, _mm512_add_epi64(v, q)));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn neg_mod_mut(a: &mut [u64], q: u64) -> usize {
+        let n = a.len() / LANES * LANES;
+        let q = _mm512_set1_epi64(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m512i>();
+            let x = _mm512_loadu_si512(a_ptr);
+            _mm512_storeu_si512(a_ptr, _mm512_sub_epi64(q, x));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f,avx512dq")]
+    pub(super) unsafe fn shoup_fma_mut(
+        out: &mut [u64],
+        a: &[u64],
+        a_shoup: &[u64],
+        b: &[u64],
+        q: u64,
+    ) -> usize {
+        let n = out.len().min(a.len()).min(a_shoup.len()).min(b.len()) / LANES * LANES;
+        let q_twice = _mm512_set1_epi64((q << 1) as i64);
+        let q_four = _mm512_set1_epi64((q << 2) as i64);
+        let q = _mm512_set1_epi64(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let out_ptr = out.as_mut_ptr().add(i).cast::<__m512i>();
+            let o = _mm512_loadu_si512(out_ptr);
+            let x = _mm512_loadu_si512(a.as_ptr().add(i).cast());
+            let x_shoup = _mm512_loadu_si512(a_shoup.as_ptr().add(i).cast());
+            let y = _mm512_loadu_si512(b.as_ptr().add(i).cast());
+
+            let quotient = mul_hi_approx(x_shoup, y);
+            let v = _mm512_add_epi64(_mm512_mullo_epi64(x, y), o);
+            let v = _mm512_sub_epi64(v, _mm512_mullo_epi64(q, quotient));
+
+            // quotient is smaller by at most 2, hence v is in [0, 6q)
+            let v = _mm512_min_epu64(v, _mm512_sub_epi64(v, q_four));
+            let v = _mm512_min_epu64(v, _mm512_sub_epi64(v, q_twice));
+            _mm512_storeu_si512(out_ptr, v);
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f,avx512dq")]
+    pub(super) unsafe fn fma_scalar_pow2_mut<const NARROW: bool>(
+        a: &mut [u64],
+        b: &[u64],
+        c: u64,
+        mask: u64,
+    ) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let c = _mm512_set1_epi64(c as i64);
+        let mask = _mm512_set1_epi64(mask as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m512i>();
+            let x = _mm512_loadu_si512(a_ptr);
+            let y = _mm512_loadu_si512(b.as_ptr().add(i).cast());
+            let yc = if NARROW {
+                _mm512_mul_epu32(y, c)
+            } else {
+                _mm512_mullo_epi64(y, c)
+            };
+            _mm512_storeu_si512(a_ptr, _mm512_and_si512(_mm512_add_epi64(x, yc), mask));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn fma_scalar_pow2_u32_mut(
+        a: &mut [u32],
+        b: &[u32],
+        c: u32,
+        mask: u32,
+    ) -> usize {
+        let n = a.len().min(b.len()) / (2 * LANES) * (2 * LANES);
+        let c = _mm512_set1_epi32(c as i32);
+        let mask = _mm512_set1_epi32(mask as i32);
+        for i in (0..n).step_by(2 * LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m512i>();
+            let x = _mm512_loadu_si512(a_ptr);
+            let y = _mm512_loadu_si512(b.as_ptr().add(i).cast());
+            let yc = _mm512_mullo_epi32(y, c);
+            _mm512_storeu_si512(a_ptr, _mm512_and_si512(_mm512_add_epi32(x, yc), mask));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f,avx512ifma")]
+    pub(super) unsafe fn fma_scalar_pow2_mut_ifma(
+        a: &mut [u64],
+        b: &[u64],
+        c: u64,
+        mask: u64,
+    ) -> usize {
+        let n = a.len().min(b.len()) / LANES * LANES;
+        let c = _mm512_set1_epi64(c as i64);
+        let mask = _mm512_set1_epi64(mask as i64);
+        for i in (0..n).step_by(LANES) {
+            let a_ptr = a.as_mut_ptr().add(i).cast::<__m512i>();
+            let x = _mm512_loadu_si512(a_ptr);
+            let y = _mm512_loadu_si512(b.as_ptr().add(i).cast());
+            // x + low 52 bits of y[0..52] * c[0..52]
+            let v = _mm512_madd52lo_epu64(x, y, c);
+            _mm512_storeu_si512(a_ptr, _mm512_and_si512(v, mask));
+        }
+        n
+    }
+
+    /// Returns a * b mod q in range [0, 2q) with Shoup's multiplication, where
+    /// b_shoup = (b * 2^64) / q
+    #[inline]
+    #[target_feature(enable = "avx512f,avx512dq")]
+    unsafe fn shoup_mul(a: __m512i, b: __m512i, b_shoup: __m512i, q: __m512i) -> __m512i {
+        let quotient = mul_hi_approx(b_shoup, a);
+        let v = _mm512_sub_epi64(_mm512_mullo_epi64(a, b), _mm512_mullo_epi64(q, quotient));
+        // quotient is smaller by at most 2, hence v is in [0, 4q)
+        let q_twice = _mm512_add_epi64(q, q);
+        _mm512_min_epu64(v, _mm512_sub_epi64(v, q_twice))
+    }
+
+    #[target_feature(enable = "avx512f,avx512dq")]
+    pub(super) unsafe fn ntt_forward_butterfly_mut(
+        x: &mut [u64],
+        y: &mut [u64],
+        w: u64,
+        w_shoup: u64,
+        q: u64,
+    ) -> usize {
+        let n = x.len().min(y.len()) / LANES * LANES;
+        let q_twice = _mm512_set1_epi64((q << 1) as i64);
+        let w = _mm512_set1_epi64(w as i64);
+        let w_shoup = _mm512_set1_epi64(w_shoup as i64);
+        let q = _mm512_set1_epi64(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let x_ptr = x.as_mut_ptr().add(i).cast::<__m512i>();
+            let y_ptr = y.as_mut_ptr().add(i).cast::<__m512i>();
+            let a = _mm512_loadu_si512(x_ptr);
+            let b = _mm512_loadu_si512(y_ptr);
+
+            let a = _mm512_min_epu64(a, _mm512_sub_epi64(a, q_twice));
+            let t = shoup_mul(b, w, w_shoup, q);
+
+            _mm512_storeu_si512(x_ptr, _mm512_add_epi64(a, t));
+            _mm512_storeu_si512(y_ptr, _mm512_sub_epi64(_mm512_add_epi64(a, q_twice), t));
+        }
+        n
+    }
+
+    #[target_feature(enable = "avx512f,avx512dq")]
+    pub(super) unsafe fn ntt_inverse_butterfly_mut<const LAST: bool, const REDUCE: bool>(
+        x: &mut [u64],
+        y: &mut [u64],
+        w: u64,
+        w_shoup: u64,
+        n_inv: u64,
+        n_inv_shoup: u64,
+        q: u64,
+    ) -> usize {
+        let n = x.len().min(y.len()) / LANES * LANES;
+        let q_twice = _mm512_set1_epi64((q << 1) as i64);
+        let w = _mm512_set1_epi64(w as i64);
+        let w_shoup = _mm512_set1_epi64(w_shoup as i64);
+        let n_inv = _mm512_set1_epi64(n_inv as i64);
+        let n_inv_shoup = _mm512_set1_epi64(n_inv_shoup as i64);
+        let q = _mm512_set1_epi64(q as i64);
+        for i in (0..n).step_by(LANES) {
+            let x_ptr = x.as_mut_ptr().add(i).cast::<__m512i>();
+            let y_ptr = y.as_mut_ptr().add(i).cast::<__m512i>();
+            let a = _mm512_loadu_si512(x_ptr);
+            let b = _mm512_loadu_si512(y_ptr);
+
+            let sum = _mm512_add_epi64(a, b);
+            let mut ox = _mm512_min_epu64(sum, _mm512_sub_epi64(sum, q_twice));
+            let mut oy = shoup_mul(
+                _mm512_sub_epi64(_mm512_add_epi64(a, q_twice), b),
+                w,
+                w_shoup,
+                q,
+            );
+
+            if LAST {
+                ox = shoup_mul(ox, n_inv, n_inv_shoup, q);
+                oy = shoup_mul(oy, n_inv, n_inv_shoup, q);
+                if REDUCE {
+                    ox = _mm512_min_epu64(ox, _mm512_sub_epi64(ox, q));
+                    oy = _mm512_min_epu64(oy, _mm512_sub_epi64(oy, q));
+                }
+            }
+
+            _mm512_storeu_si512(x_ptr, ox);
+            _mm512_storeu_si512(y_ptr, oy);
+        }
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::{izip, Itertools};
+    use rand::{thread_rng, Rng};
+    use rand_distr::Uniform;
+
+    use super::*;
+    use crate::backend::{ModInit, ModularOpsU64, ModulusPowerOf2, ShoupMatrixFMA, VectorOps};
+
+    fn levels() -> Vec<SimdLevel> {
+        [SimdLevel::Avx2, SimdLevel::Avx512, SimdLevel::Avx512Ifma]
+            .into_iter()
+            .filter(|l| *l <= SimdLevel::detect())
+            .collect_vec()
+    }
+
+    #[test]
+    fn simd_modular_ops_match_scalar() {
+        let mut rng = thread_rng();
+        for q in [36028797017456641u64, 18014398509404161, 268369921] {
+            let dist = Uniform::new(0, q);
+            // length is not a multiple of no. of lanes to exercise the tail
+            let n = 1027;
+            let a = vec![(&mut rng).sample_iter(dist).take(n).collect_vec()];
+            let b = vec![(&mut rng).sample_iter(dist).take(n).collect_vec()];
+            let a_shoup = vec![a[0]
+                .iter()
+                .map(|v| ((*v as u128 * (1u128 << 64)) / q as u128) as u64)
+                .collect_vec()];
+            let out = (&mut rng)
+                .sample_iter(Uniform::new(0, q << 1))
+                .take(n)
+                .collect_vec();
+
+            let scalar = ModularOpsU64::new(q).with_simd_level(SimdLevel::Scalar);
+            for level in levels() {
+                let simd = ModularOpsU64::new(q).with_simd_level(level);
+
+                let check =
+                    |name: &str, init: &[u64], f: &dyn Fn(&ModularOpsU64<u64>, &mut [u64])| {
+                        let mut expected = init.to_vec();
+                        let mut got = init.to_vec();
+                        f(&scalar, &mut expected);
+                        f(&simd, &mut got);
+                        assert_eq!(expected, got, "{name} with {level:?} and q={q}");
+                    };
+                check("elwise_add_mut", &a[0], &|op, v| {
+                    op.elwise_add_mut(v, &b[0])
+                });
+                check("elwise_sub_mut", &a[0], &|op, v| {
+                    op.elwise_sub_mut(v, &b[0])
+                });
+                check("elwise_neg_mut", &a[0], &|op, v| op.elwise_neg_mut(v));
+                // outputs are in [0, 2q) and may differ by q
+                check("shoup_matrix_fma", &out, &|op, v| {
+                    op.shoup_matrix_fma(v, &a, &a_shoup, &b);
+                    v.iter_mut().for_each(|vi| {
+                        assert!(*vi < q << 1);
+                        *vi %= q;
+                    });
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn simd_power_of_two_fma_matches_scalar() {
+        let mut rng = thread_rng();
+        for logq in [15, 32, 33, 52, 53, 63] {
+            let q = 1u64 << logq;
+            let n = 1027;
+            let a = (&mut rng)
+                .sample_iter(Uniform::new(0, q))
+                .take(n)
+                .collect_vec();
+            let b = (&mut rng)
+                .sample_iter(Uniform::new(0, q))
+                .take(n)
+                .collect_vec();
+            let c = rng.gen_range(0..q);
+
+            let mut expected = a.clone();
+            izip!(expected.iter_mut(), b.iter())
+                .for_each(|(e, bi)| *e = e.wrapping_add(bi.wrapping_mul(c)) & (q - 1));
+
+            for level in levels() {
+                let op = ModulusPowerOf2::new(q).with_simd_level(level);
+                let mut got = a.clone();
+                op.elwise_fma_scalar_mut(&mut got, &b, &c);
+                assert_eq!(expected, got, "{level:?} and q=2^{logq}");
+            }
+        }
+    }
+
+    #[test]
+    fn simd_power_of_two_fma_u32_matches_scalar() {
+        let mut rng = thread_rng();
+        for logq in [15, 24, 31, 32] {
+            let q = 1u64 << logq;
+            let mask = (q - 1) as u32;
+            let n = 1027;
+            let a = (&mut rng)
+                .sample_iter(Uniform::new(0, q))
+                .map(|v| v as u32)
+                .take(n)
+                .collect_vec();
+            let b = (&mut rng)
+                .sample_iter(Uniform::new(0, q))
+                .map(|v| v as u32)
+                .take(n)
+                .collect_vec();
+            let c = rng.gen_range(0..q) as u32;
+
+            let mut expected = a.clone();
+            izip!(expected.iter_mut(), b.iter())
+                .for_each(|(e, bi)| *e = e.wrapping_add(bi.wrapping_mul(c)) & mask);
+
+            for level in levels() {
+                let mut got = a.clone();
+                let done = fma_scalar_pow2_u32_mut(level, &mut got, &b, c, mask);
+                assert!(done > 0 && done <= n);
+                assert_eq!(expected[..done], got[..done], "{level:?} and q=2^{logq}");
+                assert_eq!(a[done..], got[done..]);
+            }
+        }
+    }
+}
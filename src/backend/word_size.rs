@@ -1,7 +1,8 @@
 use itertools::izip;
 use num_traits::{WrappingAdd, WrappingMul, WrappingSub, Zero};
 
-use super::{ArithmeticOps, GetModulus, ModInit, Modulus, VectorOps};
+use super::{ArithmeticOps, GetModulus, ModInit, Modulus, ShoupMatrixFMA, VectorOps};
+use crate::RowMut;
 
 pub struct WordSizeModulus<T> {
     modulus: T,
@@ -122,3 +123,27 @@ where
         &self.modulus
     }
 }
+
+/// Native modulus does not need shoup representation for fast modular
+/// multiplication. `a_shoup` is ignored and products wrap around.
+impl<R: RowMut, T> ShoupMatrixFMA<R> for WordSizeModulus<T>
+where
+    R::Element: WrappingAdd + WrappingMul,
+{
+    fn shoup_matrix_fma(&self, out: &mut [R::Element], a: &[R], _a_shoup: &[R], b: &[R]) {
+        assert!(
+            a.len() == b.len(),
+            "Unequal length {}!={}",
+            a.len(),
+            b.len()
+        );
+
+        izip!(a.iter(), b.iter()).for_each(|(a_row, b_row)| {
+            izip!(out.iter_mut(), a_row.as_ref().iter(), b_row.as_ref().iter()).for_each(
+                |(o, a0, b0)| {
+                    *o = R::Element::wrapping_add(o, &R::Element::wrapping_mul(a0, b0));
+                },
+            );
+        });
+    }
+}
@@ -8,175 +8,338 @@ use std::{
 use itertools::{izip, Itertools};
 use num_traits::{FromPrimitive, One, PrimInt, ToPrimitive, WrappingAdd, WrappingSub, Zero};
 use rand_distr::uniform::SampleUniform;
+use sha2::{Digest, Sha256};
 
 use crate::{
-    backend::{ArithmeticOps, GetModulus, ModInit, Modulus, ShoupMatrixFMA, VectorOps},
+    backend::{
+        ArithmeticOps, GetModulus, ModInit, Modulus, ModulusPowerOf2, PowerOf2Word, ShoupMatrixFMA,
+        VectorOps,
+    },
     bool::parameters::ParameterVariant,
     decomposer::{Decomposer, DefaultDecomposer, NumInfo, RlweDecomposer},
+    fft::FftBackendF64,
     lwe::{decrypt_lwe, encrypt_lwe, seeded_lwe_ksk_keygen},
     multi_party::{
-        non_interactive_ksk_gen, non_interactive_ksk_zero_encryptions_for_other_party_i,
-        public_key_share,
+        multi_party_decryption_share, non_interactive_ksk_gen,
+        non_interactive_ksk_zero_encryptions_for_other_party_i, public_key_share,
     },
     ntt::{Ntt, NttInit},
-    pbs::{pbs, PbsInfo, PbsKey, WithShoupRepr},
+    pbs::{batch_pbs, pbs, sample_extract, BlindRotationKeyCt, PbsInfo, PbsKey, WithShoupRepr},
     random::{
-        DefaultSecureRng, NewWithSeed, RandomFill, RandomFillGaussianInModulus,
+        DefaultSecureRng, NewWithSeed, PrgAlgorithm, RandomFillGaussianInModulus,
         RandomFillUniformInModulus,
     },
     rgsw::{
-        generate_auto_map, public_key_encrypt_rgsw, rgsw_by_rgsw_inplace, rgsw_x_rgsw_scratch_rows,
-        rlwe_auto_scratch_rows, rlwe_x_rgsw_scratch_rows, secret_key_encrypt_rgsw,
-        seeded_auto_key_gen, RgswCiphertextMutRef, RgswCiphertextRef, RuntimeScratchMutRef,
+        generate_auto_map, public_key_encrypt_rgsw, public_key_encrypt_rlwe, rgsw_by_rgsw_inplace,
+        rgsw_x_rgsw_scratch_rows, rlwe_auto_scratch_rows, rlwe_key_switch,
+        rlwe_x_rgsw_scratch_rows, secret_key_encrypt_rgsw, secret_key_encrypt_rgsw_fourier,
+        seeded_auto_key_gen, seeded_auto_key_gen_fourier, RgswCiphertextMutRef, RgswCiphertextRef,
+        RuntimeScratchMutRef,
     },
     utils::{
-        encode_x_pow_si_with_emebedding_factor, mod_exponent, puncture_p_rng, TryConvertFrom1,
-        WithLocal,
+        encode_x_pow_si_with_emebedding_factor, mod_exponent, wipe, MaybeSend, MaybeSync, ToShoup,
+        TryConvertFrom1, WithLocal,
     },
-    BooleanGates, Encoder, Matrix, MatrixEntity, MatrixMut, RowEntity, RowMut,
+    BoolGate, BooleanGates, Encoder, Matrix, MatrixEntity, MatrixMut, RowEntity, RowMut,
 };
 
+#[cfg(feature = "non_interactive_mp")]
+use super::keys::NonInteractiveServerKeyShareProof;
+#[cfg(feature = "non_interactive_mp")]
+use super::keys::SeededNonInteractiveMultiPartyServerKey;
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+use super::parameters::SecretKeyDistribution;
 use super::{
     keys::{
         ClientKey, CommonReferenceSeededCollectivePublicKeyShare,
         CommonReferenceSeededInteractiveMultiPartyServerKeyShare,
+        CommonReferenceSeededKeyRefreshShare,
         CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare,
-        InteractiveMultiPartyClientKey, NonInteractiveMultiPartyClientKey,
-        SeededInteractiveMultiPartyServerKey, SeededNonInteractiveMultiPartyServerKey,
+        InteractiveMultiPartyClientKey, InteractiveMultiPartyTranscript,
+        InvalidInteractiveServerKeyShares, KeyRefreshKeyEvaluationDomain, MalformedServerKeyShare,
+        NonInteractiveLateJoiningKeyShare, NonInteractiveMultiPartyClientKey,
+        NonInteractiveServerKeyShareComponents, SeededInteractiveMultiPartyServerKey,
         SeededSinglePartyServerKey, SinglePartyClientKey,
     },
-    parameters::{BoolParameters, CiphertextModulus, DecompositionCount, DoubleDecomposerParams},
+    parameters::{
+        BoolParameters, CiphertextModulus, DecompositionCount, DoubleDecomposerParams, LweKsElement,
+    },
 };
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+use crate::proof::{
+    prove_decryption_share, verify_decryption_share, DecryptionShareProof,
+    DecryptionShareStatement, ERROR_BOUND,
+};
+#[cfg(feature = "non_interactive_mp")]
+use crate::proof::{prove_short_relation, verify_short_relation, Coefficient, ShortRelation};
+
+/// Version of derivation of seeds from common reference seed. Must be bumped
+/// whenever the derivation changes.
+const CRS_KDF_VERSION: u32 = 2;
+
+/// Derives seed for `index`th instance of `component` of `protocol` from
+/// common reference `seed` of session `session_id` for parameter set
+/// `parameter_set`.
+///
+/// Seed is SHA-256 hash of length prefixed label, protocol, version, parameter
+/// set id, common reference seed, session id, component and index. Hence seeds
+/// derived for different protocols, versions, parameter sets, sessions,
+/// components or indices never collide even if the common reference seed is
+/// the same.
+fn derive_crs_seed<S: AsRef<[u8]> + From<[u8; 32]>>(
+    protocol: &str,
+    parameter_set: &str,
+    seed: &S,
+    session_id: &[u8; 32],
+    component: &str,
+    index: usize,
+) -> S {
+    let mut hasher = Sha256::new();
+    for field in [
+        b"phantom-zone/crs-kdf".as_slice(),
+        protocol.as_bytes(),
+        &CRS_KDF_VERSION.to_le_bytes(),
+        parameter_set.as_bytes(),
+        seed.as_ref(),
+        session_id,
+        component.as_bytes(),
+        &(index as u64).to_le_bytes(),
+    ] {
+        hasher.update((field.len() as u64).to_le_bytes());
+        hasher.update(field);
+    }
+    let out: [u8; 32] = hasher.finalize().into();
+    S::from(out)
+}
 
 /// Common reference seed used for Interactive multi-party,
 ///
 /// Seeds for public key shares and differents parts of server key shares are
-/// derived from common reference seed with different puncture rountines.
+/// derived from common reference seed, session id and parameter set id with
+/// labelled KDF (see [derive_crs_seed]) under protocol label `interactive-mp`.
+/// Seeds of round 2 (auto keys and LWE ksk) are derived with session id bound
+/// to round 1 transcript (see [InteractiveMultiPartyCrs::bound_to_transcript]).
 ///
-/// ## Punctures
+/// ## Components
 ///
-///     Initial Seed:
-///         Puncture 1 -> Public key share seed
-///         Puncture 2 -> Main server key share seed
-///             Puncture 1 -> Auto keys cipertexts seed
-///             Puncture 2 -> LWE ksk seed
+///     public-key-share, 0 -> Public key share seed
+///     auto-keys, 0        -> Auto keys cipertexts seed
+///     lwe-ksk, 0          -> LWE ksk seed
+///     key-refresh, 0      -> Key refresh key seed
+///     round-2-session, 0  -> Session id of round 2, keyed with digest of
+///                            round 1 transcript instead of the seed
 #[derive(Clone, PartialEq)]
 pub struct InteractiveMultiPartyCrs<S> {
     pub(super) seed: S,
+    /// Distinguishes sessions that share the common reference seed
+    pub(super) session_id: [u8; 32],
+    /// PRG that expands seeds derived from the common reference seed. Shares
+    /// and keys seeded with `Self` can only be expanded by a build that selects
+    /// the same PRG.
+    pub(super) prg: PrgAlgorithm,
+    /// Id of parameter set to which derived seeds are bound
+    pub(super) parameter_set: &'static str,
 }
 
 impl InteractiveMultiPartyCrs<[u8; 32]> {
-    pub(super) fn random() -> Self {
+    pub(super) fn new(seed: [u8; 32], session_id: [u8; 32], parameter_set: &'static str) -> Self {
+        Self {
+            seed,
+            session_id,
+            prg: DefaultSecureRng::ALGORITHM,
+            parameter_set,
+        }
+    }
+
+    pub(super) fn random(parameter_set: &'static str) -> Self {
         DefaultSecureRng::with_local_mut(|rng| {
             let mut seed = [0u8; 32];
             rng.fill_bytes(&mut seed);
-            Self { seed }
+            Self::new(seed, [0u8; 32], parameter_set)
         })
     }
 }
 
-impl<S: Default + Copy> InteractiveMultiPartyCrs<S> {
+impl<S: AsRef<[u8]> + From<[u8; 32]>> InteractiveMultiPartyCrs<S> {
+    const PROTOCOL: &'static str = "interactive-mp";
+
+    fn derive(&self, component: &str, index: usize) -> S {
+        derive_crs_seed(
+            Self::PROTOCOL,
+            self.parameter_set,
+            &self.seed,
+            &self.session_id,
+            component,
+            index,
+        )
+    }
+
     /// Seed to generate public key share
-    fn public_key_share_seed<Rng: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut prng = Rng::new_with_seed(self.seed);
-        puncture_p_rng(&mut prng, 1)
+    pub(super) fn public_key_share_seed(&self) -> S {
+        self.derive("public-key-share", 0)
+    }
+
+    pub(super) fn auto_keys_cts_seed(&self) -> S {
+        self.derive("auto-keys", 0)
     }
 
-    /// Main server key share seed
-    fn key_seed<Rng: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut prng = Rng::new_with_seed(self.seed);
-        puncture_p_rng(&mut prng, 2)
+    pub(super) fn lwe_ksk_cts_seed_seed(&self) -> S {
+        self.derive("lwe-ksk", 0)
     }
 
-    pub(super) fn auto_keys_cts_seed<Rng: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut key_prng = Rng::new_with_seed(self.key_seed::<Rng>());
-        puncture_p_rng(&mut key_prng, 1)
+    /// Seed to generate key refresh key shares
+    pub(super) fn key_refresh_seed(&self) -> S {
+        self.derive("key-refresh", 0)
     }
 
-    pub(super) fn lwe_ksk_cts_seed_seed<Rng: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut key_prng = Rng::new_with_seed(self.key_seed::<Rng>());
-        puncture_p_rng(&mut key_prng, 2)
+    /// Returns common reference seed of round 2 bound to digest of round 1
+    /// transcript
+    ///
+    /// Auto key and LWE ksk shares are seeded with the returned seed. Thus a
+    /// round 2 share only aggregates with shares generated with the same
+    /// transcript, and the transcript included in a share can not be replaced
+    /// without invalidating the share.
+    #[cfg_attr(not(feature = "interactive_mp"), allow(dead_code))]
+    pub(super) fn bound_to_transcript(&self, transcript_digest: &[u8; 32]) -> Self
+    where
+        S: Clone,
+    {
+        Self {
+            seed: self.seed.clone(),
+            session_id: derive_crs_seed(
+                Self::PROTOCOL,
+                self.parameter_set,
+                transcript_digest,
+                &self.session_id,
+                "round-2-session",
+                0,
+            ),
+            prg: self.prg,
+            parameter_set: self.parameter_set,
+        }
     }
 }
 
 /// Common reference seed used for non-interactive multi-party.
 ///
-/// Initial Seed
-///     Puncture 1 -> Key Seed
-///         Puncture 1 -> Rgsw ciphertext seed
-///             Puncture l+1 -> Seed for zero encs and non-interactive
-///                             multi-party RGSW ciphertexts of
-///                             l^th LWE index.
-///         Puncture 2 -> auto keys seed
-///         Puncture 3 -> Lwe key switching key seed
-///     Puncture 2 -> user specific seed for u_j to s ksk
-///         Punture j+1 -> user j's seed    
+/// Seeds are derived from common reference seed, session id and parameter set
+/// id with labelled KDF (see [derive_crs_seed]) under protocol label
+/// `non-interactive-mp`.
+///
+/// ## Components
+///
+///     rgsw-cts, l         -> Seed for zero encs and non-interactive
+///                            multi-party RGSW ciphertexts of l^th LWE index
+///     auto-keys, 0        -> auto keys seed
+///     lwe-ksk, 0          -> Lwe key switching key seed
+///     public-key-share, 0 -> Collective public key seed
+///     ui-to-s-ksk, j      -> user j's seed for u_j to s ksk
+///     key-refresh, 0      -> Key refresh key seed
 #[derive(Clone, PartialEq)]
 pub struct NonInteractiveMultiPartyCrs<S> {
     pub(super) seed: S,
+    /// Distinguishes sessions that share the common reference seed
+    pub(super) session_id: [u8; 32],
+    /// PRG that expands seeds derived from the common reference seed. Shares
+    /// and keys seeded with `Self` can only be expanded by a build that selects
+    /// the same PRG.
+    pub(super) prg: PrgAlgorithm,
+    /// Id of parameter set to which derived seeds are bound
+    pub(super) parameter_set: &'static str,
 }
 
 impl NonInteractiveMultiPartyCrs<[u8; 32]> {
-    pub(super) fn random() -> Self {
+    pub(super) fn new(seed: [u8; 32], session_id: [u8; 32], parameter_set: &'static str) -> Self {
+        Self {
+            seed,
+            session_id,
+            prg: DefaultSecureRng::ALGORITHM,
+            parameter_set,
+        }
+    }
+
+    pub(super) fn random(parameter_set: &'static str) -> Self {
         DefaultSecureRng::with_local_mut(|rng| {
             let mut seed = [0u8; 32];
             rng.fill_bytes(&mut seed);
-            Self { seed }
+            Self::new(seed, [0u8; 32], parameter_set)
         })
     }
 }
 
-impl<S: Default + Copy> NonInteractiveMultiPartyCrs<S> {
-    fn key_seed<R: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut p_rng = R::new_with_seed(self.seed);
-        puncture_p_rng(&mut p_rng, 1)
+impl<S: AsRef<[u8]> + From<[u8; 32]>> NonInteractiveMultiPartyCrs<S> {
+    const PROTOCOL: &'static str = "non-interactive-mp";
+
+    fn derive(&self, component: &str, index: usize) -> S {
+        derive_crs_seed(
+            Self::PROTOCOL,
+            self.parameter_set,
+            &self.seed,
+            &self.session_id,
+            component,
+            index,
+        )
     }
 
-    pub(crate) fn ni_rgsw_cts_main_seed<R: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut p_rng = R::new_with_seed(self.key_seed::<R>());
-        puncture_p_rng(&mut p_rng, 1)
+    pub(crate) fn ni_rgsw_ct_seed_for_index(&self, lwe_index: usize) -> S {
+        self.derive("rgsw-cts", lwe_index)
     }
 
-    pub(crate) fn ni_rgsw_ct_seed_for_index<R: NewWithSeed<Seed = S> + RandomFill<S>>(
-        &self,
-        lwe_index: usize,
-    ) -> S {
-        let mut p_rng = R::new_with_seed(self.ni_rgsw_cts_main_seed::<R>());
-        puncture_p_rng(&mut p_rng, lwe_index + 1)
+    pub(crate) fn auto_keys_cts_seed(&self) -> S {
+        self.derive("auto-keys", 0)
     }
 
-    pub(crate) fn auto_keys_cts_seed<R: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut p_rng = R::new_with_seed(self.key_seed::<R>());
-        puncture_p_rng(&mut p_rng, 2)
+    pub(crate) fn lwe_ksk_cts_seed(&self) -> S {
+        self.derive("lwe-ksk", 0)
     }
 
-    pub(crate) fn lwe_ksk_cts_seed<R: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut p_rng = R::new_with_seed(self.key_seed::<R>());
-        puncture_p_rng(&mut p_rng, 3)
+    pub(crate) fn public_key_share_seed(&self) -> S {
+        self.derive("public-key-share", 0)
     }
 
-    fn ui_to_s_ks_seed<R: NewWithSeed<Seed = S> + RandomFill<S>>(&self) -> S {
-        let mut p_rng = R::new_with_seed(self.seed);
-        puncture_p_rng(&mut p_rng, 2)
+    pub(crate) fn ui_to_s_ks_seed_for_user_i(&self, user_i: usize) -> S {
+        self.derive("ui-to-s-ksk", user_i)
     }
 
-    pub(crate) fn ui_to_s_ks_seed_for_user_i<R: NewWithSeed<Seed = S> + RandomFill<S>>(
-        &self,
-        user_i: usize,
-    ) -> S {
-        let ks_seed = self.ui_to_s_ks_seed::<R>();
-        let mut p_rng = R::new_with_seed(ks_seed);
-
-        puncture_p_rng(&mut p_rng, user_i + 1)
+    pub(crate) fn key_refresh_seed(&self) -> S {
+        self.derive("key-refresh", 0)
     }
 }
 
+/// Interactive multi-party server key share seeded with common reference seed
+#[cfg_attr(not(feature = "interactive_mp"), allow(dead_code))]
+type InteractiveServerKeyShare<M, S> = CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
+    M,
+    BoolParameters<<M as Matrix>::MatElement>,
+    InteractiveMultiPartyCrs<S>,
+>;
+
+/// Key refresh share seeded with common reference seed
+type KeyRefreshShare<M> =
+    CommonReferenceSeededKeyRefreshShare<M, [u8; 32], BoolParameters<<M as Matrix>::MatElement>>;
+
+/// Non-interactive multi-party server key share seeded with common reference
+/// seed
+#[cfg_attr(not(feature = "non_interactive_mp"), allow(dead_code))]
+type NonInteractiveServerKeyShare<M> = CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+    M,
+    BoolParameters<<M as Matrix>::MatElement>,
+    NonInteractiveMultiPartyCrs<[u8; 32]>,
+>;
+
 struct ScratchMemory<M>
 where
     M: Matrix,
 {
-    lwe_vector: M::R,
+    /// LWE ciphertexts mod q before and after LWE key switching
+    lwe_vectors: [Vec<LweKsElement>; 2],
     decomposition_matrix: M,
+    /// `lwe_vectors` of each ciphertext in a batch. Grown on demand by
+    /// `batch_gate`.
+    batch_lwe_vectors: [Vec<Vec<LweKsElement>>; 2],
+    /// `decomposition_matrix` of each thread a batch is split across. Grown on
+    /// demand by `batch_gate`.
+    thread_decomposition_matrices: Vec<M>,
 }
 
 impl<M: MatrixEntity> ScratchMemory<M>
@@ -184,8 +347,11 @@ where
     M::R: RowEntity,
 {
     fn new(parameters: &BoolParameters<M::MatElement>) -> Self {
-        // Vector to store LWE ciphertext with LWE dimesnion n
-        let lwe_vector = M::R::zeros(parameters.lwe_n().0 + 1);
+        // Vectors to store LWE ciphertexts with LWE dimension N and n
+        let lwe_vectors = [
+            vec![0; parameters.rlwe_n().0 + 1],
+            vec![0; parameters.lwe_n().0 + 1],
+        ];
 
         // PBS perform two operations at runtime: RLWE x RGW and RLWE auto. Since the
         // operations are performed serially same scratch space can be used for both.
@@ -200,8 +366,28 @@ where
         );
 
         Self {
-            lwe_vector,
+            lwe_vectors,
             decomposition_matrix,
+            batch_lwe_vectors: [vec![], vec![]],
+            thread_decomposition_matrices: vec![],
+        }
+    }
+
+    /// Grows scratch space for a batch of `batch_size` ciphertexts split
+    /// across `threads` threads
+    fn reserve_batch(&mut self, batch_size: usize, threads: usize) {
+        izip!(self.batch_lwe_vectors.iter_mut(), self.lwe_vectors.iter()).for_each(
+            |(batch_lwe_vectors, lwe_vector)| {
+                if batch_lwe_vectors.len() < batch_size {
+                    batch_lwe_vectors.resize(batch_size, vec![0; lwe_vector.len()]);
+                }
+            },
+        );
+
+        let (rows, cols) = self.decomposition_matrix.dimension();
+        while self.thread_decomposition_matrices.len() < threads {
+            self.thread_decomposition_matrices
+                .push(M::zeros(rows, cols));
         }
     }
 }
@@ -212,12 +398,15 @@ pub(super) trait BoolEncoding {
     fn false_el(&self) -> Self::Element;
     fn qby4(&self) -> Self::Element;
     fn decode(&self, m: Self::Element) -> bool;
+    /// Decodes `m` encoded as b * Q/2. Unbootstrapped XORs are in this
+    /// encoding.
+    fn decode_parity(&self, m: Self::Element) -> bool;
 }
 
 impl<T> BoolEncoding for CiphertextModulus<T>
 where
     CiphertextModulus<T>: Modulus<Element = T>,
-    T: PrimInt + NumInfo,
+    T: PrimInt + NumInfo + WrappingAdd,
 {
     type Element = T;
 
@@ -242,8 +431,9 @@ where
     }
     fn decode(&self, m: Self::Element) -> bool {
         let qby8 = self.true_el();
-        let m = (((m + qby8).to_f64().unwrap() * 4.0f64) / self.q_as_f64().unwrap()).round()
-            as usize
+        // wraps only when modulus is native
+        let m = (((m.wrapping_add(&qby8)).to_f64().unwrap() * 4.0f64) / self.q_as_f64().unwrap())
+            .round() as usize
             % 4usize;
 
         if m == 0 {
@@ -254,6 +444,10 @@ where
             panic!("Incorrect bool decryption. Got m={m} but expected m to be 0 or 1")
         }
     }
+    fn decode_parity(&self, m: Self::Element) -> bool {
+        let m = ((m.to_f64().unwrap() * 2.0f64) / self.q_as_f64().unwrap()).round() as usize % 2;
+        m == 1
+    }
 }
 
 impl<B> Encoder<bool, B::Element> for B
@@ -269,7 +463,7 @@ where
     }
 }
 
-pub(super) struct BoolPbsInfo<M: Matrix, Ntt, RlweModOp, LweModOp> {
+pub(crate) struct BoolPbsInfo<M: Matrix, Ntt, RlweModOp> {
     auto_decomposer: DefaultDecomposer<M::MatElement>,
     rlwe_rgsw_decomposer: (
         DefaultDecomposer<M::MatElement>,
@@ -278,15 +472,22 @@ pub(super) struct BoolPbsInfo<M: Matrix, Ntt, RlweModOp, LweModOp> {
     lwe_decomposer: DefaultDecomposer<M::MatElement>,
     g_k_dlog_map: Vec<usize>,
     rlwe_nttop: Ntt,
+    /// FFT operator for blind rotation with server key in Fourier domain and
+    /// for key generation with native RLWE modulus
+    #[cfg_attr(not(feature = "fft"), allow(dead_code))]
+    rlwe_fftop: FftBackendF64,
     rlwe_modop: RlweModOp,
-    lwe_modop: LweModOp,
+    /// LWE key switching decomposer and modulus operator with elements mod q
+    /// stored as `LweKsElement`
+    lwe_ks_decomposer: DefaultDecomposer<LweKsElement>,
+    lwe_ks_modop: ModulusPowerOf2<CiphertextModulus<LweKsElement>>,
     embedding_factor: usize,
     rlwe_qby4: M::MatElement,
     rlwe_auto_maps: Vec<(Vec<usize>, Vec<bool>)>,
     parameters: BoolParameters<M::MatElement>,
 }
 
-impl<M: Matrix, NttOp, RlweModOp, LweModOp> PbsInfo for BoolPbsInfo<M, NttOp, RlweModOp, LweModOp>
+impl<M: Matrix, NttOp, RlweModOp> PbsInfo for BoolPbsInfo<M, NttOp, RlweModOp>
 where
     M::MatElement: PrimInt
         + WrappingSub
@@ -297,14 +498,15 @@ where
         + WrappingAdd
         + Debug,
     RlweModOp: ArithmeticOps<Element = M::MatElement> + ShoupMatrixFMA<M::R>,
-    LweModOp: ArithmeticOps<Element = M::MatElement> + VectorOps<Element = M::MatElement>,
     NttOp: Ntt<Element = M::MatElement>,
 {
     type M = M;
     type Modulus = CiphertextModulus<M::MatElement>;
     type D = DefaultDecomposer<M::MatElement>;
+    type LweElement = LweKsElement;
+    type LweD = DefaultDecomposer<LweKsElement>;
     type RlweModOp = RlweModOp;
-    type LweModOp = LweModOp;
+    type LweModOp = ModulusPowerOf2<CiphertextModulus<LweKsElement>>;
     type NttOp = NttOp;
     fn rlwe_auto_map(&self, k: usize) -> &(Vec<usize>, Vec<bool>) {
         &self.rlwe_auto_maps[k]
@@ -312,8 +514,8 @@ where
     fn br_q(&self) -> usize {
         *self.parameters.br_q()
     }
-    fn lwe_decomposer(&self) -> &Self::D {
-        &self.lwe_decomposer
+    fn lwe_decomposer(&self) -> &Self::LweD {
+        &self.lwe_ks_decomposer
     }
     fn rlwe_rgsw_decomposer(&self) -> &(Self::D, Self::D) {
         &self.rlwe_rgsw_decomposer
@@ -346,7 +548,7 @@ where
         self.parameters.rlwe_q()
     }
     fn modop_lweq(&self) -> &Self::LweModOp {
-        &self.lwe_modop
+        &self.lwe_ks_modop
     }
     fn modop_rlweq(&self) -> &Self::RlweModOp {
         &self.rlwe_modop
@@ -354,39 +556,58 @@ where
     fn nttop_rlweq(&self) -> &Self::NttOp {
         &self.rlwe_nttop
     }
+    fn fftop_rlweq(&self) -> &FftBackendF64 {
+        &self.rlwe_fftop
+    }
 }
 
-pub(crate) struct BoolEvaluator<M, Ntt, RlweModOp, LweModOp, SKey>
+pub(crate) struct BoolEvaluator<M, Ntt, RlweModOp, SKey>
 where
     M: Matrix,
 {
-    pbs_info: BoolPbsInfo<M, Ntt, RlweModOp, LweModOp>,
+    pbs_info: BoolPbsInfo<M, Ntt, RlweModOp>,
     scratch_memory: ScratchMemory<M>,
+    // NAND and NOR gates are only evaluated by tests
+    #[cfg_attr(not(test), allow(dead_code))]
     nand_test_vec: M::R,
     and_test_vec: M::R,
     or_test_vec: M::R,
+    #[cfg_attr(not(all(test, feature = "interactive_mp")), allow(dead_code))]
     nor_test_vec: M::R,
     xor_test_vec: M::R,
     xnor_test_vec: M::R,
+    maj3_test_vec: M::R,
+    xor3_test_vec: M::R,
+    /// AND with outputs encoded as +/-Q/4. First bootstrap of `mux`.
+    mux_and_test_vec: M::R,
+    parity_test_vec: M::R,
     /// Non-interactive u_i -> s key switch decomposer
     ni_ui_to_s_ks_decomposer: Option<DefaultDecomposer<M::MatElement>>,
     _phantom: PhantomData<SKey>,
 }
 
-impl<M: Matrix, NttOp, RlweModOp, LweModOp, Skey>
-    BoolEvaluator<M, NttOp, RlweModOp, LweModOp, Skey>
-{
+impl<M: Matrix, NttOp, RlweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, Skey> {
     pub(crate) fn parameters(&self) -> &BoolParameters<M::MatElement> {
         &self.pbs_info.parameters
     }
 
-    pub(super) fn pbs_info(&self) -> &BoolPbsInfo<M, NttOp, RlweModOp, LweModOp> {
+    pub(super) fn pbs_info(&self) -> &BoolPbsInfo<M, NttOp, RlweModOp> {
         &self.pbs_info
     }
 
     pub(super) fn ni_ui_to_s_ks_decomposer(&self) -> &Option<DefaultDecomposer<M::MatElement>> {
         &self.ni_ui_to_s_ks_decomposer
     }
+
+    /// Returns auto element -g if `dlog` is 0, otherwise g^{dlog} % br_q
+    fn auto_element(&self, dlog: usize) -> isize {
+        let g = self.pbs_info.parameters.g();
+        if dlog == 0 {
+            -(g as isize)
+        } else {
+            (g.pow(dlog as u32) % self.pbs_info.parameters.br_q()) as isize
+        }
+    }
 }
 
 fn trim_rgsw_ct_matrix_from_rgrg_to_rlrg<
@@ -607,6 +828,48 @@ where
     rgsw_i
 }
 
+/// Returns trivial RGSW(X^{0}) ciphertext, in coefficient domain, with gadget
+/// vectors of `decomposer`.
+///
+/// RLWE'(-s) is set to (\beta^i, 0) and RLWE'(1) is set to (0, \beta^i).
+/// Since the ciphertext is noiseless it can be used as the starting point to
+/// accumulate RGSW(X^{s_j[l]}) of all users via RGSW x RGSW product.
+#[cfg(feature = "non_interactive_mp")]
+fn trivial_rgsw_ciphertext_of_one<
+    M: MatrixMut + MatrixEntity,
+    D: RlweDecomposer<Element = M::MatElement>,
+>(
+    decomposer: &D,
+    ring_size: usize,
+) -> M
+where
+    M::R: RowMut,
+{
+    let d_a = decomposer.a().decomposition_count().0;
+    let d_b = decomposer.b().decomposition_count().0;
+    let mut rgsw_ct = M::zeros(d_a * 2 + d_b * 2, ring_size);
+
+    // RLWE'(-s): part A
+    izip!(
+        rgsw_ct.iter_rows_mut().take(d_a),
+        decomposer.a().gadget_vector()
+    )
+    .for_each(|(ri, beta)| {
+        ri.as_mut()[0] = beta;
+    });
+
+    // RLWE'(1): part B
+    izip!(
+        rgsw_ct.iter_rows_mut().skip(d_a * 2 + d_b),
+        decomposer.b().gadget_vector()
+    )
+    .for_each(|(ri, beta)| {
+        ri.as_mut()[0] = beta;
+    });
+
+    rgsw_ct
+}
+
 /// Assigns user with user_id segement of LWE secret indices for which they
 /// generate RGSW(X^{s[i]}) as the leader (i.e. for RLWExRGSW). If returned
 /// tuple is (start, end), user's segment is [start, end)
@@ -625,7 +888,7 @@ pub(super) fn multi_party_user_id_lwe_segment(
     )
 }
 
-impl<M: Matrix, NttOp, RlweModOp, LweModOp, SKey> BoolEvaluator<M, NttOp, RlweModOp, LweModOp, SKey>
+impl<M: Matrix, NttOp, RlweModOp, SKey> BoolEvaluator<M, NttOp, RlweModOp, SKey>
 where
     M: MatrixEntity + MatrixMut,
     M::MatElement: PrimInt
@@ -636,25 +899,28 @@ where
         + WrappingSub
         + WrappingAdd
         + SampleUniform
-        + From<bool>,
+        + From<bool>
+        + PowerOf2Word,
     NttOp: Ntt<Element = M::MatElement>,
     RlweModOp: ArithmeticOps<Element = M::MatElement>
         + VectorOps<Element = M::MatElement>
         + GetModulus<Element = M::MatElement, M = CiphertextModulus<M::MatElement>>
         + ShoupMatrixFMA<M::R>,
-    LweModOp: ArithmeticOps<Element = M::MatElement>
-        + VectorOps<Element = M::MatElement>
-        + GetModulus<Element = M::MatElement, M = CiphertextModulus<M::MatElement>>,
     M::R: TryConvertFrom1<[i32], CiphertextModulus<M::MatElement>> + RowEntity + Debug,
     <M as Matrix>::R: RowMut,
 {
     pub(super) fn new(parameters: BoolParameters<M::MatElement>) -> Self
     where
         RlweModOp: ModInit<M = CiphertextModulus<M::MatElement>>,
-        LweModOp: ModInit<M = CiphertextModulus<M::MatElement>>,
         NttOp: NttInit<CiphertextModulus<M::MatElement>>,
     {
         //TODO(Jay): Run sanity checks for modulus values in parameters
+        // Multi-party key generation requires NTT
+        assert!(
+            !parameters.rlwe_q().is_native()
+                || parameters.variant() == &ParameterVariant::SingleParty,
+            "Native RLWE modulus is only supported for single party"
+        );
 
         // generates dlog map s.t. (+/-)g^{k} % q = a, for all a \in Z*_{q} and k \in
         // [0, q/4). We store the dlog `k` at index `a`. This makes it easier to
@@ -676,7 +942,6 @@ where
 
         let rlwe_nttop = NttOp::new(parameters.rlwe_q(), parameters.rlwe_n().0);
         let rlwe_modop = RlweModOp::new(*parameters.rlwe_q());
-        let lwe_modop = LweModOp::new(*parameters.lwe_q());
 
         let q = *parameters.br_q();
         let qby2 = q >> 1;
@@ -685,6 +950,9 @@ where
         let true_m_el = parameters.rlwe_q().true_el();
         // -Q/8
         let false_m_el = parameters.rlwe_q().false_el();
+        // +/-Q/4
+        let qby4_m_el = parameters.rlwe_q().qby4();
+        let neg_qby4_m_el = rlwe_modop.neg(&qby4_m_el);
         let (auto_map_index, auto_map_sign) = generate_auto_map(qby2, -(g as isize));
 
         let init_test_vec = |partition_el: usize,
@@ -724,6 +992,18 @@ where
         let nor_test_vec = init_test_vec(qby8, true_m_el, false_m_el);
         let xor_test_vec = init_test_vec(qby8, false_m_el, true_m_el);
         let xnor_test_vec = init_test_vec(qby8, true_m_el, false_m_el);
+        // Three input gates. Phase c0 + c1 + c2 is in {-3Q/8, -Q/8, Q/8, 3Q/8}
+        // and majority is true iff phase is in [0, Q/2). 2(c0 + c1 + c2) is -Q/4
+        // iff c0 ^ c1 ^ c2 is true and Q/4 otherwise.
+        let maj3_test_vec = init_test_vec(qby2, true_m_el, true_m_el);
+        let xor3_test_vec = init_test_vec(qby2, false_m_el, false_m_el);
+        // Mux bootstraps s & a to +/-Q/4, ie twice the bool encoding. Then
+        // phase of 2(s & a) - s + b + Q/8 is in {-3Q/8, -Q/8, Q/8, 3Q/8} and is
+        // bootstrapped with majority test vector.
+        let mux_and_test_vec = init_test_vec(3 * qby8, neg_qby4_m_el, qby4_m_el);
+        // Unbootstrapped XOR is b * Q/2. Phase in [-Q/4, Q/4) is false and phase
+        // in [Q/4, 3Q/4) is true.
+        let parity_test_vec = init_test_vec(2 * qby8, false_m_el, true_m_el);
 
         // auto map indices and sign
         // Auto maps are stored as [-g, g^{1}, g^{2}, ..., g^{w}]
@@ -758,12 +1038,14 @@ where
         let pbs_info = BoolPbsInfo {
             auto_decomposer: parameters.auto_decomposer(),
             lwe_decomposer: parameters.lwe_decomposer(),
+            lwe_ks_decomposer: parameters.lwe_ks_decomposer(),
+            lwe_ks_modop: ModulusPowerOf2::new(parameters.lwe_q_as()),
             rlwe_rgsw_decomposer: parameters.rlwe_rgsw_decomposer(),
             g_k_dlog_map,
             embedding_factor,
-            lwe_modop,
             rlwe_modop,
             rlwe_nttop,
+            rlwe_fftop: parameters.blind_rotation_fft_op(),
             rlwe_qby4,
             rlwe_auto_maps,
             parameters: parameters,
@@ -778,6 +1060,10 @@ where
             nor_test_vec,
             xnor_test_vec,
             xor_test_vec,
+            maj3_test_vec,
+            xor3_test_vec,
+            mux_and_test_vec,
+            parity_test_vec,
             ni_ui_to_s_ks_decomposer,
             _phantom: PhantomData,
         }
@@ -792,7 +1078,10 @@ where
     pub(super) fn single_party_server_key<K: SinglePartyClientKey<Element = i32>>(
         &self,
         client_key: &K,
-    ) -> SeededSinglePartyServerKey<M, BoolParameters<M::MatElement>, [u8; 32]> {
+    ) -> SeededSinglePartyServerKey<M, BoolParameters<M::MatElement>, [u8; 32]>
+    where
+        M: Matrix<MatElement = u64>,
+    {
         assert_eq!(self.parameters().variant(), &ParameterVariant::SingleParty);
 
         DefaultSecureRng::with_local_mut(|rng| {
@@ -804,6 +1093,9 @@ where
             let rlwe_n = self.pbs_info.parameters.rlwe_n().0;
             let sk_rlwe = client_key.sk_rlwe();
             let sk_lwe = client_key.sk_lwe();
+            // NTT does not exist for native modulus. Products with RLWE secret
+            // are computed with FFT instead
+            let is_native = self.pbs_info.parameters.rlwe_q().is_native();
 
             // generate auto keys
             let mut auto_keys = HashMap::new();
@@ -821,16 +1113,29 @@ where
                     self.pbs_info.auto_decomposer.decomposition_count().0,
                     rlwe_n,
                 );
-                seeded_auto_key_gen(
-                    &mut gk,
-                    &sk_rlwe,
-                    g_pow,
-                    &auto_gadget,
-                    &self.pbs_info.rlwe_modop,
-                    &self.pbs_info.rlwe_nttop,
-                    &mut main_prng,
-                    rng,
-                );
+                if is_native {
+                    seeded_auto_key_gen_fourier(
+                        &mut gk,
+                        &sk_rlwe,
+                        g_pow,
+                        &auto_gadget,
+                        &self.pbs_info.rlwe_modop,
+                        &self.pbs_info.rlwe_fftop,
+                        &mut main_prng,
+                        rng,
+                    );
+                } else {
+                    seeded_auto_key_gen(
+                        &mut gk,
+                        &sk_rlwe,
+                        g_pow,
+                        &auto_gadget,
+                        &self.pbs_info.rlwe_modop,
+                        &self.pbs_info.rlwe_nttop,
+                        &mut main_prng,
+                        rng,
+                    );
+                }
                 auto_keys.insert(i, gk);
             }
 
@@ -859,17 +1164,32 @@ where
                     }
 
                     let mut rgsw_si = M::zeros(rlrg_d_a * 2 + rlrg_d_b, ring_size);
-                    secret_key_encrypt_rgsw(
-                        &mut rgsw_si,
-                        m.as_ref(),
-                        &rlrg_gadget_a,
-                        &rlrg_gadget_b,
-                        &sk_rlwe,
-                        &self.pbs_info.rlwe_modop,
-                        &self.pbs_info.rlwe_nttop,
-                        &mut main_prng,
-                        rng,
-                    );
+                    if is_native {
+                        secret_key_encrypt_rgsw_fourier(
+                            &mut rgsw_si,
+                            m.as_ref(),
+                            &rlrg_gadget_a,
+                            &rlrg_gadget_b,
+                            &sk_rlwe,
+                            &self.pbs_info.rlwe_modop,
+                            &self.pbs_info.rlwe_fftop,
+                            &mut main_prng,
+                            rng,
+                        );
+                    } else {
+                        secret_key_encrypt_rgsw(
+                            &mut rgsw_si,
+                            m.as_ref(),
+                            &rlrg_gadget_a,
+                            &rlrg_gadget_b,
+                            &sk_rlwe,
+                            &self.pbs_info.rlwe_modop,
+                            &self.pbs_info.rlwe_nttop,
+                            &mut main_prng,
+                            rng,
+                        );
+                    }
+                    wipe(m.as_mut());
 
                     rgsw_si
                 })
@@ -877,11 +1197,12 @@ where
 
             // LWE KSK from RLWE secret s -> LWE secret z
             let d_lwe_gadget = self.pbs_info.lwe_decomposer.gadget_vector();
+            let lwe_modop = ModulusPowerOf2::new(*self.parameters().lwe_q());
             let lwe_ksk = seeded_lwe_ksk_keygen(
                 &sk_rlwe,
                 &sk_lwe,
                 &d_lwe_gadget,
-                &self.pbs_info.lwe_modop,
+                &lwe_modop,
                 &mut main_prng,
                 rng,
             );
@@ -904,6 +1225,7 @@ where
         total_users: usize,
         cr_seed: &InteractiveMultiPartyCrs<[u8; 32]>,
         collective_pk: &M,
+        transcript: &InteractiveMultiPartyTranscript,
         client_key: &K,
     ) -> CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
         M,
@@ -915,6 +1237,10 @@ where
             &ParameterVariant::InteractiveMultiParty
         );
         assert!(user_id < total_users);
+        assert!(
+            transcript.public_key_share_commitments().len() == total_users,
+            "Transcript must commit to public key shares of all {total_users} users"
+        );
 
         let sk_rlwe = client_key.sk_rlwe();
         let sk_lwe = client_key.sk_lwe();
@@ -931,9 +1257,12 @@ where
         assert!(sk_rlwe.len() == ring_size);
         assert!(sk_lwe.len() == self.pbs_info.parameters.lwe_n().0);
 
+        // seeds of round 2 are bound to the transcript
+        let round_two_seed = cr_seed.bound_to_transcript(transcript.digest());
+
         // auto keys
         let auto_keys = self._common_rountine_multi_party_auto_keys_share_gen(
-            cr_seed.auto_keys_cts_seed::<DefaultSecureRng>(),
+            round_two_seed.auto_keys_cts_seed(),
             &sk_rlwe,
         );
 
@@ -960,18 +1289,19 @@ where
                 );
                 for s_index in segment_start..segment_end {
                     let mut out_rgsw = M::zeros(rlrg_d_a.0 * 2 + rlrg_d_b.0 * 2, ring_size);
+                    // X^{s[i]}
+                    let mut m = encode_x_pow_si_with_emebedding_factor::<
+                        M::R,
+                        CiphertextModulus<M::MatElement>,
+                    >(
+                        sk_lwe[s_index],
+                        self.pbs_info().embedding_factor(),
+                        ring_size,
+                        self.pbs_info().rlwe_q(),
+                    );
                     public_key_encrypt_rgsw(
                         &mut out_rgsw,
-                        &encode_x_pow_si_with_emebedding_factor::<
-                            M::R,
-                            CiphertextModulus<M::MatElement>,
-                        >(
-                            sk_lwe[s_index],
-                            self.pbs_info().embedding_factor(),
-                            ring_size,
-                            self.pbs_info().rlwe_q(),
-                        )
-                        .as_ref(),
+                        m.as_ref(),
                         collective_pk,
                         &gadget_a,
                         &gadget_b,
@@ -979,6 +1309,7 @@ where
                         rlweq_nttop,
                         rng,
                     );
+                    wipe(m.as_mut());
                     self_leader_rgsw.push(out_rgsw);
                 }
             }
@@ -1002,18 +1333,19 @@ where
 
                 for s_index in (0..segment_start).chain(segment_end..self.parameters().lwe_n().0) {
                     let mut out_rgsw = M::zeros(rgrg_d_a.0 * 2 + rgrg_d_b.0 * 2, ring_size);
+                    // X^{s[i]}
+                    let mut m = encode_x_pow_si_with_emebedding_factor::<
+                        M::R,
+                        CiphertextModulus<M::MatElement>,
+                    >(
+                        sk_lwe[s_index],
+                        self.pbs_info().embedding_factor(),
+                        ring_size,
+                        self.pbs_info().rlwe_q(),
+                    );
                     public_key_encrypt_rgsw(
                         &mut out_rgsw,
-                        &encode_x_pow_si_with_emebedding_factor::<
-                            M::R,
-                            CiphertextModulus<M::MatElement>,
-                        >(
-                            sk_lwe[s_index],
-                            self.pbs_info().embedding_factor(),
-                            ring_size,
-                            self.pbs_info().rlwe_q(),
-                        )
-                        .as_ref(),
+                        m.as_ref(),
                         collective_pk,
                         &rgrg_gadget_a,
                         &rgrg_gadget_b,
@@ -1021,6 +1353,7 @@ where
                         rlweq_nttop,
                         rng,
                     );
+                    wipe(m.as_mut());
 
                     not_self_leader_rgsws.push(out_rgsw);
                 }
@@ -1031,7 +1364,7 @@ where
 
         // LWE Ksk
         let lwe_ksk = self._common_rountine_multi_party_lwe_ksk_share_gen(
-            cr_seed.lwe_ksk_cts_seed_seed::<DefaultSecureRng>(),
+            round_two_seed.lwe_ksk_cts_seed_seed(),
             &sk_rlwe,
             &sk_lwe,
         );
@@ -1044,23 +1377,101 @@ where
             cr_seed.clone(),
             self.pbs_info.parameters.clone(),
             user_id,
+            transcript.clone(),
         )
     }
 
+    /// Validates server key shares of interactive multi-party protocol before
+    /// aggregation. Returns the first malformed share, if any, otherwise
+    /// whether shares include different round 1 transcripts.
+    ///
+    /// Checks that each share has same parameters and common reference seed,
+    /// has a unique user_id in [0, total users), that the share is
+    /// structurally well formed, and that all shares include the same round 1
+    /// transcript bound to the common reference seed.
+    pub(super) fn validate_interactive_multi_party_server_key_shares(
+        &self,
+        cr_seed: &InteractiveMultiPartyCrs<[u8; 32]>,
+        shares: &[InteractiveServerKeyShare<M, [u8; 32]>],
+    ) -> Result<(), InvalidInteractiveServerKeyShares> {
+        let total_users = shares.len();
+        // public key shares, hence the transcript, are seeded with public key
+        // share seed derived from common reference seed
+        let pk_share_seed = cr_seed.public_key_share_seed();
+        shares
+            .iter()
+            .enumerate()
+            .try_for_each(|(share_index, share)| {
+                let user_id = share.user_id();
+                let malformed = |reason: String| MalformedServerKeyShare {
+                    share_index,
+                    user_id,
+                    reason,
+                };
+
+                if share.parameters() != self.parameters() {
+                    return Err(malformed("Parameters do not match".to_string()));
+                }
+                if share.cr_seed().prg != DefaultSecureRng::ALGORITHM {
+                    return Err(malformed(format!(
+                        "Share is seeded with PRG {:?} but expected {:?}",
+                        share.cr_seed().prg,
+                        DefaultSecureRng::ALGORITHM
+                    )));
+                }
+                if share.cr_seed() != cr_seed {
+                    return Err(malformed(
+                        "Common reference seed does not match".to_string(),
+                    ));
+                }
+                if user_id >= total_users {
+                    return Err(malformed(format!(
+                        "user_id must be < total users {total_users}"
+                    )));
+                }
+                if shares[..share_index].iter().any(|s| s.user_id() == user_id) {
+                    return Err(malformed("Duplicate user_id".to_string()));
+                }
+                let transcript = share.transcript();
+                if transcript.public_key_share_commitments().len() != total_users {
+                    return Err(malformed(format!(
+                        "Transcript must commit to public key shares of {total_users} users"
+                    )));
+                }
+                if transcript.digest()
+                    != &InteractiveMultiPartyTranscript::compute_digest(
+                        &pk_share_seed,
+                        transcript.public_key_share_commitments(),
+                    )
+                {
+                    return Err(malformed(
+                        "Transcript is not bound to common reference seed".to_string(),
+                    ));
+                }
+
+                share.validate_structure(total_users).map_err(malformed)
+            })?;
+
+        // All parties must have seen the same public key shares in round 1
+        if shares
+            .iter()
+            .any(|s| s.transcript().digest() != shares[0].transcript().digest())
+        {
+            return Err(InvalidInteractiveServerKeyShares::TranscriptMismatch);
+        }
+        Ok(())
+    }
+
     pub(super) fn aggregate_interactive_multi_party_server_key_shares<S>(
         &self,
-        shares: &[CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
-            M,
-            BoolParameters<M::MatElement>,
-            InteractiveMultiPartyCrs<S>,
-        >],
+        shares: &[InteractiveServerKeyShare<M, S>],
     ) -> SeededInteractiveMultiPartyServerKey<
         M,
         InteractiveMultiPartyCrs<S>,
         BoolParameters<M::MatElement>,
     >
     where
-        S: PartialEq + Clone,
+        S: PartialEq + Clone + AsRef<[u8]> + From<[u8; 32]>,
         M: Clone,
     {
         assert_eq!(
@@ -1073,6 +1484,7 @@ where
 
         let parameters = shares[0].parameters().clone();
         let cr_seed = shares[0].cr_seed();
+        let transcript = shares[0].transcript();
 
         let rlwe_n = parameters.rlwe_n().0;
 
@@ -1080,6 +1492,7 @@ where
         shares.iter().skip(1).for_each(|s| {
             assert!(s.parameters() == &parameters);
             assert!(s.cr_seed() == cr_seed);
+            assert!(s.transcript() == transcript);
         });
 
         let rlweq_modop = &self.pbs_info.rlwe_modop;
@@ -1204,29 +1617,91 @@ where
 
         // LWE ksks
         let mut lwe_ksk = M::R::zeros(rlwe_n * parameters.lwe_decomposition_count().0);
-        let lweq_modop = &self.pbs_info.lwe_modop;
+        let lweq_modop = &ModulusPowerOf2::new(*self.parameters().lwe_q());
         shares.iter().for_each(|si| {
             assert!(si.lwe_ksk().as_ref().len() == rlwe_n * parameters.lwe_decomposition_count().0);
             lweq_modop.elwise_add_mut(lwe_ksk.as_mut(), si.lwe_ksk().as_ref())
         });
 
+        // auto keys and LWE ksk are seeded with seed of round 2
         SeededInteractiveMultiPartyServerKey::new(
             rgsw_cts,
             auto_keys,
             lwe_ksk,
-            cr_seed.clone(),
+            cr_seed.bound_to_transcript(transcript.digest()),
             parameters,
         )
     }
 
+    /// Validates server key shares of non-interactive multi-party protocol
+    /// before aggregation. Returns the first malformed share, if any.
+    ///
+    /// Checks that each share has same parameters, common reference seed, and
+    /// total users, has a unique user_id in [0, total users), and that the
+    /// share is structurally well formed. Note that shares of users that
+    /// dropped out may be missing.
+    pub(super) fn validate_non_interactive_multi_party_server_key_shares(
+        &self,
+        cr_seed: &NonInteractiveMultiPartyCrs<[u8; 32]>,
+        key_shares: &[NonInteractiveServerKeyShare<M>],
+    ) -> Result<(), MalformedServerKeyShare> {
+        let total_users = match key_shares.first() {
+            Some(share) => share.total_users(),
+            None => return Ok(()),
+        };
+        key_shares
+            .iter()
+            .enumerate()
+            .try_for_each(|(share_index, share)| {
+                let user_id = share.user_index();
+                let malformed = |reason: String| MalformedServerKeyShare {
+                    share_index,
+                    user_id,
+                    reason,
+                };
+
+                if share.parameters() != self.parameters() {
+                    return Err(malformed("Parameters do not match".to_string()));
+                }
+                if share.cr_seed().prg != DefaultSecureRng::ALGORITHM {
+                    return Err(malformed(format!(
+                        "Share is seeded with PRG {:?} but expected {:?}",
+                        share.cr_seed().prg,
+                        DefaultSecureRng::ALGORITHM
+                    )));
+                }
+                if share.cr_seed() != cr_seed {
+                    return Err(malformed(
+                        "Common reference seed does not match".to_string(),
+                    ));
+                }
+                if share.total_users() != total_users {
+                    return Err(malformed(format!(
+                        "Total users {} does not match total users {total_users} of other shares",
+                        share.total_users()
+                    )));
+                }
+                if user_id >= total_users {
+                    return Err(malformed(format!(
+                        "user_id must be < total users {total_users}"
+                    )));
+                }
+                if key_shares[..share_index]
+                    .iter()
+                    .any(|s| s.user_index() == user_id)
+                {
+                    return Err(malformed("Duplicate user_id".to_string()));
+                }
+
+                share.validate_structure().map_err(malformed)
+            })
+    }
+
+    #[cfg(feature = "non_interactive_mp")]
     pub(super) fn aggregate_non_interactive_multi_party_server_key_shares(
         &self,
         cr_seed: &NonInteractiveMultiPartyCrs<[u8; 32]>,
-        key_shares: &[CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
-            M,
-            BoolParameters<M::MatElement>,
-            NonInteractiveMultiPartyCrs<[u8; 32]>,
-        >],
+        key_shares: &[NonInteractiveServerKeyShare<M>],
     ) -> SeededNonInteractiveMultiPartyServerKey<
         M,
         NonInteractiveMultiPartyCrs<[u8; 32]>,
@@ -1241,22 +1716,36 @@ where
             &ParameterVariant::NonInteractiveMultiParty
         );
 
-        let total_users = key_shares.len();
+        assert!(!key_shares.is_empty(), "Received 0 key shares");
+        let total_users = key_shares[0].total_users();
+        let received_shares = key_shares.len();
+
+        // Shares of users that did not drop out sorted by user_id
         let key_shares = (0..total_users)
-            .map(|user_id| {
+            .filter_map(|user_id| {
                 // find share of user_id
-                key_shares
+                let mut shares = key_shares
                     .iter()
-                    .find(|share| share.user_index() == user_id)
-                    .expect(&format!("Key Share for user_id={user_id} missing"))
+                    .filter(|share| share.user_index() == user_id);
+                let share = shares.next();
+                assert!(
+                    shares.next().is_none(),
+                    "Received more than 1 key share for user_id={user_id}"
+                );
+                share
             })
             .collect_vec();
+        assert!(
+            key_shares.len() == received_shares,
+            "Received key share with user_id >= total users {total_users}"
+        );
 
-        // check parameters and cr seed are equal
+        // check parameters, cr seed, and total users are equal
         {
             key_shares.iter().for_each(|k| {
                 assert!(k.parameters() == self.parameters());
                 assert!(k.cr_seed() == cr_seed);
+                assert!(k.total_users() == total_users);
             });
         }
 
@@ -1264,7 +1753,7 @@ where
         let nttop = &self.pbs_info().rlwe_nttop;
         let ring_size = self.parameters().rlwe_n().0;
         let rlwe_q = self.parameters().rlwe_q();
-        let lwe_modop = self.pbs_info().modop_lweq();
+        let lwe_modop = &ModulusPowerOf2::new(*self.parameters().lwe_q());
 
         // Generate Key switching key from u_j to s, where u_j user j's RLWE secret and
         // s is the ideal RLWE secret.
@@ -1319,7 +1808,7 @@ where
                 .iter()
                 .map(|share| {
                     let mut ksk_prng = DefaultSecureRng::new_seeded(
-                        cr_seed.ui_to_s_ks_seed_for_user_i::<DefaultSecureRng>(share.user_index()),
+                        cr_seed.ui_to_s_ks_seed_for_user_i(share.user_index()),
                     );
                     let mut ais =
                         M::zeros(ni_uj_to_s_decomposer.decomposition_count().0, ring_size);
@@ -1369,6 +1858,8 @@ where
             //   u_j)
             //
             // a_{i, l} * s + e = \sum_{j \in P} a_{i, l} * s_{j} + e
+            let leader_share_index =
+                |user_id: usize| key_shares.iter().position(|s| s.user_index() == user_id);
             let user_segments = (0..total_users)
                 .map(|user_id| {
                     multi_party_user_id_lwe_segment(
@@ -1393,7 +1884,7 @@ where
                             // a_i's. After sampling we decompose `-a_i`s and send them to
                             // evaluation domain for upcoming key switches.
                             let mut a_prng = DefaultSecureRng::new_seeded(
-                                cr_seed.ni_rgsw_ct_seed_for_index::<DefaultSecureRng>(lwe_index),
+                                cr_seed.ni_rgsw_ct_seed_for_index(lwe_index),
                             );
 
                             let mut scratch = M::R::zeros(self.parameters().rlwe_n().0);
@@ -1489,46 +1980,62 @@ where
                             // for RLWE x RGSW multiplication, and is then used
                             // to accumulate, using RGSW x RGSW multiplication,
                             // X^{s_{j != user_id, lwe}[l]} from other users.
-                            let mut rgsw_i = produce_rgsw_ciphertext_from_ni_rgsw(
-                                key_shares[user_id]
-                                    .ni_rgsw_cts_for_self_leader_lwe_index(lwe_index),
-                                &ni_rgsw_zero_encs[rgsw_x_rgsw_decomposer
-                                    .a()
-                                    .decomposition_count()
-                                    .0
-                                    - rlwe_x_rgsw_decomposer.a().decomposition_count().0..],
-                                &decomp_neg_ais[rgsw_x_rgsw_decomposer
-                                    .b()
-                                    .decomposition_count()
-                                    .0
-                                    - rlwe_x_rgsw_decomposer.b().decomposition_count().0..],
-                                &rlwe_x_rgsw_decomposer,
-                                self.parameters(),
-                                (&uj_to_s_ksks[user_id], &uj_to_s_ksks_part_a_eval[user_id]),
-                                rlwe_modop,
-                                nttop,
-                                false,
-                            );
+                            //
+                            // If user with user_id has dropped out, there's no
+                            // leader for the lwe_index. Instead we start with
+                            // the trivial (noiseless) RGSW(X^{0}) ciphertext for
+                            // RLWE x RGSW product and accumulate X^{s_{j}[l]} of
+                            // all remaining users using RGSW x RGSW
+                            // multiplication.
+                            let (leader, mut rgsw_i) = match leader_share_index(user_id) {
+                                Some(leader) => {
+                                    let rgsw_i = produce_rgsw_ciphertext_from_ni_rgsw(
+                                        key_shares[leader]
+                                            .ni_rgsw_cts_for_self_leader_lwe_index(lwe_index),
+                                        &ni_rgsw_zero_encs[rgsw_x_rgsw_decomposer
+                                            .a()
+                                            .decomposition_count()
+                                            .0
+                                            - rlwe_x_rgsw_decomposer.a().decomposition_count().0..],
+                                        &decomp_neg_ais[rgsw_x_rgsw_decomposer
+                                            .b()
+                                            .decomposition_count()
+                                            .0
+                                            - rlwe_x_rgsw_decomposer.b().decomposition_count().0..],
+                                        &rlwe_x_rgsw_decomposer,
+                                        self.parameters(),
+                                        (&uj_to_s_ksks[leader], &uj_to_s_ksks_part_a_eval[leader]),
+                                        rlwe_modop,
+                                        nttop,
+                                        false,
+                                    );
+                                    (Some(leader), rgsw_i)
+                                }
+                                None => (
+                                    None,
+                                    trivial_rgsw_ciphertext_of_one::<M, _>(
+                                        &rlwe_x_rgsw_decomposer,
+                                        self.parameters().rlwe_n().0,
+                                    ),
+                                ),
+                            };
 
                             // RGSW for lwe_index of users that are not leader.
                             //
                             // Recall that for users that are not leader for the
                             // lwe_index we require to produce RGSW ciphertext for
                             // RGSW x RGSW product
-                            (0..total_users)
-                                .filter(|i| *i != user_id)
-                                .for_each(|other_user_id| {
+                            (0..key_shares.len())
+                                .filter(|i| Some(*i) != leader)
+                                .for_each(|other| {
                                     let mut other_rgsw_i = produce_rgsw_ciphertext_from_ni_rgsw(
-                                        key_shares[other_user_id]
+                                        key_shares[other]
                                             .ni_rgsw_cts_for_self_not_leader_lwe_index(lwe_index),
                                         &ni_rgsw_zero_encs,
                                         &decomp_neg_ais,
                                         &rgsw_x_rgsw_decomposer,
                                         self.parameters(),
-                                        (
-                                            &uj_to_s_ksks[other_user_id],
-                                            &uj_to_s_ksks_part_a_eval[other_user_id],
-                                        ),
+                                        (&uj_to_s_ksks[other], &uj_to_s_ksks_part_a_eval[other]),
                                         rlwe_modop,
                                         nttop,
                                         true,
@@ -1611,11 +2118,34 @@ where
             lwe_ksk
         };
 
+        // Part B of collective public key. Part A is sampled from the public key
+        // seed of the CRS
+        let public_key = {
+            let mut public_key = M::R::zeros(ring_size);
+            key_shares.iter().for_each(|s| {
+                assert!(s.public_key_share().as_ref().len() == ring_size);
+                rlwe_modop.elwise_add_mut(public_key.as_mut(), s.public_key_share().as_ref());
+            });
+            public_key
+        };
+
+        // Key switching keys of users that dropped out are set to None
+        let mut uj_to_s_ksks = uj_to_s_ksks.into_iter();
+        let uj_to_s_ksks = (0..total_users)
+            .map(|user_id| {
+                key_shares
+                    .iter()
+                    .find(|s| s.user_index() == user_id)
+                    .map(|_| uj_to_s_ksks.next().unwrap())
+            })
+            .collect_vec();
+
         SeededNonInteractiveMultiPartyServerKey::new(
             uj_to_s_ksks,
             rgsw_cts,
             auto_keys,
             lwe_ksk,
+            public_key,
             cr_seed.clone(),
             self.parameters().clone(),
         )
@@ -1657,9 +2187,8 @@ where
                 );
             let non_interactive_gadget_vec = non_interactive_decomposer.gadget_vector();
             let ui_to_s_ksk = {
-                let mut p_rng = DefaultSecureRng::new_seeded(
-                    cr_seed.ui_to_s_ks_seed_for_user_i::<DefaultSecureRng>(self_index),
-                );
+                let mut p_rng =
+                    DefaultSecureRng::new_seeded(cr_seed.ui_to_s_ks_seed_for_user_i(self_index));
 
                 non_interactive_ksk_gen::<M, _, _, _, _, _>(
                     &sk_rlwe,
@@ -1677,7 +2206,7 @@ where
             let zero_encs_for_others = all_users_except_self
                 .map(|other_user_index| {
                     let mut p_rng = DefaultSecureRng::new_seeded(
-                        cr_seed.ui_to_s_ks_seed_for_user_i::<DefaultSecureRng>(other_user_index),
+                        cr_seed.ui_to_s_ks_seed_for_user_i(other_user_index),
                     );
                     let zero_encs =
                         non_interactive_ksk_zero_encryptions_for_other_party_i::<M, _, _, _, _, _>(
@@ -1714,7 +2243,7 @@ where
                     < rgsw_x_rgsw_decomposer.b().decomposition_count().0
             );
 
-            let sj_poly_eval = {
+            let mut sj_poly_eval = {
                 let mut s = M::R::try_convert_from(&sk_rlwe, rlwe_q);
                 nttop.forward(s.as_mut());
                 s
@@ -1730,7 +2259,7 @@ where
                 (0..self.parameters().lwe_n().0)
                     .map(|lwe_index| {
                         let mut p_rng = DefaultSecureRng::new_seeded(
-                            cr_seed.ni_rgsw_ct_seed_for_index::<DefaultSecureRng>(lwe_index),
+                            cr_seed.ni_rgsw_ct_seed_for_index(lwe_index),
                         );
 
                         let mut scratch = M::R::zeros(self.parameters().rlwe_n().0);
@@ -1769,13 +2298,14 @@ where
 
                             rlwe_modop.elwise_add_mut(out.as_mut(), scratch.as_ref());
                         });
+                        wipe(scratch.as_mut());
 
                         zero_enc
                     })
                     .collect_vec()
             };
 
-            let uj_poly_eval = {
+            let mut uj_poly_eval = {
                 let mut u = M::R::try_convert_from(&sk_u_rlwe, rlwe_q);
                 nttop.forward(u.as_mut());
                 u
@@ -1810,7 +2340,7 @@ where
                 (self_start_index..self_end_index)
                     .map(|lwe_index| {
                         let mut p_rng = DefaultSecureRng::new_seeded(
-                            cr_seed.ni_rgsw_ct_seed_for_index::<DefaultSecureRng>(lwe_index),
+                            cr_seed.ni_rgsw_ct_seed_for_index(lwe_index),
                         );
 
                         // puncture p_rng d_max - d'_max time to align with `a_{i, l}`s used to
@@ -1832,7 +2362,7 @@ where
                         );
 
                         // X^{s_{j, lwe}[l]}
-                        let m_poly = encode_x_pow_si_with_emebedding_factor::<M::R, _>(
+                        let mut m_poly = encode_x_pow_si_with_emebedding_factor::<M::R, _>(
                             sk_lwe[lwe_index],
                             self.pbs_info().embedding_factor(),
                             self.parameters().rlwe_n().0,
@@ -1872,6 +2402,8 @@ where
                                 rlwe_modop.elwise_add_mut(out.as_mut(), scratch.as_ref());
                             },
                         );
+                        wipe(m_poly.as_mut());
+                        wipe(scratch.as_mut());
 
                         ni_rgsw_cts
                     })
@@ -1892,7 +2424,7 @@ where
                 ((0..self_start_index).chain(self_end_index..self.parameters().lwe_n().0))
                     .map(|lwe_index| {
                         let mut p_rng = DefaultSecureRng::new_seeded(
-                            cr_seed.ni_rgsw_ct_seed_for_index::<DefaultSecureRng>(lwe_index),
+                            cr_seed.ni_rgsw_ct_seed_for_index(lwe_index),
                         );
                         let mut ni_rgsw_cts = M::zeros(
                             max_rgsw_x_rgsw_decomposer.decomposition_count().0,
@@ -1901,7 +2433,7 @@ where
                         let mut scratch = M::R::zeros(self.parameters().rlwe_n().0);
 
                         // X^{s_{j, lwe}[l]}
-                        let m_poly = encode_x_pow_si_with_emebedding_factor::<M::R, _>(
+                        let mut m_poly = encode_x_pow_si_with_emebedding_factor::<M::R, _>(
                             sk_lwe[lwe_index],
                             self.pbs_info().embedding_factor(),
                             self.parameters().rlwe_n().0,
@@ -1941,35 +2473,51 @@ where
                                 rlwe_modop.elwise_add_mut(out.as_mut(), scratch.as_ref());
                             },
                         );
+                        wipe(m_poly.as_mut());
+                        wipe(scratch.as_mut());
 
                         ni_rgsw_cts
                     })
                     .collect_vec()
             };
 
+            wipe(sj_poly_eval.as_mut());
+            wipe(uj_poly_eval.as_mut());
+
             (zero_encs, self_leader_ni_rgsw_cts, not_self_leader_rgsw_cts)
         };
 
         // Auto key share
         let auto_keys_share = {
-            let auto_seed = cr_seed.auto_keys_cts_seed::<DefaultSecureRng>();
+            let auto_seed = cr_seed.auto_keys_cts_seed();
             self._common_rountine_multi_party_auto_keys_share_gen(auto_seed, &sk_rlwe)
         };
 
         // Lwe Ksk share
         let lwe_ksk_share = {
-            let lwe_ksk_seed = cr_seed.lwe_ksk_cts_seed::<DefaultSecureRng>();
+            let lwe_ksk_seed = cr_seed.lwe_ksk_cts_seed();
             self._common_rountine_multi_party_lwe_ksk_share_gen(lwe_ksk_seed, &sk_rlwe, &sk_lwe)
         };
 
+        // Collective public key share
+        let public_key_share = DefaultSecureRng::with_local_mut(|rng| {
+            let mut share_out = M::R::zeros(self.parameters().rlwe_n().0);
+            let mut p_rng = DefaultSecureRng::new_seeded(cr_seed.public_key_share_seed());
+            public_key_share(&mut share_out, &sk_rlwe, rlwe_modop, nttop, &mut p_rng, rng);
+            share_out
+        });
+
         CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare::new(
-            self_leader_ni_rgsw_cts,
-            not_self_leader_rgsw_cts,
-            ni_rgsw_zero_encs,
-            ui_to_s_ksk,
-            ksk_zero_encs_for_others,
-            auto_keys_share,
-            lwe_ksk_share,
+            NonInteractiveServerKeyShareComponents {
+                self_leader_ni_rgsw_cts,
+                not_self_leader_ni_rgsw_cts: not_self_leader_rgsw_cts,
+                ni_rgsw_zero_encs,
+                ui_to_s_ksk,
+                ksk_zero_encs_for_others,
+                auto_keys_share,
+                lwe_ksk_share,
+                public_key_share,
+            },
             self_index,
             total_users,
             self.parameters().lwe_n().0,
@@ -1978,61 +2526,250 @@ where
         )
     }
 
-    fn _common_rountine_multi_party_auto_keys_share_gen(
+    /// Generates key share for user joining the non-interactive multi-party
+    /// protocol after the server key has been generated.
+    ///
+    /// Since user does not contribute to ideal RLWE secret `s`, key switching
+    /// key from user's RLWE secret `u_j` to `s` is generated by encrypting
+    /// \beta^k u_j with the collective `public_key`.
+    pub(super) fn gen_non_interactive_late_joining_key_share<
+        K: NonInteractiveMultiPartyClientKey<Element = i32>,
+    >(
         &self,
-        auto_seed: <DefaultSecureRng as NewWithSeed>::Seed,
-        sk_rlwe: &[i32],
-    ) -> HashMap<usize, M> {
-        let g = self.pbs_info.parameters.g();
-        let ring_size = self.pbs_info.parameters.rlwe_n().0;
-        let br_q = self.pbs_info.parameters.br_q();
-        let rlweq_modop = &self.pbs_info.rlwe_modop;
-        let rlweq_nttop = &self.pbs_info.rlwe_nttop;
+        public_key: &M,
+        client_key: &K,
+    ) -> NonInteractiveLateJoiningKeyShare<M, BoolParameters<M::MatElement>> {
+        assert_eq!(
+            self.parameters().variant(),
+            &ParameterVariant::NonInteractiveMultiParty
+        );
 
-        DefaultSecureRng::with_local_mut(|rng| {
-            let mut p_rng = DefaultSecureRng::new_seeded(auto_seed);
+        let nttop = self.pbs_info().nttop_rlweq();
+        let rlwe_modop = self.pbs_info().modop_rlweq();
+        let ring_size = self.parameters().rlwe_n().0;
+        assert!(public_key.dimension() == (2, ring_size));
 
-            let mut auto_keys = HashMap::new();
-            let auto_gadget = self.pbs_info.auto_decomposer.gadget_vector();
-            let auto_element_dlogs = self.pbs_info.parameters.auto_element_dlogs();
+        let non_interactive_decomposer = self
+            .parameters()
+            .non_interactive_ui_to_s_key_switch_decomposer::<DefaultDecomposer<M::MatElement>>();
+        let non_interactive_gadget_vec = non_interactive_decomposer.gadget_vector();
+        let d = non_interactive_gadget_vec.len();
+
+        let mut sk_u_rlwe =
+            M::R::try_convert_from(&client_key.sk_u_rlwe(), self.parameters().rlwe_q());
+
+        // (a_k, a_k * s + e + \beta^k u_j)
+        let mut ui_to_s_ksk = M::zeros(d * 2, ring_size);
+        DefaultSecureRng::with_local_mut_mut(&mut |rng| {
+            let mut beta_u = M::R::zeros(ring_size);
+            let mut rlwe = M::zeros(2, ring_size);
+            non_interactive_gadget_vec
+                .iter()
+                .enumerate()
+                .for_each(|(k, beta)| {
+                    rlwe_modop.elwise_scalar_mul(beta_u.as_mut(), sk_u_rlwe.as_ref(), beta);
+                    public_key_encrypt_rlwe::<_, _, _, _, i32, _>(
+                        &mut rlwe,
+                        public_key,
+                        beta_u.as_ref(),
+                        rlwe_modop,
+                        nttop,
+                        rng,
+                    );
+                    ui_to_s_ksk
+                        .get_row_mut(k)
+                        .copy_from_slice(rlwe.get_row_slice(0));
+                    ui_to_s_ksk
+                        .get_row_mut(d + k)
+                        .copy_from_slice(rlwe.get_row_slice(1));
+                });
+            wipe(beta_u.as_mut());
+        });
+        wipe(sk_u_rlwe.as_mut());
+
+        NonInteractiveLateJoiningKeyShare::new(ui_to_s_ksk, self.parameters().clone())
+    }
+
+    /// Generates party's share of key refresh key
+    ///
+    /// Key refresh key key switches ciphertexts from ideal RLWE secret `s` of
+    /// the current session to ideal RLWE secret `s'` of the refreshed session.
+    /// Party's share is key switching key from its RLWE secret `s_i` to its
+    /// refreshed RLWE secret `s'_i`, with a_k's sampled from `cr_seed`:
+    ///     (a_k, a_k*s'_i + e + \beta^k s_i)
+    ///
+    /// - cr_seed: Common reference seed for a_k's. Must be same for all parties
+    /// - sk_rlwe: Party's RLWE secret `s_i` of the current session
+    /// - refreshed_sk_rlwe: Party's RLWE secret `s'_i` of the refreshed session
+    pub(super) fn gen_key_refresh_share(
+        &self,
+        cr_seed: [u8; 32],
+        sk_rlwe: &[i32],
+        refreshed_sk_rlwe: &[i32],
+    ) -> CommonReferenceSeededKeyRefreshShare<M, [u8; 32], BoolParameters<M::MatElement>> {
+        let ring_size = self.parameters().rlwe_n().0;
+        assert!(sk_rlwe.len() == ring_size);
+        assert!(refreshed_sk_rlwe.len() == ring_size);
 
-            for i in auto_element_dlogs.into_iter() {
-                let g_pow = if i == 0 {
-                    -(g as isize)
-                } else {
-                    (g.pow(i as u32) % br_q) as isize
-                };
+        let gadget_vec = self
+            .parameters()
+            .auto_decomposer::<DefaultDecomposer<M::MatElement>>()
+            .gadget_vector();
 
-                let mut ksk_out = M::zeros(
-                    self.pbs_info.auto_decomposer.decomposition_count().0,
-                    ring_size,
-                );
-                seeded_auto_key_gen(
-                    &mut ksk_out,
-                    sk_rlwe,
-                    g_pow,
-                    &auto_gadget,
-                    rlweq_modop,
-                    rlweq_nttop,
-                    &mut p_rng,
-                    rng,
-                );
-                auto_keys.insert(i, ksk_out);
-            }
+        let ksk_part_b = DefaultSecureRng::with_local_mut(|rng| {
+            let mut p_rng = DefaultSecureRng::new_seeded(cr_seed);
+            non_interactive_ksk_gen::<M, _, _, _, _, _>(
+                refreshed_sk_rlwe,
+                sk_rlwe,
+                &gadget_vec,
+                &mut p_rng,
+                rng,
+                self.pbs_info().nttop_rlweq(),
+                self.pbs_info().modop_rlweq(),
+            )
+        });
 
-            auto_keys
-        })
+        CommonReferenceSeededKeyRefreshShare::new(ksk_part_b, cr_seed, self.parameters().clone())
     }
 
-    fn _common_rountine_multi_party_lwe_ksk_share_gen(
+    /// Aggregates key refresh shares of all parties into key refresh key from
+    /// ideal RLWE secret `s` to `s'`
+    ///
+    /// Panics if any share was generated with different parameters or common
+    /// reference seed than `cr_seed`.
+    pub(super) fn aggregate_key_refresh_shares(
         &self,
-        lwe_ksk_seed: <DefaultSecureRng as NewWithSeed>::Seed,
+        cr_seed: [u8; 32],
+        shares: &[KeyRefreshShare<M>],
+    ) -> KeyRefreshKeyEvaluationDomain<M>
+    where
+        M: ToShoup<Modulus = M::MatElement>,
+    {
+        assert!(!shares.is_empty(), "Key refresh shares are empty");
+        shares.iter().for_each(|s| {
+            assert!(
+                s.parameters() == self.parameters(),
+                "Key refresh share has mismatching parameters"
+            );
+            assert!(
+                s.cr_seed() == &cr_seed,
+                "Key refresh share has mismatching common reference seed"
+            );
+        });
+
+        let ring_size = self.parameters().rlwe_n().0;
+        let d = self.parameters().auto_decomposition_count().0;
+        let rlwe_q = self.parameters().rlwe_q();
+        let rlwe_modop = self.pbs_info().modop_rlweq();
+        let nttop = self.pbs_info().nttop_rlweq();
+
+        // Ksk(s -> s') = [a_k, \sum_i a_k*s'_i + e + \beta^k s_i]
+        let mut ksk = M::zeros(d * 2, ring_size);
+        let mut p_rng = DefaultSecureRng::new_seeded(cr_seed);
+        ksk.iter_rows_mut()
+            .take(d)
+            .for_each(|r| RandomFillUniformInModulus::random_fill(&mut p_rng, rlwe_q, r.as_mut()));
+        shares.iter().for_each(|s| {
+            assert!(s.ksk_part_b().dimension() == (d, ring_size));
+            izip!(ksk.iter_rows_mut().skip(d), s.ksk_part_b().iter_rows()).for_each(
+                |(add_to, add_from)| rlwe_modop.elwise_add_mut(add_to.as_mut(), add_from.as_ref()),
+            );
+        });
+
+        // send to evaluation domain
+        ksk.iter_rows_mut().for_each(|r| nttop.forward(r.as_mut()));
+
+        KeyRefreshKeyEvaluationDomain::new(ksk, rlwe_q.q().unwrap())
+    }
+
+    /// Key switches LWE ciphertext `lwe_ct` under ideal RLWE secret `s` to
+    /// ideal RLWE secret `s'` of the refreshed session
+    ///
+    /// LWE ciphertext is mapped to RLWE ciphertext whose constant coefficient
+    /// decrypts to the message (i.e. inverse of sample extraction), key
+    /// switched with the key refresh key, and the constant coefficient is
+    /// sample extracted back.
+    pub(super) fn key_refresh(
+        &self,
+        lwe_ct: &M::R,
+        key_refresh_key: &KeyRefreshKeyEvaluationDomain<M>,
+    ) -> M::R {
+        let ring_size = self.parameters().rlwe_n().0;
+        assert!(lwe_ct.as_ref().len() == ring_size + 1);
+        let rlwe_modop = self.pbs_info().modop_rlweq();
+
+        // RLWE(m) = (A(X), B(X)) with A(X) = a_0 - \sum_{j>0} a_{N-j} X^j and B(X) = b
+        let mut rlwe = M::zeros(2, ring_size);
+        let a = &lwe_ct.as_ref()[1..];
+        rlwe.get_row_mut(0)[0] = a[0];
+        for j in 1..ring_size {
+            rlwe.get_row_mut(0)[j] = rlwe_modop.neg(&a[ring_size - j]);
+        }
+        rlwe.get_row_mut(1)[0] = lwe_ct.as_ref()[0];
+
+        let rlwe_out = rlwe_key_switch(
+            &rlwe,
+            key_refresh_key.ksk().as_ref(),
+            key_refresh_key.ksk().shoup_repr(),
+            self.pbs_info().auto_decomposer(),
+            self.pbs_info().nttop_rlweq(),
+            rlwe_modop,
+        );
+
+        let mut lwe_out = M::R::zeros(ring_size + 1);
+        sample_extract(&mut lwe_out, &rlwe_out, rlwe_modop, 0);
+        lwe_out
+    }
+
+    fn _common_rountine_multi_party_auto_keys_share_gen(
+        &self,
+        auto_seed: <DefaultSecureRng as NewWithSeed>::Seed,
+        sk_rlwe: &[i32],
+    ) -> HashMap<usize, M> {
+        let ring_size = self.pbs_info.parameters.rlwe_n().0;
+        let rlweq_modop = &self.pbs_info.rlwe_modop;
+        let rlweq_nttop = &self.pbs_info.rlwe_nttop;
+
+        DefaultSecureRng::with_local_mut(|rng| {
+            let mut p_rng = DefaultSecureRng::new_seeded(auto_seed);
+
+            let mut auto_keys = HashMap::new();
+            let auto_gadget = self.pbs_info.auto_decomposer.gadget_vector();
+            let auto_element_dlogs = self.pbs_info.parameters.auto_element_dlogs();
+
+            for i in auto_element_dlogs.into_iter() {
+                let g_pow = self.auto_element(i);
+
+                let mut ksk_out = M::zeros(
+                    self.pbs_info.auto_decomposer.decomposition_count().0,
+                    ring_size,
+                );
+                seeded_auto_key_gen(
+                    &mut ksk_out,
+                    sk_rlwe,
+                    g_pow,
+                    &auto_gadget,
+                    rlweq_modop,
+                    rlweq_nttop,
+                    &mut p_rng,
+                    rng,
+                );
+                auto_keys.insert(i, ksk_out);
+            }
+
+            auto_keys
+        })
+    }
+
+    fn _common_rountine_multi_party_lwe_ksk_share_gen(
+        &self,
+        lwe_ksk_seed: <DefaultSecureRng as NewWithSeed>::Seed,
         sk_rlwe: &[i32],
         sk_lwe: &[i32],
     ) -> M::R {
         DefaultSecureRng::with_local_mut(|rng| {
             let mut p_rng = DefaultSecureRng::new_seeded(lwe_ksk_seed);
-            let lwe_modop = &self.pbs_info.lwe_modop;
+            let lwe_modop = &ModulusPowerOf2::new(*self.parameters().lwe_q());
             let d_lwe_gadget_vec = self.pbs_info.lwe_decomposer.gadget_vector();
             seeded_lwe_ksk_keygen(
                 sk_rlwe,
@@ -2058,7 +2795,7 @@ where
             let mut share_out = M::R::zeros(self.pbs_info.parameters.rlwe_n().0);
             let modop = &self.pbs_info.rlwe_modop;
             let nttop = &self.pbs_info.rlwe_nttop;
-            let pk_seed = cr_seed.public_key_share_seed::<DefaultSecureRng>();
+            let pk_seed = cr_seed.public_key_share_seed();
             let mut main_prng = DefaultSecureRng::new_seeded(pk_seed);
             public_key_share(
                 &mut share_out,
@@ -2103,15 +2840,503 @@ where
         let m = decrypt_lwe(lwe_ct, &client_key.sk_rlwe(), &self.pbs_info.rlwe_modop);
         self.pbs_info.rlwe_q().decode(m)
     }
+
+    /// Decrypts `lwe_ct` in parity encoding (i.e. b * Q/2)
+    pub(crate) fn sk_decrypt_parity<K: SinglePartyClientKey<Element = i32>>(
+        &self,
+        lwe_ct: &M::R,
+        client_key: &K,
+    ) -> bool {
+        let m = decrypt_lwe(lwe_ct, &client_key.sk_rlwe(), &self.pbs_info.rlwe_modop);
+        self.pbs_info.rlwe_q().decode_parity(m)
+    }
+}
+
+/// Component of non-interactive multi-party server key share of user j that is
+/// proven with a single [ShortWitnessProof]
+#[cfg(feature = "non_interactive_mp")]
+#[derive(Clone, Copy, Debug)]
+enum NonInteractiveShareProofGroup {
+    /// Key switching key from u_j to s. Witnesses: [s_j, u_j]
+    UiToSKsk,
+    /// Zero encryptions for key switching key of user i. Witnesses: [s_j]
+    KskZeroEncsForUser(usize),
+    /// Auto key for auto element g^k (-g if k = 0). Witnesses: [s_j,
+    /// -s_j(X^{g^k})]
+    AutoKey(usize),
+    /// Non-interactive RGSW ciphertexts and zero encryptions of l^th LWE index.
+    /// Witnesses: [s_j, u_j, X^{s_{j, lwe}[l]}]
+    LweIndex(usize),
+}
+
+/// Proofs of non-interactive multi-party server key shares
+///
+/// Each component of the share is proven to be RLWE encryptions with short
+/// error under short secrets. Every relation includes the collective public
+/// key share `a * s_j + e` to bind s_j across the proofs. Note that:
+///
+/// - u_j, -s_j(X^{g^k}) and X^{s_{j, lwe}[l]} are only proven to be short, not
+///   to be consistent with each other and with s_j.
+/// - LWE key switching key share is not proven. An accepted proof only bounds
+///   errors by the slack of the proof times [ERROR_BOUND], where the slack is
+///   at least 4 * (N * d_lwe) * challenge weight > 2^20. Since lwe_q / (2 *
+///   ERROR_BOUND) <= 2^11, every share reduced mod lwe_q satisfies such a
+///   statement. The server only checks dimension and coefficient range of the
+///   share when validating it.
+#[cfg(feature = "non_interactive_mp")]
+impl<M, NttOp, RlweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, Skey>
+where
+    M: MatrixMut<MatElement = u64> + MatrixEntity,
+    M::R: RowMut + RowEntity,
+    NttOp: Ntt<Element = u64>,
+    RlweModOp: ArithmeticOps<Element = u64>
+        + VectorOps<Element = u64>
+        + GetModulus<Element = u64, M = CiphertextModulus<u64>>,
+{
+    fn non_interactive_share_proof_groups(
+        &self,
+        user_id: usize,
+        total_users: usize,
+    ) -> Vec<NonInteractiveShareProofGroup> {
+        let mut groups = vec![NonInteractiveShareProofGroup::UiToSKsk];
+        groups.extend(
+            (0..total_users)
+                .filter(|i| *i != user_id)
+                .map(NonInteractiveShareProofGroup::KskZeroEncsForUser),
+        );
+        groups.extend(
+            self.pbs_info
+                .parameters
+                .auto_element_dlogs()
+                .into_iter()
+                .map(NonInteractiveShareProofGroup::AutoKey),
+        );
+        groups.extend(
+            (0..self.pbs_info.parameters.lwe_n().0).map(NonInteractiveShareProofGroup::LweIndex),
+        );
+        groups
+    }
+
+    /// Returns label and relation proven for `group` of `share`
+    fn non_interactive_share_proof_statement<'a>(
+        &self,
+        share: &'a CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+            M,
+            BoolParameters<u64>,
+            NonInteractiveMultiPartyCrs<[u8; 32]>,
+        >,
+        group: NonInteractiveShareProofGroup,
+    ) -> (Vec<u8>, ShortRelation<'a>) {
+        let parameters = &self.pbs_info.parameters;
+        let cr_seed = share.cr_seed();
+        let ring_size = parameters.rlwe_n().0;
+        let rlwe_q = parameters.rlwe_q();
+        let nttop = &self.pbs_info.rlwe_nttop;
+
+        // a_i in evaluation domain
+        let sample = |p_rng: &mut DefaultSecureRng| {
+            let mut a = vec![0u64; ring_size];
+            RandomFillUniformInModulus::random_fill(p_rng, rlwe_q, a.as_mut_slice());
+            nttop.forward(&mut a);
+            a
+        };
+        let puncture = |p_rng: &mut DefaultSecureRng, times: usize| {
+            let mut a = vec![0u64; ring_size];
+            (0..times).for_each(|_| {
+                RandomFillUniformInModulus::random_fill(p_rng, rlwe_q, a.as_mut_slice())
+            });
+        };
+
+        let s_bound = match parameters.rlwe_secret_key_dist() {
+            SecretKeyDistribution::TernaryDistribution => 1,
+            SecretKeyDistribution::ErrorDistribution => ERROR_BOUND,
+        };
+        let witness_bounds = match group {
+            NonInteractiveShareProofGroup::UiToSKsk => vec![s_bound, s_bound],
+            NonInteractiveShareProofGroup::KskZeroEncsForUser(_) => vec![s_bound],
+            NonInteractiveShareProofGroup::AutoKey(_) => vec![s_bound, s_bound],
+            NonInteractiveShareProofGroup::LweIndex(_) => vec![s_bound, s_bound, 1],
+        };
+        let mut relation = ShortRelation::new(ring_size, witness_bounds, ERROR_BOUND);
+
+        // a * s_j + e
+        let a_pk = sample(&mut DefaultSecureRng::new_seeded(
+            cr_seed.public_key_share_seed(),
+        ));
+        relation.add_row(
+            vec![(0, Coefficient::Poly(a_pk))],
+            share.public_key_share().as_ref(),
+        );
+
+        match group {
+            NonInteractiveShareProofGroup::UiToSKsk => {
+                // a_i * s_j + e + \beta_i u_j
+                let gadget_vec = parameters
+                    .non_interactive_ui_to_s_key_switch_decomposer::<DefaultDecomposer<u64>>()
+                    .gadget_vector();
+                let mut p_rng = DefaultSecureRng::new_seeded(
+                    cr_seed.ui_to_s_ks_seed_for_user_i(share.user_index()),
+                );
+                izip!(share.ui_to_s_ksk().iter_rows(), gadget_vec.iter()).for_each(
+                    |(row, beta)| {
+                        relation.add_row(
+                            vec![
+                                (0, Coefficient::Poly(sample(&mut p_rng))),
+                                (1, Coefficient::Scalar(*beta)),
+                            ],
+                            row.as_ref(),
+                        )
+                    },
+                );
+            }
+            NonInteractiveShareProofGroup::KskZeroEncsForUser(user_i) => {
+                // a_i * s_j + e, where a_i is sampled from user i's seed
+                let mut p_rng =
+                    DefaultSecureRng::new_seeded(cr_seed.ui_to_s_ks_seed_for_user_i(user_i));
+                share
+                    .ui_to_s_ksk_zero_encs_for_user_i(user_i)
+                    .iter_rows()
+                    .for_each(|row| {
+                        relation.add_row(
+                            vec![(0, Coefficient::Poly(sample(&mut p_rng)))],
+                            row.as_ref(),
+                        )
+                    });
+            }
+            NonInteractiveShareProofGroup::AutoKey(k) => {
+                // a_i * s_j + e + \beta_i -s_j(X^{g^k}). a_is of all auto keys are sampled
+                // from the same seed in order of auto element dlogs.
+                let d = self.pbs_info.auto_decomposer.decomposition_count().0;
+                let gadget_vec = self.pbs_info.auto_decomposer.gadget_vector();
+                let position = parameters
+                    .auto_element_dlogs()
+                    .iter()
+                    .position(|i| *i == k)
+                    .unwrap();
+                let mut p_rng = DefaultSecureRng::new_seeded(cr_seed.auto_keys_cts_seed());
+                puncture(&mut p_rng, position * d);
+                izip!(share.auto_keys_share()[&k].iter_rows(), gadget_vec.iter()).for_each(
+                    |(row, beta)| {
+                        relation.add_row(
+                            vec![
+                                (0, Coefficient::Poly(sample(&mut p_rng))),
+                                (1, Coefficient::Scalar(*beta)),
+                            ],
+                            row.as_ref(),
+                        )
+                    },
+                );
+            }
+            NonInteractiveShareProofGroup::LweIndex(lwe_index) => {
+                let rgsw_x_rgsw_decomposer =
+                    parameters.rgsw_rgsw_decomposer::<DefaultDecomposer<u64>>();
+                let rlwe_x_rgsw_decomposer =
+                    parameters.rlwe_rgsw_decomposer::<DefaultDecomposer<u64>>();
+                let d_rgsw_a = rgsw_x_rgsw_decomposer.a().decomposition_count().0;
+                let d_max =
+                    std::cmp::max(d_rgsw_a, rgsw_x_rgsw_decomposer.b().decomposition_count().0);
+                let seed = cr_seed.ni_rgsw_ct_seed_for_index(lwe_index);
+
+                // zero encryptions a_{i, l} * s_j + e for i \in [d_max - d_a, d_max)
+                let mut p_rng = DefaultSecureRng::new_seeded(seed);
+                puncture(&mut p_rng, d_max - d_rgsw_a);
+                share
+                    .ni_rgsw_zero_enc_for_lwe_index(lwe_index)
+                    .iter_rows()
+                    .for_each(|row| {
+                        relation.add_row(
+                            vec![(0, Coefficient::Poly(sample(&mut p_rng)))],
+                            row.as_ref(),
+                        )
+                    });
+
+                // non-interactive RGSW ciphertexts a_{i, l} * u_j + e + \beta_i X^{s_{j,
+                // lwe}[l]}
+                // (decomposition count, gadget vector) of the larger decomposer
+                let max_gadget = |(a, b): &(DefaultDecomposer<u64>, DefaultDecomposer<u64>)| {
+                    let d = if a.decomposition_count().0 > b.decomposition_count().0 {
+                        a
+                    } else {
+                        b
+                    };
+                    (d.decomposition_count().0, d.gadget_vector())
+                };
+                let (self_start_index, self_end_index) = multi_party_user_id_lwe_segment(
+                    share.user_index(),
+                    share.total_users(),
+                    parameters.lwe_n().0,
+                );
+                let (ni_rgsw_cts, (d, gadget_vec)) =
+                    if lwe_index >= self_start_index && lwe_index < self_end_index {
+                        (
+                            share.ni_rgsw_cts_for_self_leader_lwe_index(lwe_index),
+                            max_gadget(&rlwe_x_rgsw_decomposer),
+                        )
+                    } else {
+                        (
+                            share.ni_rgsw_cts_for_self_not_leader_lwe_index(lwe_index),
+                            max_gadget(&rgsw_x_rgsw_decomposer),
+                        )
+                    };
+                let mut p_rng = DefaultSecureRng::new_seeded(seed);
+                puncture(&mut p_rng, d_max - d);
+                izip!(ni_rgsw_cts.iter_rows(), gadget_vec.iter()).for_each(|(row, beta)| {
+                    relation.add_row(
+                        vec![
+                            (1, Coefficient::Poly(sample(&mut p_rng))),
+                            (2, Coefficient::Scalar(*beta)),
+                        ],
+                        row.as_ref(),
+                    )
+                });
+            }
+        }
+
+        let mut label = vec![];
+        for field in [
+            b"non-interactive-mp/server-key-share".as_slice(),
+            format!("{group:?}").as_bytes(),
+            &(share.user_index() as u64).to_le_bytes(),
+            &(share.total_users() as u64).to_le_bytes(),
+            &cr_seed.seed,
+            &cr_seed.session_id,
+        ] {
+            label.extend_from_slice(&(field.len() as u64).to_le_bytes());
+            label.extend_from_slice(field);
+        }
+
+        (label, relation)
+    }
+
+    /// Returns secret witnesses of `group` in the order of
+    /// [NonInteractiveShareProofGroup]
+    fn non_interactive_share_proof_witnesses<
+        K: NonInteractiveMultiPartyClientKey<Element = i32>,
+    >(
+        &self,
+        client_key: &K,
+        group: NonInteractiveShareProofGroup,
+    ) -> Vec<Vec<i64>> {
+        let parameters = &self.pbs_info.parameters;
+        let ring_size = parameters.rlwe_n().0;
+        let to_i64 = |v: &[i32]| v.iter().map(|v| *v as i64).collect_vec();
+        let s = to_i64(&client_key.sk_rlwe());
+        match group {
+            NonInteractiveShareProofGroup::UiToSKsk => vec![s, to_i64(&client_key.sk_u_rlwe())],
+            NonInteractiveShareProofGroup::KskZeroEncsForUser(_) => vec![s],
+            NonInteractiveShareProofGroup::AutoKey(k) => {
+                // -s(X^{g^k}), see `seeded_auto_key_gen`
+                let (auto_map_index, auto_map_sign) =
+                    generate_auto_map(ring_size, self.auto_element(k));
+                let mut neg_s_auto = vec![0i64; ring_size];
+                izip!(s.iter(), auto_map_index.iter(), auto_map_sign.iter()).for_each(
+                    |(el, to_index, sign)| {
+                        neg_s_auto[*to_index] = if *sign { -el } else { *el };
+                    },
+                );
+                vec![s, neg_s_auto]
+            }
+            NonInteractiveShareProofGroup::LweIndex(lwe_index) => {
+                let rlwe_q = parameters.rlwe_q();
+                let mut m = encode_x_pow_si_with_emebedding_factor::<Vec<u64>, _>(
+                    client_key.sk_lwe()[lwe_index],
+                    self.pbs_info.embedding_factor,
+                    ring_size,
+                    rlwe_q,
+                );
+                let m_i64 = m.iter().map(|v| rlwe_q.map_element_to_i64(v)).collect_vec();
+                wipe(&mut m);
+                vec![s, to_i64(&client_key.sk_u_rlwe()), m_i64]
+            }
+        }
+    }
+
+    pub(super) fn prove_non_interactive_multi_party_server_key_share<
+        K: NonInteractiveMultiPartyClientKey<Element = i32>,
+    >(
+        &self,
+        client_key: &K,
+        share: &CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+            M,
+            BoolParameters<u64>,
+            NonInteractiveMultiPartyCrs<[u8; 32]>,
+        >,
+    ) -> NonInteractiveServerKeyShareProof {
+        assert!(share.parameters() == &self.pbs_info.parameters);
+        let nttop = &self.pbs_info.rlwe_nttop;
+        let modop = &self.pbs_info.rlwe_modop;
+        let proofs = self
+            .non_interactive_share_proof_groups(share.user_index(), share.total_users())
+            .into_iter()
+            .map(|group| {
+                let (label, relation) = self.non_interactive_share_proof_statement(share, group);
+                let mut witnesses = self.non_interactive_share_proof_witnesses(client_key, group);
+                let proof = DefaultSecureRng::with_local_mut(|rng| {
+                    prove_short_relation(&label, &relation, &witnesses, nttop, modop, rng)
+                });
+                witnesses.iter_mut().for_each(|w| wipe(w));
+                proof
+            })
+            .collect_vec();
+        NonInteractiveServerKeyShareProof::new(share.user_index(), proofs)
+    }
+
+    /// Verifies proof of each server key share. Proof at index `i` must be the
+    /// proof of share at index `i`.
+    ///
+    /// Shares must be validated with
+    /// `validate_non_interactive_multi_party_server_key_shares` beforehand.
+    pub(super) fn verify_non_interactive_multi_party_server_key_share_proofs(
+        &self,
+        key_shares: &[NonInteractiveServerKeyShare<M>],
+        proofs: &[NonInteractiveServerKeyShareProof],
+    ) -> Result<(), MalformedServerKeyShare> {
+        let nttop = &self.pbs_info.rlwe_nttop;
+        let modop = &self.pbs_info.rlwe_modop;
+        key_shares
+            .iter()
+            .enumerate()
+            .try_for_each(|(share_index, share)| {
+                let user_id = share.user_index();
+                let malformed = |reason: String| MalformedServerKeyShare {
+                    share_index,
+                    user_id,
+                    reason,
+                };
+
+                let proof = proofs
+                    .get(share_index)
+                    .ok_or_else(|| malformed("Proof is missing".to_string()))?;
+                if proof.user_id() != user_id {
+                    return Err(malformed(format!(
+                        "Proof is for user_id={}",
+                        proof.user_id()
+                    )));
+                }
+                let groups = self.non_interactive_share_proof_groups(user_id, share.total_users());
+                if proof.proofs().len() != groups.len() {
+                    return Err(malformed(format!(
+                        "Proof has {} component proofs but expected {}",
+                        proof.proofs().len(),
+                        groups.len()
+                    )));
+                }
+                izip!(groups.into_iter(), proof.proofs().iter()).try_for_each(|(group, p)| {
+                    let (label, relation) =
+                        self.non_interactive_share_proof_statement(share, group);
+                    verify_short_relation(&label, &relation, p, nttop, modop)
+                        .map_err(|e| malformed(format!("{group:?}: {e}")))
+                })
+            })
+    }
 }
 
-impl<M, NttOp, RlweModOp, LweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, LweModOp, Skey>
+/// Decryption shares with proofs of correct decryption. Secret of the decryption
+/// share is tied to the public key share `a * s_j + e` of the party.
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+impl<M, NttOp, RlweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, Skey>
+where
+    M: Matrix<MatElement = u64>,
+    M::R: RowMut + RowEntity + TryConvertFrom1<[i32], CiphertextModulus<u64>>,
+    NttOp: Ntt<Element = u64>,
+    RlweModOp: ArithmeticOps<Element = u64>
+        + VectorOps<Element = u64>
+        + GetModulus<Element = u64, M = CiphertextModulus<u64>>,
+{
+    const DECRYPTION_SHARE_LABEL: &'static [u8] = b"multi-party/decryption-share";
+
+    /// Returns public polynomial `a` of public key shares in evaluation domain
+    fn public_key_share_a_eval(&self, public_key_share_seed: [u8; 32]) -> Vec<u64> {
+        let mut a = vec![0u64; self.pbs_info.parameters.rlwe_n().0];
+        RandomFillUniformInModulus::random_fill(
+            &mut DefaultSecureRng::new_seeded(public_key_share_seed),
+            self.pbs_info.parameters.rlwe_q(),
+            a.as_mut_slice(),
+        );
+        self.pbs_info.rlwe_nttop.forward(&mut a);
+        a
+    }
+
+    fn rlwe_secret_bound(&self) -> u64 {
+        match self.pbs_info.parameters.rlwe_secret_key_dist() {
+            SecretKeyDistribution::TernaryDistribution => 1,
+            SecretKeyDistribution::ErrorDistribution => ERROR_BOUND,
+        }
+    }
+
+    /// Generates decryption share of `lwe_ct` with secret `sk_rlwe` and proof
+    /// that the share is correct with respect to `public_key_share` generated
+    /// from seed `public_key_share_seed`
+    pub(super) fn multi_party_decryption_share_with_proof(
+        &self,
+        lwe_ct: &M::R,
+        sk_rlwe: &[i32],
+        public_key_share_seed: [u8; 32],
+        public_key_share: &[u64],
+    ) -> (u64, DecryptionShareProof) {
+        let modop = &self.pbs_info.rlwe_modop;
+        let public_key_a_eval = self.public_key_share_a_eval(public_key_share_seed);
+        DefaultSecureRng::with_local_mut(|rng| {
+            let share = multi_party_decryption_share(lwe_ct, sk_rlwe, modop, rng);
+            let statement = DecryptionShareStatement {
+                lwe_ct: lwe_ct.as_ref(),
+                share,
+                public_key_a_eval: &public_key_a_eval,
+                public_key_share,
+                secret_bound: self.rlwe_secret_bound(),
+            };
+            let mut secret = sk_rlwe.iter().map(|v| *v as i64).collect_vec();
+            let proof = prove_decryption_share(
+                Self::DECRYPTION_SHARE_LABEL,
+                &statement,
+                &secret,
+                &self.pbs_info.rlwe_nttop,
+                modop,
+                rng,
+            );
+            wipe(&mut secret);
+            (share, proof)
+        })
+    }
+
+    /// Verifies `proof` of decryption `share` of `lwe_ct` against
+    /// `public_key_share` generated from seed `public_key_share_seed`
+    pub(super) fn verify_multi_party_decryption_share(
+        &self,
+        lwe_ct: &M::R,
+        share: u64,
+        proof: &DecryptionShareProof,
+        public_key_share_seed: [u8; 32],
+        public_key_share: &[u64],
+    ) -> Result<(), String> {
+        let ring_size = self.pbs_info.parameters.rlwe_n().0;
+        if public_key_share.len() != ring_size || lwe_ct.as_ref().len() != ring_size + 1 {
+            return Err("Public key share or ciphertext has incorrect dimension".to_string());
+        }
+        let public_key_a_eval = self.public_key_share_a_eval(public_key_share_seed);
+        let statement = DecryptionShareStatement {
+            lwe_ct: lwe_ct.as_ref(),
+            share,
+            public_key_a_eval: &public_key_a_eval,
+            public_key_share,
+            secret_bound: self.rlwe_secret_bound(),
+        };
+        verify_decryption_share(
+            Self::DECRYPTION_SHARE_LABEL,
+            &statement,
+            proof,
+            &self.pbs_info.rlwe_nttop,
+            &self.pbs_info.rlwe_modop,
+        )
+    }
+}
+
+impl<M, NttOp, RlweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, Skey>
 where
     M: MatrixMut + MatrixEntity,
     M::R: RowMut + RowEntity,
     M::MatElement: PrimInt + FromPrimitive + One + Copy + Zero + Display + WrappingSub + NumInfo,
     RlweModOp: VectorOps<Element = M::MatElement> + ArithmeticOps<Element = M::MatElement>,
-    LweModOp: VectorOps<Element = M::MatElement> + ArithmeticOps<Element = M::MatElement>,
     NttOp: Ntt<Element = M::MatElement>,
 {
     /// Returns c0 + c1 + Q/4
@@ -2131,10 +3356,33 @@ where
         // double
         c0.as_mut().iter_mut().for_each(|v| *v = modop.add(v, v));
     }
+
+    /// Maps `c` in bool encoding (i.e. +/-Q/8) to parity encoding b * Q/2 as
+    /// 2c + Q/4. In parity encoding XOR is addition and NOT is addition of Q/2.
+    pub(crate) fn to_parity_encoding_inplace(&self, c: &mut M::R) {
+        let modop = &self.pbs_info.rlwe_modop;
+        // double
+        c.as_mut().iter_mut().for_each(|v| *v = modop.add(v, v));
+        // +Q/4
+        c.as_mut()[0] = modop.add(&c.as_ref()[0], &self.pbs_info.rlwe_qby4);
+    }
+
+    /// Negates `c` in parity encoding by adding Q/2
+    pub(crate) fn parity_not_inplace(&self, c: &mut M::R) {
+        let modop = &self.pbs_info.rlwe_modop;
+        let qby4 = &self.pbs_info.rlwe_qby4;
+        c.as_mut()[0] = modop.add(&modop.add(&c.as_ref()[0], qby4), qby4);
+    }
+
+    /// Returns c0 + c1 + c2
+    fn _add_three_lwe_cts(&self, c0: &mut M::R, c1: &M::R, c2: &M::R) {
+        let modop = &self.pbs_info.rlwe_modop;
+        modop.elwise_add_mut(c0.as_mut(), c1.as_ref());
+        modop.elwise_add_mut(c0.as_mut(), c2.as_ref());
+    }
 }
 
-impl<M, NttOp, RlweModOp, LweModOp, Skey> BooleanGates
-    for BoolEvaluator<M, NttOp, RlweModOp, LweModOp, Skey>
+impl<M, NttOp, RlweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, Skey>
 where
     M: MatrixMut + MatrixEntity,
     M::R: RowMut + RowEntity + Clone,
@@ -2152,10 +3400,140 @@ where
     RlweModOp: VectorOps<Element = M::MatElement>
         + ArithmeticOps<Element = M::MatElement>
         + ShoupMatrixFMA<M::R>,
-    LweModOp: VectorOps<Element = M::MatElement> + ArithmeticOps<Element = M::MatElement>,
     NttOp: Ntt<Element = M::MatElement>,
-    Skey: PbsKey<AutoKey = <Skey as PbsKey>::RgswCt, LweKskKey = M>,
-    <Skey as PbsKey>::RgswCt: WithShoupRepr<M = M>,
+    Skey: PbsKey<AutoKey = <Skey as PbsKey>::RgswCt, LweKskKey = Vec<Vec<LweKsElement>>>,
+    <Skey as PbsKey>::RgswCt: BlindRotationKeyCt<BoolPbsInfo<M, NttOp, RlweModOp>>,
+{
+    /// Bootstraps `c` in parity encoding (i.e. b * Q/2) to bool encoding
+    pub(crate) fn parity_bootstrap_inplace(&mut self, c: &mut M::R, server_key: &Skey) {
+        pbs(
+            &self.pbs_info,
+            &self.parity_test_vec,
+            c,
+            server_key,
+            &mut self.scratch_memory.lwe_vectors,
+            &mut self.scratch_memory.decomposition_matrix,
+        );
+    }
+}
+
+impl<M, NttOp, RlweModOp, Skey> BoolEvaluator<M, NttOp, RlweModOp, Skey>
+where
+    M: MatrixMut + MatrixEntity + MaybeSend,
+    M::R: RowMut + RowEntity + Clone,
+    M::MatElement: PrimInt
+        + FromPrimitive
+        + One
+        + Copy
+        + Zero
+        + Display
+        + WrappingSub
+        + NumInfo
+        + From<bool>
+        + WrappingAdd
+        + Debug
+        + MaybeSync,
+    RlweModOp: VectorOps<Element = M::MatElement>
+        + ArithmeticOps<Element = M::MatElement>
+        + ShoupMatrixFMA<M::R>
+        + MaybeSync,
+    NttOp: Ntt<Element = M::MatElement> + MaybeSync,
+    Skey:
+        PbsKey<AutoKey = <Skey as PbsKey>::RgswCt, LweKskKey = Vec<Vec<LweKsElement>>> + MaybeSync,
+    <Skey as PbsKey>::RgswCt: BlindRotationKeyCt<BoolPbsInfo<M, NttOp, RlweModOp>>,
+    M::R: MaybeSend + MaybeSync,
+{
+    /// Returns c - s + b + Q/8, where `c` encrypts s & a as +/-Q/4. Phase is in
+    /// [0, Q/2) iff s ? a : b is true.
+    fn _mux_select_lwe_cts(&self, c: &mut M::R, s: &M::R, b: &M::R) {
+        let modop = &self.pbs_info.rlwe_modop;
+        modop.elwise_sub_mut(c.as_mut(), s.as_ref());
+        modop.elwise_add_mut(c.as_mut(), b.as_ref());
+        c.as_mut()[0] = modop.add(&c.as_ref()[0], &self.pbs_info.parameters.rlwe_q().true_el());
+    }
+
+    /// Bootstraps each of `cts` with `test_vec` in a single batch. If
+    /// `parallel` feature is enabled the batch is split across threads of
+    /// rayon's global thread pool.
+    fn _batch_pbs(
+        pbs_info: &BoolPbsInfo<M, NttOp, RlweModOp>,
+        scratch_memory: &mut ScratchMemory<M>,
+        test_vec: &M::R,
+        cts: &mut [M::R],
+        server_key: &Skey,
+    ) {
+        #[cfg(feature = "parallel")]
+        {
+            let threads = rayon::current_num_threads();
+            if threads > 1 && cts.len() > 1 {
+                let chunk_size = cts.len().div_ceil(threads);
+                scratch_memory.reserve_batch(cts.len(), cts.len().div_ceil(chunk_size));
+                let [lwe_ins_ks, lwe_outs_ks] = &mut scratch_memory.batch_lwe_vectors;
+                rayon::scope(|s| {
+                    // each thread uses its own scratch space
+                    izip!(
+                        cts.chunks_mut(chunk_size),
+                        lwe_ins_ks.chunks_mut(chunk_size),
+                        lwe_outs_ks.chunks_mut(chunk_size),
+                        scratch_memory.thread_decomposition_matrices.iter_mut()
+                    )
+                    .for_each(
+                        |(chunk, lwe_ins_ks, lwe_outs_ks, scratch_blind_rotate_matrix)| {
+                            s.spawn(move |_| {
+                                batch_pbs(
+                                    pbs_info,
+                                    test_vec,
+                                    chunk,
+                                    server_key,
+                                    [lwe_ins_ks, lwe_outs_ks],
+                                    scratch_blind_rotate_matrix,
+                                );
+                            });
+                        },
+                    );
+                });
+                return;
+            }
+        }
+
+        scratch_memory.reserve_batch(cts.len(), 0);
+        let [lwe_ins_ks, lwe_outs_ks] = &mut scratch_memory.batch_lwe_vectors;
+        batch_pbs(
+            pbs_info,
+            test_vec,
+            cts,
+            server_key,
+            [lwe_ins_ks, lwe_outs_ks],
+            &mut scratch_memory.decomposition_matrix,
+        );
+    }
+}
+
+impl<M, NttOp, RlweModOp, Skey> BooleanGates for BoolEvaluator<M, NttOp, RlweModOp, Skey>
+where
+    M: MatrixMut + MatrixEntity + MaybeSend,
+    M::R: RowMut + RowEntity + Clone,
+    M::MatElement: PrimInt
+        + FromPrimitive
+        + One
+        + Copy
+        + Zero
+        + Display
+        + WrappingSub
+        + NumInfo
+        + From<bool>
+        + WrappingAdd
+        + Debug
+        + MaybeSync,
+    RlweModOp: VectorOps<Element = M::MatElement>
+        + ArithmeticOps<Element = M::MatElement>
+        + ShoupMatrixFMA<M::R>
+        + MaybeSync,
+    NttOp: Ntt<Element = M::MatElement> + MaybeSync,
+    Skey:
+        PbsKey<AutoKey = <Skey as PbsKey>::RgswCt, LweKskKey = Vec<Vec<LweKsElement>>> + MaybeSync,
+    <Skey as PbsKey>::RgswCt: BlindRotationKeyCt<BoolPbsInfo<M, NttOp, RlweModOp>>,
+    M::R: MaybeSend + MaybeSync,
 {
     type Ciphertext = M::R;
     type Key = Skey;
@@ -2169,7 +3547,7 @@ where
             &self.nand_test_vec,
             c0,
             server_key,
-            &mut self.scratch_memory.lwe_vector,
+            &mut self.scratch_memory.lwe_vectors,
             &mut self.scratch_memory.decomposition_matrix,
         );
     }
@@ -2183,7 +3561,7 @@ where
             &self.and_test_vec,
             c0,
             server_key,
-            &mut self.scratch_memory.lwe_vector,
+            &mut self.scratch_memory.lwe_vectors,
             &mut self.scratch_memory.decomposition_matrix,
         );
     }
@@ -2197,7 +3575,7 @@ where
             &self.or_test_vec,
             c0,
             server_key,
-            &mut self.scratch_memory.lwe_vector,
+            &mut self.scratch_memory.lwe_vectors,
             &mut self.scratch_memory.decomposition_matrix,
         );
     }
@@ -2211,7 +3589,7 @@ where
             &self.nor_test_vec,
             c0,
             server_key,
-            &mut self.scratch_memory.lwe_vector,
+            &mut self.scratch_memory.lwe_vectors,
             &mut self.scratch_memory.decomposition_matrix,
         )
     }
@@ -2225,7 +3603,7 @@ where
             &self.xor_test_vec,
             c0,
             server_key,
-            &mut self.scratch_memory.lwe_vector,
+            &mut self.scratch_memory.lwe_vectors,
             &mut self.scratch_memory.decomposition_matrix,
         );
     }
@@ -2239,7 +3617,7 @@ where
             &self.xnor_test_vec,
             c0,
             server_key,
-            &mut self.scratch_memory.lwe_vector,
+            &mut self.scratch_memory.lwe_vectors,
             &mut self.scratch_memory.decomposition_matrix,
         );
     }
@@ -2249,6 +3627,99 @@ where
         c0.as_mut().iter_mut().for_each(|v| *v = modop.neg(v));
     }
 
+    fn maj3_inplace(&mut self, c0: &mut M::R, c1: &M::R, c2: &M::R, server_key: &Self::Key) {
+        self._add_three_lwe_cts(c0, c1, c2);
+
+        // PBS
+        pbs(
+            &self.pbs_info,
+            &self.maj3_test_vec,
+            c0,
+            server_key,
+            &mut self.scratch_memory.lwe_vectors,
+            &mut self.scratch_memory.decomposition_matrix,
+        );
+    }
+
+    fn xor3_inplace(&mut self, c0: &mut M::R, c1: &M::R, c2: &M::R, server_key: &Self::Key) {
+        self._add_three_lwe_cts(c0, c1, c2);
+
+        // double
+        let modop = &self.pbs_info.rlwe_modop;
+        c0.as_mut().iter_mut().for_each(|v| *v = modop.add(v, v));
+
+        // PBS
+        pbs(
+            &self.pbs_info,
+            &self.xor3_test_vec,
+            c0,
+            server_key,
+            &mut self.scratch_memory.lwe_vectors,
+            &mut self.scratch_memory.decomposition_matrix,
+        );
+    }
+
+    fn mux(
+        &mut self,
+        selector: &M::R,
+        if_true: &M::R,
+        if_false: &M::R,
+        server_key: &Self::Key,
+    ) -> M::R {
+        let mut c = selector.clone();
+        self._add_and_shift_lwe_cts(&mut c, if_true);
+        pbs(
+            &self.pbs_info,
+            &self.mux_and_test_vec,
+            &mut c,
+            server_key,
+            &mut self.scratch_memory.lwe_vectors,
+            &mut self.scratch_memory.decomposition_matrix,
+        );
+
+        self._mux_select_lwe_cts(&mut c, selector, if_false);
+        pbs(
+            &self.pbs_info,
+            &self.maj3_test_vec,
+            &mut c,
+            server_key,
+            &mut self.scratch_memory.lwe_vectors,
+            &mut self.scratch_memory.decomposition_matrix,
+        );
+        c
+    }
+
+    fn batch_mux(
+        &mut self,
+        selector: &M::R,
+        if_true: &[M::R],
+        if_false: &[M::R],
+        server_key: &Self::Key,
+    ) -> Vec<M::R> {
+        assert!(if_true.len() == if_false.len());
+
+        let mut cts = vec![selector.clone(); if_true.len()];
+        izip!(cts.iter_mut(), if_true.iter()).for_each(|(c, a)| self._add_and_shift_lwe_cts(c, a));
+        Self::_batch_pbs(
+            &self.pbs_info,
+            &mut self.scratch_memory,
+            &self.mux_and_test_vec,
+            &mut cts,
+            server_key,
+        );
+
+        izip!(cts.iter_mut(), if_false.iter())
+            .for_each(|(c, b)| self._mux_select_lwe_cts(c, selector, b));
+        Self::_batch_pbs(
+            &self.pbs_info,
+            &mut self.scratch_memory,
+            &self.maj3_test_vec,
+            &mut cts,
+            server_key,
+        );
+        cts
+    }
+
     fn and(
         &mut self,
         c0: &Self::Ciphertext,
@@ -2320,4 +3791,49 @@ where
         self.not_inplace(&mut out);
         out
     }
+
+    fn batch_gate(&mut self, op: BoolGate, lhs: &mut [M::R], rhs: &[M::R], server_key: &Self::Key) {
+        assert!(lhs.len() == rhs.len());
+
+        izip!(lhs.iter_mut(), rhs.iter()).for_each(|(c0, c1)| match op {
+            BoolGate::And => self._add_and_shift_lwe_cts(c0, c1),
+            BoolGate::Xnor => self._subtract_double_lwe_cts(c0, c1),
+        });
+
+        let test_vec = match op {
+            BoolGate::And => &self.and_test_vec,
+            BoolGate::Xnor => &self.xnor_test_vec,
+        };
+        Self::_batch_pbs(
+            &self.pbs_info,
+            &mut self.scratch_memory,
+            test_vec,
+            lhs,
+            server_key,
+        );
+    }
+
+    fn maj3(
+        &mut self,
+        c0: &Self::Ciphertext,
+        c1: &Self::Ciphertext,
+        c2: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Self::Ciphertext {
+        let mut out = c0.clone();
+        self.maj3_inplace(&mut out, c1, c2, key);
+        out
+    }
+
+    fn xor3(
+        &mut self,
+        c0: &Self::Ciphertext,
+        c1: &Self::Ciphertext,
+        c2: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Self::Ciphertext {
+        let mut out = c0.clone();
+        self.xor3_inplace(&mut out, c1, c2, key);
+        out
+    }
 }
@@ -0,0 +1,419 @@
+//! Known-answer tests of the multi-party protocols.
+//!
+//! For each `ParameterSelector` the protocol is run with fixed common reference
+//! seed and with thread local rng seeded with a fixed seed. Client key seeds,
+//! digests of key shares and ciphertexts, decryption shares and bootstrapped
+//! gate outputs are compared against the KAT file in `kats/`. Independent
+//! builds must agree on all of them.
+//!
+//! To regenerate KAT files after an intended change of the wire outputs run
+//! `PHANTOM_ZONE_REGENERATE_KATS=1 cargo test --release --features <feature>
+//! kat_`.
+
+use std::{fmt::Display, path::PathBuf};
+
+use itertools::Itertools;
+use sha2::{Digest, Sha256};
+
+use crate::random::{DefaultSecureRng, NewWithSeed, PrgAlgorithm};
+
+use super::keys::kat::update_row;
+
+/// Seed of the thread local rng
+const KAT_RNG_SEED: [u8; 32] = [0x5a; 32];
+/// Common reference seed
+const KAT_CRS_SEED: [u8; 32] = [0xc3; 32];
+/// No. of parties. Parameters for more parties are tested with 2 parties as
+/// well to keep the tests fast.
+const KAT_PARTIES: usize = 2;
+/// Inputs to the bootstrapped gates
+const KAT_INPUTS: (bool, bool) = (true, false);
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).join("")
+}
+
+fn row_digest(row: &[u64]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    update_row(&mut hasher, row);
+    hasher.finalize().into()
+}
+
+/// Ordered key value entries of a KAT file
+struct Kat {
+    name: &'static str,
+    entries: Vec<(String, String)>,
+}
+
+impl Kat {
+    fn new(name: &'static str) -> Self {
+        let mut kat = Kat {
+            name,
+            entries: vec![],
+        };
+        kat.push("prg", format!("{:?}", DefaultSecureRng::ALGORITHM));
+        kat.push("rng_seed", hex(&KAT_RNG_SEED));
+        kat.push("crs_seed", hex(&KAT_CRS_SEED));
+        kat.push("parties", KAT_PARTIES);
+        kat
+    }
+
+    fn push<V: Display>(&mut self, key: impl Into<String>, value: V) {
+        self.entries.push((key.into(), value.to_string()));
+    }
+
+    fn push_digest(&mut self, key: impl Into<String>, digest: [u8; 32]) {
+        self.push(key, hex(&digest));
+    }
+
+    fn path(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("kats")
+            .join(format!("{}.kat", self.name))
+    }
+
+    fn serialize(&self) -> String {
+        let mut out = format!("# phantom-zone known-answer test: {}\n", self.name);
+        self.entries.iter().for_each(|(k, v)| {
+            out.push_str(&format!("{k} = {v}\n"));
+        });
+        out
+    }
+
+    fn parse(content: &str) -> Vec<(String, String)> {
+        content
+            .lines()
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| {
+                let (k, v) = l.split_once(" = ").expect("Malformed KAT line");
+                (k.to_string(), v.to_string())
+            })
+            .collect_vec()
+    }
+
+    /// Compares entries with the KAT file, or overwrites the KAT file if
+    /// `PHANTOM_ZONE_REGENERATE_KATS` is set
+    fn check(&self) {
+        if std::env::var_os("PHANTOM_ZONE_REGENERATE_KATS").is_some() {
+            std::fs::create_dir_all(self.path().parent().unwrap()).unwrap();
+            std::fs::write(self.path(), self.serialize()).unwrap();
+            return;
+        }
+
+        let expected = Kat::parse(
+            &std::fs::read_to_string(self.path())
+                .unwrap_or_else(|e| panic!("Failed to read {:?}: {e}", self.path())),
+        );
+        for (e, g) in expected.iter().zip(self.entries.iter()) {
+            assert_eq!(e.0, g.0, "{}: unexpected entry", self.name);
+            assert_eq!(e.1, g.1, "{}: mismatch at {}", self.name, e.0);
+        }
+        assert_eq!(
+            expected.len(),
+            self.entries.len(),
+            "{}: no. of entries mismatch",
+            self.name
+        );
+    }
+}
+
+/// KAT files are generated with the default PRG. Other PRGs expand seeds
+/// differently.
+fn default_prg_selected() -> bool {
+    if DefaultSecureRng::ALGORITHM != PrgAlgorithm::ChaCha8 {
+        println!("Skipping known-answer test: KAT files are generated with ChaCha8");
+        return false;
+    }
+    true
+}
+
+#[cfg(feature = "interactive_mp")]
+mod interactive {
+    use itertools::Itertools;
+
+    use crate::{
+        backend::ModularOpsU64,
+        bool::{
+            evaluator::InteractiveMultiPartyCrs,
+            keys::{
+                kat::{client_key_seed, interactive_server_key_share_digest},
+                PublicKey, ServerKeyEvaluationDomain, ShoupServerKeyEvaluationDomain,
+            },
+            mp_api::{gen_client_key, set_parameter_set, BoolEvaluator, ParameterSelector},
+            BooleanGates,
+        },
+        ntt::NttBackendU64,
+        parameters::CiphertextModulus,
+        random::{with_seeded_rng, DefaultSecureRng},
+        utils::WithLocal,
+        Encryptor, MultiPartyDecryptor,
+    };
+
+    use super::*;
+
+    fn run(selector: ParameterSelector, name: &'static str) -> Kat {
+        set_parameter_set(selector);
+        let cr_seed = InteractiveMultiPartyCrs::new(
+            KAT_CRS_SEED,
+            [0u8; 32],
+            BoolEvaluator::with_local(|e| e.parameters().id()),
+        );
+        let mut kat = Kat::new(name);
+
+        with_seeded_rng(KAT_RNG_SEED, || {
+            let cks = (0..KAT_PARTIES).map(|_| gen_client_key()).collect_vec();
+            cks.iter().enumerate().for_each(|(i, ck)| {
+                kat.push(format!("client_key_seed.{i}"), hex(&client_key_seed(ck)));
+            });
+
+            // round 1
+            let pk_shares = cks
+                .iter()
+                .map(|k| BoolEvaluator::with_local(|e| e.multi_party_public_key_share(&cr_seed, k)))
+                .collect_vec();
+            pk_shares.iter().enumerate().for_each(|(i, s)| {
+                kat.push_digest(format!("public_key_share.{i}"), s.commitment());
+            });
+            let pk: PublicKey<
+                Vec<Vec<u64>>,
+                DefaultSecureRng,
+                ModularOpsU64<CiphertextModulus<u64>>,
+            > = PublicKey::from(pk_shares.as_slice());
+
+            // round 2
+            let server_key_shares = (0..KAT_PARTIES)
+                .map(|user_id| {
+                    BoolEvaluator::with_local_mut(|e| {
+                        e.gen_interactive_multi_party_server_key_share(
+                            user_id,
+                            KAT_PARTIES,
+                            &cr_seed,
+                            pk.key(),
+                            pk.transcript().unwrap(),
+                            &cks[user_id],
+                        )
+                    })
+                })
+                .collect_vec();
+            server_key_shares.iter().enumerate().for_each(|(i, s)| {
+                kat.push_digest(
+                    format!("server_key_share.{i}"),
+                    interactive_server_key_share_digest(s),
+                );
+            });
+            let seeded_server_key = BoolEvaluator::with_local(|e| {
+                e.aggregate_interactive_multi_party_server_key_shares(&server_key_shares)
+            });
+            let server_key =
+                ShoupServerKeyEvaluationDomain::from(ServerKeyEvaluationDomain::<
+                    _,
+                    _,
+                    DefaultSecureRng,
+                    NttBackendU64,
+                >::from(&seeded_server_key));
+
+            let (m0, m1) = KAT_INPUTS;
+            let ct0: Vec<u64> = pk.encrypt(&m0);
+            let ct1: Vec<u64> = pk.encrypt(&m1);
+            kat.push_digest("ciphertext.0", row_digest(&ct0));
+            kat.push_digest("ciphertext.1", row_digest(&ct1));
+
+            let outputs = BoolEvaluator::with_local_mut(|e| {
+                vec![
+                    ("nand", e.nand(&ct0, &ct1, &server_key), !(m0 && m1)),
+                    ("xor", e.xor(&ct0, &ct1, &server_key), m0 ^ m1),
+                    ("or", e.or(&ct0, &ct1, &server_key), m0 || m1),
+                ]
+            });
+            outputs.iter().for_each(|(gate, ct, m_expected)| {
+                kat.push_digest(format!("pbs.{gate}"), row_digest(ct));
+                let shares = cks.iter().map(|k| k.gen_decryption_share(ct)).collect_vec();
+                shares.iter().enumerate().for_each(|(i, s)| {
+                    kat.push(format!("pbs.{gate}.decryption_share.{i}"), s);
+                });
+                let m = cks[0].aggregate_decryption_shares(ct, &shares);
+                assert_eq!(m, *m_expected);
+                kat.push(format!("pbs.{gate}.output"), m);
+            });
+        });
+
+        kat
+    }
+
+    #[test]
+    fn kat_interactive_lte2party() {
+        if default_prg_selected() {
+            run(
+                ParameterSelector::InteractiveLTE2Party,
+                "interactive_lte2party",
+            )
+            .check();
+        }
+    }
+
+    #[test]
+    fn kat_interactive_lte4party() {
+        if default_prg_selected() {
+            run(
+                ParameterSelector::InteractiveLTE4Party,
+                "interactive_lte4party",
+            )
+            .check();
+        }
+    }
+
+    #[test]
+    fn kat_interactive_lte8party() {
+        if default_prg_selected() {
+            run(
+                ParameterSelector::InteractiveLTE8Party,
+                "interactive_lte8party",
+            )
+            .check();
+        }
+    }
+}
+
+#[cfg(feature = "non_interactive_mp")]
+mod non_interactive {
+    use itertools::Itertools;
+
+    use crate::{
+        bool::{
+            evaluator::NonInteractiveMultiPartyCrs,
+            keys::{
+                kat::{client_key_seed, non_interactive_server_key_share_digest, update_matrix},
+                NonInteractiveServerKeyEvaluationDomain,
+                ShoupNonInteractiveServerKeyEvaluationDomain,
+            },
+            ni_mp_api::{gen_client_key, set_parameter_set, BoolEvaluator, ParameterSelector},
+            parameters::BoolParameters,
+            BooleanGates,
+        },
+        ntt::NttBackendU64,
+        random::{with_seeded_rng, DefaultSecureRng},
+        utils::WithLocal,
+        Encryptor, MultiPartyDecryptor,
+    };
+
+    use super::*;
+
+    fn run(selector: ParameterSelector, name: &'static str) -> Kat {
+        set_parameter_set(selector);
+        let cr_seed = NonInteractiveMultiPartyCrs::new(
+            KAT_CRS_SEED,
+            [0u8; 32],
+            BoolEvaluator::with_local(|e| e.parameters().id()),
+        );
+        let mut kat = Kat::new(name);
+
+        with_seeded_rng(KAT_RNG_SEED, || {
+            let cks = (0..KAT_PARTIES).map(|_| gen_client_key()).collect_vec();
+            cks.iter().enumerate().for_each(|(i, ck)| {
+                kat.push(format!("client_key_seed.{i}"), hex(&client_key_seed(ck)));
+            });
+
+            let server_key_shares = cks
+                .iter()
+                .enumerate()
+                .map(|(user_id, k)| {
+                    BoolEvaluator::with_local(|e| {
+                        e.gen_non_interactive_multi_party_key_share(
+                            &cr_seed,
+                            user_id,
+                            KAT_PARTIES,
+                            k,
+                        )
+                    })
+                })
+                .collect_vec();
+            server_key_shares.iter().enumerate().for_each(|(i, s)| {
+                kat.push_digest(
+                    format!("server_key_share.{i}"),
+                    non_interactive_server_key_share_digest(s),
+                );
+            });
+            let seeded_server_key = BoolEvaluator::with_local(|e| {
+                e.aggregate_non_interactive_multi_party_server_key_shares(
+                    &cr_seed,
+                    &server_key_shares,
+                )
+            });
+            let server_key = ShoupNonInteractiveServerKeyEvaluationDomain::from(
+                NonInteractiveServerKeyEvaluationDomain::<
+                    _,
+                    BoolParameters<u64>,
+                    DefaultSecureRng,
+                    NttBackendU64,
+                >::from(&seeded_server_key),
+            );
+            let pk = seeded_server_key.public_key();
+
+            // seeded encryption under client's secret
+            let (m0, m1) = KAT_INPUTS;
+            let (seeded_cts, seed): (Vec<Vec<u64>>, [u8; 32]) = cks[0].encrypt(&[m0, m1][..]);
+            let mut hasher = Sha256::new();
+            update_matrix(&mut hasher, &seeded_cts);
+            hasher.update(seed);
+            kat.push_digest("seeded_ciphertext", hasher.finalize().into());
+
+            let ct0: Vec<u64> = pk.encrypt(&m0);
+            let ct1: Vec<u64> = pk.encrypt(&m1);
+            kat.push_digest("ciphertext.0", row_digest(&ct0));
+            kat.push_digest("ciphertext.1", row_digest(&ct1));
+
+            let outputs = BoolEvaluator::with_local_mut(|e| {
+                vec![
+                    ("nand", e.nand(&ct0, &ct1, &server_key), !(m0 && m1)),
+                    ("xor", e.xor(&ct0, &ct1, &server_key), m0 ^ m1),
+                    ("or", e.or(&ct0, &ct1, &server_key), m0 || m1),
+                ]
+            });
+            outputs.iter().for_each(|(gate, ct, m_expected)| {
+                kat.push_digest(format!("pbs.{gate}"), row_digest(ct));
+                let shares = cks.iter().map(|k| k.gen_decryption_share(ct)).collect_vec();
+                shares.iter().enumerate().for_each(|(i, s)| {
+                    kat.push(format!("pbs.{gate}.decryption_share.{i}"), s);
+                });
+                let m = cks[0].aggregate_decryption_shares(ct, &shares);
+                assert_eq!(m, *m_expected);
+                kat.push(format!("pbs.{gate}.output"), m);
+            });
+        });
+
+        kat
+    }
+
+    #[test]
+    fn kat_non_interactive_lte2party() {
+        if default_prg_selected() {
+            run(
+                ParameterSelector::NonInteractiveLTE2Party,
+                "non_interactive_lte2party",
+            )
+            .check();
+        }
+    }
+
+    #[test]
+    fn kat_non_interactive_lte4party() {
+        if default_prg_selected() {
+            run(
+                ParameterSelector::NonInteractiveLTE4Party,
+                "non_interactive_lte4party",
+            )
+            .check();
+        }
+    }
+
+    #[test]
+    fn kat_non_interactive_lte8party() {
+        if default_prg_selected() {
+            run(
+                ParameterSelector::NonInteractiveLTE8Party,
+                "non_interactive_lte8party",
+            )
+            .check();
+        }
+    }
+}
@@ -1,32 +1,68 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::{collections::HashMap, marker::PhantomData, ops::Deref, sync::RwLock};
+
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
 
 use crate::{
-    backend::{ModInit, VectorOps},
-    pbs::WithShoupRepr,
+    backend::{ModInit, Modulus, VectorOps},
+    fft::FftBackendF64,
+    ntt::Ntt,
+    pbs::{FourierCt, WithShoupRepr},
     random::{NewWithSeed, RandomFillUniformInModulus},
-    utils::ToShoup,
+    utils::{wipe, ToShoup},
     Matrix, MatrixEntity, MatrixMut, RowEntity, RowMut,
 };
 
-use super::parameters::{BoolParameters, CiphertextModulus};
+use super::parameters::{BoolParameters, CiphertextModulus, LweKsElement};
+#[cfg(feature = "non_interactive_mp")]
+use crate::proof::ShortWitnessProof;
+
+/// Secret vector, for ex. RLWE or LWE secret, derived from client key
+///
+/// With `zeroize` feature the vector is wiped when dropped
+pub(crate) struct SecretVector<E: Zero + Copy>(Vec<E>);
+
+impl<E: Zero + Copy> From<Vec<E>> for SecretVector<E> {
+    fn from(value: Vec<E>) -> Self {
+        SecretVector(value)
+    }
+}
+
+impl<E: Zero + Copy> Deref for SecretVector<E> {
+    type Target = [E];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Zero + Copy> AsRef<[E]> for SecretVector<E> {
+    fn as_ref(&self) -> &[E] {
+        &self.0
+    }
+}
+
+impl<E: Zero + Copy> Drop for SecretVector<E> {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
 
 pub(crate) trait SinglePartyClientKey {
-    type Element;
-    fn sk_rlwe(&self) -> Vec<Self::Element>;
-    fn sk_lwe(&self) -> Vec<Self::Element>;
+    type Element: Zero + Copy;
+    fn sk_rlwe(&self) -> SecretVector<Self::Element>;
+    fn sk_lwe(&self) -> SecretVector<Self::Element>;
 }
 
 pub(crate) trait InteractiveMultiPartyClientKey {
-    type Element;
-    fn sk_rlwe(&self) -> Vec<Self::Element>;
-    fn sk_lwe(&self) -> Vec<Self::Element>;
+    type Element: Zero + Copy;
+    fn sk_rlwe(&self) -> SecretVector<Self::Element>;
+    fn sk_lwe(&self) -> SecretVector<Self::Element>;
 }
 
 pub(crate) trait NonInteractiveMultiPartyClientKey {
-    type Element;
-    fn sk_rlwe(&self) -> Vec<Self::Element>;
-    fn sk_u_rlwe(&self) -> Vec<Self::Element>;
-    fn sk_lwe(&self) -> Vec<Self::Element>;
+    type Element: Zero + Copy;
+    fn sk_rlwe(&self) -> SecretVector<Self::Element>;
+    fn sk_u_rlwe(&self) -> SecretVector<Self::Element>;
+    fn sk_lwe(&self) -> SecretVector<Self::Element>;
 }
 
 /// Client key
@@ -46,37 +82,45 @@ pub(crate) trait NonInteractiveMultiPartyClientKey {
 ///
 ///     Puncture 3 -> Seed of RLWE secret used as `u` in
 ///                   non-interactive multi-party.
+///
+/// With `zeroize` feature the seed is wiped when client key is dropped.
 #[derive(Clone)]
-pub struct ClientKey<S, E> {
+pub struct ClientKey<S: AsMut<[u8]>, E> {
     seed: S,
     parameters: BoolParameters<E>,
 }
 
+impl<S: AsMut<[u8]>, E> Drop for ClientKey<S, E> {
+    fn drop(&mut self) {
+        wipe(self.seed.as_mut());
+    }
+}
+
 mod impl_ck {
     use crate::{
         parameters::SecretKeyDistribution,
         random::{DefaultSecureRng, RandomFillGaussian},
-        utils::{fill_random_ternary_secret_with_hamming_weight, puncture_p_rng},
+        utils::{fill_random_ternary_secret_with_hamming_weight, puncture_p_rng, WithLocal},
     };
 
     use super::*;
 
     impl<E> ClientKey<[u8; 32], E> {
         pub(in super::super) fn new(parameters: BoolParameters<E>) -> ClientKey<[u8; 32], E> {
-            let mut rng = DefaultSecureRng::new();
             let mut seed = [0u8; 32];
-            rng.fill_bytes(&mut seed);
+            DefaultSecureRng::with_local_mut_mut(&mut |rng| rng.fill_bytes(&mut seed));
             Self { seed, parameters }
         }
     }
 
     impl<E> SinglePartyClientKey for ClientKey<[u8; 32], E> {
         type Element = i32;
-        fn sk_lwe(&self) -> Vec<Self::Element> {
+        fn sk_lwe(&self) -> SecretVector<Self::Element> {
             let mut p_rng = DefaultSecureRng::new_seeded(self.seed);
-            let lwe_seed = puncture_p_rng::<[u8; 32], DefaultSecureRng>(&mut p_rng, 2);
+            let mut lwe_seed = puncture_p_rng::<[u8; 32], DefaultSecureRng>(&mut p_rng, 2);
 
             let mut lwe_prng = DefaultSecureRng::new_seeded(lwe_seed);
+            wipe(&mut lwe_seed);
 
             let mut out = vec![0i32; self.parameters.lwe_n().0];
 
@@ -92,35 +136,36 @@ mod impl_ck {
                     );
                 }
             }
-            out
+            out.into()
         }
-        fn sk_rlwe(&self) -> Vec<Self::Element> {
+        fn sk_rlwe(&self) -> SecretVector<Self::Element> {
             assert!(
                 self.parameters.rlwe_secret_key_dist()
                     == &SecretKeyDistribution::TernaryDistribution
             );
 
             let mut p_rng = DefaultSecureRng::new_seeded(self.seed);
-            let rlwe_seed = puncture_p_rng::<[u8; 32], DefaultSecureRng>(&mut p_rng, 1);
+            let mut rlwe_seed = puncture_p_rng::<[u8; 32], DefaultSecureRng>(&mut p_rng, 1);
 
             let mut rlwe_prng = DefaultSecureRng::new_seeded(rlwe_seed);
+            wipe(&mut rlwe_seed);
             let mut out = vec![0i32; self.parameters.rlwe_n().0];
             fill_random_ternary_secret_with_hamming_weight(
                 &mut out,
                 self.parameters.rlwe_n().0 >> 1,
                 &mut rlwe_prng,
             );
-            out
+            out.into()
         }
     }
 
     #[cfg(feature = "interactive_mp")]
     impl<E> InteractiveMultiPartyClientKey for ClientKey<[u8; 32], E> {
         type Element = i32;
-        fn sk_lwe(&self) -> Vec<Self::Element> {
+        fn sk_lwe(&self) -> SecretVector<Self::Element> {
             <Self as SinglePartyClientKey>::sk_lwe(&self)
         }
-        fn sk_rlwe(&self) -> Vec<Self::Element> {
+        fn sk_rlwe(&self) -> SecretVector<Self::Element> {
             <Self as SinglePartyClientKey>::sk_rlwe(&self)
         }
     }
@@ -128,29 +173,30 @@ mod impl_ck {
     #[cfg(feature = "non_interactive_mp")]
     impl<E> NonInteractiveMultiPartyClientKey for ClientKey<[u8; 32], E> {
         type Element = i32;
-        fn sk_lwe(&self) -> Vec<Self::Element> {
+        fn sk_lwe(&self) -> SecretVector<Self::Element> {
             <Self as SinglePartyClientKey>::sk_lwe(&self)
         }
-        fn sk_rlwe(&self) -> Vec<Self::Element> {
+        fn sk_rlwe(&self) -> SecretVector<Self::Element> {
             <Self as SinglePartyClientKey>::sk_rlwe(&self)
         }
-        fn sk_u_rlwe(&self) -> Vec<Self::Element> {
+        fn sk_u_rlwe(&self) -> SecretVector<Self::Element> {
             assert!(
                 self.parameters.rlwe_secret_key_dist()
                     == &SecretKeyDistribution::TernaryDistribution
             );
 
             let mut p_rng = DefaultSecureRng::new_seeded(self.seed);
-            let rlwe_seed = puncture_p_rng::<[u8; 32], DefaultSecureRng>(&mut p_rng, 3);
+            let mut rlwe_seed = puncture_p_rng::<[u8; 32], DefaultSecureRng>(&mut p_rng, 3);
 
             let mut rlwe_prng = DefaultSecureRng::new_seeded(rlwe_seed);
+            wipe(&mut rlwe_seed);
             let mut out = vec![0i32; self.parameters.rlwe_n().0];
             fill_random_ternary_secret_with_hamming_weight(
                 &mut out,
                 self.parameters.rlwe_n().0 >> 1,
                 &mut rlwe_prng,
             );
-            out
+            out.into()
         }
     }
 }
@@ -158,16 +204,28 @@ mod impl_ck {
 /// Public key
 pub struct PublicKey<M, Rng, ModOp> {
     key: M,
+    /// Transcript of round 1 if public key is aggregated from interactive
+    /// multi-party public key shares
+    transcript: Option<InteractiveMultiPartyTranscript>,
     _phantom: PhantomData<(Rng, ModOp)>,
 }
 
 pub(super) mod impl_pk {
+    use num_traits::ToPrimitive;
+
     use super::*;
 
     impl<M, R, Mo> PublicKey<M, R, Mo> {
         pub(in super::super) fn key(&self) -> &M {
             &self.key
         }
+
+        /// Transcript of round 1 of interactive multi-party protocol. Returns
+        /// None if public key is not aggregated from interactive multi-party
+        /// public key shares.
+        pub fn transcript(&self) -> Option<&InteractiveMultiPartyTranscript> {
+            self.transcript.as_ref()
+        }
     }
 
     impl<
@@ -198,6 +256,7 @@ pub(super) mod impl_pk {
 
             PublicKey {
                 key,
+                transcript: None,
                 _phantom: PhantomData,
             }
         }
@@ -218,8 +277,8 @@ pub(super) mod impl_pk {
         > for PublicKey<M, Rng, ModOp>
     where
         <M as Matrix>::R: RowMut,
-        Rng::Seed: Copy + PartialEq,
-        M::MatElement: PartialEq + Copy,
+        Rng::Seed: Copy + PartialEq + AsRef<[u8]>,
+        M::MatElement: PartialEq + Copy + ToPrimitive,
     {
         fn from(
             value: &[CommonReferenceSeededCollectivePublicKeyShare<
@@ -251,8 +310,75 @@ pub(super) mod impl_pk {
                 rlweq_modop.elwise_add_mut(key.get_row_mut(1), share_i.share.as_ref());
             });
 
+            // Shares are ordered by user_id
+            let transcript = InteractiveMultiPartyTranscript::new(
+                seed.as_ref(),
+                value.iter().map(|share_i| share_i.commitment()).collect(),
+            );
+
+            PublicKey {
+                key,
+                transcript: Some(transcript),
+                _phantom: PhantomData,
+            }
+        }
+    }
+}
+
+mod impl_ni_pk {
+    use crate::{bool::evaluator::NonInteractiveMultiPartyCrs, random::RandomFill};
+
+    use super::*;
+
+    impl<M, Rng, ModOp>
+        From<
+            &SeededNonInteractiveMultiPartyServerKey<
+                M,
+                NonInteractiveMultiPartyCrs<Rng::Seed>,
+                BoolParameters<M::MatElement>,
+            >,
+        > for PublicKey<M, Rng, ModOp>
+    where
+        M: MatrixMut + MatrixEntity,
+        Rng: NewWithSeed
+            + RandomFillUniformInModulus<[M::MatElement], CiphertextModulus<M::MatElement>>
+            + RandomFill<Rng::Seed>,
+        <M as Matrix>::R: RowMut,
+        M::MatElement: Copy,
+        Rng::Seed: Copy + Default + AsRef<[u8]> + From<[u8; 32]>,
+    {
+        /// Derive collective public key under ideal RLWE secret `s` from seeded
+        /// non-interactive multi-party server key
+        fn from(
+            value: &SeededNonInteractiveMultiPartyServerKey<
+                M,
+                NonInteractiveMultiPartyCrs<Rng::Seed>,
+                BoolParameters<M::MatElement>,
+            >,
+        ) -> Self {
+            assert_eq!(
+                value.cr_seed.prg,
+                Rng::ALGORITHM,
+                "Server key was seeded with a different PRG"
+            );
+
+            let mut key = M::zeros(2, value.parameters.rlwe_n().0);
+
+            // sample A
+            let mut p_rng = Rng::new_with_seed(value.cr_seed.public_key_share_seed());
+            RandomFillUniformInModulus::random_fill(
+                &mut p_rng,
+                value.parameters.rlwe_q(),
+                key.get_row_mut(0),
+            );
+
+            // Copy over B
+            key.get_row_mut(1)
+                .copy_from_slice(value.public_key.as_ref());
+
             PublicKey {
                 key,
+                transcript: None,
                 _phantom: PhantomData,
             }
         }
@@ -328,6 +454,124 @@ impl<Ro, S, P> CommonReferenceSeededCollectivePublicKeyShare<Ro, S, P> {
             parameters,
         }
     }
+
+    pub(super) fn share(&self) -> &Ro {
+        &self.share
+    }
+
+    pub(super) fn cr_seed(&self) -> &S {
+        &self.cr_seed
+    }
+
+    pub(super) fn parameters(&self) -> &P {
+        &self.parameters
+    }
+}
+
+/// Transcript of round 1 of interactive multi-party protocol
+///
+/// Transcript commits to public key share of each party and binds the
+/// commitments to the common reference seed. Transcript is derived when public
+/// key shares are aggregated and is included in each party's round 2 server key
+/// share. Thus if a party sends different public key shares to different
+/// parties, transcripts included in round 2 shares will not match and the
+/// server detects it before aggregating server key shares.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InteractiveMultiPartyTranscript {
+    /// Commitment to public key share of party with user_id `i` is stored at
+    /// index `i`
+    public_key_share_commitments: Vec<[u8; 32]>,
+    /// Hash of common reference seed and all public key share commitments
+    digest: [u8; 32],
+}
+
+mod impl_transcript {
+    use num_traits::ToPrimitive;
+    use sha2::{Digest, Sha256};
+
+    use crate::Row;
+
+    use super::*;
+
+    const PUBLIC_KEY_SHARE_DOMAIN: &[u8] = b"phantom-zone/interactive/public-key-share";
+    const TRANSCRIPT_DOMAIN: &[u8] = b"phantom-zone/interactive/transcript";
+
+    impl<Ro: Row, S: AsRef<[u8]>, P> CommonReferenceSeededCollectivePublicKeyShare<Ro, S, P>
+    where
+        Ro::Element: ToPrimitive,
+    {
+        /// Commitment to public key share
+        ///
+        /// Commitment is hash of the common reference seed and the public key
+        /// share polynomial. Parties can broadcast the commitment before
+        /// broadcasting the share and check, once the public key is
+        /// aggregated, that the transcript commits to the same shares.
+        pub fn commitment(&self) -> [u8; 32] {
+            let mut hasher = Sha256::new();
+            hasher.update(PUBLIC_KEY_SHARE_DOMAIN);
+            hasher.update(self.cr_seed.as_ref());
+            self.share.as_ref().iter().for_each(|v| {
+                hasher.update(v.to_u64().unwrap().to_le_bytes());
+            });
+            hasher.finalize().into()
+        }
+    }
+
+    impl InteractiveMultiPartyTranscript {
+        pub(in super::super) fn new(
+            cr_seed: &[u8],
+            public_key_share_commitments: Vec<[u8; 32]>,
+        ) -> Self {
+            let digest = Self::compute_digest(cr_seed, &public_key_share_commitments);
+            InteractiveMultiPartyTranscript {
+                public_key_share_commitments,
+                digest,
+            }
+        }
+
+        pub(in super::super) fn compute_digest(
+            cr_seed: &[u8],
+            public_key_share_commitments: &[[u8; 32]],
+        ) -> [u8; 32] {
+            let mut hasher = Sha256::new();
+            hasher.update(TRANSCRIPT_DOMAIN);
+            hasher.update(cr_seed);
+            hasher.update((public_key_share_commitments.len() as u64).to_le_bytes());
+            public_key_share_commitments.iter().for_each(|c| {
+                hasher.update(c);
+            });
+            hasher.finalize().into()
+        }
+
+        /// Digest of the transcript
+        pub fn digest(&self) -> &[u8; 32] {
+            &self.digest
+        }
+
+        /// Commitments to public key shares. Commitment to public key share of
+        /// party with user_id `i` is at index `i`.
+        pub fn public_key_share_commitments(&self) -> &[[u8; 32]] {
+            &self.public_key_share_commitments
+        }
+
+        /// Returns true if transcript commits to `share` as the public key share
+        /// of party with `user_id`
+        ///
+        /// Each party must check its own round 1 share before generating its
+        /// round 2 share.
+        pub fn contains_public_key_share<Ro: Row, S: AsRef<[u8]>, P>(
+            &self,
+            user_id: usize,
+            share: &CommonReferenceSeededCollectivePublicKeyShare<Ro, S, P>,
+        ) -> bool
+        where
+            Ro::Element: ToPrimitive,
+        {
+            self.public_key_share_commitments
+                .get(user_id)
+                .is_some_and(|c| c == &share.commitment())
+        }
+    }
 }
 
 /// Common reference seed seeded interactive multi-party server key share
@@ -357,9 +601,12 @@ pub struct CommonReferenceSeededInteractiveMultiPartyServerKeyShare<M: Matrix, P
     ///
     /// User id must be unique and  a number in range [0, total_users)
     user_id: usize,
+    /// Transcript of round 1 as seen by the party
+    pub(super) transcript: InteractiveMultiPartyTranscript,
 }
 
 impl<M: Matrix, P, S> CommonReferenceSeededInteractiveMultiPartyServerKeyShare<M, P, S> {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         self_leader_rgsws: Vec<M>,
         not_self_leader_rgsws: Vec<M>,
@@ -368,6 +615,7 @@ impl<M: Matrix, P, S> CommonReferenceSeededInteractiveMultiPartyServerKeyShare<M
         cr_seed: S,
         parameters: P,
         user_id: usize,
+        transcript: InteractiveMultiPartyTranscript,
     ) -> Self {
         CommonReferenceSeededInteractiveMultiPartyServerKeyShare {
             self_leader_rgsws,
@@ -377,9 +625,15 @@ impl<M: Matrix, P, S> CommonReferenceSeededInteractiveMultiPartyServerKeyShare<M
             cr_seed,
             parameters,
             user_id,
+            transcript,
         }
     }
 
+    /// Transcript of round 1 included in the share
+    pub fn transcript(&self) -> &InteractiveMultiPartyTranscript {
+        &self.transcript
+    }
+
     pub(super) fn cr_seed(&self) -> &S {
         &self.cr_seed
     }
@@ -423,7 +677,7 @@ pub struct SeededInteractiveMultiPartyServerKey<M: Matrix, S, P> {
     /// s}(m) to LWE_{q, z}(m) where s is ideal RLWE secret and z is ideal LWE
     /// secret.
     lwe_ksk: M::R,
-    /// Common reference seed
+    /// Common reference seed of round 2, ie bound to round 1 transcript
     cr_seed: S,
     parameters: P,
 }
@@ -679,7 +933,7 @@ pub(super) mod impl_server_key_eval_domain {
         > for ServerKeyEvaluationDomain<M, BoolParameters<M::MatElement>, Rng, N>
     where
         <M as Matrix>::R: RowMut,
-        Rng::Seed: Copy + Default,
+        Rng::Seed: Copy + Default + AsRef<[u8]> + From<[u8; 32]>,
         Rng: RandomFillUniformInModulus<[M::MatElement], CiphertextModulus<M::MatElement>>
             + RandomFill<Rng::Seed>,
         M::MatElement: Copy,
@@ -691,6 +945,12 @@ pub(super) mod impl_server_key_eval_domain {
                 BoolParameters<M::MatElement>,
             >,
         ) -> Self {
+            assert_eq!(
+                value.cr_seed.prg,
+                Rng::ALGORITHM,
+                "Server key was seeded with a different PRG"
+            );
+
             let g = value.parameters.g() as isize;
             let rlwe_n = value.parameters.rlwe_n().0;
             let lwe_n = value.parameters.lwe_n().0;
@@ -702,7 +962,7 @@ pub(super) mod impl_server_key_eval_domain {
             // auto keys
             let mut auto_keys = HashMap::new();
             {
-                let mut auto_prng = Rng::new_with_seed(value.cr_seed.auto_keys_cts_seed::<Rng>());
+                let mut auto_prng = Rng::new_with_seed(value.cr_seed.auto_keys_cts_seed());
                 let auto_d_count = value.parameters.auto_decomposition_count().0;
                 let auto_element_dlogs = value.parameters.auto_element_dlogs();
                 for i in auto_element_dlogs.into_iter() {
@@ -757,7 +1017,7 @@ pub(super) mod impl_server_key_eval_domain {
                 .collect_vec();
 
             // lwe ksk
-            let mut lwe_ksk_prng = Rng::new_with_seed(value.cr_seed.lwe_ksk_cts_seed_seed::<Rng>());
+            let mut lwe_ksk_prng = Rng::new_with_seed(value.cr_seed.lwe_ksk_cts_seed_seed());
             let d_lwe = value.parameters.lwe_decomposition_count().0;
             let mut lwe_ksk = M::zeros(rlwe_n * d_lwe, lwe_n + 1);
             izip!(lwe_ksk.iter_rows_mut(), value.lwe_ksk.as_ref().iter()).for_each(
@@ -829,8 +1089,9 @@ pub(crate) struct NonInteractiveServerKeyEvaluationDomain<M, P, R, N> {
     /// LWE key switching key to key switch LWE_{q, s}(m) to LWE_{q, z}(m)
     lwe_ksk: M,
     /// Key switching key from user j's secret u_j to ideal RLWE secret key `s`
-    /// in evaluation domain. User j's key switching key is at j'th index.
-    ui_to_s_ksks: Vec<M>,
+    /// in evaluation domain. User j's key switching key is at j'th index. Key
+    /// switching key of user j is None if user j dropped out.
+    ui_to_s_ksks: Vec<Option<M>>,
     parameters: P,
     _phanton: PhantomData<(R, N)>,
 }
@@ -864,7 +1125,7 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
         N: Ntt<Element = M::MatElement> + NttInit<CiphertextModulus<M::MatElement>>,
         M::R: RowMut,
         M::MatElement: Copy,
-        Rng::Seed: Clone + Copy + Default,
+        Rng::Seed: Clone + Copy + Default + AsRef<[u8]> + From<[u8; 32]>,
     {
         fn from(
             value: &SeededNonInteractiveMultiPartyServerKey<
@@ -873,6 +1134,12 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
                 BoolParameters<M::MatElement>,
             >,
         ) -> Self {
+            assert_eq!(
+                value.cr_seed.prg,
+                Rng::ALGORITHM,
+                "Server key was seeded with a different PRG"
+            );
+
             let rlwe_nttop = N::new(value.parameters.rlwe_q(), value.parameters.rlwe_n().0);
             let ring_size = value.parameters.rlwe_n().0;
 
@@ -888,7 +1155,7 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
             // populate pseudo random part of auto keys. Then send auto keys to
             // evaluation domain
             let mut auto_keys = HashMap::new();
-            let auto_seed = value.cr_seed.auto_keys_cts_seed::<Rng>();
+            let auto_seed = value.cr_seed.auto_keys_cts_seed();
             let mut auto_prng = Rng::new_with_seed(auto_seed);
             let auto_element_dlogs = value.parameters.auto_element_dlogs();
             let d_auto = value.parameters.auto_decomposition_count().0;
@@ -929,7 +1196,7 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
             // LWE ksk
             // populate pseudo random part of lwe ciphertexts in ksk and copy over part b
             // elements
-            let lwe_ksk_seed = value.cr_seed.lwe_ksk_cts_seed::<Rng>();
+            let lwe_ksk_seed = value.cr_seed.lwe_ksk_cts_seed();
             let mut lwe_ksk_prng = Rng::new_with_seed(lwe_ksk_seed);
             let mut lwe_ksk = M::zeros(
                 value.parameters.lwe_decomposition_count().0 * ring_size,
@@ -964,7 +1231,8 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
                 .iter()
                 .enumerate()
                 .map(|(user_id, incoming_ksk_partb)| {
-                    let user_i_seed = value.cr_seed.ui_to_s_ks_seed_for_user_i::<Rng>(user_id);
+                    let incoming_ksk_partb = incoming_ksk_partb.as_ref()?;
+                    let user_i_seed = value.cr_seed.ui_to_s_ks_seed_for_user_i(user_id);
                     let mut prng = Rng::new_with_seed(user_i_seed);
 
                     let mut ksk_ct = M::zeros(d_uitos * 2, ring_size);
@@ -989,7 +1257,7 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
                     ksk_ct
                         .iter_rows_mut()
                         .for_each(|r| rlwe_nttop.forward(r.as_mut()));
-                    ksk_ct
+                    Some(ksk_ct)
                 })
                 .collect_vec();
 
@@ -1028,14 +1296,18 @@ pub(super) mod impl_non_interactive_server_key_eval_domain {
 /// `BoolEvaluator::aggregate_non_interactive_multi_party_key_share`
 pub struct SeededNonInteractiveMultiPartyServerKey<M: Matrix, S, P> {
     /// Key switching key from user j's secret u_j to ideal RLWE secret key `s`.
-    /// User j's key switching key is at j'th index.
-    ui_to_s_ksks: Vec<M>,
+    /// User j's key switching key is at j'th index. Key switching key of user
+    /// j is None if user j dropped out before aggregation.
+    ui_to_s_ksks: Vec<Option<M>>,
     /// RGSW ciphertexts RGSW(X^{s[i]}) under ideal RLWE secret key
     rgsw_cts: Vec<M>,
     /// Auto keys for all auto elements [-g, g, g^2, g^w]
     auto_keys: HashMap<usize, M>,
     /// LWE key switching key to key switch LWE_{q, s}(m) to LWE_{q, z}(m)
     lwe_ksk: M::R,
+    /// Part B of collective public key under ideal RLWE secret key `s`. Part A
+    /// is derived from common reference seed.
+    public_key: M::R,
     /// Common reference seed
     cr_seed: S,
     parameters: P,
@@ -1043,10 +1315,11 @@ pub struct SeededNonInteractiveMultiPartyServerKey<M: Matrix, S, P> {
 
 impl<M: Matrix, S, P> SeededNonInteractiveMultiPartyServerKey<M, S, P> {
     pub(super) fn new(
-        ui_to_s_ksks: Vec<M>,
+        ui_to_s_ksks: Vec<Option<M>>,
         rgsw_cts: Vec<M>,
         auto_keys: HashMap<usize, M>,
         lwe_ksk: M::R,
+        public_key: M::R,
         cr_seed: S,
         parameters: P,
     ) -> Self {
@@ -1056,6 +1329,7 @@ impl<M: Matrix, S, P> SeededNonInteractiveMultiPartyServerKey<M, S, P> {
             rgsw_cts,
             auto_keys,
             lwe_ksk,
+            public_key,
             cr_seed,
             parameters,
         }
@@ -1065,11 +1339,18 @@ impl<M: Matrix, S, P> SeededNonInteractiveMultiPartyServerKey<M, S, P> {
 /// This key is equivalent to NonInteractiveServerKeyEvaluationDomain with the
 /// addition that each polynomial in evaluation domain has a corresponding shoup
 /// representation suitable for shoup multiplication.
-pub(crate) struct ShoupNonInteractiveServerKeyEvaluationDomain<M> {
-    rgsw_cts: Vec<NormalAndShoup<M>>,
-    auto_keys: HashMap<usize, NormalAndShoup<M>>,
-    lwe_ksk: M,
-    ui_to_s_ksks: Vec<NormalAndShoup<M>>,
+///
+/// RGSW ciphertexts and auto keys are stored in representation `C`, which
+/// selects the backend for blind rotation (see `FromEvaluationDomain`).
+pub(crate) struct ShoupNonInteractiveServerKeyEvaluationDomain<M, C = NormalAndShoup<M>> {
+    rgsw_cts: Vec<C>,
+    auto_keys: HashMap<usize, C>,
+    /// LWE key switching key with elements mod q stored as `LweKsElement`
+    lwe_ksk: Vec<Vec<LweKsElement>>,
+    ui_to_s_ksks: Vec<Option<NormalAndShoup<M>>>,
+    /// Key switching keys of users that joined after the server key was
+    /// generated. k^th late joining user's user_id is `ui_to_s_ksks.len() + k`
+    late_joining_ui_to_s_ksks: RwLock<Vec<NormalAndShoup<M>>>,
 }
 
 mod impl_shoup_non_interactive_server_key_eval_domain {
@@ -1077,24 +1358,85 @@ mod impl_shoup_non_interactive_server_key_eval_domain {
     use num_traits::{FromPrimitive, PrimInt, ToPrimitive};
 
     use super::*;
-    use crate::{backend::Modulus, decomposer::NumInfo, pbs::PbsKey};
+    use crate::{backend::Modulus, decomposer::NumInfo, pbs::PbsKey, Ntt, NttInit};
+
+    impl<M, C> ShoupNonInteractiveServerKeyEvaluationDomain<M, C> {
+        /// Calls `func` with key switching key from user_id's RLWE secret `u_j`
+        /// to ideal RLWE secret `s`
+        pub(in super::super) fn with_ui_to_s_ksk<F, R>(&self, user_id: usize, func: F) -> R
+        where
+            F: FnOnce(&NormalAndShoup<M>) -> R,
+        {
+            if user_id < self.ui_to_s_ksks.len() {
+                func(
+                    self.ui_to_s_ksks[user_id]
+                        .as_ref()
+                        .unwrap_or_else(|| panic!("User {user_id} dropped out of the protocol")),
+                )
+            } else {
+                let late_joining_ksks = self.late_joining_ui_to_s_ksks.read().unwrap();
+                func(
+                    late_joining_ksks
+                        .get(user_id - self.ui_to_s_ksks.len())
+                        .unwrap_or_else(|| panic!("User {user_id} has not joined the protocol")),
+                )
+            }
+        }
+    }
 
-    impl<M> ShoupNonInteractiveServerKeyEvaluationDomain<M> {
-        pub(in super::super) fn ui_to_s_ksk(&self, user_id: usize) -> &NormalAndShoup<M> {
-            &self.ui_to_s_ksks[user_id]
+    impl<M: MatrixMut + Clone + ToShoup<Modulus = M::MatElement>, C>
+        ShoupNonInteractiveServerKeyEvaluationDomain<M, C>
+    where
+        M::R: RowMut,
+        M::MatElement: FromPrimitive + ToPrimitive + PrimInt + NumInfo,
+    {
+        /// Adds key switching key of the late joining user and returns user's
+        /// user_id
+        pub(in super::super) fn add_late_joining_user<
+            N: Ntt<Element = M::MatElement> + NttInit<CiphertextModulus<M::MatElement>>,
+        >(
+            &self,
+            key_share: &NonInteractiveLateJoiningKeyShare<M, BoolParameters<M::MatElement>>,
+        ) -> usize {
+            let parameters = key_share.parameters();
+            let rlwe_nttop = N::new(parameters.rlwe_q(), parameters.rlwe_n().0);
+
+            let mut ksk = key_share.ui_to_s_ksk().clone();
+            assert!(
+                ksk.dimension()
+                    == (
+                        parameters
+                            .non_interactive_ui_to_s_key_switch_decomposition_count()
+                            .0
+                            * 2,
+                        parameters.rlwe_n().0
+                    )
+            );
+            ksk.iter_rows_mut()
+                .for_each(|r| rlwe_nttop.forward(r.as_mut()));
+
+            let mut late_joining_ksks = self.late_joining_ui_to_s_ksks.write().unwrap();
+            late_joining_ksks.push(NormalAndShoup::new_with_modulus(
+                ksk,
+                parameters.rlwe_q().q().unwrap(),
+            ));
+            self.ui_to_s_ksks.len() + late_joining_ksks.len() - 1
         }
     }
 
-    impl<M: Matrix + ToShoup<Modulus = M::MatElement>, R, N>
+    impl<M: Matrix + ToShoup<Modulus = M::MatElement>, C: FromEvaluationDomain<M>, R, N>
         From<NonInteractiveServerKeyEvaluationDomain<M, BoolParameters<M::MatElement>, R, N>>
-        for ShoupNonInteractiveServerKeyEvaluationDomain<M>
+        for ShoupNonInteractiveServerKeyEvaluationDomain<M, C>
     where
         M::MatElement: FromPrimitive + ToPrimitive + PrimInt + NumInfo,
+        N: Ntt<Element = M::MatElement> + NttInit<CiphertextModulus<M::MatElement>>,
     {
         fn from(
             value: NonInteractiveServerKeyEvaluationDomain<M, BoolParameters<M::MatElement>, R, N>,
         ) -> Self {
-            let rlwe_q = value.parameters.rlwe_q().q().unwrap();
+            let rlwe_q = value.parameters.rlwe_q();
+            let ntt_op = N::new(rlwe_q, value.parameters.rlwe_n().0);
+            let fft_op = value.parameters.blind_rotation_fft_op();
 
             let rgsw_dim = (
                 value.parameters.rlwe_rgsw_decomposition_count().0 .0 * 2
@@ -1106,7 +1448,7 @@ mod impl_shoup_non_interactive_server_key_eval_domain {
                 .into_iter()
                 .map(|m| {
                     assert!(m.dimension() == rgsw_dim);
-                    NormalAndShoup::new_with_modulus(m, rlwe_q)
+                    C::from_evaluation_domain(m, rlwe_q, &ntt_op, &fft_op)
                 })
                 .collect_vec();
 
@@ -1117,7 +1459,7 @@ mod impl_shoup_non_interactive_server_key_eval_domain {
             let mut auto_keys = HashMap::new();
             value.auto_keys.into_iter().for_each(|(k, v)| {
                 assert!(v.dimension() == auto_dim);
-                auto_keys.insert(k, NormalAndShoup::new_with_modulus(v, rlwe_q));
+                auto_keys.insert(k, C::from_evaluation_domain(v, rlwe_q, &ntt_op, &fft_op));
             });
 
             let ui_ks_dim = (
@@ -1132,8 +1474,10 @@ mod impl_shoup_non_interactive_server_key_eval_domain {
                 .ui_to_s_ksks
                 .into_iter()
                 .map(|m| {
-                    assert!(m.dimension() == ui_ks_dim);
-                    NormalAndShoup::new_with_modulus(m, rlwe_q)
+                    m.map(|m| {
+                        assert!(m.dimension() == ui_ks_dim);
+                        NormalAndShoup::new_with_modulus(m, rlwe_q.q().unwrap())
+                    })
                 })
                 .collect_vec();
 
@@ -1148,16 +1492,17 @@ mod impl_shoup_non_interactive_server_key_eval_domain {
             Self {
                 rgsw_cts,
                 auto_keys,
-                lwe_ksk: value.lwe_ksk,
+                lwe_ksk: lwe_ksk_to_ks_element(&value.lwe_ksk),
                 ui_to_s_ksks,
+                late_joining_ui_to_s_ksks: RwLock::new(vec![]),
             }
         }
     }
 
-    impl<M: Matrix> PbsKey for ShoupNonInteractiveServerKeyEvaluationDomain<M> {
-        type AutoKey = NormalAndShoup<M>;
-        type LweKskKey = M;
-        type RgswCt = NormalAndShoup<M>;
+    impl<M: Matrix, C> PbsKey for ShoupNonInteractiveServerKeyEvaluationDomain<M, C> {
+        type AutoKey = C;
+        type LweKskKey = Vec<Vec<LweKsElement>>;
+        type RgswCt = C;
 
         fn galois_key_for_auto(&self, k: usize) -> &Self::AutoKey {
             let d = self.auto_keys.get(&k).unwrap();
@@ -1173,56 +1518,82 @@ mod impl_shoup_non_interactive_server_key_eval_domain {
     }
 }
 
+/// Returns LWE key switching key `lwe_ksk` with elements mod q stored as
+/// `LweKsElement`
+fn lwe_ksk_to_ks_element<M: Matrix>(lwe_ksk: &M) -> Vec<Vec<LweKsElement>>
+where
+    M::MatElement: ToPrimitive,
+{
+    lwe_ksk
+        .iter_rows()
+        .map(|r| {
+            r.as_ref()
+                .iter()
+                .map(|v| v.to_u64().and_then(LweKsElement::from_u64).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
 /// This is equivalent to ServerKeyEvaluationDomain with the addition that each
 /// polynomial in evaluation domain has corresponding shoup representation
 /// suitable for shoup multiplication.
-pub(crate) struct ShoupServerKeyEvaluationDomain<M> {
-    rgsw_cts: Vec<NormalAndShoup<M>>,
-    galois_keys: HashMap<usize, NormalAndShoup<M>>,
-    lwe_ksk: M,
+///
+/// RGSW ciphertexts and auto keys are stored in representation `C`, which
+/// selects the backend for blind rotation (see `FromEvaluationDomain`).
+pub(crate) struct ShoupServerKeyEvaluationDomain<M, C = NormalAndShoup<M>> {
+    rgsw_cts: Vec<C>,
+    galois_keys: HashMap<usize, C>,
+    /// LWE key switching key with elements mod q stored as `LweKsElement`
+    lwe_ksk: Vec<Vec<LweKsElement>>,
+    _phantom: PhantomData<M>,
 }
 
 mod shoup_server_key_eval_domain {
     use itertools::{izip, Itertools};
     use num_traits::{FromPrimitive, PrimInt};
 
-    use crate::{backend::Modulus, decomposer::NumInfo, pbs::PbsKey};
+    use crate::{decomposer::NumInfo, pbs::PbsKey, NttInit};
 
     use super::*;
 
-    impl<M: MatrixMut + MatrixEntity + ToShoup<Modulus = M::MatElement>, R, N>
+    impl<M: MatrixMut + MatrixEntity, C: FromEvaluationDomain<M>, R, N>
         From<ServerKeyEvaluationDomain<M, BoolParameters<M::MatElement>, R, N>>
-        for ShoupServerKeyEvaluationDomain<M>
+        for ShoupServerKeyEvaluationDomain<M, C>
     where
         <M as Matrix>::R: RowMut,
         M::MatElement: PrimInt + FromPrimitive + NumInfo,
+        N: Ntt<Element = M::MatElement> + NttInit<CiphertextModulus<M::MatElement>>,
     {
         fn from(value: ServerKeyEvaluationDomain<M, BoolParameters<M::MatElement>, R, N>) -> Self {
-            let q = value.parameters.rlwe_q().q().unwrap();
+            let q = value.parameters.rlwe_q();
+            let ntt_op = N::new(q, value.parameters.rlwe_n().0);
+            let fft_op = value.parameters.blind_rotation_fft_op();
             // Rgsw ciphertexts
             let rgsw_cts = value
                 .rgsw_cts
                 .into_iter()
-                .map(|ct| NormalAndShoup::new_with_modulus(ct, q))
+                .map(|ct| C::from_evaluation_domain(ct, q, &ntt_op, &fft_op))
                 .collect_vec();
 
             let mut auto_keys = HashMap::new();
             value.galois_keys.into_iter().for_each(|(index, key)| {
-                auto_keys.insert(index, NormalAndShoup::new_with_modulus(key, q));
+                auto_keys.insert(index, C::from_evaluation_domain(key, q, &ntt_op, &fft_op));
             });
 
             Self {
                 rgsw_cts,
                 galois_keys: auto_keys,
-                lwe_ksk: value.lwe_ksk,
+                lwe_ksk: lwe_ksk_to_ks_element(&value.lwe_ksk),
+                _phantom: PhantomData,
             }
         }
     }
 
-    impl<M: Matrix> PbsKey for ShoupServerKeyEvaluationDomain<M> {
-        type AutoKey = NormalAndShoup<M>;
-        type LweKskKey = M;
-        type RgswCt = NormalAndShoup<M>;
+    impl<M: Matrix, C> PbsKey for ShoupServerKeyEvaluationDomain<M, C> {
+        type AutoKey = C;
+        type LweKskKey = Vec<Vec<LweKsElement>>;
+        type RgswCt = C;
 
         fn galois_key_for_auto(&self, k: usize) -> &Self::AutoKey {
             self.galois_keys.get(&k).unwrap()
@@ -1237,6 +1608,21 @@ mod shoup_server_key_eval_domain {
     }
 }
 
+/// Key components of a non-interactive multi-party server key share generated
+/// by a user. See [CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare]
+/// for description of each component.
+#[cfg_attr(not(feature = "non_interactive_mp"), allow(dead_code))]
+pub(super) struct NonInteractiveServerKeyShareComponents<M: Matrix> {
+    pub(super) self_leader_ni_rgsw_cts: Vec<M>,
+    pub(super) not_self_leader_ni_rgsw_cts: Vec<M>,
+    pub(super) ni_rgsw_zero_encs: Vec<M>,
+    pub(super) ui_to_s_ksk: M,
+    pub(super) ksk_zero_encs_for_others: Vec<M>,
+    pub(super) auto_keys_share: HashMap<usize, M>,
+    pub(super) lwe_ksk_share: M::R,
+    pub(super) public_key_share: M::R,
+}
+
 #[derive(Clone)]
 pub struct CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<M: Matrix, P, S> {
     /// Non-interactive RGSW ciphertexts for LWE secret indices for which user
@@ -1275,6 +1661,9 @@ pub struct CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<M: Matrix
     /// LWE key switching key share to key switching LWE_{q, s}(m) to LWE_{q,
     /// z}(m)
     lwe_ksk_share: M::R,
+    /// Collective public key share `a*s_j + e` where `a` is derived from common
+    /// reference seed
+    public_key_share: M::R,
 
     /// User's id.
     ///
@@ -1297,19 +1686,23 @@ mod impl_common_ref_non_interactive_multi_party_server_share {
 
     impl<M: Matrix, P, S> CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<M, P, S> {
         pub(in super::super) fn new(
-            self_leader_ni_rgsw_cts: Vec<M>,
-            not_self_leader_ni_rgsw_cts: Vec<M>,
-            ni_rgsw_zero_encs: Vec<M>,
-            ui_to_s_ksk: M,
-            ksk_zero_encs_for_others: Vec<M>,
-            auto_keys_share: HashMap<usize, M>,
-            lwe_ksk_share: M::R,
+            components: NonInteractiveServerKeyShareComponents<M>,
             user_index: usize,
             total_users: usize,
             lwe_n: usize,
             cr_seed: S,
             parameters: P,
         ) -> Self {
+            let NonInteractiveServerKeyShareComponents {
+                self_leader_ni_rgsw_cts,
+                not_self_leader_ni_rgsw_cts,
+                ni_rgsw_zero_encs,
+                ui_to_s_ksk,
+                ksk_zero_encs_for_others,
+                auto_keys_share,
+                lwe_ksk_share,
+                public_key_share,
+            } = components;
             Self {
                 self_leader_ni_rgsw_cts,
                 not_self_leader_ni_rgsw_cts,
@@ -1318,6 +1711,7 @@ mod impl_common_ref_non_interactive_multi_party_server_share {
                 ksk_zero_encs_for_others,
                 auto_keys_share,
                 lwe_ksk_share,
+                public_key_share,
                 user_id: user_index,
                 total_users,
                 lwe_n,
@@ -1367,6 +1761,10 @@ mod impl_common_ref_non_interactive_multi_party_server_share {
             self.user_id
         }
 
+        pub(in super::super) fn total_users(&self) -> usize {
+            self.total_users
+        }
+
         pub(in super::super) fn auto_keys_share(&self) -> &HashMap<usize, M> {
             &self.auto_keys_share
         }
@@ -1375,6 +1773,10 @@ mod impl_common_ref_non_interactive_multi_party_server_share {
             &self.lwe_ksk_share
         }
 
+        pub(in super::super) fn public_key_share(&self) -> &M::R {
+            &self.public_key_share
+        }
+
         pub(in super::super) fn ui_to_s_ksk_zero_encs_for_user_i(&self, user_i: usize) -> &M {
             assert!(user_i != self.user_id);
             if user_i < self.user_id {
@@ -1394,6 +1796,482 @@ mod impl_common_ref_non_interactive_multi_party_server_share {
     }
 }
 
+/// Proof that non-interactive multi-party server key share of user `user_id`
+/// is generated with short secrets and errors.
+///
+/// Consists of one proof for each of: key switching key from u_j to s, zero
+/// encryptions for every other user, each auto key, and non-interactive RGSW
+/// ciphertexts and zero encryptions of each LWE index. Every proof also proves
+/// the collective public key share, which binds RLWE secret `s_j` across the
+/// proofs.
+#[cfg(feature = "non_interactive_mp")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NonInteractiveServerKeyShareProof {
+    user_id: usize,
+    proofs: Vec<ShortWitnessProof>,
+}
+
+#[cfg(feature = "non_interactive_mp")]
+mod impl_ni_server_key_share_proof {
+    use super::*;
+
+    impl NonInteractiveServerKeyShareProof {
+        pub(in super::super) fn new(user_id: usize, proofs: Vec<ShortWitnessProof>) -> Self {
+            Self { user_id, proofs }
+        }
+
+        pub(in super::super) fn user_id(&self) -> usize {
+            self.user_id
+        }
+
+        pub(in super::super) fn proofs(&self) -> &[ShortWitnessProof] {
+            &self.proofs
+        }
+    }
+}
+
+/// Key share of user that joins non-interactive multi-party protocol after the
+/// server key has been generated.
+///
+/// Late joining user j does not contribute to ideal RLWE secret `s` and cannot
+/// participate in multi-party decryption. Instead, user j encrypts key
+/// switching key from its RLWE secret u_j to `s` with the collective public
+/// key. Once the server adds the key share to the server key, user j's private
+/// inputs can be key switched to `s` as of any other user.
+pub struct NonInteractiveLateJoiningKeyShare<M, P> {
+    /// Key switching key from u_j to `s` encrypted with the collective public
+    /// key. That is, the key share has the following key switching key:
+    ///     (a_k, a_k*s + e + \beta^k u_j)
+    ui_to_s_ksk: M,
+    parameters: P,
+}
+
+mod impl_ni_late_joining_key_share {
+    use super::*;
+
+    impl<M, P> NonInteractiveLateJoiningKeyShare<M, P> {
+        pub(in super::super) fn new(ui_to_s_ksk: M, parameters: P) -> Self {
+            Self {
+                ui_to_s_ksk,
+                parameters,
+            }
+        }
+
+        pub(in super::super) fn ui_to_s_ksk(&self) -> &M {
+            &self.ui_to_s_ksk
+        }
+
+        pub(in super::super) fn parameters(&self) -> &P {
+            &self.parameters
+        }
+    }
+}
+
+/// CRS seeded share of key refresh key
+///
+/// Party i generates the share with its RLWE secret `s_i` of the current
+/// session and RLWE secret `s'_i` of the client key it will use in the
+/// refreshed session. Since key switching keys are linear in the secrets, sum
+/// of shares of all parties is a key switching key from ideal RLWE secret `s`
+/// to ideal RLWE secret `s'` of the refreshed session.
+pub struct CommonReferenceSeededKeyRefreshShare<M, S, P> {
+    /// Part B of key switching key from `s_i` to `s'_i`. That is, k^th row is
+    ///     a_k*s'_i + e + \beta^k s_i
+    /// where a_k is sampled from common reference seed
+    ksk_part_b: M,
+    /// Common reference seed of a_k's
+    cr_seed: S,
+    parameters: P,
+}
+
+mod impl_key_refresh_share {
+    use super::*;
+
+    impl<M, S, P> CommonReferenceSeededKeyRefreshShare<M, S, P> {
+        pub(in super::super) fn new(ksk_part_b: M, cr_seed: S, parameters: P) -> Self {
+            Self {
+                ksk_part_b,
+                cr_seed,
+                parameters,
+            }
+        }
+
+        pub(in super::super) fn ksk_part_b(&self) -> &M {
+            &self.ksk_part_b
+        }
+
+        pub(in super::super) fn cr_seed(&self) -> &S {
+            &self.cr_seed
+        }
+
+        pub(in super::super) fn parameters(&self) -> &P {
+            &self.parameters
+        }
+    }
+}
+
+/// Key switching key from ideal RLWE secret `s` of the current session to ideal
+/// RLWE secret `s'` of the refreshed session. Polynomials are in evaluation
+/// domain.
+///
+/// Server aggregates key refresh shares of all parties into the key and uses
+/// it to key switch ciphertexts produced under `s` to `s'`.
+pub struct KeyRefreshKeyEvaluationDomain<M> {
+    ksk: NormalAndShoup<M>,
+}
+
+mod impl_key_refresh_key {
+    use super::*;
+
+    impl<M> KeyRefreshKeyEvaluationDomain<M> {
+        pub(in super::super) fn new(ksk: M, modulus: <M as ToShoup>::Modulus) -> Self
+        where
+            M: ToShoup,
+        {
+            Self {
+                ksk: NormalAndShoup::new_with_modulus(ksk, modulus),
+            }
+        }
+
+        pub(in super::super) fn ksk(&self) -> &NormalAndShoup<M> {
+            &self.ksk
+        }
+    }
+}
+
+/// Server key share that is malformed and must not be aggregated
+#[derive(Debug, Clone, PartialEq)]
+pub struct MalformedServerKeyShare {
+    /// Index of the share in received server key shares
+    pub share_index: usize,
+    /// User id of the party that sent the share
+    pub user_id: usize,
+    /// Reason why the share is malformed
+    pub reason: String,
+}
+
+impl std::fmt::Display for MalformedServerKeyShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Malformed server key share at index {} from user_id={}: {}",
+            self.share_index, self.user_id, self.reason
+        )
+    }
+}
+
+impl std::error::Error for MalformedServerKeyShare {}
+
+/// Reason why server key shares of interactive multi-party protocol must not
+/// be aggregated
+#[cfg_attr(not(feature = "interactive_mp"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvalidInteractiveServerKeyShares {
+    /// Share sent by a party is malformed
+    Malformed(MalformedServerKeyShare),
+    /// Shares include different round 1 transcripts, ie parties did not see
+    /// the same public key shares in round 1.
+    ///
+    /// Transcripts are not authenticated. A party that sent different public
+    /// key shares to different parties in round 1 and a party that includes a
+    /// forged transcript in its round 2 share look the same to the server,
+    /// hence the mismatch is not attributed to any party.
+    TranscriptMismatch,
+}
+
+impl From<MalformedServerKeyShare> for InvalidInteractiveServerKeyShares {
+    fn from(value: MalformedServerKeyShare) -> Self {
+        InvalidInteractiveServerKeyShares::Malformed(value)
+    }
+}
+
+impl std::fmt::Display for InvalidInteractiveServerKeyShares {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidInteractiveServerKeyShares::Malformed(e) => e.fmt(f),
+            InvalidInteractiveServerKeyShares::TranscriptMismatch => {
+                write!(f, "Server key shares include different round 1 transcripts")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidInteractiveServerKeyShares {}
+
+/// Structural checks of server key shares, ie dimensions of ciphertexts and
+/// that all coefficients are in range [0, q), with respect to parameters of
+/// the share
+mod impl_server_key_share_validation {
+    use itertools::Itertools;
+    use num_traits::{FromPrimitive, PrimInt};
+
+    use super::*;
+    use crate::{
+        backend::Modulus, bool::evaluator::multi_party_user_id_lwe_segment, decomposer::NumInfo,
+        Row,
+    };
+
+    fn check_row<R: Row, Mo: Modulus<Element = R::Element>>(
+        r: &R,
+        len: usize,
+        q: &Mo,
+        name: &str,
+    ) -> Result<(), String>
+    where
+        R::Element: PartialOrd,
+    {
+        if r.as_ref().len() != len {
+            return Err(format!(
+                "{name} has length {} but expected {len}",
+                r.as_ref().len()
+            ));
+        }
+        let max = q.largest_unsigned_value();
+        if r.as_ref().iter().any(|el| *el > max) {
+            return Err(format!("{name} has coefficient >= q"));
+        }
+        Ok(())
+    }
+
+    fn check_matrix<M: Matrix, Mo: Modulus<Element = M::MatElement>>(
+        m: &M,
+        dimension: (usize, usize),
+        q: &Mo,
+        name: &str,
+    ) -> Result<(), String>
+    where
+        M::MatElement: PartialOrd,
+    {
+        if m.dimension() != dimension {
+            return Err(format!(
+                "{name} has dimension {:?} but expected {dimension:?}",
+                m.dimension()
+            ));
+        }
+        m.iter_rows()
+            .try_for_each(|r| check_row(r, dimension.1, q, name))
+    }
+
+    fn check_auto_keys<M, El>(
+        auto_keys: &HashMap<usize, M>,
+        parameters: &BoolParameters<El>,
+    ) -> Result<(), String>
+    where
+        M: Matrix<MatElement = El>,
+        El: PrimInt + FromPrimitive + NumInfo,
+    {
+        let mut expected_ks = parameters.auto_element_dlogs();
+        let mut ks = auto_keys.keys().copied().collect_vec();
+        expected_ks.sort();
+        ks.sort();
+        if ks != expected_ks {
+            return Err(format!(
+                "Auto keys are for elements {ks:?} but expected {expected_ks:?}"
+            ));
+        }
+        auto_keys.iter().try_for_each(|(k, v)| {
+            check_matrix(
+                v,
+                (
+                    parameters.auto_decomposition_count().0,
+                    parameters.rlwe_n().0,
+                ),
+                parameters.rlwe_q(),
+                &format!("Auto key {k}"),
+            )
+        })
+    }
+
+    impl<M: Matrix, S>
+        CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
+            M,
+            BoolParameters<M::MatElement>,
+            S,
+        >
+    where
+        M::MatElement: PrimInt + FromPrimitive + NumInfo,
+    {
+        /// Checks structure of the share assuming `total_users` parties
+        /// participate in the protocol
+        pub(in super::super) fn validate_structure(
+            &self,
+            total_users: usize,
+        ) -> Result<(), String> {
+            let parameters = &self.parameters;
+            let ring_size = parameters.rlwe_n().0;
+            let rlwe_q = parameters.rlwe_q();
+
+            let (segment_start, segment_end) =
+                multi_party_user_id_lwe_segment(self.user_id, total_users, parameters.lwe_n().0);
+
+            // RGSW cts
+            let (rlrg_d_a, rlrg_d_b) = parameters.rlwe_rgsw_decomposition_count();
+            let (rgrg_d_a, rgrg_d_b) = parameters.rgsw_rgsw_decomposition_count();
+            if self.self_leader_rgsws.len() != segment_end - segment_start {
+                return Err(format!(
+                    "Has {} RGSW ciphertexts as leader but expected {}",
+                    self.self_leader_rgsws.len(),
+                    segment_end - segment_start
+                ));
+            }
+            self.self_leader_rgsws.iter().try_for_each(|c| {
+                check_matrix(
+                    c,
+                    (rlrg_d_a.0 * 2 + rlrg_d_b.0 * 2, ring_size),
+                    rlwe_q,
+                    "RGSW ciphertext as leader",
+                )
+            })?;
+            if self.not_self_leader_rgsws.len()
+                != parameters.lwe_n().0 - (segment_end - segment_start)
+            {
+                return Err(format!(
+                    "Has {} RGSW ciphertexts as not leader but expected {}",
+                    self.not_self_leader_rgsws.len(),
+                    parameters.lwe_n().0 - (segment_end - segment_start)
+                ));
+            }
+            self.not_self_leader_rgsws.iter().try_for_each(|c| {
+                check_matrix(
+                    c,
+                    (rgrg_d_a.0 * 2 + rgrg_d_b.0 * 2, ring_size),
+                    rlwe_q,
+                    "RGSW ciphertext as not leader",
+                )
+            })?;
+
+            check_auto_keys(&self.auto_keys, parameters)?;
+
+            check_row(
+                &self.lwe_ksk,
+                ring_size * parameters.lwe_decomposition_count().0,
+                parameters.lwe_q(),
+                "LWE key switching key",
+            )
+        }
+    }
+
+    impl<M: Matrix, S>
+        CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+            M,
+            BoolParameters<M::MatElement>,
+            S,
+        >
+    where
+        M::MatElement: PrimInt + FromPrimitive + NumInfo,
+    {
+        /// Checks structure of the share
+        pub(in super::super) fn validate_structure(&self) -> Result<(), String> {
+            let parameters = &self.parameters;
+            let ring_size = parameters.rlwe_n().0;
+            let rlwe_q = parameters.rlwe_q();
+            let lwe_n = parameters.lwe_n().0;
+
+            if self.lwe_n != lwe_n {
+                return Err(format!(
+                    "LWE dimension is {} but expected {lwe_n}",
+                    self.lwe_n
+                ));
+            }
+
+            let (segment_start, segment_end) =
+                multi_party_user_id_lwe_segment(self.user_id, self.total_users, lwe_n);
+
+            // Non-interactive RGSW cts
+            let (rlrg_d_a, rlrg_d_b) = parameters.rlwe_rgsw_decomposition_count();
+            let (rgrg_d_a, rgrg_d_b) = parameters.rgsw_rgsw_decomposition_count();
+            let rlrg_d_max = std::cmp::max(rlrg_d_a.0, rlrg_d_b.0);
+            let rgrg_d_max = std::cmp::max(rgrg_d_a.0, rgrg_d_b.0);
+            if self.self_leader_ni_rgsw_cts.len() != segment_end - segment_start {
+                return Err(format!(
+                    "Has {} non-interactive RGSW ciphertexts as leader but expected {}",
+                    self.self_leader_ni_rgsw_cts.len(),
+                    segment_end - segment_start
+                ));
+            }
+            self.self_leader_ni_rgsw_cts.iter().try_for_each(|c| {
+                check_matrix(
+                    c,
+                    (rlrg_d_max, ring_size),
+                    rlwe_q,
+                    "Non-interactive RGSW ciphertext as leader",
+                )
+            })?;
+            if self.not_self_leader_ni_rgsw_cts.len() != lwe_n - (segment_end - segment_start) {
+                return Err(format!(
+                    "Has {} non-interactive RGSW ciphertexts as not leader but expected {}",
+                    self.not_self_leader_ni_rgsw_cts.len(),
+                    lwe_n - (segment_end - segment_start)
+                ));
+            }
+            self.not_self_leader_ni_rgsw_cts.iter().try_for_each(|c| {
+                check_matrix(
+                    c,
+                    (rgrg_d_max, ring_size),
+                    rlwe_q,
+                    "Non-interactive RGSW ciphertext as not leader",
+                )
+            })?;
+            if self.ni_rgsw_zero_encs.len() != lwe_n {
+                return Err(format!(
+                    "Has {} zero encryptions for RGSW ciphertexts but expected {lwe_n}",
+                    self.ni_rgsw_zero_encs.len(),
+                ));
+            }
+            self.ni_rgsw_zero_encs.iter().try_for_each(|c| {
+                check_matrix(
+                    c,
+                    (rgrg_d_a.0, ring_size),
+                    rlwe_q,
+                    "Zero encryption for RGSW ciphertext",
+                )
+            })?;
+
+            // u_j to s ksk
+            let d_uitos = parameters
+                .non_interactive_ui_to_s_key_switch_decomposition_count()
+                .0;
+            check_matrix(
+                &self.ui_to_s_ksk,
+                (d_uitos, ring_size),
+                rlwe_q,
+                "Key switching key from u_j to s",
+            )?;
+            if self.ksk_zero_encs_for_others.len() != self.total_users - 1 {
+                return Err(format!(
+                    "Has {} zero encryption sets for other users' key switching keys but expected {}",
+                    self.ksk_zero_encs_for_others.len(),
+                    self.total_users - 1
+                ));
+            }
+            self.ksk_zero_encs_for_others.iter().try_for_each(|c| {
+                check_matrix(
+                    c,
+                    (d_uitos, ring_size),
+                    rlwe_q,
+                    "Zero encryptions for other user's key switching key",
+                )
+            })?;
+
+            check_auto_keys(&self.auto_keys_share, parameters)?;
+
+            check_row(
+                &self.lwe_ksk_share,
+                ring_size * parameters.lwe_decomposition_count().0,
+                parameters.lwe_q(),
+                "LWE key switching key",
+            )?;
+
+            check_row(
+                &self.public_key_share,
+                ring_size,
+                rlwe_q,
+                "Public key share",
+            )
+        }
+    }
+}
+
 /// Stores both normal and shoup representation of elements in the container
 /// (for ex, a matrix).
 ///
@@ -1421,6 +2299,57 @@ impl<M> WithShoupRepr for NormalAndShoup<M> {
     }
 }
 
+/// RGSW ciphertext or auto key of blind rotation key in representation that
+/// selects the backend for blind rotation (see `BlindRotationKeyCt`)
+pub(crate) trait FromEvaluationDomain<M: Matrix> {
+    /// Converts `ct` with polynomials mod `q` in evaluation domain of `ntt_op`
+    fn from_evaluation_domain<N: Ntt<Element = M::MatElement>>(
+        ct: M,
+        q: &CiphertextModulus<M::MatElement>,
+        ntt_op: &N,
+        fft_op: &FftBackendF64,
+    ) -> Self;
+}
+
+impl<M: Matrix + ToShoup<Modulus = M::MatElement>> FromEvaluationDomain<M> for NormalAndShoup<M>
+where
+    CiphertextModulus<M::MatElement>: Modulus<Element = M::MatElement>,
+{
+    fn from_evaluation_domain<N: Ntt<Element = M::MatElement>>(
+        ct: M,
+        q: &CiphertextModulus<M::MatElement>,
+        _ntt_op: &N,
+        _fft_op: &FftBackendF64,
+    ) -> Self {
+        let q = q
+            .q()
+            .expect("Shoup representation does not exist for native modulus");
+        NormalAndShoup::new_with_modulus(ct, q)
+    }
+}
+
+impl<M: MatrixMut<MatElement = u64>> FromEvaluationDomain<M> for FourierCt
+where
+    M::R: RowMut,
+{
+    fn from_evaluation_domain<N: Ntt<Element = M::MatElement>>(
+        mut ct: M,
+        _q: &CiphertextModulus<M::MatElement>,
+        ntt_op: &N,
+        fft_op: &FftBackendF64,
+    ) -> Self {
+        ct.iter_rows_mut().for_each(|r| ntt_op.backward(r.as_mut()));
+        FourierCt::new(&ct, fft_op)
+    }
+}
+
+/// Representation of RGSW ciphertexts and auto keys of runtime server key.
+/// Blind rotation uses NTT by default and f64 FFT with `fft` feature.
+#[cfg(not(feature = "fft"))]
+pub(crate) type RuntimeBlindRotationKeyCt = NormalAndShoup<Vec<Vec<u64>>>;
+#[cfg(feature = "fft")]
+pub(crate) type RuntimeBlindRotationKeyCt = FourierCt;
+
 #[cfg(test)]
 pub(crate) mod key_size {
     use num_traits::{FromPrimitive, PrimInt};
@@ -1490,6 +2419,8 @@ pub(crate) mod key_size {
                 .values()
                 .for_each(|v| total += v.size(log_rlweq));
 
+            total += self.public_key_share.size(log_rlweq);
+
             let log_lweq = self.parameters.lwe_q().log_q();
             total += self.lwe_ksk_share.size(log_lweq);
 
@@ -1498,6 +2429,89 @@ pub(crate) mod key_size {
     }
 }
 
+/// Digests of client keys and key shares for known-answer tests. Digests cover
+/// every field sent over the wire, except parameters.
+#[cfg(test)]
+pub(super) mod kat {
+    use itertools::Itertools;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+
+    pub(in super::super) fn update_row(hasher: &mut Sha256, row: &[u64]) {
+        hasher.update((row.len() as u64).to_le_bytes());
+        row.iter().for_each(|v| hasher.update(v.to_le_bytes()));
+    }
+
+    pub(in super::super) fn update_matrix(hasher: &mut Sha256, m: &[Vec<u64>]) {
+        hasher.update((m.len() as u64).to_le_bytes());
+        m.iter().for_each(|r| update_row(hasher, r));
+    }
+
+    fn update_auto_keys(hasher: &mut Sha256, auto_keys: &HashMap<usize, Vec<Vec<u64>>>) {
+        auto_keys
+            .iter()
+            .sorted_by_key(|(k, _)| **k)
+            .for_each(|(k, m)| {
+                hasher.update((*k as u64).to_le_bytes());
+                update_matrix(hasher, m);
+            });
+    }
+
+    pub(in super::super) fn client_key_seed(ck: &crate::bool::ClientKey) -> [u8; 32] {
+        ck.seed
+    }
+
+    #[cfg(feature = "interactive_mp")]
+    pub(in super::super) fn interactive_server_key_share_digest(
+        share: &CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
+            Vec<Vec<u64>>,
+            BoolParameters<u64>,
+            crate::bool::evaluator::InteractiveMultiPartyCrs<[u8; 32]>,
+        >,
+    ) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        share
+            .self_leader_rgsws
+            .iter()
+            .chain(share.not_self_leader_rgsws.iter())
+            .for_each(|m| update_matrix(&mut hasher, m));
+        update_auto_keys(&mut hasher, &share.auto_keys);
+        update_row(&mut hasher, &share.lwe_ksk);
+        hasher.update(share.cr_seed.seed);
+        hasher.update((share.user_id as u64).to_le_bytes());
+        hasher.update(share.transcript.digest());
+        hasher.finalize().into()
+    }
+
+    #[cfg(feature = "non_interactive_mp")]
+    pub(in super::super) fn non_interactive_server_key_share_digest(
+        share: &CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+            Vec<Vec<u64>>,
+            BoolParameters<u64>,
+            crate::bool::evaluator::NonInteractiveMultiPartyCrs<[u8; 32]>,
+        >,
+    ) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        share
+            .self_leader_ni_rgsw_cts
+            .iter()
+            .chain(share.not_self_leader_ni_rgsw_cts.iter())
+            .chain(share.ni_rgsw_zero_encs.iter())
+            .chain(std::iter::once(&share.ui_to_s_ksk))
+            .chain(share.ksk_zero_encs_for_others.iter())
+            .for_each(|m| update_matrix(&mut hasher, m));
+        update_auto_keys(&mut hasher, &share.auto_keys_share);
+        update_row(&mut hasher, &share.lwe_ksk_share);
+        update_row(&mut hasher, &share.public_key_share);
+        hasher.update(share.cr_seed.seed);
+        hasher.update((share.user_id as u64).to_le_bytes());
+        hasher.update((share.total_users as u64).to_le_bytes());
+        hasher.update((share.lwe_n as u64).to_le_bytes());
+        hasher.finalize().into()
+    }
+}
+
 pub(super) mod tests {
     use itertools::izip;
     use num_traits::{FromPrimitive, PrimInt, Zero};
@@ -1510,7 +2524,7 @@ pub(super) mod tests {
     use super::SinglePartyClientKey;
 
     pub(crate) fn ideal_sk_rlwe(cks: &[ClientKey]) -> Vec<i32> {
-        let mut ideal_rlwe_sk = cks[0].sk_rlwe();
+        let mut ideal_rlwe_sk = cks[0].sk_rlwe().to_vec();
         cks.iter().skip(1).for_each(|k| {
             let sk_rlwe = k.sk_rlwe();
             izip!(ideal_rlwe_sk.iter_mut(), sk_rlwe.iter()).for_each(|(a, b)| {
@@ -1521,7 +2535,7 @@ pub(super) mod tests {
     }
 
     pub(crate) fn ideal_sk_lwe(cks: &[ClientKey]) -> Vec<i32> {
-        let mut ideal_rlwe_sk = cks[0].sk_lwe();
+        let mut ideal_rlwe_sk = cks[0].sk_lwe().to_vec();
         cks.iter().skip(1).for_each(|k| {
             let sk_rlwe = k.sk_lwe();
             izip!(ideal_rlwe_sk.iter_mut(), sk_rlwe.iter()).for_each(|(a, b)| {
@@ -1550,6 +2564,168 @@ pub(super) mod tests {
         let noise = modop.sub(&m_expected, &noisy_m);
         noise
     }
+    #[test]
+    #[cfg(feature = "non_interactive_mp")]
+    fn validate_non_interactive_server_key_shares_works() {
+        use crate::bool::{
+            evaluator::NonInteractiveMultiPartyCrs,
+            ni_mp_api::{gen_client_key, set_parameter_set, BoolEvaluator, ParameterSelector},
+            parameters::NI_2P,
+        };
+        use crate::{
+            random::{DefaultSecureRng, NewWithSeed, PrgAlgorithm},
+            utils::WithLocal,
+        };
+        use itertools::Itertools;
+
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let shares = BoolEvaluator::with_local(|e| {
+            cks.iter()
+                .enumerate()
+                .map(|(user_id, ck)| {
+                    e.gen_non_interactive_multi_party_key_share(&cr_seed, user_id, parties, ck)
+                })
+                .collect_vec()
+        });
+        let validate = |shares: &[_]| {
+            BoolEvaluator::with_local(|e| {
+                e.validate_non_interactive_multi_party_server_key_shares(&cr_seed, shares)
+            })
+        };
+
+        assert!(validate(&shares).is_ok());
+
+        // duplicate user_id
+        let mut malformed = shares.clone();
+        malformed.push(shares[1].clone());
+        let err = validate(&malformed).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (2, 1));
+
+        // user_id out of range
+        let mut malformed = shares.clone();
+        malformed[1].user_id = parties;
+        let err = validate(&malformed).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, parties));
+
+        // coefficient >= q
+        let mut malformed = shares.clone();
+        malformed[1].ui_to_s_ksk[0][0] = u64::MAX;
+        let err = validate(&malformed).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, 1));
+
+        // missing auto key
+        let mut malformed = shares.clone();
+        malformed[0].auto_keys_share.remove(&0);
+        let err = validate(&malformed).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (0, 0));
+
+        // missing RGSW ciphertext
+        let mut malformed = shares.clone();
+        malformed[0].not_self_leader_ni_rgsw_cts.pop();
+        let err = validate(&malformed).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (0, 0));
+
+        // seeded with a different PRG
+        let mut malformed = shares.clone();
+        malformed[1].cr_seed.prg = match DefaultSecureRng::ALGORITHM {
+            PrgAlgorithm::ChaCha8 => PrgAlgorithm::ChaCha20,
+            _ => PrgAlgorithm::ChaCha8,
+        };
+        let err = validate(&malformed).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, 1));
+        assert!(err.reason.starts_with("Share is seeded with PRG"));
+    }
+
+    #[test]
+    #[cfg(feature = "non_interactive_mp")]
+    fn non_interactive_server_key_share_proofs_identify_cheating_party() {
+        use crate::bool::{
+            evaluator::NonInteractiveMultiPartyCrs,
+            ni_mp_api::{gen_client_key, set_parameter_set, BoolEvaluator, ParameterSelector},
+            parameters::NI_2P,
+        };
+        use crate::{backend::Modulus, utils::WithLocal};
+        use itertools::Itertools;
+
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let (shares, proofs): (Vec<_>, Vec<_>) = BoolEvaluator::with_local(|e| {
+            cks.iter()
+                .enumerate()
+                .map(|(user_id, ck)| {
+                    let share =
+                        e.gen_non_interactive_multi_party_key_share(&cr_seed, user_id, parties, ck);
+                    let proof = e.prove_non_interactive_multi_party_server_key_share(ck, &share);
+                    (share, proof)
+                })
+                .unzip()
+        });
+        let verify = |shares: &[_], proofs: &[_]| {
+            BoolEvaluator::with_local(|e| {
+                e.validate_non_interactive_multi_party_server_key_shares(&cr_seed, shares)?;
+                e.verify_non_interactive_multi_party_server_key_share_proofs(shares, proofs)
+            })
+        };
+
+        assert!(verify(&shares, &proofs).is_ok());
+
+        let q = shares[0].parameters().rlwe_q().q().unwrap();
+        let add_noise = |v: &mut u64| *v = (*v + (q >> 3)) % q;
+
+        // huge noise in a non-interactive RGSW ciphertext
+        let mut malformed = shares.clone();
+        add_noise(&mut malformed[1].not_self_leader_ni_rgsw_cts[3][0][5]);
+        let err = verify(&malformed, &proofs).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, 1));
+        assert!(err.reason.starts_with("LweIndex"));
+
+        // huge noise in an auto key
+        let mut malformed = shares.clone();
+        add_noise(&mut malformed[0].auto_keys_share.get_mut(&1).unwrap()[0][7]);
+        let err = verify(&malformed, &proofs).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (0, 0));
+        assert!(err.reason.starts_with("AutoKey(1)"));
+
+        // huge noise in zero encryptions for other user's key switching key
+        let mut malformed = shares.clone();
+        add_noise(&mut malformed[1].ksk_zero_encs_for_others[0][2][0]);
+        let err = verify(&malformed, &proofs).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, 1));
+
+        // public key share under a different secret
+        let mut malformed = shares.clone();
+        malformed[0].public_key_share = shares[1].public_key_share.clone();
+        let err = verify(&malformed, &proofs).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (0, 0));
+
+        // LWE key switching key share with coefficient >= lwe_q
+        let lwe_q = shares[0].parameters().lwe_q().q().unwrap();
+        let mut malformed = shares.clone();
+        malformed[1].lwe_ksk_share[4] = lwe_q;
+        let err = verify(&malformed, &proofs).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, 1));
+        assert!(err.reason.starts_with("LWE key switching key"));
+
+        // LWE key switching key share is not proven, hence huge noise within [0, lwe_q)
+        // is not detected
+        let mut malformed = shares.clone();
+        malformed[0].lwe_ksk_share[4] = (malformed[0].lwe_ksk_share[4] + (lwe_q >> 1)) % lwe_q;
+        assert!(verify(&malformed, &proofs).is_ok());
+
+        // proofs of another user
+        let err = verify(&shares, &[proofs[1].clone(), proofs[0].clone()]).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (0, 0));
+
+        // missing proof
+        let err = verify(&shares, &proofs[..1]).unwrap_err();
+        assert_eq!((err.share_index, err.user_id), (1, 1));
+    }
+
     // #[test]
     // fn trial() {
     //     let parameters = I_2P;
@@ -17,14 +17,45 @@ use crate::RowEntity;
 
 pub type ClientKey = keys::ClientKey<[u8; 32], u64>;
 #[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+pub use keys::MalformedServerKeyShare;
+#[cfg(feature = "interactive_mp")]
+pub use keys::{InteractiveMultiPartyTranscript, InvalidInteractiveServerKeyShares};
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
 pub type FheBool = impl_bool_frontend::FheBool<Vec<u64>>;
 
+#[cfg(feature = "interactive_mp")]
+pub type ServerKeyShare = keys::CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
+    Vec<Vec<u64>>,
+    parameters::BoolParameters<u64>,
+    evaluator::InteractiveMultiPartyCrs<[u8; 32]>,
+>;
 #[cfg(any(feature = "non_interactive_mp"))]
 pub type ServerKeyShare = keys::CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
     Vec<Vec<u64>>,
     parameters::BoolParameters<u64>,
     evaluator::NonInteractiveMultiPartyCrs<[u8; 32]>,
 >;
+#[cfg(feature = "non_interactive_mp")]
+pub use keys::NonInteractiveServerKeyShareProof;
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+pub type KeyRefreshShare = keys::CommonReferenceSeededKeyRefreshShare<
+    Vec<Vec<u64>>,
+    [u8; 32],
+    parameters::BoolParameters<u64>,
+>;
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+pub type KeyRefreshKey = keys::KeyRefreshKeyEvaluationDomain<Vec<Vec<u64>>>;
+#[cfg(feature = "non_interactive_mp")]
+pub type LateJoiningKeyShare =
+    keys::NonInteractiveLateJoiningKeyShare<Vec<Vec<u64>>, parameters::BoolParameters<u64>>;
+
+/// Two input gates that shortint operations evaluate in a batch with
+/// [`BooleanGates::batch_gate`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BoolGate {
+    And,
+    Xnor,
+}
 
 pub(crate) trait BooleanGates {
     type Ciphertext: RowEntity;
@@ -38,6 +69,46 @@ pub(crate) trait BooleanGates {
     fn xnor_inplace(&mut self, c0: &mut Self::Ciphertext, c1: &Self::Ciphertext, key: &Self::Key);
     fn not_inplace(&self, c: &mut Self::Ciphertext);
 
+    /// Sets c0 to majority of c0, c1, c2 with a single blind rotation
+    fn maj3_inplace(
+        &mut self,
+        c0: &mut Self::Ciphertext,
+        c1: &Self::Ciphertext,
+        c2: &Self::Ciphertext,
+        key: &Self::Key,
+    );
+    /// Sets c0 = c0 ^ c1 ^ c2 with a single blind rotation
+    fn xor3_inplace(
+        &mut self,
+        c0: &mut Self::Ciphertext,
+        c1: &Self::Ciphertext,
+        c2: &Self::Ciphertext,
+        key: &Self::Key,
+    );
+    /// Returns `if_true` if `selector` is true, `if_false` otherwise.
+    ///
+    /// Evaluated with two blind rotations. First bootstraps s & a to
+    /// ciphertext encoded as +/-Q/4. Second bootstraps 2(s & a) - s + b + Q/8,
+    /// which is the input of majority gate with one input weighted by 2.
+    /// Noise at input of the second blind rotation equals noise at input of
+    /// `maj3_inplace`.
+    fn mux(
+        &mut self,
+        selector: &Self::Ciphertext,
+        if_true: &Self::Ciphertext,
+        if_false: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Self::Ciphertext;
+    /// Returns `mux(selector, if_true[i], if_false[i])` for all i. Both blind
+    /// rotations of all muxes are bootstrapped in a batch like `batch_gate`.
+    fn batch_mux(
+        &mut self,
+        selector: &Self::Ciphertext,
+        if_true: &[Self::Ciphertext],
+        if_false: &[Self::Ciphertext],
+        key: &Self::Key,
+    ) -> Vec<Self::Ciphertext>;
+
     fn and(
         &mut self,
         c0: &Self::Ciphertext,
@@ -75,75 +146,261 @@ pub(crate) trait BooleanGates {
         key: &Self::Key,
     ) -> Self::Ciphertext;
     fn not(&self, c: &Self::Ciphertext) -> Self::Ciphertext;
+
+    /// Sets lhs[i] = op(lhs[i], rhs[i]) for all i.
+    ///
+    /// All gates are bootstrapped together: LWE key switching key and blind
+    /// rotation keys are read once for the entire batch and scratch space is
+    /// shared. If `parallel` feature is enabled the batch is split across
+    /// threads of rayon's global thread pool.
+    fn batch_gate(
+        &mut self,
+        op: BoolGate,
+        lhs: &mut [Self::Ciphertext],
+        rhs: &[Self::Ciphertext],
+        key: &Self::Key,
+    );
+    fn maj3(
+        &mut self,
+        c0: &Self::Ciphertext,
+        c1: &Self::Ciphertext,
+        c2: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Self::Ciphertext;
+    fn xor3(
+        &mut self,
+        c0: &Self::Ciphertext,
+        c1: &Self::Ciphertext,
+        c2: &Self::Ciphertext,
+        key: &Self::Key,
+    ) -> Self::Ciphertext;
 }
 
 #[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
 mod impl_bool_frontend {
-    use crate::MultiPartyDecryptor;
+    use crate::{
+        multi_party::{
+            multi_party_aggregate_decryption_shares_and_decrypt, MalformedDecryptionShare,
+        },
+        pbs::PbsInfo,
+        utils::WithLocal,
+        KeyRefresher, MultiPartyDecryptor, VerifiableMultiPartyDecryptor,
+    };
+
+    use super::{evaluator::BoolEncoding, BoolEvaluator, RuntimeServerKey};
 
     /// Fhe Bool ciphertext
+    ///
+    /// XORs are evaluated lazily. Instead of bootstrapping after every XOR,
+    /// ciphertexts are mapped to parity encoding (i.e. b * Q/2) where XOR is
+    /// addition and NOT is addition of Q/2. The sum is only bootstrapped back
+    /// to bool encoding when it is input to a non-linear gate or when no. of
+    /// ciphertexts summed exceeds `LAZY_XOR_BUDGET`.
     #[derive(Default, Clone)]
     pub struct FheBool<C> {
         pub data: C,
+        /// No. of XORs accumulated in `data` since it was last bootstrapped. If
+        /// non-zero, `data` is in parity encoding.
+        pub(crate) lazy_xors: usize,
     }
 
     impl<C> FheBool<C> {
+        /// Returns `FheBool` for ciphertext `data` of a bool
+        pub fn new(data: C) -> Self {
+            FheBool { data, lazy_xors: 0 }
+        }
+
+        /// Returns ciphertext in bool encoding. Callers must bootstrap lazy XORs
+        /// first or read parity encoded `data` directly.
         pub(crate) fn data(&self) -> &C {
+            debug_assert!(!self.is_lazy(), "FheBool has unbootstrapped XORs");
             &self.data
         }
 
         pub(crate) fn data_mut(&mut self) -> &mut C {
             &mut self.data
         }
+
+        /// Returns true if `data` is in parity encoding
+        pub(crate) fn is_lazy(&self) -> bool {
+            self.lazy_xors != 0
+        }
+    }
+
+    impl FheBool<Vec<u64>> {
+        /// Bootstraps `self` to bool encoding if it has accumulated lazy XORs
+        pub(crate) fn bootstrap_lazy_xors(
+            &mut self,
+            e: &mut BoolEvaluator,
+            key: &RuntimeServerKey,
+        ) {
+            if self.is_lazy() {
+                e.parity_bootstrap_inplace(&mut self.data, key);
+                self.lazy_xors = 0;
+            }
+        }
+
+        /// Returns `self` in bool encoding. `self` is bootstrapped if it has
+        /// accumulated lazy XORs.
+        pub(crate) fn bootstrapped(&self, e: &mut BoolEvaluator, key: &RuntimeServerKey) -> Self {
+            let mut out = self.clone();
+            out.bootstrap_lazy_xors(e, key);
+            out
+        }
     }
 
-    impl<C, K> MultiPartyDecryptor<bool, FheBool<C>> for K
+    impl<K> MultiPartyDecryptor<bool, FheBool<Vec<u64>>> for K
     where
-        K: MultiPartyDecryptor<bool, C>,
+        K: MultiPartyDecryptor<bool, Vec<u64>, DecryptionShare = u64>,
     {
-        type DecryptionShare = <K as MultiPartyDecryptor<bool, C>>::DecryptionShare;
+        type DecryptionShare = u64;
 
         fn aggregate_decryption_shares(
             &self,
-            c: &FheBool<C>,
+            c: &FheBool<Vec<u64>>,
             shares: &[Self::DecryptionShare],
         ) -> bool {
-            self.aggregate_decryption_shares(&c.data, shares)
+            if c.is_lazy() {
+                BoolEvaluator::with_local(|e| {
+                    let noisy_m = multi_party_aggregate_decryption_shares_and_decrypt(
+                        &c.data,
+                        shares,
+                        e.pbs_info().modop_rlweq(),
+                    );
+                    e.pbs_info().rlwe_q().decode_parity(noisy_m)
+                })
+            } else {
+                self.aggregate_decryption_shares(&c.data, shares)
+            }
         }
 
-        fn gen_decryption_share(&self, c: &FheBool<C>) -> Self::DecryptionShare {
+        fn gen_decryption_share(&self, c: &FheBool<Vec<u64>>) -> Self::DecryptionShare {
+            // decryption share does not depend on encoding
             self.gen_decryption_share(&c.data)
         }
     }
 
+    impl<K> VerifiableMultiPartyDecryptor<bool, FheBool<Vec<u64>>> for K
+    where
+        K: VerifiableMultiPartyDecryptor<bool, Vec<u64>, DecryptionShare = u64>,
+    {
+        type DecryptionShareProof = K::DecryptionShareProof;
+        type PublicKeyShare = K::PublicKeyShare;
+
+        fn gen_decryption_share_with_proof(
+            &self,
+            c: &FheBool<Vec<u64>>,
+            public_key_share: &Self::PublicKeyShare,
+        ) -> (Self::DecryptionShare, Self::DecryptionShareProof) {
+            self.gen_decryption_share_with_proof(&c.data, public_key_share)
+        }
+
+        fn aggregate_decryption_shares_verified(
+            &self,
+            c: &FheBool<Vec<u64>>,
+            shares: &[Self::DecryptionShare],
+            proofs: &[Self::DecryptionShareProof],
+            public_key_shares: &[Self::PublicKeyShare],
+        ) -> Result<bool, MalformedDecryptionShare> {
+            // proofs do not depend on encoding
+            VerifiableMultiPartyDecryptor::<bool, Vec<u64>>::aggregate_decryption_shares_verified(
+                self,
+                &c.data,
+                shares,
+                proofs,
+                public_key_shares,
+            )?;
+            Ok(
+                MultiPartyDecryptor::<bool, FheBool<Vec<u64>>>::aggregate_decryption_shares(
+                    self, c, shares,
+                ),
+            )
+        }
+    }
+
+    impl<C, K> KeyRefresher<FheBool<C>> for K
+    where
+        K: KeyRefresher<C>,
+    {
+        fn refresh(&self, c: &FheBool<C>) -> FheBool<C> {
+            FheBool {
+                data: self.refresh(&c.data),
+                lazy_xors: c.lazy_xors,
+            }
+        }
+    }
+
     mod ops {
         use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 
         use crate::{
+            backend::VectorOps,
+            pbs::PbsInfo,
             utils::{Global, WithLocal},
             BooleanGates,
         };
 
-        use super::super::{BoolEvaluator, RuntimeServerKey};
+        use super::super::{parameters::LAZY_XOR_BUDGET, BoolEvaluator, RuntimeServerKey};
 
         type FheBool = super::super::FheBool;
 
+        /// Sets c0 = c0 ^ c1 without bootstrapping. Operands are bootstrapped
+        /// first only if no. of ciphertexts in the sum would exceed lazy XOR
+        /// budget.
+        fn lazy_xor_inplace(
+            e: &mut BoolEvaluator,
+            c0: &mut FheBool,
+            c1: &FheBool,
+            key: &RuntimeServerKey,
+        ) {
+            let mut c1 = c1.clone();
+
+            // Each operand is sum of `lazy_xors + 1` ciphertexts. Bootstrap the
+            // operand with more accumulated XORs first.
+            if c0.lazy_xors + c1.lazy_xors + 2 > LAZY_XOR_BUDGET {
+                if c0.lazy_xors >= c1.lazy_xors {
+                    c0.bootstrap_lazy_xors(e, key);
+                } else {
+                    c1.bootstrap_lazy_xors(e, key);
+                }
+            }
+            if c0.lazy_xors + c1.lazy_xors + 2 > LAZY_XOR_BUDGET {
+                c0.bootstrap_lazy_xors(e, key);
+                c1.bootstrap_lazy_xors(e, key);
+            }
+
+            if !c0.is_lazy() {
+                e.to_parity_encoding_inplace(c0.data_mut());
+            }
+            if !c1.is_lazy() {
+                e.to_parity_encoding_inplace(c1.data_mut());
+            }
+
+            e.pbs_info()
+                .modop_rlweq()
+                .elwise_add_mut(c0.data_mut(), &c1.data);
+            c0.lazy_xors += c1.lazy_xors + 1;
+        }
+
         impl BitAnd for &FheBool {
             type Output = FheBool;
             fn bitand(self, rhs: Self) -> Self::Output {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
-                    FheBool {
-                        data: e.and(self.data(), rhs.data(), key),
-                    }
+                    let mut out = self.bootstrapped(e, key);
+                    let rhs = rhs.bootstrapped(e, key);
+                    e.and_inplace(out.data_mut(), rhs.data(), key);
+                    out
                 })
             }
         }
 
         impl BitAndAssign for FheBool {
-            fn bitand_assign(&mut self, rhs: Self) {
+            fn bitand_assign(&mut self, mut rhs: Self) {
                 BoolEvaluator::with_local_mut_mut(&mut |e| {
                     let key = RuntimeServerKey::global();
+                    self.bootstrap_lazy_xors(e, key);
+                    rhs.bootstrap_lazy_xors(e, key);
                     e.and_inplace(&mut self.data_mut(), rhs.data(), key);
                 });
             }
@@ -154,17 +411,20 @@ mod impl_bool_frontend {
             fn bitor(self, rhs: Self) -> Self::Output {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
-                    FheBool {
-                        data: e.or(self.data(), rhs.data(), key),
-                    }
+                    let mut out = self.bootstrapped(e, key);
+                    let rhs = rhs.bootstrapped(e, key);
+                    e.or_inplace(out.data_mut(), rhs.data(), key);
+                    out
                 })
             }
         }
 
         impl BitOrAssign for FheBool {
-            fn bitor_assign(&mut self, rhs: Self) {
+            fn bitor_assign(&mut self, mut rhs: Self) {
                 BoolEvaluator::with_local_mut_mut(&mut |e| {
                     let key = RuntimeServerKey::global();
+                    self.bootstrap_lazy_xors(e, key);
+                    rhs.bootstrap_lazy_xors(e, key);
                     e.or_inplace(&mut self.data_mut(), rhs.data(), key);
                 });
             }
@@ -175,9 +435,9 @@ mod impl_bool_frontend {
             fn bitxor(self, rhs: Self) -> Self::Output {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
-                    FheBool {
-                        data: e.xor(self.data(), rhs.data(), key),
-                    }
+                    let mut out = self.clone();
+                    lazy_xor_inplace(e, &mut out, rhs, key);
+                    out
                 })
             }
         }
@@ -186,7 +446,7 @@ mod impl_bool_frontend {
             fn bitxor_assign(&mut self, rhs: Self) {
                 BoolEvaluator::with_local_mut_mut(&mut |e| {
                     let key = RuntimeServerKey::global();
-                    e.xor_inplace(&mut self.data_mut(), rhs.data(), key);
+                    lazy_xor_inplace(e, self, &rhs, key);
                 });
             }
         }
@@ -194,8 +454,14 @@ mod impl_bool_frontend {
         impl Not for &FheBool {
             type Output = FheBool;
             fn not(self) -> Self::Output {
-                BoolEvaluator::with_local(|e| FheBool {
-                    data: e.not(self.data()),
+                BoolEvaluator::with_local(|e| {
+                    let mut out = self.clone();
+                    if out.is_lazy() {
+                        e.parity_not_inplace(out.data_mut());
+                    } else {
+                        e.not_inplace(out.data_mut());
+                    }
+                    out
                 })
             }
         }
@@ -206,15 +472,91 @@ mod impl_bool_frontend {
 mod common_mp_enc_dec {
     use itertools::Itertools;
 
-    use super::BoolEvaluator;
+    use num_traits::{ToPrimitive, Zero};
+
+    use super::{
+        evaluator::BoolEncoding,
+        keys::{KeyRefreshKeyEvaluationDomain, PublicKey},
+        BoolEvaluator,
+    };
     use crate::{
         pbs::{sample_extract, PbsInfo},
+        random::DefaultSecureRng,
+        rgsw::public_key_encrypt_rlwe,
         utils::WithLocal,
-        Matrix, RowEntity, SampleExtractor,
+        Encryptor, KeyRefresher, Matrix, MatrixEntity, RowEntity, SampleExtractor,
     };
 
     type Mat = Vec<Vec<u64>>;
 
+    impl<Rng, ModOp> Encryptor<[bool], Vec<Mat>> for PublicKey<Mat, Rng, ModOp> {
+        fn encrypt(&self, m: &[bool]) -> Vec<Mat> {
+            BoolEvaluator::with_local(|e| {
+                DefaultSecureRng::with_local_mut(|rng| {
+                    let parameters = e.parameters();
+                    let ring_size = parameters.rlwe_n().0;
+
+                    let rlwe_count = ((m.len() as f64 / ring_size as f64).ceil())
+                        .to_usize()
+                        .unwrap();
+
+                    // encrypt `m` into ceil(len(m)/N) RLWE ciphertexts
+                    let rlwes = (0..rlwe_count)
+                        .map(|index| {
+                            let mut message = vec![<Mat as Matrix>::MatElement::zero(); ring_size];
+                            m[(index * ring_size)..std::cmp::min(m.len(), (index + 1) * ring_size)]
+                                .iter()
+                                .enumerate()
+                                .for_each(|(i, v)| {
+                                    if *v {
+                                        message[i] = parameters.rlwe_q().true_el()
+                                    } else {
+                                        message[i] = parameters.rlwe_q().false_el()
+                                    }
+                                });
+
+                            // encrypt message
+                            let mut rlwe_out =
+                                <Mat as MatrixEntity>::zeros(2, parameters.rlwe_n().0);
+
+                            public_key_encrypt_rlwe::<_, _, _, _, i32, _>(
+                                &mut rlwe_out,
+                                self.key(),
+                                &message,
+                                e.pbs_info().modop_rlweq(),
+                                e.pbs_info().nttop_rlweq(),
+                                rng,
+                            );
+
+                            rlwe_out
+                        })
+                        .collect_vec();
+                    rlwes
+                })
+            })
+        }
+    }
+
+    impl<Rng, ModOp> Encryptor<bool, <Mat as Matrix>::R> for PublicKey<Mat, Rng, ModOp> {
+        fn encrypt(&self, m: &bool) -> <Mat as Matrix>::R {
+            let m = vec![*m];
+            let rlwe = &self.encrypt(m.as_slice())[0];
+            BoolEvaluator::with_local(|e| {
+                let mut lwe = <Mat as Matrix>::R::zeros(e.parameters().rlwe_n().0 + 1);
+                sample_extract(&mut lwe, rlwe, e.pbs_info().modop_rlweq(), 0);
+                lwe
+            })
+        }
+    }
+
+    impl KeyRefresher<<Mat as Matrix>::R> for KeyRefreshKeyEvaluationDomain<Mat> {
+        /// Key switch LWE ciphertext under ideal RLWE secret `s` of the current
+        /// session to ideal RLWE secret `s'` of the refreshed session
+        fn refresh(&self, c: &<Mat as Matrix>::R) -> <Mat as Matrix>::R {
+            BoolEvaluator::with_local(|e| e.key_refresh(c, self))
+        }
+    }
+
     impl SampleExtractor<<Mat as Matrix>::R> for Mat {
         /// Sample extract coefficient at `index` as a LWE ciphertext from RLWE
         /// ciphertext `Self`
@@ -269,5 +611,7 @@ mod common_mp_enc_dec {
     }
 }
 
+#[cfg(all(test, any(feature = "interactive_mp", feature = "non_interactive_mp")))]
+mod kat;
 #[cfg(test)]
 mod print_noise;
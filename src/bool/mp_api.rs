@@ -1,27 +1,29 @@
 use std::{cell::RefCell, sync::OnceLock};
 
 use crate::{
-    backend::{ModularOpsU64, ModulusPowerOf2},
-    ntt::NttBackendU64,
+    backend::ModularOpsU64,
+    ntt::NttBackendU64Simd,
     random::{DefaultSecureRng, NewWithSeed},
     utils::{Global, WithLocal},
 };
 
-use super::{evaluator::InteractiveMultiPartyCrs, keys::*, parameters::*, ClientKey};
+use super::{
+    evaluator::InteractiveMultiPartyCrs, keys::*, parameters::*, ClientKey, KeyRefreshKey,
+    KeyRefreshShare, ServerKeyShare,
+};
 
 pub(crate) type BoolEvaluator = super::evaluator::BoolEvaluator<
     Vec<Vec<u64>>,
-    NttBackendU64,
+    NttBackendU64Simd,
     ModularOpsU64<CiphertextModulus<u64>>,
-    ModulusPowerOf2<CiphertextModulus<u64>>,
-    ShoupServerKeyEvaluationDomain<Vec<Vec<u64>>>,
+    RuntimeServerKey,
 >;
 
 thread_local! {
     static BOOL_EVALUATOR: RefCell<Option<BoolEvaluator>> = RefCell::new(None);
 
 }
-static BOOL_SERVER_KEY: OnceLock<ShoupServerKeyEvaluationDomain<Vec<Vec<u64>>>> = OnceLock::new();
+static BOOL_SERVER_KEY: OnceLock<RuntimeServerKey> = OnceLock::new();
 
 static MULTI_PARTY_CRS: OnceLock<InteractiveMultiPartyCrs<[u8; 32]>> = OnceLock::new();
 
@@ -47,10 +49,32 @@ pub fn set_parameter_set(select: ParameterSelector) {
 }
 
 /// Set application specific interactive multi-party common reference string
+///
+/// Equivalent to [`set_common_reference_seed_for_session`] with an all-zero
+/// session id.
 pub fn set_common_reference_seed(seed: [u8; 32]) {
+    set_common_reference_seed_for_session(seed, [0u8; 32])
+}
+
+/// Set application specific interactive multi-party common reference string
+/// bound to `session_id`.
+///
+/// All sub-seeds of the CRS are derived from `seed` and `session_id`. Parties
+/// re-using the same `seed` across protocol runs must pick a fresh
+/// `session_id` for each run; otherwise the public randomness of the runs
+/// coincides.
+///
+/// Sub-seeds are also bound to the parameter set, hence the parameter set must
+/// be selected with [`set_parameter_set`] beforehand.
+pub fn set_common_reference_seed_for_session(seed: [u8; 32], session_id: [u8; 32]) {
+    let parameter_set = BoolEvaluator::with_local(|e| e.parameters().id());
     assert!(
         MULTI_PARTY_CRS
-            .set(InteractiveMultiPartyCrs { seed: seed })
+            .set(InteractiveMultiPartyCrs::new(
+                seed,
+                session_id,
+                parameter_set
+            ))
             .is_ok(),
         "Attempted to set MP SEED twice."
     )
@@ -74,22 +98,28 @@ pub fn collective_pk_share(
 
 /// Generate clients share for collective server key, i.e. round 2, of the
 /// 2 round protocol
+///
+/// Round 1 transcript of public key `pk` is included in the share. Before
+/// generating the share, party should check that the transcript commits to its
+/// own round 1 share with
+/// [`InteractiveMultiPartyTranscript::contains_public_key_share`].
+///
+/// Panics if `pk` is not aggregated from interactive multi-party public key
+/// shares of all `total_users`.
 pub fn collective_server_key_share<R, ModOp>(
     ck: &ClientKey,
     user_id: usize,
     total_users: usize,
     pk: &PublicKey<Vec<Vec<u64>>, R, ModOp>,
-) -> CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
-    Vec<Vec<u64>>,
-    BoolParameters<u64>,
-    InteractiveMultiPartyCrs<[u8; 32]>,
-> {
+) -> ServerKeyShare {
     BoolEvaluator::with_local_mut(|e| {
         let server_key_share = e.gen_interactive_multi_party_server_key_share(
             user_id,
             total_users,
             InteractiveMultiPartyCrs::global(),
             pk.key(),
+            pk.transcript()
+                .expect("Public key must be aggregated from round 1 public key shares"),
             ck,
         );
         server_key_share
@@ -100,6 +130,9 @@ pub fn collective_server_key_share<R, ModOp>(
 ///
 /// Public key shares are generated per client in round 1. Aggregation of public
 /// key shares marks the end of round 1.
+///
+/// Share of party with user_id `i` must be at index `i`. Returned public key
+/// holds round 1 transcript that commits to all shares.
 pub fn aggregate_public_key_shares(
     shares: &[CommonReferenceSeededCollectivePublicKeyShare<
         Vec<u64>,
@@ -112,19 +145,74 @@ pub fn aggregate_public_key_shares(
 
 /// Aggregate server key shares
 pub fn aggregate_server_key_shares(
-    shares: &[CommonReferenceSeededInteractiveMultiPartyServerKeyShare<
-        Vec<Vec<u64>>,
-        BoolParameters<u64>,
-        InteractiveMultiPartyCrs<[u8; 32]>,
-    >],
+    shares: &[ServerKeyShare],
 ) -> SeededInteractiveMultiPartyServerKey<
     Vec<Vec<u64>>,
     InteractiveMultiPartyCrs<[u8; 32]>,
     BoolParameters<u64>,
 > {
+    if let Err(e) = validate_server_key_shares(shares) {
+        panic!("{e}");
+    }
     BoolEvaluator::with_local(|e| e.aggregate_interactive_multi_party_server_key_shares(shares))
 }
 
+/// Validates server key shares before aggregation
+///
+/// Returns the first malformed share, along with user_id of the party that sent
+/// it and the reason, if any share has mismatching parameters or common
+/// reference seed, an invalid or duplicate user_id, ciphertexts with incorrect
+/// dimensions, auto keys for unexpected elements, or coefficients >= q.
+/// Otherwise returns [InvalidInteractiveServerKeyShares::TranscriptMismatch] if
+/// shares include different round 1 transcripts. The mismatch is not
+/// attributed to any party, because transcripts are not authenticated.
+///
+/// `aggregate_server_key_shares` validates the shares and panics if any share
+/// is malformed.
+pub fn validate_server_key_shares(
+    shares: &[ServerKeyShare],
+) -> Result<(), InvalidInteractiveServerKeyShares> {
+    BoolEvaluator::with_local(|e| {
+        e.validate_interactive_multi_party_server_key_shares(
+            InteractiveMultiPartyCrs::global(),
+            shares,
+        )
+    })
+}
+
+/// Generate client's share of key refresh key
+///
+/// To refresh the collective secret, each client generates a fresh client key
+/// `refreshed_ck` with `gen_client_key` and sends the key refresh share to the
+/// server. The server aggregates shares of all clients with
+/// `aggregate_key_refresh_shares` and key switches ciphertexts under the
+/// current collective secret to the refreshed one with `KeyRefresher`. Clients
+/// then generate the refreshed server key with their refreshed client keys in
+/// a new session (see `set_common_reference_seed_for_session`). Old client
+/// keys must be discarded afterwards.
+pub fn gen_key_refresh_share(ck: &ClientKey, refreshed_ck: &ClientKey) -> KeyRefreshShare {
+    BoolEvaluator::with_local(|e| {
+        e.gen_key_refresh_share(
+            InteractiveMultiPartyCrs::global().key_refresh_seed(),
+            &InteractiveMultiPartyClientKey::sk_rlwe(ck),
+            &InteractiveMultiPartyClientKey::sk_rlwe(refreshed_ck),
+        )
+    })
+}
+
+/// Aggregate key refresh shares of all clients into key refresh key
+///
+/// Panics if any share was generated with different parameters or common
+/// reference seed.
+pub fn aggregate_key_refresh_shares(shares: &[KeyRefreshShare]) -> KeyRefreshKey {
+    BoolEvaluator::with_local(|e| {
+        e.aggregate_key_refresh_shares(
+            InteractiveMultiPartyCrs::global().key_refresh_seed(),
+            shares,
+        )
+    })
+}
+
 impl
     SeededInteractiveMultiPartyServerKey<
         Vec<Vec<u64>>,
@@ -137,7 +225,9 @@ impl
         assert!(
             BOOL_SERVER_KEY
                 .set(ShoupServerKeyEvaluationDomain::from(
-                    ServerKeyEvaluationDomain::<_, _, DefaultSecureRng, NttBackendU64>::from(self),
+                    ServerKeyEvaluationDomain::<_, _, DefaultSecureRng, NttBackendU64Simd>::from(
+                        self
+                    ),
                 ))
                 .is_ok(),
             "Attempted to set server key twice."
@@ -178,7 +268,8 @@ impl WithLocal for BoolEvaluator {
     }
 }
 
-pub(crate) type RuntimeServerKey = ShoupServerKeyEvaluationDomain<Vec<Vec<u64>>>;
+pub(crate) type RuntimeServerKey =
+    ShoupServerKeyEvaluationDomain<Vec<Vec<u64>>, RuntimeBlindRotationKeyCt>;
 impl Global for RuntimeServerKey {
     fn global() -> &'static Self {
         BOOL_SERVER_KEY.get().expect("Server key not set!")
@@ -190,79 +281,18 @@ mod impl_enc_dec {
         bool::evaluator::BoolEncoding,
         multi_party::{
             multi_party_aggregate_decryption_shares_and_decrypt, multi_party_decryption_share,
+            verify_decryption_shares, MalformedDecryptionShare,
         },
-        pbs::{sample_extract, PbsInfo},
-        rgsw::public_key_encrypt_rlwe,
+        pbs::PbsInfo,
+        proof::DecryptionShareProof,
         utils::TryConvertFrom1,
-        Encryptor, Matrix, MatrixEntity, MultiPartyDecryptor, RowEntity,
+        Matrix, MultiPartyDecryptor, VerifiableMultiPartyDecryptor,
     };
-    use itertools::Itertools;
-    use num_traits::{ToPrimitive, Zero};
 
     use super::*;
 
     type Mat = Vec<Vec<u64>>;
 
-    impl<Rng, ModOp> Encryptor<[bool], Vec<Mat>> for PublicKey<Mat, Rng, ModOp> {
-        fn encrypt(&self, m: &[bool]) -> Vec<Mat> {
-            BoolEvaluator::with_local(|e| {
-                DefaultSecureRng::with_local_mut(|rng| {
-                    let parameters = e.parameters();
-                    let ring_size = parameters.rlwe_n().0;
-
-                    let rlwe_count = ((m.len() as f64 / ring_size as f64).ceil())
-                        .to_usize()
-                        .unwrap();
-
-                    // encrypt `m` into ceil(len(m)/N) RLWE ciphertexts
-                    let rlwes = (0..rlwe_count)
-                        .map(|index| {
-                            let mut message = vec![<Mat as Matrix>::MatElement::zero(); ring_size];
-                            m[(index * ring_size)..std::cmp::min(m.len(), (index + 1) * ring_size)]
-                                .iter()
-                                .enumerate()
-                                .for_each(|(i, v)| {
-                                    if *v {
-                                        message[i] = parameters.rlwe_q().true_el()
-                                    } else {
-                                        message[i] = parameters.rlwe_q().false_el()
-                                    }
-                                });
-
-                            // encrypt message
-                            let mut rlwe_out =
-                                <Mat as MatrixEntity>::zeros(2, parameters.rlwe_n().0);
-
-                            public_key_encrypt_rlwe::<_, _, _, _, i32, _>(
-                                &mut rlwe_out,
-                                self.key(),
-                                &message,
-                                e.pbs_info().modop_rlweq(),
-                                e.pbs_info().nttop_rlweq(),
-                                rng,
-                            );
-
-                            rlwe_out
-                        })
-                        .collect_vec();
-                    rlwes
-                })
-            })
-        }
-    }
-
-    impl<Rng, ModOp> Encryptor<bool, <Mat as Matrix>::R> for PublicKey<Mat, Rng, ModOp> {
-        fn encrypt(&self, m: &bool) -> <Mat as Matrix>::R {
-            let m = vec![*m];
-            let rlwe = &self.encrypt(m.as_slice())[0];
-            BoolEvaluator::with_local(|e| {
-                let mut lwe = <Mat as Matrix>::R::zeros(e.parameters().rlwe_n().0 + 1);
-                sample_extract(&mut lwe, rlwe, e.pbs_info().modop_rlweq(), 0);
-                lwe
-            })
-        }
-    }
-
     impl<K> MultiPartyDecryptor<bool, <Mat as Matrix>::R> for K
     where
         K: InteractiveMultiPartyClientKey,
@@ -276,7 +306,7 @@ mod impl_enc_dec {
                 DefaultSecureRng::with_local_mut(|rng| {
                     multi_party_decryption_share(
                         c,
-                        self.sk_rlwe().as_slice(),
+                        self.sk_rlwe().as_ref(),
                         e.pbs_info().modop_rlweq(),
                         rng,
                     )
@@ -300,17 +330,130 @@ mod impl_enc_dec {
             })
         }
     }
+
+    impl<K> VerifiableMultiPartyDecryptor<bool, <Mat as Matrix>::R> for K
+    where
+        K: InteractiveMultiPartyClientKey<Element = i32>,
+        <Mat as Matrix>::R:
+            TryConvertFrom1<[K::Element], CiphertextModulus<<Mat as Matrix>::MatElement>>,
+    {
+        type DecryptionShareProof = DecryptionShareProof;
+        type PublicKeyShare =
+            CommonReferenceSeededCollectivePublicKeyShare<Vec<u64>, [u8; 32], BoolParameters<u64>>;
+
+        fn gen_decryption_share_with_proof(
+            &self,
+            c: &<Mat as Matrix>::R,
+            public_key_share: &Self::PublicKeyShare,
+        ) -> (Self::DecryptionShare, Self::DecryptionShareProof) {
+            BoolEvaluator::with_local(|e| {
+                assert!(
+                    public_key_share.parameters() == e.parameters(),
+                    "Public key share has different parameters"
+                );
+                e.multi_party_decryption_share_with_proof(
+                    c,
+                    self.sk_rlwe().as_ref(),
+                    *public_key_share.cr_seed(),
+                    public_key_share.share(),
+                )
+            })
+        }
+
+        fn aggregate_decryption_shares_verified(
+            &self,
+            c: &<Mat as Matrix>::R,
+            shares: &[Self::DecryptionShare],
+            proofs: &[Self::DecryptionShareProof],
+            public_key_shares: &[Self::PublicKeyShare],
+        ) -> Result<bool, MalformedDecryptionShare> {
+            BoolEvaluator::with_local(|e| {
+                verify_decryption_shares(
+                    shares,
+                    proofs,
+                    public_key_shares,
+                    |_, share, proof, pk_share| {
+                        if pk_share.parameters() != e.parameters() {
+                            return Err("Public key share has different parameters".to_string());
+                        }
+                        e.verify_multi_party_decryption_share(
+                            c,
+                            *share,
+                            proof,
+                            *pk_share.cr_seed(),
+                            pk_share.share(),
+                        )
+                    },
+                )
+            })?;
+            Ok(self.aggregate_decryption_shares(c, shares))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use itertools::Itertools;
+    use itertools::{izip, Itertools};
     use rand::{thread_rng, Rng, RngCore};
 
-    use crate::{bool::evaluator::BoolEncoding, Encryptor, MultiPartyDecryptor, SampleExtractor};
+    use crate::{
+        backend::Modulus, bool::evaluator::BoolEncoding, ntt::NttBackendU64, BatchedFheUint8,
+        Encryptor, FheBool, FheUint8, KeyRefresher, MultiPartyDecryptor, SampleExtractor,
+        VerifiableMultiPartyDecryptor,
+    };
 
     use super::*;
 
+    #[test]
+    fn crs_seeds_are_domain_separated() {
+        let seed = [7u8; 32];
+        let crs = InteractiveMultiPartyCrs::new(seed, [0u8; 32], I_2P_LB_SR.id());
+
+        // derivation is deterministic
+        assert!(
+            crs.public_key_share_seed()
+                == InteractiveMultiPartyCrs::new(seed, [0u8; 32], I_2P_LB_SR.id())
+                    .public_key_share_seed()
+        );
+
+        // different components never share a seed
+        let seeds = [
+            crs.public_key_share_seed(),
+            crs.auto_keys_cts_seed(),
+            crs.lwe_ksk_cts_seed_seed(),
+        ];
+        assert!(seeds.iter().all_unique());
+
+        // different sessions never share a seed
+        let other_session = InteractiveMultiPartyCrs::new(seed, [1u8; 32], I_2P_LB_SR.id());
+        assert!(crs.public_key_share_seed() != other_session.public_key_share_seed());
+        assert!(crs.auto_keys_cts_seed() != other_session.auto_keys_cts_seed());
+
+        // different parameter sets never share a seed
+        let other_parameter_set = InteractiveMultiPartyCrs::new(seed, [0u8; 32], I_4P.id());
+        assert!(crs.public_key_share_seed() != other_parameter_set.public_key_share_seed());
+        assert!(crs.auto_keys_cts_seed() != other_parameter_set.auto_keys_cts_seed());
+        assert!(crs.lwe_ksk_cts_seed_seed() != other_parameter_set.lwe_ksk_cts_seed_seed());
+        assert!(crs.key_refresh_seed() != other_parameter_set.key_refresh_seed());
+
+        // round 2 seeds are bound to round 1 transcript
+        let round_two = crs.bound_to_transcript(&[1u8; 32]);
+        let other_round_two = crs.bound_to_transcript(&[2u8; 32]);
+        assert!(round_two.auto_keys_cts_seed() != crs.auto_keys_cts_seed());
+        assert!(round_two.auto_keys_cts_seed() != other_round_two.auto_keys_cts_seed());
+        assert!(round_two.lwe_ksk_cts_seed_seed() != other_round_two.lwe_ksk_cts_seed_seed());
+
+        // different protocols never share a seed
+        let ni_crs = crate::bool::evaluator::NonInteractiveMultiPartyCrs::new(
+            seed,
+            [0u8; 32],
+            I_2P_LB_SR.id(),
+        );
+        assert!(crs.public_key_share_seed() != ni_crs.public_key_share_seed());
+        assert!(crs.auto_keys_cts_seed() != ni_crs.auto_keys_cts_seed());
+        assert!(crs.lwe_ksk_cts_seed_seed() != ni_crs.lwe_ksk_cts_seed());
+    }
+
     #[test]
     fn batched_fhe_u8s_extract_works() {
         set_parameter_set(ParameterSelector::InteractiveLTE2Party);
@@ -349,6 +492,229 @@ mod tests {
             .collect_vec();
 
         assert_eq!(m, m_back);
+
+        // extract all FheUint8s at once from batch encrypted with public key
+        let batched_ct: BatchedFheUint8 = pk.encrypt(m.as_slice());
+        let m_back = batched_ct
+            .extract_all()
+            .iter()
+            .map(|ct| {
+                cks[1].aggregate_decryption_shares(
+                    ct,
+                    &cks.iter().map(|k| k.gen_decryption_share(ct)).collect_vec(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(m, m_back);
+
+        // public key encryption of single u8
+        let m = thread_rng().gen::<u8>();
+        let ct: FheUint8 = pk.encrypt(&m);
+        let m_back = cks[0].aggregate_decryption_shares(
+            &ct,
+            &cks.iter()
+                .map(|k| k.gen_decryption_share(&ct))
+                .collect_vec(),
+        );
+        assert_eq!(m, m_back);
+    }
+
+    #[test]
+    fn key_refresh_switches_ciphertexts_to_refreshed_secret() {
+        set_parameter_set(ParameterSelector::InteractiveLTE2Party);
+        let cr_seed = InteractiveMultiPartyCrs::random(I_2P_LB_SR.id());
+
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let pk_shares = cks
+            .iter()
+            .map(|k| BoolEvaluator::with_local(|e| e.multi_party_public_key_share(&cr_seed, k)))
+            .collect_vec();
+        let pk = aggregate_public_key_shares(&pk_shares);
+
+        let m = (0..16).map(|_| thread_rng().gen_bool(0.5)).collect_vec();
+        let cts = m.iter().map(|v| FheBool::new(pk.encrypt(v))).collect_vec();
+        let ct_u8 = pk.encrypt(&123u8);
+
+        // each party refreshes its secret
+        let refreshed_cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let key_refresh_shares = cks
+            .iter()
+            .zip(refreshed_cks.iter())
+            .map(|(ck, refreshed_ck)| {
+                BoolEvaluator::with_local(|e| {
+                    e.gen_key_refresh_share(
+                        cr_seed.key_refresh_seed(),
+                        &InteractiveMultiPartyClientKey::sk_rlwe(ck),
+                        &InteractiveMultiPartyClientKey::sk_rlwe(refreshed_ck),
+                    )
+                })
+            })
+            .collect_vec();
+        let key_refresh_key = BoolEvaluator::with_local(|e| {
+            e.aggregate_key_refresh_shares(cr_seed.key_refresh_seed(), &key_refresh_shares)
+        });
+
+        izip!(cts.iter(), m.iter()).for_each(|(ct, m)| {
+            let refreshed_ct = key_refresh_key.refresh(ct);
+            let dec_shares = refreshed_cks
+                .iter()
+                .map(|k| k.gen_decryption_share(&refreshed_ct))
+                .collect_vec();
+            let m_back = refreshed_cks[0].aggregate_decryption_shares(&refreshed_ct, &dec_shares);
+            assert_eq!(m_back, *m);
+        });
+
+        let refreshed_ct_u8 = key_refresh_key.refresh(&ct_u8);
+        let dec_shares = refreshed_cks
+            .iter()
+            .map(|k| k.gen_decryption_share(&refreshed_ct_u8))
+            .collect_vec();
+        let m_back: u8 =
+            refreshed_cks[0].aggregate_decryption_shares(&refreshed_ct_u8, &dec_shares);
+        assert_eq!(m_back, 123u8);
+    }
+
+    #[test]
+    fn round_one_transcript_detects_inconsistent_public_key_shares() {
+        set_parameter_set(ParameterSelector::InteractiveLTE2Party);
+        let cr_seed = InteractiveMultiPartyCrs::random(I_2P_LB_SR.id());
+
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+
+        let mut pk_shares = cks
+            .iter()
+            .map(|k| BoolEvaluator::with_local(|e| e.multi_party_public_key_share(&cr_seed, k)))
+            .collect_vec();
+        let pk: PublicKey<Vec<Vec<u64>>, DefaultSecureRng, ModularOpsU64<CiphertextModulus<u64>>> =
+            PublicKey::from(pk_shares.as_slice());
+
+        // transcript commits to each party's share
+        let transcript = pk.transcript().unwrap();
+        assert!(transcript.contains_public_key_share(0, &pk_shares[0]));
+        assert!(transcript.contains_public_key_share(1, &pk_shares[1]));
+        assert!(!transcript.contains_public_key_share(0, &pk_shares[1]));
+
+        // user 0 sends a different public key share to user 1
+        let other_pk_share =
+            BoolEvaluator::with_local(|e| e.multi_party_public_key_share(&cr_seed, &cks[0]));
+        let other_pk: PublicKey<
+            Vec<Vec<u64>>,
+            DefaultSecureRng,
+            ModularOpsU64<CiphertextModulus<u64>>,
+        > = PublicKey::from(vec![other_pk_share, pk_shares.remove(1)].as_slice());
+        assert!(!other_pk
+            .transcript()
+            .unwrap()
+            .contains_public_key_share(0, &pk_shares[0]));
+
+        let server_key_share = |user_id: usize, pk: &PublicKey<_, DefaultSecureRng, _>| {
+            BoolEvaluator::with_local_mut(|e| {
+                e.gen_interactive_multi_party_server_key_share(
+                    user_id,
+                    parties,
+                    &cr_seed,
+                    pk.key(),
+                    pk.transcript().unwrap(),
+                    &cks[user_id],
+                )
+            })
+        };
+
+        let honest_shares = vec![server_key_share(0, &pk), server_key_share(1, &pk)];
+        assert!(BoolEvaluator::with_local(
+            |e| e.validate_interactive_multi_party_server_key_shares(&cr_seed, &honest_shares)
+        )
+        .is_ok());
+
+        let shares = vec![
+            honest_shares.into_iter().next().unwrap(),
+            server_key_share(1, &other_pk),
+        ];
+        let validate = |shares: &[_]| {
+            BoolEvaluator::with_local(|e| {
+                e.validate_interactive_multi_party_server_key_shares(&cr_seed, shares)
+            })
+        };
+        assert_eq!(
+            validate(&shares).unwrap_err(),
+            InvalidInteractiveServerKeyShares::TranscriptMismatch
+        );
+
+        // user 1 includes a transcript in which user 0's public key share differs.
+        // Mismatch is not attributed to user 0.
+        let mut shares = shares;
+        shares[1] = server_key_share(1, &pk);
+        shares[1].transcript = other_pk.transcript().unwrap().clone();
+        assert_eq!(
+            validate(&shares).unwrap_err(),
+            InvalidInteractiveServerKeyShares::TranscriptMismatch
+        );
+    }
+
+    #[test]
+    fn verified_decryption_identifies_cheating_party() {
+        set_parameter_set(ParameterSelector::InteractiveLTE2Party);
+        let cr_seed = InteractiveMultiPartyCrs::random(I_2P_LB_SR.id());
+
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let mut pk_shares = cks
+            .iter()
+            .map(|k| BoolEvaluator::with_local(|e| e.multi_party_public_key_share(&cr_seed, k)))
+            .collect_vec();
+        let pk = aggregate_public_key_shares(&pk_shares);
+        let q = BoolEvaluator::with_local(|e| e.parameters().rlwe_q().q().unwrap());
+
+        let ct = FheBool::new(pk.encrypt(&true));
+        let (shares, proofs): (Vec<_>, Vec<_>) = izip!(cks.iter(), pk_shares.iter())
+            .map(|(k, pk_share)| k.gen_decryption_share_with_proof(&ct, pk_share))
+            .unzip();
+        assert_eq!(
+            cks[0].aggregate_decryption_shares_verified(&ct, &shares, &proofs, &pk_shares),
+            Ok(true)
+        );
+
+        // party 1 flips the output by shifting its share by -Q/4
+        let mut cheat_shares = shares.clone();
+        cheat_shares[1] = (cheat_shares[1] + q - (q >> 2)) % q;
+        assert!(!cks[0].aggregate_decryption_shares(&ct, &cheat_shares));
+        let err = cks[0]
+            .aggregate_decryption_shares_verified(&ct, &cheat_shares, &proofs, &pk_shares)
+            .unwrap_err();
+        assert_eq!(err.share_index, 1);
+
+        // proof is checked against public key share of the party at the same index
+        pk_shares.swap(0, 1);
+        let err = cks[0]
+            .aggregate_decryption_shares_verified(&ct, &shares, &proofs, &pk_shares)
+            .unwrap_err();
+        assert_eq!(err.share_index, 0);
+        pk_shares.swap(0, 1);
+
+        // missing proof
+        let err = cks[0]
+            .aggregate_decryption_shares_verified(&ct, &shares, &proofs[..1], &pk_shares)
+            .unwrap_err();
+        assert_eq!(err.share_index, 1);
+
+        // party 0 flips bit 3 of FheUint8
+        let m = thread_rng().gen::<u8>();
+        let ct: FheUint8 = pk.encrypt(&m);
+        let (mut shares, proofs): (Vec<_>, Vec<_>) = izip!(cks.iter(), pk_shares.iter())
+            .map(|(k, pk_share)| k.gen_decryption_share_with_proof(&ct, pk_share))
+            .unzip();
+        assert_eq!(
+            cks[1].aggregate_decryption_shares_verified(&ct, &shares, &proofs, &pk_shares),
+            Ok(m)
+        );
+        shares[0][3] = (shares[0][3] + (q >> 2)) % q;
+        let err = cks[1]
+            .aggregate_decryption_shares_verified(&ct, &shares, &proofs, &pk_shares)
+            .unwrap_err();
+        assert_eq!(err.share_index, 0);
+        assert!(err.reason.starts_with("bit 3"));
     }
 
     mod sp_api {
@@ -415,18 +781,17 @@ mod tests {
             K: Encryptor<bool, C>,
         {
             fn encrypt(&self, m: &bool) -> FheBool<C> {
-                FheBool {
-                    data: self.encrypt(m),
-                }
+                FheBool::new(self.encrypt(m))
             }
         }
 
-        impl<K: SinglePartyClientKey<Element = i32>, C> Decryptor<bool, FheBool<C>> for K
-        where
-            K: Decryptor<bool, C>,
-        {
-            fn decrypt(&self, c: &FheBool<C>) -> bool {
-                self.decrypt(c.data())
+        impl<K: SinglePartyClientKey<Element = i32>> Decryptor<bool, FheBool<Vec<u64>>> for K {
+            fn decrypt(&self, c: &FheBool<Vec<u64>>) -> bool {
+                if c.is_lazy() {
+                    BoolEvaluator::with_local(|e| e.sk_decrypt_parity(&c.data, self))
+                } else {
+                    self.decrypt(c.data())
+                }
             }
         }
 
@@ -662,7 +1027,7 @@ mod tests {
         #[test]
         #[cfg(feature = "interactive_mp")]
         fn all_bool_apis() {
-            use crate::FheBool;
+            use crate::{bool::parameters::LAZY_XOR_BUDGET, FheBool};
 
             set_single_party_parameter_sets(SP_TEST_BOOL_PARAMS);
 
@@ -692,6 +1057,363 @@ mod tests {
                 let out = ck.decrypt(&c_out);
                 assert_eq!(out, !a, "Expected {} but got {out}", !a);
             }
+
+            // XOR chains are evaluated lazily
+            let xor_chain = |len: usize| {
+                let mut m = thread_rng().gen_bool(0.5);
+                let mut c: FheBool = ck.encrypt(&m);
+                for i in 0..len {
+                    let b = thread_rng().gen_bool(0.5);
+                    let c_b: FheBool = ck.encrypt(&b);
+                    if i & 1 == 0 {
+                        c ^= c_b;
+                    } else {
+                        c = &c_b ^ &c;
+                    }
+                    m ^= b;
+                    if thread_rng().gen_bool(0.5) {
+                        c = !(&c);
+                        m = !m;
+                    }
+                    assert!(c.lazy_xors < LAZY_XOR_BUDGET);
+                    assert_eq!(ck.decrypt(&c), m);
+                }
+                (m, c)
+            };
+            for _ in 0..20 {
+                let (a, c_a) = xor_chain(thread_rng().gen_range(1..2 * LAZY_XOR_BUDGET));
+                let (b, c_b) = xor_chain(thread_rng().gen_range(0..2 * LAZY_XOR_BUDGET));
+
+                let c_out = &c_a ^ &c_b;
+                assert!(c_out.lazy_xors < LAZY_XOR_BUDGET);
+                let out = ck.decrypt(&c_out);
+                assert_eq!(out, a ^ b, "Expected {} but got {out}", a ^ b);
+
+                let c_out = &c_a & &c_b;
+                assert!(!c_out.is_lazy());
+                let out = ck.decrypt(&c_out);
+                assert_eq!(out, a & b, "Expected {} but got {out}", a & b);
+
+                let mut c_out = c_a.clone();
+                c_out |= c_b.clone();
+                let out = ck.decrypt(&c_out);
+                assert_eq!(out, a | b, "Expected {} but got {out}", a | b);
+            }
+        }
+
+        #[test]
+        fn three_input_gates_work() {
+            use crate::BooleanGates;
+
+            let mut evaluator = BoolEvaluator::new(SP_TEST_BOOL_PARAMS);
+            let ck = evaluator.client_key();
+            let server_key = ShoupServerKeyEvaluationDomain::from(ServerKeyEvaluationDomain::<
+                _,
+                _,
+                DefaultSecureRng,
+                NttBackendU64,
+            >::from(
+                &evaluator.single_party_server_key(&ck),
+            ));
+
+            // outputs of gates are fed back as inputs
+            let mut m = [true, false, true];
+            let mut c = m.map(|v| evaluator.sk_encrypt(v, &ck));
+            for i in 0..60 {
+                let (c_out, out) = match i % 3 {
+                    0 => (
+                        evaluator.maj3(&c[0], &c[1], &c[2], &server_key),
+                        (m[0] & m[1]) | (m[0] & m[2]) | (m[1] & m[2]),
+                    ),
+                    1 => (
+                        evaluator.xor3(&c[0], &c[1], &c[2], &server_key),
+                        m[0] ^ m[1] ^ m[2],
+                    ),
+                    _ => (
+                        evaluator.mux(&c[0], &c[1], &c[2], &server_key),
+                        if m[0] { m[1] } else { m[2] },
+                    ),
+                };
+
+                let out_back = evaluator.sk_decrypt(&c_out, &ck);
+                assert_eq!(out_back, out, "Expected {out} but got {out_back} at {i}");
+
+                let index = thread_rng().gen_range(0..3);
+                m[index] = out;
+                c[index] = c_out;
+                let index = (index + 1) % 3;
+                m[index] = thread_rng().gen_bool(0.5);
+                c[index] = evaluator.sk_encrypt(m[index], &ck);
+            }
+        }
+
+        #[test]
+        fn batch_gate_works() {
+            use crate::{BoolGate, BooleanGates};
+
+            let mut evaluator = BoolEvaluator::new(SP_TEST_BOOL_PARAMS);
+            let ck = evaluator.client_key();
+            let server_key = ShoupServerKeyEvaluationDomain::from(ServerKeyEvaluationDomain::<
+                _,
+                _,
+                DefaultSecureRng,
+                NttBackendU64,
+            >::from(
+                &evaluator.single_party_server_key(&ck),
+            ));
+
+            let batch_size = 8;
+            for op in [BoolGate::And, BoolGate::Xnor] {
+                let m0 = (0..batch_size)
+                    .map(|_| thread_rng().gen_bool(0.5))
+                    .collect_vec();
+                let m1 = (0..batch_size)
+                    .map(|_| thread_rng().gen_bool(0.5))
+                    .collect_vec();
+                let mut c0 = m0
+                    .iter()
+                    .map(|m| evaluator.sk_encrypt(*m, &ck))
+                    .collect_vec();
+                let c1 = m1
+                    .iter()
+                    .map(|m| evaluator.sk_encrypt(*m, &ck))
+                    .collect_vec();
+
+                evaluator.batch_gate(op, &mut c0, &c1, &server_key);
+
+                izip!(c0.iter(), m0.iter(), m1.iter()).for_each(|(c, a, b)| {
+                    let out = match op {
+                        BoolGate::And => a & b,
+                        BoolGate::Xnor => !(a ^ b),
+                    };
+                    let out_back = evaluator.sk_decrypt(c, &ck);
+                    assert_eq!(
+                        out_back, out,
+                        "Expected {out} but got {out_back} for {op:?}"
+                    );
+                });
+            }
+
+            for s in [true, false] {
+                let c_s = evaluator.sk_encrypt(s, &ck);
+                let [m0, m1] = [0, 1].map(|_| {
+                    (0..batch_size)
+                        .map(|_| thread_rng().gen_bool(0.5))
+                        .collect_vec()
+                });
+                let [c0, c1] = [&m0, &m1].map(|m| {
+                    m.iter()
+                        .map(|m| evaluator.sk_encrypt(*m, &ck))
+                        .collect_vec()
+                });
+
+                let c_out = evaluator.batch_mux(&c_s, &c0, &c1, &server_key);
+
+                let out = if s { &m0 } else { &m1 };
+                let out_back = c_out
+                    .iter()
+                    .map(|c| evaluator.sk_decrypt(c, &ck))
+                    .collect_vec();
+                assert_eq!(&out_back, out, "batch mux with selector {s}");
+            }
+        }
+
+        #[test]
+        fn fft_blind_rotation_matches_ntt_blind_rotation() {
+            use crate::{pbs::FourierCt, BooleanGates};
+
+            type Evaluator<C> = crate::bool::evaluator::BoolEvaluator<
+                Vec<Vec<u64>>,
+                NttBackendU64,
+                ModularOpsU64<CiphertextModulus<u64>>,
+                ShoupServerKeyEvaluationDomain<Vec<Vec<u64>>, C>,
+            >;
+
+            for parameters in [SP_TEST_BOOL_PARAMS] {
+                let mut ntt_evaluator = Evaluator::<NormalAndShoup<_>>::new(parameters.clone());
+                let mut fft_evaluator = Evaluator::<FourierCt>::new(parameters);
+                let ck = ntt_evaluator.client_key();
+                let seeded_key = ntt_evaluator.single_party_server_key(&ck);
+                let eval_key = || {
+                    ServerKeyEvaluationDomain::<_, _, DefaultSecureRng, NttBackendU64>::from(
+                        &seeded_key,
+                    )
+                };
+                let ntt_key = ShoupServerKeyEvaluationDomain::from(eval_key());
+                let fft_key = ShoupServerKeyEvaluationDomain::from(eval_key());
+
+                for i in 0..24 {
+                    let (a, b) = (thread_rng().gen_bool(0.5), thread_rng().gen_bool(0.5));
+                    let c_a = ntt_evaluator.sk_encrypt(a, &ck);
+                    let c_b = ntt_evaluator.sk_encrypt(b, &ck);
+
+                    let (mut c_ntt, mut c_fft) = (c_a.clone(), c_a);
+                    let out = match i % 6 {
+                        0 => {
+                            ntt_evaluator.nand_inplace(&mut c_ntt, &c_b, &ntt_key);
+                            fft_evaluator.nand_inplace(&mut c_fft, &c_b, &fft_key);
+                            !(a & b)
+                        }
+                        1 => {
+                            ntt_evaluator.and_inplace(&mut c_ntt, &c_b, &ntt_key);
+                            fft_evaluator.and_inplace(&mut c_fft, &c_b, &fft_key);
+                            a & b
+                        }
+                        2 => {
+                            ntt_evaluator.or_inplace(&mut c_ntt, &c_b, &ntt_key);
+                            fft_evaluator.or_inplace(&mut c_fft, &c_b, &fft_key);
+                            a | b
+                        }
+                        3 => {
+                            ntt_evaluator.nor_inplace(&mut c_ntt, &c_b, &ntt_key);
+                            fft_evaluator.nor_inplace(&mut c_fft, &c_b, &fft_key);
+                            !(a | b)
+                        }
+                        4 => {
+                            ntt_evaluator.xor_inplace(&mut c_ntt, &c_b, &ntt_key);
+                            fft_evaluator.xor_inplace(&mut c_fft, &c_b, &fft_key);
+                            a ^ b
+                        }
+                        _ => {
+                            ntt_evaluator.xnor_inplace(&mut c_ntt, &c_b, &ntt_key);
+                            fft_evaluator.xnor_inplace(&mut c_fft, &c_b, &fft_key);
+                            !(a ^ b)
+                        }
+                    };
+
+                    // FFT products are exact, hence blind rotation outputs are equal
+                    assert_eq!(c_ntt, c_fft);
+                    let out_back = fft_evaluator.sk_decrypt(&c_fft, &ck);
+                    assert_eq!(out_back, out, "Expected {out} but got {out_back}");
+                }
+            }
+        }
+
+        #[test]
+        fn simd_ntt_backend_gate_outputs_match_scalar() {
+            use crate::BooleanGates;
+
+            // `BoolEvaluator` uses NttBackendU64Simd
+            let mut simd_evaluator = BoolEvaluator::new(SP_TEST_BOOL_PARAMS);
+            let mut scalar_evaluator = crate::bool::evaluator::BoolEvaluator::<
+                Vec<Vec<u64>>,
+                NttBackendU64,
+                ModularOpsU64<CiphertextModulus<u64>>,
+                RuntimeServerKey,
+            >::new(SP_TEST_BOOL_PARAMS);
+            let ck = simd_evaluator.client_key();
+            let server_key = ShoupServerKeyEvaluationDomain::from(ServerKeyEvaluationDomain::<
+                _,
+                _,
+                DefaultSecureRng,
+                NttBackendU64,
+            >::from(
+                &simd_evaluator.single_party_server_key(&ck),
+            ));
+
+            for i in 0..24 {
+                let (a, b) = (thread_rng().gen_bool(0.5), thread_rng().gen_bool(0.5));
+                let c_a = simd_evaluator.sk_encrypt(a, &ck);
+                let c_b = simd_evaluator.sk_encrypt(b, &ck);
+
+                let (mut c_simd, mut c_scalar) = (c_a.clone(), c_a);
+                let out = match i % 6 {
+                    0 => {
+                        simd_evaluator.nand_inplace(&mut c_simd, &c_b, &server_key);
+                        scalar_evaluator.nand_inplace(&mut c_scalar, &c_b, &server_key);
+                        !(a & b)
+                    }
+                    1 => {
+                        simd_evaluator.and_inplace(&mut c_simd, &c_b, &server_key);
+                        scalar_evaluator.and_inplace(&mut c_scalar, &c_b, &server_key);
+                        a & b
+                    }
+                    2 => {
+                        simd_evaluator.or_inplace(&mut c_simd, &c_b, &server_key);
+                        scalar_evaluator.or_inplace(&mut c_scalar, &c_b, &server_key);
+                        a | b
+                    }
+                    3 => {
+                        simd_evaluator.nor_inplace(&mut c_simd, &c_b, &server_key);
+                        scalar_evaluator.nor_inplace(&mut c_scalar, &c_b, &server_key);
+                        !(a | b)
+                    }
+                    4 => {
+                        simd_evaluator.xor_inplace(&mut c_simd, &c_b, &server_key);
+                        scalar_evaluator.xor_inplace(&mut c_scalar, &c_b, &server_key);
+                        a ^ b
+                    }
+                    _ => {
+                        simd_evaluator.xnor_inplace(&mut c_simd, &c_b, &server_key);
+                        scalar_evaluator.xnor_inplace(&mut c_scalar, &c_b, &server_key);
+                        !(a ^ b)
+                    }
+                };
+
+                // SIMD butterflies output the same residues as scalar butterflies
+                assert_eq!(c_simd, c_scalar);
+                let out_back = simd_evaluator.sk_decrypt(&c_simd, &ck);
+                assert_eq!(out_back, out, "Expected {out} but got {out_back}");
+            }
+        }
+
+        #[test]
+        fn native_modulus_gates_work() {
+            use crate::{backend::WordSizeModulus, ntt::IdentityNtt, pbs::FourierCt, BooleanGates};
+
+            // NTT does not exist mod 2^64. Server key is generated with FFT and
+            // blind rotation runs with server key in Fourier domain.
+            let mut evaluator = crate::bool::evaluator::BoolEvaluator::<
+                Vec<Vec<u64>>,
+                IdentityNtt,
+                WordSizeModulus<CiphertextModulus<u64>>,
+                ShoupServerKeyEvaluationDomain<Vec<Vec<u64>>, FourierCt>,
+            >::new(SP_TEST_NATIVE_PARAMS);
+            let ck = evaluator.client_key();
+            let server_key = ShoupServerKeyEvaluationDomain::from(ServerKeyEvaluationDomain::<
+                _,
+                _,
+                DefaultSecureRng,
+                IdentityNtt,
+            >::from(
+                &evaluator.single_party_server_key(&ck),
+            ));
+
+            let mut m = thread_rng().gen_bool(0.5);
+            let mut c = evaluator.sk_encrypt(m, &ck);
+            for i in 0..60 {
+                let b = thread_rng().gen_bool(0.5);
+                let c_b = evaluator.sk_encrypt(b, &ck);
+                // chain gates such that every gate bootstraps output of previous gate
+                m = match i % 6 {
+                    0 => {
+                        evaluator.nand_inplace(&mut c, &c_b, &server_key);
+                        !(m & b)
+                    }
+                    1 => {
+                        evaluator.and_inplace(&mut c, &c_b, &server_key);
+                        m & b
+                    }
+                    2 => {
+                        evaluator.or_inplace(&mut c, &c_b, &server_key);
+                        m | b
+                    }
+                    3 => {
+                        evaluator.nor_inplace(&mut c, &c_b, &server_key);
+                        !(m | b)
+                    }
+                    4 => {
+                        evaluator.xor_inplace(&mut c, &c_b, &server_key);
+                        m ^ b
+                    }
+                    _ => {
+                        evaluator.xnor_inplace(&mut c, &c_b, &server_key);
+                        !(m ^ b)
+                    }
+                };
+                let m_back = evaluator.sk_decrypt(&c, &ck);
+                assert_eq!(m_back, m, "Expected {m} but got {m_back} after gate {i}");
+            }
         }
     }
 }
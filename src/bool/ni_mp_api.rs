@@ -1,38 +1,37 @@
 use std::{cell::RefCell, sync::OnceLock};
 
 use crate::{
-    backend::ModulusPowerOf2,
     bool::parameters::ParameterVariant,
-    random::DefaultSecureRng,
+    random::{DefaultSecureRng, PrgAlgorithm},
     utils::{Global, WithLocal},
-    ModularOpsU64, NttBackendU64,
+    ModularOpsU64, NttBackendU64Simd,
 };
 
 use super::{
     evaluator::NonInteractiveMultiPartyCrs,
     keys::{
-        CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare,
-        NonInteractiveServerKeyEvaluationDomain, SeededNonInteractiveMultiPartyServerKey,
+        CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare, MalformedServerKeyShare,
+        NonInteractiveLateJoiningKeyShare, NonInteractiveMultiPartyClientKey,
+        NonInteractiveServerKeyEvaluationDomain, NonInteractiveServerKeyShareProof, PublicKey,
+        RuntimeBlindRotationKeyCt, SeededNonInteractiveMultiPartyServerKey,
         ShoupNonInteractiveServerKeyEvaluationDomain,
     },
     parameters::{BoolParameters, CiphertextModulus, NI_2P, NI_4P_HB_FR, NI_8P},
-    ClientKey,
+    ClientKey, KeyRefreshKey, KeyRefreshShare, ServerKeyShare,
 };
 
 pub(crate) type BoolEvaluator = super::evaluator::BoolEvaluator<
     Vec<Vec<u64>>,
-    NttBackendU64,
+    NttBackendU64Simd,
     ModularOpsU64<CiphertextModulus<u64>>,
-    ModulusPowerOf2<CiphertextModulus<u64>>,
-    ShoupNonInteractiveServerKeyEvaluationDomain<Vec<Vec<u64>>>,
+    RuntimeServerKey,
 >;
 
 thread_local! {
     static BOOL_EVALUATOR: RefCell<Option<BoolEvaluator>> = RefCell::new(None);
 
 }
-static BOOL_SERVER_KEY: OnceLock<ShoupNonInteractiveServerKeyEvaluationDomain<Vec<Vec<u64>>>> =
-    OnceLock::new();
+static BOOL_SERVER_KEY: OnceLock<RuntimeServerKey> = OnceLock::new();
 
 static MULTI_PARTY_CRS: OnceLock<NonInteractiveMultiPartyCrs<[u8; 32]>> = OnceLock::new();
 
@@ -56,18 +55,41 @@ pub fn set_parameter_set(select: ParameterSelector) {
     }
 }
 
+/// Set application specific non-interactive multi-party common reference
+/// string
+///
+/// Equivalent to [`set_common_reference_seed_for_session`] with an all-zero
+/// session id.
 pub fn set_common_reference_seed(seed: [u8; 32]) {
-    BoolEvaluator::with_local(|e| {
+    set_common_reference_seed_for_session(seed, [0u8; 32])
+}
+
+/// Set application specific non-interactive multi-party common reference
+/// string bound to `session_id`.
+///
+/// All sub-seeds of the CRS are derived from `seed` and `session_id`. Parties
+/// re-using the same `seed` across protocol runs must pick a fresh
+/// `session_id` for each run.
+///
+/// Sub-seeds are also bound to the parameter set, hence the parameter set must
+/// be selected with [`set_parameter_set`] beforehand.
+pub fn set_common_reference_seed_for_session(seed: [u8; 32], session_id: [u8; 32]) {
+    let parameter_set = BoolEvaluator::with_local(|e| {
         assert_eq!(
             e.parameters().variant(),
             &ParameterVariant::NonInteractiveMultiParty,
             "Set parameters do not support Non interactive multi-party"
         );
+        e.parameters().id()
     });
 
     assert!(
         MULTI_PARTY_CRS
-            .set(NonInteractiveMultiPartyCrs { seed: seed })
+            .set(NonInteractiveMultiPartyCrs::new(
+                seed,
+                session_id,
+                parameter_set
+            ))
             .is_ok(),
         "Attempted to set MP SEED twice."
     )
@@ -81,34 +103,186 @@ pub fn gen_server_key_share(
     user_id: usize,
     total_users: usize,
     client_key: &ClientKey,
-) -> CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+) -> ServerKeyShare {
+    BoolEvaluator::with_local(|e| {
+        let cr_seed = NonInteractiveMultiPartyCrs::global();
+        e.gen_non_interactive_multi_party_key_share(cr_seed, user_id, total_users, client_key)
+    })
+}
+
+pub fn aggregate_server_key_shares(
+    shares: &[ServerKeyShare],
+) -> SeededNonInteractiveMultiPartyServerKey<
     Vec<Vec<u64>>,
-    BoolParameters<u64>,
     NonInteractiveMultiPartyCrs<[u8; 32]>,
+    BoolParameters<u64>,
 > {
+    assert!(!shares.is_empty(), "Received 0 key shares");
+    if let Err(e) = validate_server_key_shares(shares) {
+        panic!("{e}");
+    }
+    let total_users = shares[0].total_users();
+    (0..total_users).for_each(|user_id| {
+        assert!(
+            shares.iter().any(|s| s.user_index() == user_id),
+            "Key Share for user_id={user_id} missing"
+        )
+    });
+
     BoolEvaluator::with_local(|e| {
         let cr_seed = NonInteractiveMultiPartyCrs::global();
-        e.gen_non_interactive_multi_party_key_share(cr_seed, user_id, total_users, client_key)
+        e.aggregate_non_interactive_multi_party_server_key_shares(cr_seed, shares)
     })
 }
 
-pub fn aggregate_server_key_shares(
-    shares: &[CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
-        Vec<Vec<u64>>,
-        BoolParameters<u64>,
-        NonInteractiveMultiPartyCrs<[u8; 32]>,
-    >],
+/// Aggregate server key shares of users that did not drop out
+///
+/// Users whose server key shares are missing are treated as dropped out. The
+/// server key is then generated for the ideal RLWE secret `s` that equals sum
+/// of secrets of the remaining users, without requiring any additional message
+/// from the remaining users. Note that:
+///
+/// - Only the remaining users must participate in multi-party decryption.
+/// - Users that dropped out cannot key switch their private inputs, since their
+///   key switching keys are missing. They can still provide private inputs
+///   using the collective public key.
+pub fn aggregate_server_key_shares_with_dropouts(
+    shares: &[ServerKeyShare],
 ) -> SeededNonInteractiveMultiPartyServerKey<
     Vec<Vec<u64>>,
     NonInteractiveMultiPartyCrs<[u8; 32]>,
     BoolParameters<u64>,
 > {
+    if let Err(e) = validate_server_key_shares(shares) {
+        panic!("{e}");
+    }
+
     BoolEvaluator::with_local(|e| {
         let cr_seed = NonInteractiveMultiPartyCrs::global();
         e.aggregate_non_interactive_multi_party_server_key_shares(cr_seed, shares)
     })
 }
 
+/// Validates server key shares before aggregation
+///
+/// Returns the first malformed share, along with user_id of the party that sent
+/// it and the reason, if any share has mismatching parameters or common
+/// reference seed, an invalid or duplicate user_id, ciphertexts with incorrect
+/// dimensions, auto keys for unexpected elements, or coefficients >= q.
+///
+/// Aggregation functions validate the shares and panic if any share is
+/// malformed.
+pub fn validate_server_key_shares(
+    shares: &[ServerKeyShare],
+) -> Result<(), MalformedServerKeyShare> {
+    BoolEvaluator::with_local(|e| {
+        let cr_seed = NonInteractiveMultiPartyCrs::global();
+        e.validate_non_interactive_multi_party_server_key_shares(cr_seed, shares)
+    })
+}
+
+/// Generate proof that server key `share` is generated with short secrets and
+/// errors of `client_key`
+///
+/// Proofs are optional. The server checks them with
+/// `verify_server_key_share_proofs` before aggregation to detect and identify a
+/// party that uploads a share with huge noise or ciphertexts under inconsistent
+/// secrets. Proofs are significantly larger and more expensive to generate and
+/// verify than the share itself. LWE key switching key share is not proven,
+/// because the slack of the proofs exceeds the LWE modulus. Its dimension and
+/// coefficient range are only checked by `validate_server_key_shares`.
+pub fn prove_server_key_share(
+    client_key: &ClientKey,
+    share: &ServerKeyShare,
+) -> NonInteractiveServerKeyShareProof {
+    BoolEvaluator::with_local(|e| {
+        e.prove_non_interactive_multi_party_server_key_share(client_key, share)
+    })
+}
+
+/// Validates server key shares and verifies proof of each share
+///
+/// Proof at index `i` must be the proof of share at index `i`. Returns the
+/// first share that is malformed or whose proof is missing or does not verify,
+/// along with user_id of the party that sent it.
+pub fn verify_server_key_share_proofs(
+    shares: &[ServerKeyShare],
+    proofs: &[NonInteractiveServerKeyShareProof],
+) -> Result<(), MalformedServerKeyShare> {
+    validate_server_key_shares(shares)?;
+    BoolEvaluator::with_local(|e| {
+        e.verify_non_interactive_multi_party_server_key_share_proofs(shares, proofs)
+    })
+}
+
+/// Generate client's share of key refresh key
+///
+/// To refresh the collective secret, each client generates a fresh client key
+/// `refreshed_ck` with `gen_client_key` and sends the key refresh share to the
+/// server. The server aggregates shares of all clients with
+/// `aggregate_key_refresh_shares` and key switches ciphertexts under the
+/// current collective secret to the refreshed one with `KeyRefresher`. Clients
+/// then generate the refreshed server key with their refreshed client keys in
+/// a new session (see `set_common_reference_seed_for_session`). Old client
+/// keys must be discarded afterwards.
+pub fn gen_key_refresh_share(ck: &ClientKey, refreshed_ck: &ClientKey) -> KeyRefreshShare {
+    BoolEvaluator::with_local(|e| {
+        e.gen_key_refresh_share(
+            NonInteractiveMultiPartyCrs::global().key_refresh_seed(),
+            &NonInteractiveMultiPartyClientKey::sk_rlwe(ck),
+            &NonInteractiveMultiPartyClientKey::sk_rlwe(refreshed_ck),
+        )
+    })
+}
+
+/// Aggregate key refresh shares of all clients into key refresh key
+///
+/// Panics if any share was generated with different parameters or common
+/// reference seed.
+pub fn aggregate_key_refresh_shares(shares: &[KeyRefreshShare]) -> KeyRefreshKey {
+    BoolEvaluator::with_local(|e| {
+        e.aggregate_key_refresh_shares(
+            NonInteractiveMultiPartyCrs::global().key_refresh_seed(),
+            shares,
+        )
+    })
+}
+
+/// Generates key share for user joining the protocol after the server key has
+/// been generated
+///
+/// `public_key` is the collective public key derived from the server key with
+/// `server_key.public_key()`. Late joining user does not contribute to the ideal
+/// RLWE secret and thus cannot participate in multi-party decryption. However,
+/// once the server adds the key share with `add_late_joining_user`, user can
+/// provide private inputs encrypted with its client key like any other user.
+pub fn gen_late_joining_key_share(
+    client_key: &ClientKey,
+    public_key: &PublicKey<Vec<Vec<u64>>, DefaultSecureRng, ModularOpsU64<CiphertextModulus<u64>>>,
+) -> NonInteractiveLateJoiningKeyShare<Vec<Vec<u64>>, BoolParameters<u64>> {
+    BoolEvaluator::with_local(|e| {
+        e.gen_non_interactive_late_joining_key_share(public_key.key(), client_key)
+    })
+}
+
+/// Adds late joining user's key share to the server key and returns user's
+/// user_id
+///
+/// Must be called after the server key is set. Late joining users are assigned
+/// user_ids in order starting from total users in the protocol. Private inputs
+/// of the user must be key switched with the returned user_id.
+pub fn add_late_joining_user(
+    key_share: &NonInteractiveLateJoiningKeyShare<Vec<Vec<u64>>, BoolParameters<u64>>,
+) -> usize {
+    BoolEvaluator::with_local(|e| {
+        assert!(
+            key_share.parameters() == e.parameters(),
+            "Late joining key share parameters do not match the set parameters"
+        )
+    });
+    RuntimeServerKey::global().add_late_joining_user::<NttBackendU64Simd>(key_share)
+}
+
 impl
     SeededNonInteractiveMultiPartyServerKey<
         Vec<Vec<u64>>,
@@ -121,7 +295,7 @@ impl
             _,
             BoolParameters<u64>,
             DefaultSecureRng,
-            NttBackendU64,
+            NttBackendU64Simd,
         >::from(self);
         assert!(
             BOOL_SERVER_KEY
@@ -130,6 +304,21 @@ impl
             "Attempted to set server key twice!"
         );
     }
+
+    /// Derives collective public key from the server key
+    ///
+    /// The collective public key encrypts under the ideal RLWE secret `s` of
+    /// the protocol. Thus anyone with access to the public key, not only the
+    /// parties participating in the protocol, can encrypt private inputs. For
+    /// ex, to encrypt a batch of uint8s call `pk.encrypt(&[u8])`. Unlike
+    /// private inputs encrypted by the parties, ciphertexts produced with the
+    /// public key must not be key switched. Instead FheUint8s can be directly
+    /// extracted from the batch.
+    pub fn public_key(
+        &self,
+    ) -> PublicKey<Vec<Vec<u64>>, DefaultSecureRng, ModularOpsU64<CiphertextModulus<u64>>> {
+        PublicKey::from(self)
+    }
 }
 
 impl Global for NonInteractiveMultiPartyCrs<[u8; 32]> {
@@ -164,7 +353,8 @@ impl WithLocal for BoolEvaluator {
     }
 }
 
-pub(crate) type RuntimeServerKey = ShoupNonInteractiveServerKeyEvaluationDomain<Vec<Vec<u64>>>;
+pub(crate) type RuntimeServerKey =
+    ShoupNonInteractiveServerKeyEvaluationDomain<Vec<Vec<u64>>, RuntimeBlindRotationKeyCt>;
 impl Global for RuntimeServerKey {
     fn global() -> &'static Self {
         BOOL_SERVER_KEY.get().expect("Server key not set!")
@@ -183,6 +373,27 @@ pub struct NonInteractiveBatchedFheBools<C> {
     data: Vec<C>,
 }
 
+/// Batch of bool ciphertexts stored as vector of seeded RLWE ciphertexts under
+/// user j's RLWE secret `u_j`
+///
+/// Unseed with `NonInteractiveBatchedFheBools::from`.
+pub struct SeededNonInteractiveBatchedFheBools<C, S> {
+    /// Part `b` of each seeded RLWE ciphertext
+    data: Vec<C>,
+    /// Seed of part `a` of the ciphertexts
+    seed: S,
+    /// PRG that expands the seed
+    prg: PrgAlgorithm,
+}
+
+impl<C, S> SeededNonInteractiveBatchedFheBools<C, S> {
+    /// PRG that expands the seed of the ciphertexts. Unseeding panics if it
+    /// differs from PRG selected by this build.
+    pub fn prg(&self) -> PrgAlgorithm {
+        self.prg
+    }
+}
+
 /// Batch of Bool cipphertexts stored as vector of RLWE ciphertexts under the
 /// ideal RLWE secret key `s` of the protocol
 ///
@@ -201,13 +412,15 @@ mod impl_enc_dec {
         bool::{evaluator::BoolEncoding, keys::NonInteractiveMultiPartyClientKey},
         multi_party::{
             multi_party_aggregate_decryption_shares_and_decrypt, multi_party_decryption_share,
+            verify_decryption_shares, MalformedDecryptionShare,
         },
         pbs::{sample_extract, PbsInfo, WithShoupRepr},
+        proof::DecryptionShareProof,
         random::{NewWithSeed, RandomFillUniformInModulus},
         rgsw::{rlwe_key_switch, seeded_secret_key_encrypt_rlwe},
         utils::TryConvertFrom1,
         Encryptor, KeySwitchWithId, Matrix, MatrixEntity, MatrixMut, MultiPartyDecryptor,
-        RowEntity, RowMut,
+        RowEntity, RowMut, VerifiableMultiPartyDecryptor,
     };
     use itertools::Itertools;
     use num_traits::{ToPrimitive, Zero};
@@ -239,25 +452,31 @@ mod impl_enc_dec {
         }
     }
 
-    impl<M: MatrixEntity + MatrixMut<MatElement = u64>> From<&(Vec<M::R>, [u8; 32])>
+    impl<M: MatrixEntity + MatrixMut<MatElement = u64>>
+        From<&SeededNonInteractiveBatchedFheBools<M::R, [u8; 32]>>
         for NonInteractiveBatchedFheBools<M>
     where
         <M as Matrix>::R: RowMut,
     {
         /// Derive `NonInteractiveBatchedFheBools` from a vector seeded RLWE
-        /// ciphertexts (Vec<RLWE>, Seed)
+        /// ciphertexts
         ///
         /// Unseed the RLWE ciphertexts and store them as vector RLWE
         /// ciphertexts in `NonInteractiveBatchedFheBools`
-        fn from(value: &(Vec<M::R>, [u8; 32])) -> Self {
+        fn from(value: &SeededNonInteractiveBatchedFheBools<M::R, [u8; 32]>) -> Self {
             BoolEvaluator::with_local(|e| {
                 let parameters = e.parameters();
                 let ring_size = parameters.rlwe_n().0;
                 let rlwe_q = parameters.rlwe_q();
 
-                let mut prng = DefaultSecureRng::new_seeded(value.1);
+                assert_eq!(
+                    value.prg,
+                    DefaultSecureRng::ALGORITHM,
+                    "Seeded ciphertexts were expanded with a different PRG"
+                );
+                let mut prng = DefaultSecureRng::new_seeded(value.seed);
                 let rlwes = value
-                    .0
+                    .data
                     .iter()
                     .map(|partb| {
                         let mut rlwe = M::zeros(2, ring_size);
@@ -282,7 +501,7 @@ mod impl_enc_dec {
 
     impl<K> Encryptor<[bool], NonInteractiveBatchedFheBools<Mat>> for K
     where
-        K: Encryptor<[bool], (Mat, [u8; 32])>,
+        K: Encryptor<[bool], SeededNonInteractiveBatchedFheBools<<Mat as Matrix>::R, [u8; 32]>>,
     {
         /// Encrypt a vector bool of arbitrary length as vector of unseeded RLWE
         /// ciphertexts in `NonInteractiveBatchedFheBools`
@@ -291,6 +510,25 @@ mod impl_enc_dec {
         }
     }
 
+    impl<K> Encryptor<[bool], SeededNonInteractiveBatchedFheBools<<Mat as Matrix>::R, [u8; 32]>> for K
+    where
+        K: Encryptor<[bool], (Mat, [u8; 32])>,
+    {
+        /// Encrypt a vector of bool of arbitrary length as vector of seeded
+        /// RLWE ciphertexts in `SeededNonInteractiveBatchedFheBools`
+        fn encrypt(
+            &self,
+            m: &[bool],
+        ) -> SeededNonInteractiveBatchedFheBools<<Mat as Matrix>::R, [u8; 32]> {
+            let (data, seed) = K::encrypt(self, m);
+            SeededNonInteractiveBatchedFheBools {
+                data,
+                seed,
+                prg: DefaultSecureRng::ALGORITHM,
+            }
+        }
+    }
+
     impl<K> Encryptor<[bool], (Vec<<Mat as Matrix>::R>, [u8; 32])> for K
     where
         K: NonInteractiveMultiPartyClientKey,
@@ -367,7 +605,7 @@ mod impl_enc_dec {
                 DefaultSecureRng::with_local_mut(|rng| {
                     multi_party_decryption_share(
                         c,
-                        self.sk_rlwe().as_slice(),
+                        self.sk_rlwe().as_ref(),
                         e.pbs_info().modop_rlweq(),
                         rng,
                     )
@@ -392,6 +630,75 @@ mod impl_enc_dec {
         }
     }
 
+    impl<K> VerifiableMultiPartyDecryptor<bool, <Mat as Matrix>::R> for K
+    where
+        K: NonInteractiveMultiPartyClientKey<Element = i32>,
+        <Mat as Matrix>::R:
+            TryConvertFrom1<[K::Element], CiphertextModulus<<Mat as Matrix>::MatElement>>,
+    {
+        type DecryptionShareProof = DecryptionShareProof;
+        /// Public key share of each party is part of its server key share
+        type PublicKeyShare = CommonReferenceSeededNonInteractiveMultiPartyServerKeyShare<
+            Mat,
+            BoolParameters<u64>,
+            NonInteractiveMultiPartyCrs<[u8; 32]>,
+        >;
+
+        fn gen_decryption_share_with_proof(
+            &self,
+            c: &<Mat as Matrix>::R,
+            public_key_share: &Self::PublicKeyShare,
+        ) -> (Self::DecryptionShare, Self::DecryptionShareProof) {
+            BoolEvaluator::with_local(|e| {
+                assert!(
+                    public_key_share.parameters() == e.parameters(),
+                    "Server key share has different parameters"
+                );
+                e.multi_party_decryption_share_with_proof(
+                    c,
+                    self.sk_rlwe().as_ref(),
+                    public_key_share.cr_seed().public_key_share_seed(),
+                    public_key_share.public_key_share(),
+                )
+            })
+        }
+
+        fn aggregate_decryption_shares_verified(
+            &self,
+            c: &<Mat as Matrix>::R,
+            shares: &[Self::DecryptionShare],
+            proofs: &[Self::DecryptionShareProof],
+            public_key_shares: &[Self::PublicKeyShare],
+        ) -> Result<bool, MalformedDecryptionShare> {
+            BoolEvaluator::with_local(|e| {
+                verify_decryption_shares(
+                    shares,
+                    proofs,
+                    public_key_shares,
+                    |user_id, share, proof, key_share| {
+                        if key_share.parameters() != e.parameters() {
+                            return Err("Server key share has different parameters".to_string());
+                        }
+                        if key_share.user_index() != user_id {
+                            return Err(format!(
+                                "Server key share is of user_id={}",
+                                key_share.user_index()
+                            ));
+                        }
+                        e.verify_multi_party_decryption_share(
+                            c,
+                            *share,
+                            proof,
+                            key_share.cr_seed().public_key_share_seed(),
+                            key_share.public_key_share(),
+                        )
+                    },
+                )
+            })?;
+            Ok(self.aggregate_decryption_shares(c, shares))
+        }
+    }
+
     impl KeySwitchWithId<Mat> for Mat {
         /// Key switch RLWE ciphertext `Self` from user j's RLWE secret u_j
         /// to ideal RLWE secret `s` of non-interactive multi-party protocol.
@@ -401,18 +708,19 @@ mod impl_enc_dec {
             BoolEvaluator::with_local(|e| {
                 assert!(self.dimension() == (2, e.parameters().rlwe_n().0));
                 let server_key = BOOL_SERVER_KEY.get().unwrap();
-                let ksk = server_key.ui_to_s_ksk(user_id);
                 let decomposer = e.ni_ui_to_s_ks_decomposer().as_ref().unwrap();
 
                 // perform key switch
-                rlwe_key_switch(
-                    self,
-                    ksk.as_ref(),
-                    ksk.shoup_repr(),
-                    decomposer,
-                    e.pbs_info().nttop_rlweq(),
-                    e.pbs_info().modop_rlweq(),
-                )
+                server_key.with_ui_to_s_ksk(user_id, |ksk| {
+                    rlwe_key_switch(
+                        self,
+                        ksk.as_ref(),
+                        ksk.shoup_repr(),
+                        decomposer,
+                        e.pbs_info().nttop_rlweq(),
+                        e.pbs_info().modop_rlweq(),
+                    )
+                })
             })
         }
     }
@@ -442,18 +750,278 @@ mod impl_enc_dec {
 
 #[cfg(test)]
 mod tests {
-    use itertools::Itertools;
-    use rand::{thread_rng, RngCore};
+    use itertools::{izip, Itertools};
+    use rand::{thread_rng, Rng};
 
     use crate::{
         backend::Modulus,
         bool::{
-            keys::tests::{ideal_sk_rlwe, measure_noise_lwe},
+            keys::{
+                tests::{ideal_sk_rlwe, measure_noise_lwe},
+                NonInteractiveMultiPartyClientKey,
+            },
             BooleanGates,
         },
+        pbs::{PbsInfo, WithShoupRepr},
+        rgsw::rlwe_key_switch,
         utils::tests::Stats,
-        Encoder, Encryptor, KeySwitchWithId, MultiPartyDecryptor,
+        with_seeded_rng, BatchedFheUint8, Encoder, Encryptor, FheBool, KeySwitchWithId, Matrix,
+        MultiPartyDecryptor, NttBackendU64, SampleExtractor, VerifiableMultiPartyDecryptor,
     };
 
     use super::*;
+
+    /// Generates server key shares of users in `user_ids` with `cr_seed`
+    /// without setting the common reference seed globally
+    fn gen_server_key_shares(
+        cr_seed: &NonInteractiveMultiPartyCrs<[u8; 32]>,
+        cks: &[ClientKey],
+        user_ids: &[usize],
+    ) -> Vec<ServerKeyShare> {
+        BoolEvaluator::with_local(|e| {
+            user_ids
+                .iter()
+                .map(|user_id| {
+                    e.gen_non_interactive_multi_party_key_share(
+                        cr_seed,
+                        *user_id,
+                        cks.len(),
+                        &cks[*user_id],
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
+    #[test]
+    fn seeded_rng_reproduces_runs() {
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+
+        let run = || {
+            let cks = (0..2).map(|_| gen_client_key()).collect_vec();
+            let server_key_shares = gen_server_key_shares(&cr_seed, &cks, &[0, 1]);
+            let pk = BoolEvaluator::with_local(|e| {
+                e.aggregate_non_interactive_multi_party_server_key_shares(
+                    &cr_seed,
+                    &server_key_shares,
+                )
+            })
+            .public_key();
+
+            let seeded_cts: NonInteractiveBatchedFheBools<Vec<Vec<u64>>> =
+                cks[0].encrypt(vec![true, false, true].as_slice());
+            let ct: <Vec<Vec<u64>> as Matrix>::R = pk.encrypt(&true);
+            let decryption_shares = cks
+                .iter()
+                .map(|k| k.gen_decryption_share(&ct))
+                .collect_vec();
+            (
+                cks.iter().map(|k| k.sk_rlwe().to_vec()).collect_vec(),
+                server_key_shares
+                    .iter()
+                    .map(|s| (s.ui_to_s_ksk().clone(), s.lwe_ksk_share().clone()))
+                    .collect_vec(),
+                seeded_cts.data,
+                ct,
+                decryption_shares,
+            )
+        };
+
+        let a = with_seeded_rng([1u8; 32], run);
+        assert!(a == with_seeded_rng([1u8; 32], run));
+
+        let sk = |ck: ClientKey| ck.sk_rlwe().to_vec();
+        assert_ne!(a.0[0], with_seeded_rng([2u8; 32], || sk(gen_client_key())));
+        // thread local rng is restored afterwards
+        assert_ne!(a.0[0], sk(gen_client_key()));
+    }
+
+    #[test]
+    fn public_key_encryption_works() {
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let server_key_shares = gen_server_key_shares(&cr_seed, &cks, &[0, 1]);
+        let server_key = BoolEvaluator::with_local(|e| {
+            e.aggregate_non_interactive_multi_party_server_key_shares(&cr_seed, &server_key_shares)
+        });
+
+        // collective public key derived by the server
+        let pk = server_key.public_key();
+
+        let batch_size = 123;
+        let m = (0..batch_size)
+            .map(|_| thread_rng().gen::<u8>())
+            .collect_vec();
+        let batched_ct: BatchedFheUint8 = pk.encrypt(m.as_slice());
+
+        let m_back = batched_ct
+            .extract_all()
+            .iter()
+            .map(|ct| {
+                cks[0].aggregate_decryption_shares(
+                    ct,
+                    &cks.iter().map(|k| k.gen_decryption_share(ct)).collect_vec(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(m, m_back);
+    }
+
+    #[test]
+    fn verified_decryption_identifies_cheating_party() {
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let server_key_shares = gen_server_key_shares(&cr_seed, &cks, &[0, 1]);
+        let pk = BoolEvaluator::with_local(|e| {
+            e.aggregate_non_interactive_multi_party_server_key_shares(&cr_seed, &server_key_shares)
+        })
+        .public_key();
+        let q = BoolEvaluator::with_local(|e| e.parameters().rlwe_q().q().unwrap());
+
+        let ct = FheBool::new(pk.encrypt(&false));
+        let (mut shares, proofs): (Vec<_>, Vec<_>) = izip!(cks.iter(), server_key_shares.iter())
+            .map(|(k, key_share)| k.gen_decryption_share_with_proof(&ct, key_share))
+            .unzip();
+        assert_eq!(
+            cks[0].aggregate_decryption_shares_verified(&ct, &shares, &proofs, &server_key_shares),
+            Ok(false)
+        );
+
+        // party 0 flips the output by shifting its share by Q/4
+        shares[0] = (shares[0] + (q >> 2)) % q;
+        assert!(cks[0].aggregate_decryption_shares(&ct, &shares));
+        let err = cks[0]
+            .aggregate_decryption_shares_verified(&ct, &shares, &proofs, &server_key_shares)
+            .unwrap_err();
+        assert_eq!(err.share_index, 0);
+
+        // server key shares must be ordered by user_id
+        let reordered = gen_server_key_shares(&cr_seed, &cks, &[1, 0]);
+        let err = cks[0]
+            .aggregate_decryption_shares_verified(&ct, &shares, &proofs, &reordered)
+            .unwrap_err();
+        assert_eq!(err.share_index, 0);
+        assert!(err.reason.starts_with("Server key share is of user_id=1"));
+    }
+
+    #[test]
+    fn aggregation_with_dropouts_works() {
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+
+        // user 1 drops out after generating its client key
+        let parties = 2;
+        let remaining_users = vec![0];
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let server_key_shares = gen_server_key_shares(&cr_seed, &cks, &remaining_users);
+        let seeded_server_key = BoolEvaluator::with_local(|e| {
+            e.aggregate_non_interactive_multi_party_server_key_shares(&cr_seed, &server_key_shares)
+        });
+        let server_key = ShoupNonInteractiveServerKeyEvaluationDomain::from(
+            NonInteractiveServerKeyEvaluationDomain::<
+                _,
+                BoolParameters<u64>,
+                DefaultSecureRng,
+                NttBackendU64,
+            >::from(&seeded_server_key),
+        );
+        let pk = seeded_server_key.public_key();
+
+        // only the remaining users can decrypt
+        let remaining_cks = remaining_users
+            .iter()
+            .map(|user_id| cks[*user_id].clone())
+            .collect_vec();
+        for _ in 0..10 {
+            let m0 = thread_rng().gen_bool(0.5);
+            let m1 = thread_rng().gen_bool(0.5);
+            let ct0: <Vec<Vec<u64>> as Matrix>::R = pk.encrypt(&m0);
+            let ct1: <Vec<Vec<u64>> as Matrix>::R = pk.encrypt(&m1);
+
+            let ct_out = BoolEvaluator::with_local_mut(|e| e.nand(&ct0, &ct1, &server_key));
+
+            let decryption_shares = remaining_cks
+                .iter()
+                .map(|k| k.gen_decryption_share(&ct_out))
+                .collect_vec();
+            let m_out = remaining_cks[0].aggregate_decryption_shares(&ct_out, &decryption_shares);
+            assert_eq!(m_out, !(m0 && m1));
+        }
+    }
+
+    #[test]
+    fn late_joining_user_works() {
+        set_parameter_set(ParameterSelector::NonInteractiveLTE2Party);
+        let cr_seed = NonInteractiveMultiPartyCrs::random(NI_2P.id());
+
+        let parties = 2;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let server_key_shares = gen_server_key_shares(&cr_seed, &cks, &[0, 1]);
+        let seeded_server_key = BoolEvaluator::with_local(|e| {
+            e.aggregate_non_interactive_multi_party_server_key_shares(&cr_seed, &server_key_shares)
+        });
+        let server_key = ShoupNonInteractiveServerKeyEvaluationDomain::from(
+            NonInteractiveServerKeyEvaluationDomain::<
+                _,
+                BoolParameters<u64>,
+                DefaultSecureRng,
+                NttBackendU64,
+            >::from(&seeded_server_key),
+        );
+
+        // new user joins with the collective public key
+        let pk = seeded_server_key.public_key();
+        let late_ck = gen_client_key();
+        let late_key_share = gen_late_joining_key_share(&late_ck, &pk);
+        let late_user_id = server_key.add_late_joining_user::<NttBackendU64>(&late_key_share);
+        assert_eq!(late_user_id, parties);
+
+        let batch_size = 20;
+        let m = (0..batch_size)
+            .map(|_| thread_rng().gen_bool(0.5))
+            .collect_vec();
+        let ni_cts: NonInteractiveBatchedFheBools<Vec<Vec<u64>>> = late_ck.encrypt(m.as_slice());
+
+        // key switch late joining user's ciphertexts to ideal RLWE secret
+        let cts = BoolEvaluator::with_local(|e| {
+            let data = ni_cts
+                .data
+                .iter()
+                .map(|c| {
+                    server_key.with_ui_to_s_ksk(late_user_id, |ksk| {
+                        rlwe_key_switch(
+                            c,
+                            ksk.as_ref(),
+                            ksk.shoup_repr(),
+                            e.ni_ui_to_s_ks_decomposer().as_ref().unwrap(),
+                            e.pbs_info().nttop_rlweq(),
+                            e.pbs_info().modop_rlweq(),
+                        )
+                    })
+                })
+                .collect_vec();
+            BatchedFheBools { data }
+        });
+
+        // only the original users decrypt
+        for i in (0..batch_size).step_by(2) {
+            let ct0 = cts.extract(i);
+            let ct1 = cts.extract(i + 1);
+            let ct_out = BoolEvaluator::with_local_mut(|e| e.nand(&ct0, &ct1, &server_key));
+
+            let decryption_shares = cks
+                .iter()
+                .map(|k| k.gen_decryption_share(&ct_out))
+                .collect_vec();
+            let m_out = cks[0].aggregate_decryption_shares(&ct_out, &decryption_shares);
+            assert_eq!(m_out, !(m[i] && m[i + 1]));
+        }
+    }
 }
@@ -1,8 +1,9 @@
-use num_traits::{ConstZero, FromPrimitive, PrimInt};
+use num_traits::{ConstZero, FromPrimitive, PrimInt, ToPrimitive};
 
 use crate::{
     backend::Modulus,
     decomposer::{Decomposer, NumInfo},
+    fft::FftBackendF64,
     utils::log2,
 };
 
@@ -94,6 +95,27 @@ pub(crate) enum ParameterVariant {
     InteractiveMultiParty,
     NonInteractiveMultiParty,
 }
+/// Maximum no. of bootstrapped ciphertexts that can be XORed without
+/// bootstrapping.
+///
+/// Sum of k bootstrapped ciphertexts in parity encoding (i.e. 2c + Q/4) has
+/// noise variance 4kσ^2, where σ^2 is noise variance of a bootstrapped
+/// ciphertext. Input 2(c0 - c1) of bootstrapped XOR gate has noise variance
+/// 8σ^2. Both are bootstrapped with same LWE key switching and mod switching
+/// noise and same margin br_q/4, hence the lazy sum fails no more often than
+/// XOR gate iff 4kσ^2 <= 8σ^2. Budget is the largest such k for every
+/// parameter set (see `lazy_xor_noise` in `print_noise`).
+pub(crate) const LAZY_XOR_BUDGET: usize = 8 / 4;
+
+/// Storage type of LWE ciphertexts and LWE key switching key during LWE key
+/// switching.
+///
+/// `u16` is not used because `CiphertextModulus<u16>` cannot represent q =
+/// 2^16 or q = 2^17, which all parameter sets except `I_2P_LB_SR` use.
+/// Supporting `u16` only for q <= 2^15 would require a second evaluator and
+/// server key type for a single parameter set.
+pub(crate) type LweKsElement = u32;
+
 #[derive(Clone, PartialEq)]
 pub struct BoolParameters<El> {
     /// RLWE secret key distribution
@@ -164,6 +186,9 @@ pub struct BoolParameters<El> {
     w: usize,
     /// Parameter variant
     variant: ParameterVariant,
+    /// Identifier of the parameter set. Seeds derived from common reference
+    /// seed are bound to it (see `derive_crs_seed`).
+    id: &'static str,
 }
 
 impl<El> BoolParameters<El> {
@@ -252,6 +277,38 @@ impl<El> BoolParameters<El> {
         self.auto_decomposer_params.decomposition_count()
     }
 
+    /// Returns largest decomposition log base and largest no. of accumulated
+    /// products in blind rotation, that is in RLWE x RGSW and RLWE
+    /// automorphism. FFT backend for blind rotation must support both to output
+    /// exact products.
+    pub(crate) fn blind_rotation_fft_bounds(&self) -> (usize, usize) {
+        let (d_a, d_b) = self.rlrg_decomposer_params.1;
+        (
+            self.rlrg_decomposer_params
+                .0
+                 .0
+                .max(self.auto_decomposer_params.0 .0),
+            (d_a.0 + d_b.0).max(self.auto_decomposer_params.1 .0),
+        )
+    }
+
+    /// Returns FFT backend for blind rotation with server key in Fourier
+    /// domain. Backend supports `blind_rotation_fft_bounds`, hence outputs of
+    /// blind rotation equal outputs of blind rotation with NTT as long as FFT
+    /// rounding errors stay below 1/2 (see `FFT_EXACT_BITS`).
+    pub(crate) fn blind_rotation_fft_op(&self) -> FftBackendF64
+    where
+        El: ToPrimitive,
+        CiphertextModulus<El>: Modulus<Element = El>,
+    {
+        let (log_base, max_terms) = self.blind_rotation_fft_bounds();
+        let n = self.rlwe_n.0;
+        match self.rlwe_q.q() {
+            Some(q) => FftBackendF64::new(q.to_u64().unwrap(), n, log_base, max_terms),
+            None => FftBackendF64::new_native(n, log_base, max_terms),
+        }
+    }
+
     pub(crate) fn lwe_decomposition_base(&self) -> DecompostionLogBase {
         self.lwe_decomposer_params.decomposition_base()
     }
@@ -307,6 +364,38 @@ impl<El> BoolParameters<El> {
         )
     }
 
+    /// LWE modulus q with elements stored as `T`. Elements mod q can be
+    /// stored in narrower words than elements mod Q.
+    pub(crate) fn lwe_q_as<T: FromPrimitive + ConstZero>(&self) -> CiphertextModulus<T>
+    where
+        El: ToPrimitive,
+    {
+        assert!(!self.lwe_q.1, "LWE modulus must be non-native");
+        let q = self
+            .lwe_q
+            .0
+            .to_u64()
+            .and_then(T::from_u64)
+            .unwrap_or_else(|| {
+                panic!("LWE modulus does not fit in {}", std::any::type_name::<T>())
+            });
+        CiphertextModulus::new_non_native(q)
+    }
+
+    /// LWE key switching decomposer with elements stored as `T`
+    pub(crate) fn lwe_ks_decomposer<T: FromPrimitive + ConstZero, D: Decomposer<Element = T>>(
+        &self,
+    ) -> D
+    where
+        El: ToPrimitive,
+    {
+        D::new(
+            self.lwe_q_as::<T>().0,
+            self.lwe_decomposer_params.decomposition_base().0,
+            self.lwe_decomposer_params.decomposition_count().0,
+        )
+    }
+
     pub(crate) fn lwe_decomposer<D: Decomposer<Element = El>>(&self) -> D
     where
         El: Copy,
@@ -372,6 +461,10 @@ impl<El> BoolParameters<El> {
     pub(crate) fn variant(&self) -> &ParameterVariant {
         &self.variant
     }
+
+    pub(crate) fn id(&self) -> &'static str {
+        self.id
+    }
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -400,13 +493,13 @@ pub(crate) struct PolynomialSize(pub(crate) usize);
 pub struct CiphertextModulus<T>(T, bool);
 
 impl<T: ConstZero> CiphertextModulus<T> {
-    const fn new_native() -> Self {
+    pub(crate) const fn new_native() -> Self {
         // T::zero is stored only for convenience. It has no use when modulus
         // is native. That is, either u128,u64,u32,u16
         Self(T::ZERO, true)
     }
 
-    const fn new_non_native(q: T) -> Self {
+    pub(crate) const fn new_non_native(q: T) -> Self {
         Self(q, false)
     }
 }
@@ -493,13 +586,18 @@ where
     }
 
     fn map_element_from_i64(&self, v: i64) -> Self::Element {
-        let v_el = T::from_i64(v.abs()).unwrap();
+        // branchless since v may be secret
+        let sign = v >> 63;
+        let v_el = T::from_i64((v ^ sign) - sign).unwrap();
         assert!(v_el <= self.largest_unsigned_value());
-        if v < 0 {
-            self.largest_unsigned_value() - v_el + T::one()
-        } else {
-            v_el
-        }
+
+        // sign_el = 1 iff v < 0. For v < 0, v_el >= 1 and negation equals
+        // largest_unsigned_value - (v_el - 1), which does not overflow for
+        // native modulus
+        let sign_el = T::from_i64(-sign).unwrap();
+        let neg_v_el = self.largest_unsigned_value() - (v_el - sign_el);
+        let mask = T::max_value() * sign_el;
+        (v_el & !mask) | (neg_v_el & mask)
     }
 
     fn q(&self) -> Option<Self::Element> {
@@ -545,6 +643,7 @@ pub(crate) const I_2P_LB_SR: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::InteractiveMultiParty,
+    id: "i-2p-lb-sr",
 };
 
 pub(crate) const I_4P: BoolParameters<u64> = BoolParameters::<u64> {
@@ -569,6 +668,7 @@ pub(crate) const I_4P: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::InteractiveMultiParty,
+    id: "i-4p",
 };
 
 pub(crate) const I_8P: BoolParameters<u64> = BoolParameters::<u64> {
@@ -593,6 +693,7 @@ pub(crate) const I_8P: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::InteractiveMultiParty,
+    id: "i-8p",
 };
 
 pub(crate) const NI_2P: BoolParameters<u64> = BoolParameters::<u64> {
@@ -620,6 +721,7 @@ pub(crate) const NI_2P: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::NonInteractiveMultiParty,
+    id: "ni-2p",
 };
 
 pub(crate) const NI_4P_HB_FR: BoolParameters<u64> = BoolParameters::<u64> {
@@ -647,6 +749,7 @@ pub(crate) const NI_4P_HB_FR: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::NonInteractiveMultiParty,
+    id: "ni-4p-hb-fr",
 };
 
 pub(crate) const NI_4P_LB_SR: BoolParameters<u64> = BoolParameters::<u64> {
@@ -674,6 +777,7 @@ pub(crate) const NI_4P_LB_SR: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::NonInteractiveMultiParty,
+    id: "ni-4p-lb-sr",
 };
 
 pub(crate) const NI_8P: BoolParameters<u64> = BoolParameters::<u64> {
@@ -701,6 +805,7 @@ pub(crate) const NI_8P: BoolParameters<u64> = BoolParameters::<u64> {
     g: 5,
     w: 10,
     variant: ParameterVariant::NonInteractiveMultiParty,
+    id: "ni-8p",
 };
 
 #[cfg(test)]
@@ -723,6 +828,33 @@ pub(crate) const SP_TEST_BOOL_PARAMS: BoolParameters<u64> = BoolParameters::<u64
     g: 5,
     w: 5,
     variant: ParameterVariant::SingleParty,
+    id: "sp-test-bool-params",
+};
+
+/// Single party parameters with native RLWE modulus Q = 2^64. NTT does not
+/// exist mod 2^64, hence server key must be in Fourier domain. Parameters are
+/// only meant to test native modulus and are not secure.
+#[cfg(test)]
+pub(crate) const SP_TEST_NATIVE_PARAMS: BoolParameters<u64> = BoolParameters::<u64> {
+    rlwe_secret_key_dist: SecretKeyDistribution::TernaryDistribution,
+    lwe_secret_key_dist: SecretKeyDistribution::ErrorDistribution,
+    rlwe_q: CiphertextModulus::new_native(),
+    lwe_q: CiphertextModulus::new_non_native(1 << 16),
+    br_q: 1 << 9,
+    rlwe_n: PolynomialSize(1 << 9),
+    lwe_n: LweDimension(100),
+    lwe_decomposer_params: (DecompostionLogBase(4), DecompositionCount(4)),
+    rlrg_decomposer_params: (
+        DecompostionLogBase(7),
+        (DecompositionCount(4), DecompositionCount(4)),
+    ),
+    rgrg_decomposer_params: None,
+    auto_decomposer_params: (DecompostionLogBase(7), DecompositionCount(4)),
+    non_interactive_ui_to_s_key_switch_decomposer: None,
+    g: 5,
+    w: 5,
+    variant: ParameterVariant::SingleParty,
+    id: "sp-test-native-params",
 };
 
 // #[cfg(test)]
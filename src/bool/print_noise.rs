@@ -391,6 +391,7 @@ mod tests {
             bool::{
                 evaluator::InteractiveMultiPartyCrs,
                 keys::{key_size::KeySize, ServerKeyEvaluationDomain},
+                parameters::I_8P,
             },
             collective_pk_share, collective_server_key_share, gen_client_key,
             parameters::CiphertextModulus,
@@ -403,7 +404,7 @@ mod tests {
         use super::*;
 
         set_parameter_set(crate::ParameterSelector::InteractiveLTE8Party);
-        set_common_reference_seed(InteractiveMultiPartyCrs::random().seed);
+        set_common_reference_seed(InteractiveMultiPartyCrs::random(I_8P.id()).seed);
         let parties = 8;
 
         let mut server_key_stats = ServerKeyStats::default();
@@ -745,6 +746,7 @@ mod tests {
             bool::{
                 evaluator::NonInteractiveMultiPartyCrs,
                 keys::{key_size::KeySize, NonInteractiveServerKeyEvaluationDomain},
+                parameters::NI_8P,
             },
             decomposer::DefaultDecomposer,
             gen_client_key, gen_server_key_share,
@@ -758,7 +760,7 @@ mod tests {
         use super::*;
 
         set_parameter_set(crate::ParameterSelector::NonInteractiveLTE8Party);
-        set_common_reference_seed(NonInteractiveMultiPartyCrs::random().seed);
+        set_common_reference_seed(NonInteractiveMultiPartyCrs::random(NI_8P.id()).seed);
         let parties = 8;
 
         let mut server_key_stats = ServerKeyStats::default();
@@ -892,6 +894,194 @@ mod tests {
         println!("Noise std log2: {}", stats.std_dev().abs().log2());
     }
 
+    /// Number of bootstrapped XOR ciphertexts measured. Lazy sums are evaluated
+    /// on all combinations.
+    const XOR_POOL: usize = 20;
+
+    /// Returns noise at input of blind rotation of `lwe` with expected phase
+    /// `phase` mod br_q
+    #[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+    fn noise_at_blind_rotation_input(lwe: &Vec<u64>, phase: i64, ideal_sk_lwe: &[i32]) -> i64 {
+        use itertools::izip;
+
+        use crate::{
+            pbs::{blind_rotation_input, PbsInfo},
+            utils::{Global, WithLocal},
+            BoolEvaluator, RuntimeServerKey,
+        };
+
+        let (ct, br_q) = BoolEvaluator::with_local(|e| {
+            (
+                blind_rotation_input(e.pbs_info(), lwe, RuntimeServerKey::global()),
+                e.pbs_info().br_q() as i64,
+            )
+        });
+        let mut v = ct[0] as i64 - phase;
+        izip!(ct.iter().skip(1), ideal_sk_lwe.iter()).for_each(|(a, z)| {
+            v -= *a as i64 * *z as i64;
+        });
+        let v = v.rem_euclid(br_q);
+        if v >= br_q / 2 {
+            v - br_q
+        } else {
+            v
+        }
+    }
+
+    /// Measures noise at input of blind rotation of lazy XOR sums of k
+    /// bootstrapped ciphertexts in parity encoding and compares it with noise
+    /// at input of blind rotation of XOR gate fed with bootstrapped ciphertexts.
+    ///
+    /// Variance of lazy sum of `LAZY_XOR_BUDGET` ciphertexts must match
+    /// variance of XOR gate input. Variance of sum of `LAZY_XOR_BUDGET + 1`
+    /// ciphertexts exceeds it by noise variance of a bootstrapped ciphertext
+    /// scaled to br_q times 4.
+    #[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+    fn lazy_xor_noise(mut encrypt: impl FnMut(bool) -> Vec<u64>, ideal_sk_lwe: &[i32]) {
+        use rand::{thread_rng, Rng};
+
+        use crate::{
+            backend::VectorOps,
+            bool::parameters::LAZY_XOR_BUDGET,
+            utils::{tests::Stats, Global, WithLocal},
+            BoolEvaluator, BooleanGates, ModInit, ModularOpsU64, RuntimeServerKey,
+        };
+
+        let parameters = BoolEvaluator::with_local(|e| e.parameters().clone());
+        let rlwe_modop = ModularOpsU64::new(*parameters.rlwe_q());
+        let br_q = *parameters.br_q() as i64;
+
+        let mut rng = thread_rng();
+        let bootstrapped = (0..XOR_POOL)
+            .map(|_| {
+                let (m0, m1) = (rng.gen_bool(0.5), rng.gen_bool(0.5));
+                let (c0, c1) = (encrypt(m0), encrypt(m1));
+                let c =
+                    BoolEvaluator::with_local_mut(|e| e.xor(&c0, &c1, RuntimeServerKey::global()));
+                (c, m0 ^ m1)
+            })
+            .collect_vec();
+
+        let noise_br_q =
+            |lwe: &Vec<u64>, phase: i64| noise_at_blind_rotation_input(lwe, phase, ideal_sk_lwe);
+
+        // XOR gate input 2(c0 - c1) encodes m0 ^ m1 as (m0 ^ m1) * br_q/2
+        let mut xor_gate = Stats::new();
+        bootstrapped
+            .iter()
+            .tuple_combinations()
+            .for_each(|((c0, m0), (c1, m1))| {
+                let mut c = c0.clone();
+                rlwe_modop.elwise_sub_mut(c.as_mut(), c1.as_ref());
+                rlwe_modop.elwise_scalar_mul_mut(c.as_mut(), &2);
+                xor_gate.add_sample(noise_br_q(&c, (*m0 ^ *m1) as i64 * br_q / 2));
+            });
+        println!("## Noise at input of blind rotation ##");
+        println!("XOR gate std_dev: {}", xor_gate.std_dev());
+
+        // Lazy sum of k ciphertexts in parity encoding encodes XOR of messages as
+        // m * br_q/2
+        let parity = bootstrapped
+            .iter()
+            .map(|(c, m)| {
+                let c = BoolEvaluator::with_local(|e| {
+                    let mut c = c.clone();
+                    e.to_parity_encoding_inplace(&mut c);
+                    c
+                });
+                (c, *m)
+            })
+            .collect_vec();
+        for k in 2..=LAZY_XOR_BUDGET + 1 {
+            let mut lazy_sum = Stats::new();
+            parity.iter().combinations(k).for_each(|cts| {
+                let mut c = cts[0].0.clone();
+                cts.iter()
+                    .skip(1)
+                    .for_each(|(ci, _)| rlwe_modop.elwise_add_mut(c.as_mut(), ci.as_ref()));
+                let m = cts.iter().fold(false, |acc, (_, m)| acc ^ *m);
+                lazy_sum.add_sample(noise_br_q(&c, m as i64 * br_q / 2));
+            });
+            println!(
+                "Lazy sum of {k} ciphertexts std_dev: {} ({} of XOR gate variance)",
+                lazy_sum.std_dev(),
+                lazy_sum.variance() / xor_gate.variance()
+            );
+        }
+    }
+
+    // Sets the global parameters and seed, hence panics if other tests set them
+    // in the same process. Run alone with `cargo test --release --features
+    // interactive_mp unbootstrapped_xor_noise -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    #[cfg(feature = "interactive_mp")]
+    fn interactive_mp_unbootstrapped_xor_noise() {
+        use rand::{thread_rng, RngCore};
+
+        use crate::{
+            aggregate_public_key_shares, aggregate_server_key_shares,
+            bool::keys::tests::ideal_sk_lwe, collective_pk_share, collective_server_key_share,
+            gen_client_key, set_common_reference_seed, set_parameter_set, Encryptor,
+            ParameterSelector,
+        };
+
+        set_parameter_set(ParameterSelector::InteractiveLTE8Party);
+        let mut seed = [0u8; 32];
+        thread_rng().fill_bytes(&mut seed);
+        set_common_reference_seed(seed);
+
+        let parties = 8;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let pk_shares = cks.iter().map(collective_pk_share).collect_vec();
+        let pk = aggregate_public_key_shares(&pk_shares);
+        let server_key_shares = cks
+            .iter()
+            .enumerate()
+            .map(|(user_id, k)| collective_server_key_share(k, user_id, parties, &pk))
+            .collect_vec();
+        aggregate_server_key_shares(&server_key_shares).set_server_key();
+
+        lazy_xor_noise(|m| pk.encrypt(&m), &ideal_sk_lwe(&cks));
+    }
+
+    // Sets the global parameters and seed, hence panics if other tests set them
+    // in the same process. Run alone with `cargo test --release --features
+    // non_interactive_mp unbootstrapped_xor_noise -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    #[cfg(feature = "non_interactive_mp")]
+    fn non_interactive_mp_unbootstrapped_xor_noise() {
+        use rand::{thread_rng, RngCore};
+
+        use crate::{
+            aggregate_server_key_shares,
+            bool::{keys::tests::ideal_sk_lwe, NonInteractiveBatchedFheBools},
+            gen_client_key, gen_server_key_share, set_common_reference_seed, set_parameter_set,
+            Encryptor, KeySwitchWithId, ParameterSelector,
+        };
+
+        set_parameter_set(ParameterSelector::NonInteractiveLTE8Party);
+        let mut seed = [0u8; 32];
+        thread_rng().fill_bytes(&mut seed);
+        set_common_reference_seed(seed);
+
+        let parties = 8;
+        let cks = (0..parties).map(|_| gen_client_key()).collect_vec();
+        let server_key_shares = cks
+            .iter()
+            .enumerate()
+            .map(|(user_index, ck)| gen_server_key_share(user_index, parties, ck))
+            .collect_vec();
+        aggregate_server_key_shares(&server_key_shares).set_server_key();
+
+        let encrypt = |m: bool| {
+            let ct: NonInteractiveBatchedFheBools<_> = cks[0].encrypt(vec![m].as_slice());
+            ct.key_switch(0).extract(0)
+        };
+        lazy_xor_noise(encrypt, &ideal_sk_lwe(&cks));
+    }
+
     #[test]
     fn mod_switch_noise() {
         // Experiment to check mod switch noise using different secret dist in
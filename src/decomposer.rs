@@ -92,7 +92,7 @@ pub trait Decomposer {
 }
 
 pub struct DefaultDecomposer<T> {
-    /// Ciphertext modulus
+    /// Ciphertext modulus. Equals 0 if modulus is native.
     q: T,
     /// Log of ciphertext modulus
     logq: usize,
@@ -121,6 +121,9 @@ impl NumInfo for u64 {
 impl NumInfo for u32 {
     const BITS: u32 = u32::BITS;
 }
+impl NumInfo for u16 {
+    const BITS: u32 = u16::BITS;
+}
 impl NumInfo for u128 {
     const BITS: u32 = u128::BITS;
 }
@@ -156,8 +159,13 @@ impl<
     type Iter = DecomposerIter<T>;
 
     fn new(q: T, logb: usize, d: usize) -> DefaultDecomposer<T> {
-        // if q is power of 2, then `BITS - leading_zeros` outputs logq + 1.
-        let logq = log2(&q);
+        // q = 0 when modulus is native (see `CiphertextModulus`)
+        let logq = if q.is_zero() {
+            T::BITS as usize
+        } else {
+            // if q is power of 2, then `BITS - leading_zeros` outputs logq + 1.
+            log2(&q)
+        };
         assert!(
             logq >= (logb * d),
             "Decomposer wants logq >= logb*d but got logq={logq}, logb={logb}, d={d}"
@@ -185,7 +193,8 @@ impl<
         let bby2 = b >> 1;
 
         let mut value = *value;
-        if value >= (q >> 1) {
+        // values mod native modulus already are in two's complement
+        if !q.is_zero() && value >= (q >> 1) {
             value = !(q - value) + T::one()
         }
         value = round_value(value, self.ignore_bits);
@@ -196,7 +205,7 @@ impl<
             value = (value - k_i) >> logb;
 
             if k_i > bby2 || (k_i == bby2 && ((value & T::one()) == T::one())) {
-                out.push(q - (b - k_i));
+                out.push(q.wrapping_sub(&(b - k_i)));
                 value = value + T::one();
             } else {
                 out.push(k_i);
@@ -216,7 +225,8 @@ impl<
 
     fn decompose_iter(&self, value: &T) -> DecomposerIter<T> {
         let mut value = *value;
-        if value >= (self.q >> 1) {
+        // values mod native modulus already are in two's complement
+        if !self.q.is_zero() && value >= (self.q >> 1) {
             value = !(self.q - value) + T::one()
         }
         value = round_value(value, self.ignore_bits);
@@ -258,7 +268,7 @@ pub struct DecomposerIter<T> {
     b: T,
 }
 
-impl<T: PrimInt + From<bool> + WrappingSub + Display> Iterator for DecomposerIter<T> {
+impl<T: PrimInt + From<bool> + WrappingAdd + WrappingSub + Display> Iterator for DecomposerIter<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -286,7 +296,12 @@ impl<T: PrimInt + From<bool> + WrappingSub + Display> Iterator for DecomposerIte
             let carry = <T as From<bool>>::from(carry_bool);
             let neg_carry = T::zero().wrapping_sub(&carry);
             self.value = self.value + carry;
-            Some((neg_carry & self.q) + k_i - (carry << self.logb))
+            // wraps only when modulus is native
+            Some(
+                (neg_carry & self.q)
+                    .wrapping_add(&k_i)
+                    .wrapping_sub(&(carry << self.logb)),
+            )
 
             // Some(
             //     (self.q & ((carry << self.logq) - (T::one() & carry))) + k_i
@@ -315,8 +330,9 @@ mod tests {
     use rand::{thread_rng, Rng};
 
     use crate::{
-        backend::{ModInit, ModularOpsU64},
+        backend::{ModInit, ModularOpsU64, WordSizeModulus},
         decomposer::round_value,
+        parameters::CiphertextModulus,
         utils::generate_prime,
     };
 
@@ -368,4 +384,29 @@ mod tests {
             // });
         }
     }
+
+    #[test]
+    fn native_decomposition_works() {
+        let mut rng = thread_rng();
+        let modq_op = WordSizeModulus::new(CiphertextModulus::<u64>::new_native());
+        for (logb, d) in [(17, 1), (24, 1), (11, 5), (16, 4)] {
+            // q = 0 for native modulus
+            let decomposer = DefaultDecomposer::new(0u64, logb, d);
+            for _ in 0..100000 {
+                let value: u64 = rng.gen();
+                let limbs = decomposer.decompose_to_vec(&value);
+                let limbs_from_iter = decomposer.decompose_iter(&value).collect_vec();
+                assert_eq!(limbs, limbs_from_iter);
+
+                // limbs are in [-B/2, B/2]
+                limbs.iter().for_each(|l| {
+                    assert!((*l as i64).unsigned_abs() <= 1 << (logb - 1));
+                });
+
+                let value_back = decomposer.recompose(&limbs, &modq_op);
+                let diff = (value_back.wrapping_sub(value) as i64).unsigned_abs();
+                assert!(diff <= 1 << decomposer.ignore_bits);
+            }
+        }
+    }
 }
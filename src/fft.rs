@@ -0,0 +1,603 @@
+use std::f64::consts::PI;
+
+use itertools::izip;
+
+use crate::{backend::Modulus, ntt::NttInit, Row};
+
+/// Largest magnitude, in bits, of coefficients of a product computed with f64
+/// FFT for which rounding error of the FFT is small enough to round the
+/// coefficients to the exact integers.
+///
+/// The value is heuristic. With N = 2^11 the largest rounding error measured
+/// for products with coefficients up to 2^48 is 0.094 (see
+/// `fft_rounding_error_of_bool_parameter_sets_is_small`), whereas Percival's
+/// worst-case bound for FFT products evaluates to ~5 and only guarantees
+/// errors below 1/2 for coefficients up to ~2^44.
+const FFT_EXACT_BITS: usize = 48;
+
+/// Largest decomposition log base of bool parameter sets. RLWE x RGSW uses 17
+/// and RLWE automorphism uses 24.
+const DEFAULT_LOG_BASE: usize = 24;
+
+/// Largest no. of products accumulated before transforming back to
+/// coefficient domain with bool parameter sets
+const DEFAULT_MAX_TERMS: usize = 2;
+
+/// Polynomial in Fourier domain in split representation with one row per limb
+pub(crate) type FourierSplitPoly = Vec<Vec<Complex64>>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex64 {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex64 {
+    fn new(re: f64, im: f64) -> Self {
+        Complex64 { re, im }
+    }
+
+    fn from_angle(theta: f64) -> Self {
+        Complex64::new(theta.cos(), theta.sin())
+    }
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Complex64::new(self.re + other.re, self.im + other.im)
+    }
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Complex64::new(self.re - other.re, self.im - other.im)
+    }
+
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        Complex64::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    #[inline]
+    fn conj(self) -> Self {
+        Complex64::new(self.re, -self.im)
+    }
+}
+
+/// Negacyclic polynomial multiplication in Z_q[X]/(X^N + 1) with f64 FFT.
+///
+/// A polynomial of size `N` is folded into `N/2` complex values, twisted by
+/// powers of 2N^{th} root of unity and transformed with N/2 point complex FFT.
+/// Pointwise product of transformed polynomials equals transform of their
+/// negacyclic product.
+///
+/// To keep products exact, one of the operands (ex, RGSW ciphertext) is
+/// stored in split representation: each coefficient is written in signed
+/// base 2^{split_bits} with `limbs` limbs and each limb polynomial is
+/// transformed separately. The other operand (ex, decomposed RLWE ciphertext)
+/// must have small coefficients. Limb products are rounded and recombined mod
+/// q, hence outputs equal outputs of NTT based multiplication as long as
+/// rounding errors of every limb product stay below 1/2, which is expected,
+/// but not proven, when coefficients stay below 2^{FFT_EXACT_BITS}.
+///
+/// With native modulus q = 2^64 limb products are recombined with wrapping
+/// arithmetic and outputs do not require any modular reduction.
+#[derive(Debug)]
+pub struct FftBackendF64 {
+    /// None if modulus is native
+    q: Option<u64>,
+    n: usize,
+    split_bits: usize,
+    limbs: usize,
+    /// \psi^i for i in [0, N/2) where \psi is 2N^{th} root of unity
+    twist: Box<[Complex64]>,
+    /// \psi^{-i} / (N/2) for i in [0, N/2)
+    untwist: Box<[Complex64]>,
+    /// \omega^i for i in [0, N/4) where \omega is (N/2)^{th} root of unity
+    roots: Box<[Complex64]>,
+}
+
+impl FftBackendF64 {
+    /// Returns FFT backend for ring Z_q[X]/(X^n + 1) that multiplies
+    /// polynomials with coefficients decomposed with log base `log_base` by
+    /// polynomials in split representation. At most `max_terms` products can
+    /// be accumulated before transforming back.
+    pub fn new(q: u64, n: usize, log_base: usize, max_terms: usize) -> Self {
+        FftBackendF64::_new(Some(q), n, log_base, max_terms)
+    }
+
+    /// Same as `new` for ring Z_{2^64}[X]/(X^n + 1)
+    pub fn new_native(n: usize, log_base: usize, max_terms: usize) -> Self {
+        FftBackendF64::_new(None, n, log_base, max_terms)
+    }
+
+    fn _new(q: Option<u64>, n: usize, log_base: usize, max_terms: usize) -> Self {
+        assert!(
+            n.is_power_of_two() && n >= 4,
+            "{n} is not power of two >= 4"
+        );
+        assert!(max_terms > 0);
+
+        // Signed decomposed digits are in [-B/2, B/2] and limbs are in
+        // [-2^{split_bits-1}, 2^{split_bits-1}). Coefficients of sum of
+        // `max_terms` products are bounded by
+        // max_terms * n * 2^{log_base - 1} * 2^{split_bits - 1}
+        let product_bits = log_base
+            + n.trailing_zeros() as usize
+            + max_terms.next_power_of_two().trailing_zeros() as usize;
+        assert!(
+            product_bits + 1 < FFT_EXACT_BITS,
+            "Products of decomposed polynomials with log base {log_base} do not fit in f64"
+        );
+        let split_bits = FFT_EXACT_BITS + 2 - product_bits;
+
+        let limbs = match q {
+            // centered coefficients are in [-q/2, q/2]. Balanced limbs need one
+            // additional bit
+            Some(q) => (64 - q.leading_zeros() as usize + 1).div_ceil(split_bits),
+            // limbs are only required to be correct mod 2^64
+            None => 64usize.div_ceil(split_bits),
+        };
+
+        let h = n >> 1;
+        let twist = (0..h)
+            .map(|i| Complex64::from_angle(PI * i as f64 / n as f64))
+            .collect::<Vec<_>>();
+        let untwist = (0..h)
+            .map(|i| {
+                let v = Complex64::from_angle(-PI * i as f64 / n as f64);
+                Complex64::new(v.re / h as f64, v.im / h as f64)
+            })
+            .collect::<Vec<_>>();
+        let roots = (0..h >> 1)
+            .map(|i| Complex64::from_angle(-2.0 * PI * i as f64 / h as f64))
+            .collect::<Vec<_>>();
+
+        FftBackendF64 {
+            q,
+            n,
+            split_bits,
+            limbs,
+            twist: twist.into_boxed_slice(),
+            untwist: untwist.into_boxed_slice(),
+            roots: roots.into_boxed_slice(),
+        }
+    }
+
+    /// Ring size `N`
+    pub fn ring_size(&self) -> usize {
+        self.n
+    }
+
+    /// No. of limbs of polynomials in split representation
+    pub fn limbs(&self) -> usize {
+        self.limbs
+    }
+
+    /// Bits of each limb of polynomials in split representation
+    pub fn split_bits(&self) -> usize {
+        self.split_bits
+    }
+
+    /// Is modulus native?
+    pub fn is_native(&self) -> bool {
+        self.q.is_none()
+    }
+
+    /// Returns `v` mod q as signed value in [-q/2, q/2]
+    #[inline]
+    fn centered(&self, v: u64) -> i64 {
+        match self.q {
+            Some(q) if v > q >> 1 => -((q - v) as i64),
+            _ => v as i64,
+        }
+    }
+
+    /// Cooley-Tukey FFT with decimation in frequency. Input is in natural
+    /// order and output is in bit reversed order.
+    fn fft(&self, a: &mut [Complex64]) {
+        let h = a.len();
+        let mut len = h;
+        while len >= 2 {
+            let half = len >> 1;
+            let stride = h / len;
+            for chunk in a.chunks_exact_mut(len) {
+                let (left, right) = chunk.split_at_mut(half);
+                for (k, (x, y)) in izip!(left.iter_mut(), right.iter_mut()).enumerate() {
+                    let u = *x;
+                    let v = *y;
+                    *x = u.add(v);
+                    *y = u.sub(v).mul(self.roots[k * stride]);
+                }
+            }
+            len = half;
+        }
+    }
+
+    /// Inverse of `fft` without scaling by 1/(N/2). Input is in bit reversed
+    /// order and output is in natural order.
+    fn ifft(&self, a: &mut [Complex64]) {
+        let h = a.len();
+        let mut len = 2;
+        while len <= h {
+            let half = len >> 1;
+            let stride = h / len;
+            for chunk in a.chunks_exact_mut(len) {
+                let (left, right) = chunk.split_at_mut(half);
+                for (k, (x, y)) in izip!(left.iter_mut(), right.iter_mut()).enumerate() {
+                    let u = *x;
+                    let v = y.mul(self.roots[k * stride].conj());
+                    *x = u.add(v);
+                    *y = u.sub(v);
+                }
+            }
+            len <<= 1;
+        }
+    }
+
+    fn forward_signed(&self, a: impl Fn(usize) -> i64, out: &mut [Complex64]) {
+        let h = self.n >> 1;
+        assert!(out.len() == h);
+        izip!(out.iter_mut(), self.twist.iter())
+            .enumerate()
+            .for_each(|(i, (o, t))| {
+                *o = Complex64::new(a(i) as f64, a(i + h) as f64).mul(*t);
+            });
+        self.fft(out);
+    }
+
+    /// Transforms polynomial `a` with small coefficients mod q (ex, a
+    /// decomposed polynomial) to Fourier domain. `out` must have N/2 elements.
+    pub fn forward(&self, a: &[u64], out: &mut [Complex64]) {
+        assert!(a.len() == self.n);
+        self.forward_signed(|i| self.centered(a[i]), out);
+    }
+
+    /// Transforms polynomial `a` mod q to Fourier domain in split
+    /// representation. `out` must have `limbs` rows of N/2 elements.
+    pub fn forward_split<R: AsMut<[Complex64]>>(&self, a: &[u64], out: &mut [R]) {
+        assert!(a.len() == self.n);
+        assert!(out.len() == self.limbs);
+
+        let mut rest = a.iter().map(|v| self.centered(*v)).collect::<Vec<_>>();
+        let half = 1i64 << (self.split_bits - 1);
+        let mask = (1i64 << self.split_bits) - 1;
+        let mut limb = vec![0i64; self.n];
+        out.iter_mut().for_each(|o| {
+            // signed digit in [-2^{split_bits-1}, 2^{split_bits-1})
+            izip!(limb.iter_mut(), rest.iter_mut()).for_each(|(l, r)| {
+                let d = ((*r + half) & mask) - half;
+                *l = d;
+                // wraps only when modulus is native, in which case limbs are
+                // correct mod 2^64
+                *r = r.wrapping_sub(d) >> self.split_bits;
+            });
+            self.forward_signed(|i| limb[i], o.as_mut());
+        });
+        debug_assert!(self.is_native() || rest.iter().all(|r| *r == 0));
+    }
+
+    /// Sets acc[l] = acc[l] + a * b[l] for each limb `l`, where `a` is in
+    /// Fourier domain and `b` is in Fourier domain in split representation.
+    pub fn fma_split<R: AsMut<[Complex64]>, S: AsRef<[Complex64]>>(
+        &self,
+        acc: &mut [R],
+        a: &[Complex64],
+        b: &[S],
+    ) {
+        assert!(acc.len() == self.limbs);
+        assert!(b.len() == self.limbs);
+        izip!(acc.iter_mut(), b.iter()).for_each(|(acc_l, b_l)| {
+            izip!(acc_l.as_mut().iter_mut(), a.iter(), b_l.as_ref().iter())
+                .for_each(|(o, a0, b0)| *o = o.add(a0.mul(*b0)));
+        });
+    }
+
+    /// Transforms `acc`, a polynomial in Fourier domain in split
+    /// representation, back to coefficient domain and writes the coefficients
+    /// mod q to `out`. `acc` is used as scratch space.
+    pub fn backward_split<R: AsMut<[Complex64]>>(&self, acc: &mut [R], out: &mut [u64]) {
+        assert!(acc.len() == self.limbs);
+        assert!(out.len() == self.n);
+
+        let h = self.n >> 1;
+        let mut sum = vec![0i128; self.n];
+        acc.iter_mut().enumerate().for_each(|(l, acc_l)| {
+            let acc_l = acc_l.as_mut();
+            self.ifft(acc_l);
+            let shift = l * self.split_bits;
+            izip!(acc_l.iter(), self.untwist.iter())
+                .enumerate()
+                .for_each(|(i, (v, t))| {
+                    let v = v.mul(*t);
+                    sum[i] += (v.re.round() as i128) << shift;
+                    sum[i + h] += (v.im.round() as i128) << shift;
+                });
+        });
+
+        match self.q {
+            Some(q) => {
+                let q = q as i128;
+                izip!(out.iter_mut(), sum.iter()).for_each(|(o, s)| *o = s.rem_euclid(q) as u64);
+            }
+            // truncation equals reduction mod 2^64
+            None => izip!(out.iter_mut(), sum.iter()).for_each(|(o, s)| *o = *s as u64),
+        }
+    }
+
+    /// Sets `out` = a * s, where `s` has small coefficients (ex, secret
+    /// polynomial). Used to encrypt and decrypt RLWE ciphertexts when the
+    /// modulus does not support NTT.
+    pub fn mul_small(&self, a: &[u64], s: &[u64], out: &mut [u64]) {
+        let h = self.n >> 1;
+        let mut a_split = vec![vec![Complex64::default(); h]; self.limbs];
+        self.forward_split(a, &mut a_split);
+        let mut s_fourier = vec![Complex64::default(); h];
+        self.forward(s, &mut s_fourier);
+        let mut acc = vec![vec![Complex64::default(); h]; self.limbs];
+        self.fma_split(&mut acc, &s_fourier, &a_split);
+        self.backward_split(&mut acc, out);
+    }
+
+    /// Transforms polynomials `rows` in coefficient domain to Fourier domain
+    /// in split representation
+    pub(crate) fn forward_split_rows<R: Row<Element = u64>>(
+        &self,
+        rows: &[R],
+    ) -> Vec<FourierSplitPoly> {
+        rows.iter()
+            .map(|r| {
+                let mut out = vec![vec![Complex64::default(); self.n >> 1]; self.limbs];
+                self.forward_split(r.as_ref(), &mut out);
+                out
+            })
+            .collect()
+    }
+}
+
+impl<M: Modulus<Element = u64>> NttInit<M> for FftBackendF64 {
+    /// Returns FFT backend with split representation wide enough for
+    /// decomposition parameters of bool parameter sets
+    fn new(q: &M, n: usize) -> Self {
+        if q.is_native() {
+            FftBackendF64::new_native(n, DEFAULT_LOG_BASE, DEFAULT_MAX_TERMS)
+        } else {
+            FftBackendF64::new(q.q().unwrap(), n, DEFAULT_LOG_BASE, DEFAULT_MAX_TERMS)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::{thread_rng, Rng};
+    use rand_distr::{Standard, Uniform};
+
+    use super::*;
+    use crate::{
+        backend::{ModInit, ModularOpsU64, VectorOps},
+        bool::parameters::{I_2P_LB_SR, I_4P, I_8P, NI_2P, NI_4P_HB_FR, NI_4P_LB_SR, NI_8P},
+        decomposer::{Decomposer, DefaultDecomposer},
+        ntt::{Ntt, NttBackendU64, NttInit},
+        utils::{generate_prime, negacyclic_mul},
+    };
+
+    #[test]
+    fn fft_negacyclic_mul_is_exact() {
+        let mut rng = thread_rng();
+        for (logq, n, logb, d) in [
+            (55, 1 << 11, 17, 1),
+            (55, 1 << 11, 24, 1),
+            (50, 1 << 4, 5, 10),
+        ] {
+            let q = generate_prime(logq, 2 * n as u64, 1u64 << logq).unwrap();
+            let fft = FftBackendF64::new(q, n, logb, 2 * d);
+            let ntt = NttBackendU64::new(&q, n);
+            let mod_op = ModularOpsU64::new(q);
+            let decomposer = DefaultDecomposer::new(q, logb, d);
+
+            for _ in 0..10 {
+                let a = (&mut rng)
+                    .sample_iter(Uniform::new(0, q))
+                    .take(n)
+                    .collect_vec();
+                let b = (&mut rng)
+                    .sample_iter(Uniform::new(0, q))
+                    .take(n)
+                    .collect_vec();
+
+                // decompose a
+                let mut a_decomposed = vec![vec![0u64; n]; d];
+                a.iter().enumerate().for_each(|(i, v)| {
+                    decomposer
+                        .decompose_iter(v)
+                        .enumerate()
+                        .for_each(|(j, el)| a_decomposed[j][i] = el);
+                });
+
+                // \sum decomp<a>_j * b with FFT
+                let mut b_split = vec![vec![Complex64::default(); n >> 1]; fft.limbs()];
+                fft.forward_split(&b, &mut b_split);
+                let mut acc = vec![vec![Complex64::default(); n >> 1]; fft.limbs()];
+                let mut a_fourier = vec![Complex64::default(); n >> 1];
+                for a_j in a_decomposed.iter() {
+                    fft.forward(a_j, &mut a_fourier);
+                    fft.fma_split(&mut acc, &a_fourier, &b_split);
+                }
+                let mut got = vec![0u64; n];
+                fft.backward_split(&mut acc, &mut got);
+
+                // same with schoolbook multiplication
+                let mul = |a: &u64, b: &u64| ((*a as u128 * *b as u128) % q as u128) as u64;
+                let mut expected = vec![0u64; n];
+                for a_j in a_decomposed.iter() {
+                    mod_op.elwise_add_mut(&mut expected, &negacyclic_mul(a_j, &b, mul, q));
+                }
+                assert_eq!(expected, got, "logq={logq} n={n} logb={logb} d={d}");
+
+                // and with NTT
+                let mut b_ntt = b.clone();
+                ntt.forward(&mut b_ntt);
+                let mut expected_ntt = vec![0u64; n];
+                for a_j in a_decomposed.iter() {
+                    let mut a_j = a_j.clone();
+                    ntt.forward(&mut a_j);
+                    mod_op.elwise_mul_mut(&mut a_j, &b_ntt);
+                    mod_op.elwise_add_mut(&mut expected_ntt, &a_j);
+                }
+                ntt.backward(&mut expected_ntt);
+                assert_eq!(expected_ntt, got);
+            }
+        }
+    }
+
+    #[test]
+    fn native_fft_negacyclic_mul_is_exact() {
+        let mut rng = thread_rng();
+        for (n, logb, d) in [(1 << 11, 17, 1), (1 << 11, 24, 1), (1 << 4, 8, 8)] {
+            let fft: FftBackendF64 = FftBackendF64::new_native(n, logb, 2 * d);
+            assert!(fft.limbs() * fft.split_bits() >= 64);
+            let decomposer = DefaultDecomposer::new(0u64, logb, d);
+
+            for _ in 0..10 {
+                let a = (&mut rng).sample_iter(Standard).take(n).collect_vec();
+                let b: Vec<u64> = (&mut rng).sample_iter(Standard).take(n).collect_vec();
+
+                let mut a_decomposed = vec![vec![0u64; n]; d];
+                a.iter().enumerate().for_each(|(i, v)| {
+                    decomposer
+                        .decompose_iter(v)
+                        .enumerate()
+                        .for_each(|(j, el)| a_decomposed[j][i] = el);
+                });
+
+                let mut got = vec![0u64; n];
+                let mut b_split = vec![vec![Complex64::default(); n >> 1]; fft.limbs()];
+                fft.forward_split(&b, &mut b_split);
+                let mut acc = vec![vec![Complex64::default(); n >> 1]; fft.limbs()];
+                let mut a_fourier = vec![Complex64::default(); n >> 1];
+                for a_j in a_decomposed.iter() {
+                    fft.forward(a_j, &mut a_fourier);
+                    fft.fma_split(&mut acc, &a_fourier, &b_split);
+                }
+                fft.backward_split(&mut acc, &mut got);
+
+                // schoolbook multiplication mod 2^64
+                let mut expected = vec![0u64; n];
+                for a_j in a_decomposed.iter() {
+                    for i in 0..n {
+                        for j in 0..n {
+                            let v = a_j[i].wrapping_mul(b[j]);
+                            if i + j < n {
+                                expected[i + j] = expected[i + j].wrapping_add(v);
+                            } else {
+                                expected[i + j - n] = expected[i + j - n].wrapping_sub(v);
+                            }
+                        }
+                    }
+                }
+                assert_eq!(expected, got, "n={n} logb={logb} d={d}");
+            }
+        }
+    }
+
+    #[test]
+    fn fft_rounding_error_of_bool_parameter_sets_is_small() {
+        let mut rng = thread_rng();
+        for parameters in [
+            I_2P_LB_SR,
+            I_4P,
+            I_8P,
+            NI_2P,
+            NI_4P_HB_FR,
+            NI_4P_LB_SR,
+            NI_8P,
+        ] {
+            let (log_base, max_terms) = parameters.blind_rotation_fft_bounds();
+            let fft = parameters.blind_rotation_fft_op();
+            let n = fft.ring_size();
+            let h = n >> 1;
+            let digit = 1i64 << (log_base - 1);
+            let limb = 1i64 << (fft.split_bits() - 1);
+
+            // Limb products with largest coefficients are products of
+            // polynomials with all coefficients at maximum magnitude. Products
+            // of polynomials with maximum magnitude and random signs have
+            // typical rounding errors.
+            let mut max_err = 0f64;
+            for trial in 0..4 {
+                let mut acc = vec![Complex64::default(); h];
+                let mut a_fourier = vec![Complex64::default(); h];
+                let mut b_fourier = vec![Complex64::default(); h];
+                let mut expected = vec![0i128; n];
+                for _ in 0..max_terms {
+                    let [a, b] = [digit, limb].map(|v| {
+                        (0..n)
+                            .map(|_| {
+                                if trial == 0 || rng.gen_bool(0.5) {
+                                    v
+                                } else {
+                                    -v
+                                }
+                            })
+                            .collect_vec()
+                    });
+                    fft.forward_signed(|i| a[i], &mut a_fourier);
+                    fft.forward_signed(|i| b[i], &mut b_fourier);
+                    izip!(acc.iter_mut(), a_fourier.iter(), b_fourier.iter())
+                        .for_each(|(o, a0, b0)| *o = o.add(a0.mul(*b0)));
+
+                    for i in 0..n {
+                        for j in 0..n {
+                            let v = a[i] as i128 * b[j] as i128;
+                            if i + j < n {
+                                expected[i + j] += v;
+                            } else {
+                                expected[i + j - n] -= v;
+                            }
+                        }
+                    }
+                }
+
+                fft.ifft(&mut acc);
+                izip!(acc.iter(), fft.untwist.iter())
+                    .enumerate()
+                    .for_each(|(i, (v, t))| {
+                        let v = v.mul(*t);
+                        max_err = max_err
+                            .max((v.re - expected[i] as f64).abs())
+                            .max((v.im - expected[i + h] as f64).abs());
+                    });
+            }
+
+            // Must stay below 1/2 for rounding to be exact. Keep a margin of 2x.
+            assert!(
+                max_err < 0.25,
+                "{:?}: log_base={log_base} max_terms={max_terms} split_bits={} max rounding error={max_err}",
+                parameters.variant(),
+                fft.split_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn default_fft_backend_supports_bool_parameter_sets() {
+        for parameters in [
+            I_2P_LB_SR,
+            I_4P,
+            I_8P,
+            NI_2P,
+            NI_4P_HB_FR,
+            NI_4P_LB_SR,
+            NI_8P,
+        ] {
+            let (log_base, max_terms) = parameters.blind_rotation_fft_bounds();
+            assert!(log_base <= DEFAULT_LOG_BASE);
+            assert!(max_terms <= DEFAULT_MAX_TERMS);
+
+            let fft_op: FftBackendF64 = NttInit::new(parameters.rlwe_q(), parameters.rlwe_n().0);
+            assert!(fft_op.limbs() * fft_op.split_bits() > parameters.rlwe_q().log_q());
+        }
+    }
+}
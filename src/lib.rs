@@ -3,10 +3,13 @@ use num_traits::Zero;
 mod backend;
 mod bool;
 mod decomposer;
+mod fft;
 mod lwe;
 mod multi_party;
 mod ntt;
 mod pbs;
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+mod proof;
 mod random;
 mod rgsw;
 #[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
@@ -14,15 +17,23 @@ mod shortint;
 mod utils;
 
 pub use backend::{
-    ArithmeticLazyOps, ArithmeticOps, ModInit, ModularOpsU64, ShoupMatrixFMA, VectorOps,
+    ArithmeticLazyOps, ArithmeticOps, ModInit, ModularOpsU64, ShoupMatrixFMA, SimdLevel, VectorOps,
+    WordSizeModulus,
 };
 
 pub use bool::*;
-pub use ntt::{Ntt, NttBackendU64, NttInit};
+pub use fft::{Complex64, FftBackendF64};
+pub use ntt::{IdentityNtt, Ntt, NttBackendU64, NttBackendU64Simd, NttInit};
 #[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
-pub use shortint::{div_zero_error_flag, reset_error_flags, EncFheUint8, FheUint8};
+pub use shortint::{
+    div_zero_error_flag, reset_error_flags, BatchedFheUint8, EncFheUint8, FheUint8,
+};
 
 pub use decomposer::{Decomposer, DecomposerIter, DefaultDecomposer};
+pub use multi_party::MalformedDecryptionShare;
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+pub use proof::DecryptionShareProof;
+pub use random::{with_seeded_rng, PrgAlgorithm};
 
 type RowIterator<'a, T> = Box<dyn Iterator<Item = &'a T> + 'a>;
 type RowIteratorMut<'a, T> = Box<dyn Iterator<Item = &'a mut T> + 'a>;
@@ -176,10 +187,55 @@ pub trait MultiPartyDecryptor<M, C> {
     fn aggregate_decryption_shares(&self, c: &C, shares: &[Self::DecryptionShare]) -> M;
 }
 
+/// Multi-party decryption with proofs that each decryption share is computed
+/// with the secret of the party's public key share
+pub trait VerifiableMultiPartyDecryptor<M, C>: MultiPartyDecryptor<M, C> {
+    type DecryptionShareProof;
+    type PublicKeyShare;
+
+    /// Generates decryption share of `c` with proof of correct decryption
+    /// under the secret of `public_key_share`
+    ///
+    /// The proof uses 128 parallel repetitions with binary challenges, each
+    /// with responses for the secret and the public key share error (ring
+    /// size N each) and the decryption share error. Hence a proof consists of
+    /// 128 * (2N + 1) i32s, i.e. ~2 MB per decrypted bit for N = 2^11 (~16 MB
+    /// for a FheUint8). Generating it costs 128 negacyclic multiplications of
+    /// size N and 128 inner products of size N per attempt, with ~1.6 expected
+    /// attempts due to rejection sampling. Verifying it costs the same as a
+    /// single attempt.
+    ///
+    /// Panics if `public_key_share` is not the party's own public key share.
+    fn gen_decryption_share_with_proof(
+        &self,
+        c: &C,
+        public_key_share: &Self::PublicKeyShare,
+    ) -> (Self::DecryptionShare, Self::DecryptionShareProof);
+
+    /// Verifies proof of each decryption share against public key share of the
+    /// party and aggregates the shares. Returns the first share that does not
+    /// verify otherwise.
+    ///
+    /// Share, proof and public key share of party with user_id `i` must be at
+    /// index `i`.
+    fn aggregate_decryption_shares_verified(
+        &self,
+        c: &C,
+        shares: &[Self::DecryptionShare],
+        proofs: &[Self::DecryptionShareProof],
+        public_key_shares: &[Self::PublicKeyShare],
+    ) -> Result<M, MalformedDecryptionShare>;
+}
+
 pub trait KeySwitchWithId<C> {
     fn key_switch(&self, user_id: usize) -> C;
 }
 
+pub trait KeyRefresher<C> {
+    /// Key switch ciphertext `c` from the current key to the refreshed key
+    fn refresh(&self, c: &C) -> C;
+}
+
 pub trait SampleExtractor<R> {
     /// Extract ciphertext at `index`
     fn extract_at(&self, index: usize) -> R;
@@ -7,7 +7,7 @@ use crate::{
     backend::{ArithmeticOps, GetModulus, VectorOps},
     decomposer::Decomposer,
     random::{RandomFillUniformInModulus, RandomGaussianElementInModulus},
-    utils::TryConvertFrom1,
+    utils::{wipe, TryConvertFrom1},
     Matrix, Row, RowEntity, RowMut,
 };
 
@@ -43,6 +43,60 @@ pub(crate) fn lwe_key_switch<
     lwe_out.as_mut()[0] = out_b;
 }
 
+/// Key switches each LWE ciphertext in `lwe_ins` to corresponding LWE
+/// ciphertext in `lwe_outs`. Unlike calling `lwe_key_switch` for each
+/// ciphertext, each row of `lwe_ksk` is read once for all ciphertexts.
+pub(crate) fn lwe_key_switch_batch<
+    M: Matrix,
+    Ro: AsMut<[M::MatElement]> + AsRef<[M::MatElement]>,
+    Op: VectorOps<Element = M::MatElement> + ArithmeticOps<Element = M::MatElement>,
+    D: Decomposer<Element = M::MatElement>,
+>(
+    lwe_outs: &mut [Ro],
+    lwe_ins: &[Ro],
+    lwe_ksk: &M,
+    operator: &Op,
+    decomposer: &D,
+) {
+    assert!(lwe_outs.len() == lwe_ins.len());
+    if lwe_ins.is_empty() {
+        return;
+    }
+
+    let d = decomposer.decomposition_count().0;
+    let lwe_in_n = lwe_ins[0].as_ref().len();
+    assert!(lwe_ksk.dimension().0 == ((lwe_in_n - 1) * d));
+    assert!(lwe_ins
+        .iter()
+        .all(|lwe_in| lwe_in.as_ref().len() == lwe_in_n));
+    assert!(lwe_outs
+        .iter()
+        .all(|lwe_out| lwe_out.as_ref().len() == lwe_ksk.dimension().1));
+
+    let mut beta_i_lwe = lwe_ksk.iter_rows();
+    let mut ai_decomposed = (0..lwe_ins.len())
+        .map(|_| Vec::with_capacity(d))
+        .collect::<Vec<_>>();
+    for i in 1..lwe_in_n {
+        izip!(ai_decomposed.iter_mut(), lwe_ins.iter()).for_each(|(ai_decomposed, lwe_in)| {
+            ai_decomposed.clear();
+            ai_decomposed.extend(decomposer.decompose_iter(&lwe_in.as_ref()[i]));
+        });
+
+        for j in 0..d {
+            let beta_ij_lwe = beta_i_lwe.next().unwrap();
+            izip!(lwe_outs.iter_mut(), ai_decomposed.iter()).for_each(|(lwe_out, ai)| {
+                operator.elwise_fma_scalar_mut(lwe_out.as_mut(), beta_ij_lwe.as_ref(), &ai[j]);
+            });
+        }
+    }
+
+    izip!(lwe_outs.iter_mut(), lwe_ins.iter()).for_each(|(lwe_out, lwe_in)| {
+        let out_b = operator.add(&lwe_out.as_ref()[0], &lwe_in.as_ref()[0]);
+        lwe_out.as_mut()[0] = out_b;
+    });
+}
+
 pub(crate) fn seeded_lwe_ksk_keygen<
     Ro: RowMut + RowEntity,
     S,
@@ -61,7 +115,7 @@ pub(crate) fn seeded_lwe_ksk_keygen<
 ) -> Ro
 where
     Ro: TryConvertFrom1<[S], Op::M>,
-    Ro::Element: Zero + Debug,
+    Ro::Element: Zero + Copy + Debug,
 {
     let mut ksk_out = Ro::zeros(from_lwe_sk.len() * gadget.len());
 
@@ -70,7 +124,7 @@ where
     let modulus = operator.modulus();
     let mut neg_sk_in_m = Ro::try_convert_from(from_lwe_sk, modulus);
     operator.elwise_neg_mut(neg_sk_in_m.as_mut());
-    let sk_out_m = Ro::try_convert_from(to_lwe_sk, modulus);
+    let mut sk_out_m = Ro::try_convert_from(to_lwe_sk, modulus);
 
     let mut scratch = Ro::zeros(to_lwe_sk.len());
 
@@ -97,6 +151,9 @@ where
         },
     );
 
+    wipe(neg_sk_in_m.as_mut());
+    wipe(sk_out_m.as_mut());
+
     ksk_out
 }
 
@@ -115,9 +172,9 @@ pub(crate) fn encrypt_lwe<
 ) -> Ro
 where
     Ro: TryConvertFrom1<[S], Op::M>,
-    Ro::Element: Zero,
+    Ro::Element: Zero + Copy,
 {
-    let s = Ro::try_convert_from(s, operator.modulus());
+    let mut s = Ro::try_convert_from(s, operator.modulus());
     let mut lwe_out = Ro::zeros(s.as_ref().len() + 1);
 
     // a*s
@@ -133,6 +190,8 @@ where
     let b = operator.add(&operator.add(&sa, &e), m);
     lwe_out.as_mut()[0] = b;
 
+    wipe(s.as_mut());
+
     lwe_out
 }
 
@@ -170,7 +229,8 @@ mod tests {
 
     use crate::{
         backend::{ModInit, ModulusPowerOf2},
-        decomposer::DefaultDecomposer,
+        bool::parameters::CiphertextModulus,
+        decomposer::{Decomposer, DefaultDecomposer},
         random::{DefaultSecureRng, NewWithSeed},
         utils::{fill_random_ternary_secret_with_hamming_weight, WithLocal},
         MatrixEntity, MatrixMut,
@@ -331,4 +391,132 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn key_switch_with_u32_storage_matches_u64() {
+        let logq = 16;
+        let q = 1u64 << logq;
+        let lwe_in_n = 1024;
+        let lwe_out_n = 500;
+        let d_ks = 4;
+        let logb = 4;
+
+        let lwe_sk_in = LweSecret::random(lwe_in_n >> 1, lwe_in_n);
+        let lwe_sk_out = LweSecret::random(lwe_out_n >> 1, lwe_out_n);
+
+        let mut rng = DefaultSecureRng::new();
+        let modq_op = ModulusPowerOf2::new(q);
+        let modq_op_u32 = ModulusPowerOf2::new(CiphertextModulus::<u32>::new_non_native(q as u32));
+
+        let mut ksk_seed = [0u8; 32];
+        rng.fill_bytes(&mut ksk_seed);
+        let mut p_rng = DefaultSecureRng::new_seeded(ksk_seed);
+        let decomposer = DefaultDecomposer::new(q, logb, d_ks);
+        let decomposer_u32 = DefaultDecomposer::new(q as u32, logb, d_ks);
+        let seeded_ksk = seeded_lwe_ksk_keygen(
+            &lwe_sk_in.values(),
+            &lwe_sk_out.values(),
+            &decomposer.gadget_vector(),
+            &modq_op,
+            &mut p_rng,
+            &mut rng,
+        );
+        let ksk = LweKeySwitchingKey::<Vec<Vec<u64>>, DefaultSecureRng>::from(&(
+            seeded_ksk, ksk_seed, lwe_out_n, q,
+        ));
+        let ksk_u32 = ksk
+            .data
+            .iter()
+            .map(|r| r.iter().map(|v| *v as u32).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        for m in 0..4u64 {
+            let lwe_in_ct = encrypt_lwe::<Vec<u64>, _, _, _>(
+                &(m << (logq - 2)),
+                lwe_sk_in.values(),
+                &modq_op,
+                &mut rng,
+            );
+            let lwe_in_ct_u32 = lwe_in_ct.iter().map(|v| *v as u32).collect::<Vec<_>>();
+
+            let mut lwe_out_ct = vec![0u64; lwe_out_n + 1];
+            lwe_key_switch(
+                &mut lwe_out_ct,
+                &lwe_in_ct,
+                &ksk.data,
+                &modq_op,
+                &decomposer,
+            );
+            let mut lwe_out_ct_u32 = vec![0u32; lwe_out_n + 1];
+            lwe_key_switch(
+                &mut lwe_out_ct_u32,
+                &lwe_in_ct_u32,
+                &ksk_u32,
+                &modq_op_u32,
+                &decomposer_u32,
+            );
+
+            assert!(izip!(lwe_out_ct.iter(), lwe_out_ct_u32.iter()).all(|(a, b)| *a == *b as u64));
+        }
+    }
+
+    #[test]
+    fn key_switch_batch_matches_key_switch() {
+        let logq = 16;
+        let q = 1u64 << logq;
+        let lwe_in_n = 256;
+        let lwe_out_n = 100;
+        let d_ks = 4;
+        let logb = 4;
+
+        let lwe_sk_in = LweSecret::random(lwe_in_n >> 1, lwe_in_n);
+        let lwe_sk_out = LweSecret::random(lwe_out_n >> 1, lwe_out_n);
+
+        let mut rng = DefaultSecureRng::new();
+        let modq_op = ModulusPowerOf2::new(q);
+
+        let mut ksk_seed = [0u8; 32];
+        rng.fill_bytes(&mut ksk_seed);
+        let mut p_rng = DefaultSecureRng::new_seeded(ksk_seed);
+        let decomposer = DefaultDecomposer::new(q, logb, d_ks);
+        let seeded_ksk = seeded_lwe_ksk_keygen(
+            lwe_sk_in.values(),
+            lwe_sk_out.values(),
+            &decomposer.gadget_vector(),
+            &modq_op,
+            &mut p_rng,
+            &mut rng,
+        );
+        let ksk = LweKeySwitchingKey::<Vec<Vec<u64>>, DefaultSecureRng>::from(&(
+            seeded_ksk, ksk_seed, lwe_out_n, q,
+        ));
+
+        let lwe_in_cts = (0..4u64)
+            .map(|m| {
+                encrypt_lwe::<Vec<u64>, _, _, _>(
+                    &(m << (logq - 2)),
+                    lwe_sk_in.values(),
+                    &modq_op,
+                    &mut rng,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut lwe_out_cts_batch = vec![vec![0u64; lwe_out_n + 1]; lwe_in_cts.len()];
+        lwe_key_switch_batch(
+            &mut lwe_out_cts_batch,
+            &lwe_in_cts,
+            &ksk.data,
+            &modq_op,
+            &decomposer,
+        );
+
+        izip!(lwe_in_cts.iter(), lwe_out_cts_batch.iter()).for_each(
+            |(lwe_in_ct, lwe_out_batch)| {
+                let mut lwe_out_ct = vec![0u64; lwe_out_n + 1];
+                lwe_key_switch(&mut lwe_out_ct, lwe_in_ct, &ksk.data, &modq_op, &decomposer);
+                assert_eq!(&lwe_out_ct, lwe_out_batch);
+            },
+        );
+    }
 }
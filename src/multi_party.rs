@@ -9,7 +9,7 @@ use crate::{
     random::{
         RandomFillGaussianInModulus, RandomFillUniformInModulus, RandomGaussianElementInModulus,
     },
-    utils::TryConvertFrom1,
+    utils::{wipe, TryConvertFrom1},
     ArithmeticOps, Matrix, MatrixEntity, MatrixMut, Row, RowEntity, RowMut,
 };
 
@@ -29,6 +29,7 @@ pub(crate) fn public_key_share<
     rng: &mut Rng,
 ) where
     R: TryConvertFrom1<[S], ModOp::M>,
+    R::Element: Copy + Zero,
 {
     let ring_size = share_out.as_ref().len();
     assert!(s_i.len() == ring_size);
@@ -51,6 +52,8 @@ pub(crate) fn public_key_share<
 
     RandomFillGaussianInModulus::random_fill(rng, &q, share_out.as_mut());
     modop.elwise_add_mut(share_out.as_mut(), s.as_ref()); // s*e + e
+
+    wipe(s.as_mut());
 }
 
 /// Generate decryption share for LWE ciphertext `lwe_ct` with user's secret `s`
@@ -68,7 +71,7 @@ pub(crate) fn multi_party_decryption_share<
 ) -> R::Element
 where
     R: TryConvertFrom1<[S], Mod>,
-    R::Element: Zero,
+    R::Element: Zero + Copy,
 {
     assert!(lwe_ct.as_ref().len() == s.len() + 1);
     let mut neg_s = R::try_convert_from(s, mod_op.modulus());
@@ -83,6 +86,8 @@ where
     let e = rng.random(mod_op.modulus());
     share = mod_op.add(&share, &e);
 
+    wipe(neg_s.as_mut());
+
     share
 }
 
@@ -106,6 +111,57 @@ where
     mod_op.add(&lwe_ct.as_ref()[0], &sum_shares)
 }
 
+/// Error returned when a decryption share does not verify
+#[derive(Clone, Debug, PartialEq)]
+pub struct MalformedDecryptionShare {
+    /// Index of the share in received decryption shares, i.e. user_id of the
+    /// party that sent it
+    pub share_index: usize,
+    /// Reason why the share is malformed
+    pub reason: String,
+}
+
+impl std::fmt::Display for MalformedDecryptionShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Malformed decryption share at index {}: {}",
+            self.share_index, self.reason
+        )
+    }
+}
+
+impl std::error::Error for MalformedDecryptionShare {}
+
+/// Verifies decryption share of each party with `verify` and returns the first
+/// share whose proof or public key share is missing or does not verify.
+///
+/// Share, proof and public key share of party with user_id `i` must be at
+/// index `i`.
+#[cfg(any(feature = "interactive_mp", feature = "non_interactive_mp"))]
+pub(crate) fn verify_decryption_shares<S, P, K>(
+    shares: &[S],
+    proofs: &[P],
+    public_key_shares: &[K],
+    verify: impl Fn(usize, &S, &P, &K) -> Result<(), String>,
+) -> Result<(), MalformedDecryptionShare> {
+    (0..shares.len().max(proofs.len()).max(public_key_shares.len())).try_for_each(|i| {
+        let reason = match (shares.get(i), proofs.get(i), public_key_shares.get(i)) {
+            (Some(share), Some(proof), Some(pk_share)) => verify(i, share, proof, pk_share).err(),
+            (None, ..) => Some("Decryption share is missing".to_string()),
+            (_, None, _) => Some("Proof is missing".to_string()),
+            (.., None) => Some("Public key share is missing".to_string()),
+        };
+        match reason {
+            Some(reason) => Err(MalformedDecryptionShare {
+                share_index: i,
+                reason,
+            }),
+            None => Ok(()),
+        }
+    })
+}
+
 pub(crate) fn non_interactive_rgsw_ct<
     M: MatrixMut + MatrixEntity,
     S,
@@ -125,7 +181,7 @@ pub(crate) fn non_interactive_rgsw_ct<
 ) -> (M, M)
 where
     <M as Matrix>::R: RowMut + TryConvertFrom1<[S], ModOp::M> + RowEntity,
-    M::MatElement: Copy,
+    M::MatElement: Copy + Zero,
 {
     assert_eq!(s.len(), u.len());
     assert_eq!(s.len(), m.len());
@@ -180,6 +236,10 @@ where
         modop.elwise_add_mut(e_zero.as_mut(), scratch_space.as_ref());
     });
 
+    wipe(s_poly_eval.as_mut());
+    wipe(u_poly_eval.as_mut());
+    wipe(scratch_space.as_mut());
+
     (enc_beta_m, zero_encryptions)
 }
 
@@ -201,7 +261,7 @@ pub(crate) fn non_interactive_ksk_gen<
 ) -> M
 where
     <M as Matrix>::R: RowMut + TryConvertFrom1<[S], ModOp::M> + RowEntity,
-    M::MatElement: Copy + Debug,
+    M::MatElement: Copy + Zero + Debug,
 {
     assert_eq!(s.len(), u.len());
 
@@ -211,7 +271,7 @@ where
 
     let mut s_poly_eval = M::R::try_convert_from(s, q);
     nttop.forward(s_poly_eval.as_mut());
-    let u_poly = M::R::try_convert_from(u, q);
+    let mut u_poly = M::R::try_convert_from(u, q);
     // a_i * s + \beta u + e
     let mut ksk = M::zeros(d, ring_size);
 
@@ -235,6 +295,10 @@ where
         modop.elwise_add_mut(e_ksk.as_mut(), scratch_space.as_ref());
     });
 
+    wipe(s_poly_eval.as_mut());
+    wipe(u_poly.as_mut());
+    wipe(scratch_space.as_mut());
+
     ksk
 }
 
@@ -255,7 +319,7 @@ pub(crate) fn non_interactive_ksk_zero_encryptions_for_other_party_i<
 ) -> M
 where
     <M as Matrix>::R: RowMut + TryConvertFrom1<[S], ModOp::M> + RowEntity,
-    M::MatElement: Copy + Debug,
+    M::MatElement: Copy + Zero + Debug,
 {
     let q = modop.modulus();
     let d = gadget_vec.len();
@@ -282,5 +346,7 @@ where
         modop.elwise_add_mut(e_zero.as_mut(), scratch_space.as_ref());
     });
 
+    wipe(s_poly_eval.as_mut());
+
     zero_encs
 }
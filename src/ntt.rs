@@ -3,7 +3,10 @@ use rand::{Rng, RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
 use crate::{
-    backend::{ArithmeticOps, ModInit, ModularOpsU64, Modulus},
+    backend::{
+        simd::{self, SimdLevel},
+        ArithmeticOps, ModInit, ModularOpsU64, Modulus,
+    },
     utils::{mod_exponent, mod_inverse, ShoupMul},
 };
 
@@ -39,9 +42,7 @@ pub fn forward_butterly_0_to_4q(
     debug_assert!(x < q * 4, "{} >= (4q){}", x, 4 * q);
     debug_assert!(y < q * 4, "{} >= (4q){}", y, 4 * q);
 
-    if x >= q_twice {
-        x = x - q_twice;
-    }
+    x = x.min(x.wrapping_sub(q_twice));
 
     let t = ShoupMul::mul(y, w, w_shoup, q);
 
@@ -59,9 +60,7 @@ pub fn forward_butterly_0_to_2q(
     debug_assert!(x < q * 4, "{} >= (4q){}", x, 4 * q);
     debug_assert!(y < q * 4, "{} >= (4q){}", y, 4 * q);
 
-    if x >= q_twice {
-        x = x - q_twice;
-    }
+    x = x.min(x.wrapping_sub(q_twice));
 
     let t = ShoupMul::mul(y, w, w_shoup, q);
 
@@ -93,9 +92,7 @@ pub fn inverse_butterfly_0_to_2q(
     debug_assert!(y < q_twice, "{} >= (2q){q_twice}", y);
 
     let mut x_dash = x + y;
-    if x_dash >= q_twice {
-        x_dash -= q_twice
-    }
+    x_dash = x_dash.min(x_dash.wrapping_sub(q_twice));
 
     let t = x + q_twice - y;
     let y = ShoupMul::mul(t, w_inv, w_inv_shoup, q);
@@ -277,6 +274,125 @@ pub fn ntt_inv(
     }
 }
 
+/// Same as `ntt_lazy` with butterflies of each layer vectorised at SIMD
+/// `level`. Layers with fewer butterflies per twiddle than SIMD lanes use
+/// scalar butterflies. Output is in range [0, q) if `REDUCE` is true.
+fn ntt_simd<const REDUCE: bool>(
+    level: SimdLevel,
+    a: &mut [u64],
+    psi: &[u64],
+    psi_shoup: &[u64],
+    q: u64,
+    q_twice: u64,
+) {
+    assert!(a.len() == psi.len());
+
+    let n = a.len();
+    let mut t = n;
+
+    let mut m = 1;
+    while m < n {
+        t >>= 1;
+        let w = &psi[m..];
+        let w_shoup = &psi_shoup[m..];
+
+        if t == 1 {
+            for (a, w, w_shoup) in izip!(a.chunks_mut(2), w.iter(), w_shoup.iter()) {
+                let (ox, oy) = forward_butterly_0_to_2q(a[0], a[1], *w, *w_shoup, q, q_twice);
+                if REDUCE {
+                    a[0] = ox.min(ox.wrapping_sub(q));
+                    a[1] = oy.min(oy.wrapping_sub(q));
+                } else {
+                    a[0] = ox;
+                    a[1] = oy;
+                }
+            }
+        } else {
+            for i in 0..m {
+                let a = &mut a[2 * i * t..(2 * (i + 1) * t)];
+                let (left, right) = a.split_at_mut(t);
+
+                let done = simd::ntt_forward_butterfly_mut(level, left, right, w[i], w_shoup[i], q);
+                for (x, y) in izip!(left[done..].iter_mut(), right[done..].iter_mut()) {
+                    let (ox, oy) = forward_butterly_0_to_4q(*x, *y, w[i], w_shoup[i], q, q_twice);
+                    *x = ox;
+                    *y = oy;
+                }
+            }
+        }
+
+        m <<= 1;
+    }
+}
+
+/// Same as `ntt_inv_lazy` with twiddles of `ntt` and butterflies of each layer
+/// vectorised at SIMD `level`. Output is in range [0, q) if `REDUCE` is true.
+fn ntt_inv_simd<const REDUCE: bool>(level: SimdLevel, a: &mut [u64], ntt: &NttBackendU64) {
+    let psi_inv = &ntt.psi_inv_powers_bo;
+    let psi_inv_shoup = &ntt.psi_inv_powers_bo_shoup;
+    let (n_inv, n_inv_shoup) = (ntt.n_inv, ntt.n_inv_shoup);
+    let (q, q_twice) = (ntt.q, ntt.q_twice);
+    assert!(a.len() == psi_inv.len());
+
+    let mut m = a.len() >> 1;
+    let mut t = 1;
+
+    while m > 0 {
+        if m == 1 {
+            let (left, right) = a.split_at_mut(t);
+
+            let done = simd::ntt_inverse_last_butterfly_mut::<REDUCE>(
+                level,
+                left,
+                right,
+                psi_inv[1],
+                psi_inv_shoup[1],
+                n_inv,
+                n_inv_shoup,
+                q,
+            );
+            for (x, y) in izip!(left[done..].iter_mut(), right[done..].iter_mut()) {
+                let (ox, oy) =
+                    inverse_butterfly_0_to_2q(*x, *y, psi_inv[1], psi_inv_shoup[1], q, q_twice);
+                let ox = ShoupMul::mul(ox, n_inv, n_inv_shoup, q);
+                let oy = ShoupMul::mul(oy, n_inv, n_inv_shoup, q);
+                if REDUCE {
+                    *x = ox.min(ox.wrapping_sub(q));
+                    *y = oy.min(oy.wrapping_sub(q));
+                } else {
+                    *x = ox;
+                    *y = oy;
+                }
+            }
+        } else {
+            let w_inv = &psi_inv[m..];
+            let w_inv_shoup = &psi_inv_shoup[m..];
+            for i in 0..m {
+                let a = &mut a[2 * i * t..2 * (i + 1) * t];
+                let (left, right) = a.split_at_mut(t);
+
+                let done = simd::ntt_inverse_butterfly_mut(
+                    level,
+                    left,
+                    right,
+                    w_inv[i],
+                    w_inv_shoup[i],
+                    q,
+                );
+                for (x, y) in izip!(left[done..].iter_mut(), right[done..].iter_mut()) {
+                    let (ox, oy) =
+                        inverse_butterfly_0_to_2q(*x, *y, w_inv[i], w_inv_shoup[i], q, q_twice);
+                    *x = ox;
+                    *y = oy;
+                }
+            }
+        }
+
+        t *= 2;
+        m >>= 1;
+    }
+}
+
 /// Find n^{th} root of unity in field F_q, if one exists
 ///
 /// Note: n^{th} root of unity exists if and only if $q = 1 \mod{n}$
@@ -387,8 +503,10 @@ impl NttBackendU64 {
 
 impl<M: Modulus<Element = u64>> NttInit<M> for NttBackendU64 {
     fn new(q: &M, n: usize) -> Self {
-        // This NTT does not support native modulus
-        assert!(!q.is_native());
+        assert!(
+            !q.is_native(),
+            "NTT does not exist for native modulus. Use `IdentityNtt` instead and multiply polynomials with `FftBackendF64`"
+        );
         NttBackendU64::_new(q.q().unwrap(), n)
     }
 }
@@ -441,16 +559,118 @@ impl Ntt for NttBackendU64 {
     }
 }
 
+/// [NttBackendU64] with butterflies vectorised with the highest SIMD
+/// instruction set available on the running CPU, see [SimdLevel].
+///
+/// Outputs of `forward` and `backward` equal outputs of [NttBackendU64].
+/// Outputs of lazy variants are in the same range but may differ by q.
+#[derive(Debug)]
+pub struct NttBackendU64Simd {
+    ntt: NttBackendU64,
+    /// SIMD level of butterflies
+    simd: SimdLevel,
+}
+
+impl NttBackendU64Simd {
+    /// SIMD butterflies require 4q < 2^62
+    fn max_simd_level(q: u64) -> SimdLevel {
+        if q < (1 << 60) {
+            SimdLevel::detect()
+        } else {
+            SimdLevel::Scalar
+        }
+    }
+
+    /// Returns `self` with butterflies using SIMD `level`. Level is capped to
+    /// the highest level supported by the CPU and the modulus.
+    pub fn with_simd_level(mut self, level: SimdLevel) -> Self {
+        self.simd = level.min(Self::max_simd_level(self.ntt.q));
+        self
+    }
+}
+
+impl<M: Modulus<Element = u64>> NttInit<M> for NttBackendU64Simd {
+    fn new(q: &M, n: usize) -> Self {
+        let ntt = <NttBackendU64 as NttInit<M>>::new(q, n);
+        let simd = Self::max_simd_level(ntt.q);
+        NttBackendU64Simd { ntt, simd }
+    }
+}
+
+impl Ntt for NttBackendU64Simd {
+    type Element = u64;
+
+    fn forward_lazy(&self, v: &mut [Self::Element]) {
+        ntt_simd::<false>(
+            self.simd,
+            v,
+            &self.ntt.psi_powers_bo,
+            &self.ntt.psi_powers_bo_shoup,
+            self.ntt.q,
+            self.ntt.q_twice,
+        )
+    }
+
+    fn forward(&self, v: &mut [Self::Element]) {
+        ntt_simd::<true>(
+            self.simd,
+            v,
+            &self.ntt.psi_powers_bo,
+            &self.ntt.psi_powers_bo_shoup,
+            self.ntt.q,
+            self.ntt.q_twice,
+        );
+    }
+
+    fn backward_lazy(&self, v: &mut [Self::Element]) {
+        ntt_inv_simd::<false>(self.simd, v, &self.ntt)
+    }
+
+    fn backward(&self, v: &mut [Self::Element]) {
+        ntt_inv_simd::<true>(self.simd, v, &self.ntt);
+    }
+}
+
+/// Placeholder NTT for native modulus Z_{2^64}, for which negacyclic NTT
+/// does not exist. Evaluation domain equals coefficient domain, that is
+/// `forward` and `backward` are identity maps.
+///
+/// Polynomials mod native modulus must be multiplied with `FftBackendF64`.
+/// `IdentityNtt` can only be used where polynomials are sent to and from
+/// evaluation domain but not multiplied in it (for ex, when server key is
+/// unseeded before it is sent to Fourier domain).
+#[derive(Debug)]
+pub struct IdentityNtt;
+
+impl<M: Modulus<Element = u64>> NttInit<M> for IdentityNtt {
+    fn new(q: &M, _n: usize) -> Self {
+        assert!(q.is_native(), "Use NTT for non-native modulus");
+        IdentityNtt
+    }
+}
+
+impl Ntt for IdentityNtt {
+    type Element = u64;
+
+    fn forward_lazy(&self, _v: &mut [Self::Element]) {}
+
+    fn forward(&self, _v: &mut [Self::Element]) {}
+
+    fn backward_lazy(&self, _v: &mut [Self::Element]) {}
+
+    fn backward(&self, _v: &mut [Self::Element]) {}
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
     use rand::{thread_rng, Rng};
     use rand_distr::Uniform;
 
-    use super::NttBackendU64;
+    use super::{NttBackendU64, NttBackendU64Simd};
     use crate::{
-        backend::{ModInit, ModularOpsU64, VectorOps},
-        ntt::Ntt,
+        backend::{ModInit, ModularOpsU64, SimdLevel, VectorOps},
+        ntt::{Ntt, NttInit},
         utils::{generate_prime, negacyclic_mul},
     };
 
@@ -538,4 +758,41 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn simd_ntt_backend_matches_scalar() {
+        let levels = [SimdLevel::Avx2, SimdLevel::Avx512, SimdLevel::Avx512Ifma]
+            .into_iter()
+            .filter(|l| *l <= SimdLevel::detect())
+            .collect_vec();
+
+        for (bits, n) in [(50, 1 << 4), (55, 1 << 11), (59, 1 << 11)] {
+            let p = generate_prime(bits, (2 * n) as u64, 1u64 << bits).unwrap();
+            let scalar = NttBackendU64::new(&p, n);
+            for level in levels.iter() {
+                let simd = NttBackendU64Simd::new(&p, n).with_simd_level(*level);
+                for _ in 0..K {
+                    let a = random_vec_in_fq(n, p);
+
+                    let mut expected = a.clone();
+                    let mut got = a.clone();
+                    scalar.forward(&mut expected);
+                    simd.forward(&mut got);
+                    assert_eq!(expected, got, "forward with {level:?} and q={p}");
+
+                    scalar.backward(&mut expected);
+                    simd.backward(&mut got);
+                    assert_eq!(expected, got, "backward with {level:?} and q={p}");
+                    assert_eq!(a, got);
+
+                    simd.forward_lazy(&mut got);
+                    assert_output_range(&got, 2 * p - 1);
+                    simd.backward_lazy(&mut got);
+                    assert_output_range(&got, 2 * p - 1);
+                    got.iter_mut().for_each(|v| *v %= p);
+                    assert_eq!(a, got, "lazy with {level:?} and q={p}");
+                }
+            }
+        }
+    }
 }
@@ -1,15 +1,17 @@
 use std::fmt::Display;
 
+use itertools::{izip, Itertools};
 use num_traits::{FromPrimitive, One, PrimInt, ToPrimitive, Zero};
 
 use crate::{
     backend::{ArithmeticOps, Modulus, ShoupMatrixFMA, VectorOps},
     decomposer::{Decomposer, RlweDecomposer},
-    lwe::lwe_key_switch,
+    fft::{FftBackendF64, FourierSplitPoly},
+    lwe::{lwe_key_switch, lwe_key_switch_batch},
     ntt::Ntt,
     rgsw::{
-        rlwe_auto_shoup, rlwe_by_rgsw_shoup, RgswCiphertextRef, RlweCiphertextMutRef, RlweKskRef,
-        RuntimeScratchMutRef,
+        rlwe_auto_fourier, rlwe_auto_shoup, rlwe_by_rgsw_fourier, rlwe_by_rgsw_shoup,
+        FourierScratch, RgswCiphertextRef, RlweCiphertextMutRef, RlweKskRef, RuntimeScratchMutRef,
     },
     Matrix, MatrixEntity, MatrixMut, RowMut,
 };
@@ -40,17 +42,22 @@ pub(crate) trait PbsInfo {
     type NttOp: Ntt<Element = <Self::M as Matrix>::MatElement>;
     /// Type of Signed Decomposer
     type D: Decomposer<Element = <Self::M as Matrix>::MatElement>;
+    /// Type of elements mod LWE modulus q. Since q is much smaller than Q,
+    /// LWE ciphertexts are key switched with elements stored in narrower
+    /// words than elements of `M`.
+    type LweElement: PrimInt + FromPrimitive + ToPrimitive;
+    /// Type of Signed Decomposer for LWE key switching
+    type LweD: Decomposer<Element = Self::LweElement>;
 
-    // Although both `RlweModOp` and `LweModOp` types have same bounds, they can be
-    // different types. For ex, type RlweModOp may only support native modulus,
-    // where LweModOp may only support prime modulus, etc.
+    // `RlweModOp` and `LweModOp` can be different types. For ex, type RlweModOp
+    // may only support native modulus, where LweModOp may only support prime
+    // modulus, etc.
 
     /// Type of RLWE Modulus Operator
     type RlweModOp: ArithmeticOps<Element = <Self::M as Matrix>::MatElement>
         + ShoupMatrixFMA<<Self::M as Matrix>::R>;
     /// Type of LWE Modulus Operator
-    type LweModOp: VectorOps<Element = <Self::M as Matrix>::MatElement>
-        + ArithmeticOps<Element = <Self::M as Matrix>::MatElement>;
+    type LweModOp: VectorOps<Element = Self::LweElement> + ArithmeticOps<Element = Self::LweElement>;
 
     /// RLWE ciphertext modulus
     fn rlwe_q(&self) -> &Self::Modulus;
@@ -71,7 +78,7 @@ pub(crate) trait PbsInfo {
     /// generator `g` for group Z^*_{br_q}
     fn g(&self) -> isize;
     /// LWE key switching decomposer
-    fn lwe_decomposer(&self) -> &Self::D;
+    fn lwe_decomposer(&self) -> &Self::LweD;
     /// RLWE x RGSW decoposer
     fn rlwe_rgsw_decomposer(&self) -> &(Self::D, Self::D);
     /// RLWE auto decomposer
@@ -84,6 +91,9 @@ pub(crate) trait PbsInfo {
 
     /// Ntt operators
     fn nttop_rlweq(&self) -> &Self::NttOp;
+    /// FFT operator for blind rotation with key in Fourier domain
+    #[cfg_attr(not(feature = "fft"), allow(dead_code))]
+    fn fftop_rlweq(&self) -> &FftBackendF64;
 
     /// Maps a \in Z^*_{br_q} to discrete log k, with generator g (i.e. g^k =
     /// a). Returned vector is of size q that stores dlog of `a` at `vec[a]`.
@@ -101,72 +111,255 @@ pub(crate) trait PbsInfo {
 /// - key switching
 /// - mod down
 /// - blind rotate
+///
+/// LWE ciphertext is key switched mod q with elements of type
+/// `P::LweElement`. `scratch_lwe_vecs` must have space for LWE ciphertexts of
+/// dimension N and n respectively.
 pub(crate) fn pbs<
     M: MatrixMut + MatrixEntity,
-    MShoup: WithShoupRepr<M = M>,
     P: PbsInfo<M = M>,
-    K: PbsKey<RgswCt = MShoup, AutoKey = MShoup, LweKskKey = M>,
+    Ct: BlindRotationKeyCt<P>,
+    LweKsk: Matrix<MatElement = P::LweElement>,
+    K: PbsKey<RgswCt = Ct, AutoKey = Ct, LweKskKey = LweKsk>,
 >(
     pbs_info: &P,
     test_vec: &M::R,
     lwe_in: &mut M::R,
     pbs_key: &K,
-    scratch_lwe_vec: &mut M::R,
+    scratch_lwe_vecs: &mut [Vec<P::LweElement>; 2],
     scratch_blind_rotate_matrix: &mut M,
 ) where
     <M as Matrix>::R: RowMut,
     M::MatElement: PrimInt + FromPrimitive + One + Copy + Zero + Display,
 {
-    let rlwe_q = pbs_info.rlwe_q();
-    let lwe_q = pbs_info.lwe_q();
-    let br_q = pbs_info.br_q();
-    let rlwe_qf64 = rlwe_q.q_as_f64().unwrap();
-    let lwe_qf64 = lwe_q.q_as_f64().unwrap();
-    let br_qf64 = br_q.to_f64().unwrap();
-    let rlwe_n = pbs_info.rlwe_n();
+    let trivial_rlwe_test_poly = blind_rotate_lwe(
+        pbs_info,
+        test_vec,
+        lwe_in,
+        pbs_key,
+        scratch_lwe_vecs,
+        scratch_blind_rotate_matrix,
+    );
+
+    // sample extract
+    sample_extract(lwe_in, &trivial_rlwe_test_poly, pbs_info.modop_rlweq(), 0);
+}
+
+/// Key switches and mod switches `lwe_in` and returns RLWE ciphertext
+/// `test_vec * X^{-phase}` obtained via blind rotation. `lwe_in` is
+/// overwritten.
+fn blind_rotate_lwe<
+    M: MatrixMut + MatrixEntity,
+    P: PbsInfo<M = M>,
+    Ct: BlindRotationKeyCt<P>,
+    LweKsk: Matrix<MatElement = P::LweElement>,
+    K: PbsKey<RgswCt = Ct, AutoKey = Ct, LweKskKey = LweKsk>,
+>(
+    pbs_info: &P,
+    test_vec: &M::R,
+    lwe_in: &mut M::R,
+    pbs_key: &K,
+    scratch_lwe_vecs: &mut [Vec<P::LweElement>; 2],
+    scratch_blind_rotate_matrix: &mut M,
+) -> M
+where
+    <M as Matrix>::R: RowMut,
+    M::MatElement: PrimInt + FromPrimitive + One + Copy + Zero + Display,
+{
+    let [scratch_lwe_in, scratch_lwe_out] = scratch_lwe_vecs;
 
     // moddown Q -> Q_ks
-    lwe_in.as_mut().iter_mut().for_each(|v| {
-        *v =
-            M::MatElement::from_f64(((v.to_f64().unwrap() * lwe_qf64) / rlwe_qf64).round()).unwrap()
-    });
+    mod_down_to_lwe_q(pbs_info, lwe_in.as_ref(), scratch_lwe_in);
 
     // key switch RLWE secret to LWE secret
     // let now = std::time::Instant::now();
-    scratch_lwe_vec.as_mut().fill(M::MatElement::zero());
+    scratch_lwe_out.fill(P::LweElement::zero());
     lwe_key_switch(
-        scratch_lwe_vec,
-        lwe_in,
+        scratch_lwe_out,
+        scratch_lwe_in,
         pbs_key.lwe_ksk(),
         pbs_info.modop_lweq(),
         pbs_info.lwe_decomposer(),
     );
     // println!("Time: {:?}", now.elapsed());
 
+    let (trivial_rlwe_test_poly, g_k_si) =
+        init_blind_rotation(pbs_info, test_vec, scratch_lwe_out, lwe_in);
+    let mut trivial_rlwe_test_polys = [trivial_rlwe_test_poly];
+
+    // let now = std::time::Instant::now();
+    // blind rotate
+    Ct::blind_rotate(
+        &mut trivial_rlwe_test_polys,
+        scratch_blind_rotate_matrix,
+        &[g_k_si],
+        pbs_info,
+        pbs_key,
+    );
+    // println!("Blind rotation time: {:?}", now.elapsed());
+
+    let [trivial_rlwe_test_poly] = trivial_rlwe_test_polys;
+    trivial_rlwe_test_poly
+}
+
+/// Batched PBS
+///
+/// Bootstraps each LWE ciphertext in `lwe_ins` with `test_vec`. In contrast
+/// to calling `pbs` for each ciphertext, LWE key switching key is read once
+/// for all ciphertexts and blind rotation of all ciphertexts proceeds in
+/// lockstep (see `blind_rotation`). `scratch_blind_rotate_matrix` is shared by
+/// all ciphertexts.
+///
+/// `scratch_lwe_vecs` stores LWE ciphertexts mod q before and after LWE key
+/// switching, one per ciphertext in `lwe_ins`, hence each must contain at
+/// least `lwe_ins.len()` vectors.
+pub(crate) fn batch_pbs<
+    M: MatrixMut + MatrixEntity,
+    P: PbsInfo<M = M>,
+    Ct: BlindRotationKeyCt<P>,
+    LweKsk: Matrix<MatElement = P::LweElement>,
+    K: PbsKey<RgswCt = Ct, AutoKey = Ct, LweKskKey = LweKsk>,
+>(
+    pbs_info: &P,
+    test_vec: &M::R,
+    lwe_ins: &mut [M::R],
+    pbs_key: &K,
+    scratch_lwe_vecs: [&mut [Vec<P::LweElement>]; 2],
+    scratch_blind_rotate_matrix: &mut M,
+) where
+    <M as Matrix>::R: RowMut,
+    M::MatElement: PrimInt + FromPrimitive + One + Copy + Zero + Display,
+{
+    let [lwe_ins_ks, lwe_outs_ks] = scratch_lwe_vecs;
+    let lwe_ins_ks = &mut lwe_ins_ks[..lwe_ins.len()];
+    let lwe_outs_ks = &mut lwe_outs_ks[..lwe_ins.len()];
+
+    // moddown Q -> Q_ks
+    izip!(lwe_ins_ks.iter_mut(), lwe_ins.iter()).for_each(|(lwe_in_ks, lwe_in)| {
+        mod_down_to_lwe_q(pbs_info, lwe_in.as_ref(), lwe_in_ks);
+    });
+
+    // key switch RLWE secret to LWE secret
+    lwe_outs_ks
+        .iter_mut()
+        .for_each(|lwe_out_ks| lwe_out_ks.fill(P::LweElement::zero()));
+    lwe_key_switch_batch(
+        lwe_outs_ks,
+        lwe_ins_ks,
+        pbs_key.lwe_ksk(),
+        pbs_info.modop_lweq(),
+        pbs_info.lwe_decomposer(),
+    );
+
+    let (mut trivial_rlwe_test_polys, g_k_sis): (Vec<M>, Vec<_>) =
+        izip!(lwe_outs_ks.iter(), lwe_ins.iter_mut())
+            .map(|(lwe_ks, lwe_in)| init_blind_rotation(pbs_info, test_vec, lwe_ks, lwe_in))
+            .unzip();
+
+    // blind rotate
+    Ct::blind_rotate(
+        &mut trivial_rlwe_test_polys,
+        scratch_blind_rotate_matrix,
+        &g_k_sis,
+        pbs_info,
+        pbs_key,
+    );
+
+    // sample extract
+    izip!(lwe_ins.iter_mut(), trivial_rlwe_test_polys.iter()).for_each(|(lwe_in, rlwe)| {
+        sample_extract(lwe_in, rlwe, pbs_info.modop_rlweq(), 0);
+    });
+}
+
+/// Returns LWE ciphertext mod `br_q` that is blind rotated when `lwe_in` is
+/// bootstrapped, that is `lwe_in` after mod down to q, key switch and odd mod
+/// switch to `br_q`. Used to measure noise at input of blind rotation.
+#[cfg(test)]
+pub(crate) fn blind_rotation_input<
+    M: Matrix,
+    P: PbsInfo<M = M>,
+    LweKsk: Matrix<MatElement = P::LweElement>,
+    K: PbsKey<LweKskKey = LweKsk>,
+>(
+    pbs_info: &P,
+    lwe_in: &M::R,
+    pbs_key: &K,
+) -> Vec<usize>
+where
+    M::MatElement: ToPrimitive,
+{
+    let mut lwe_in_ks = vec![P::LweElement::zero(); lwe_in.as_ref().len()];
+    mod_down_to_lwe_q(pbs_info, lwe_in.as_ref(), &mut lwe_in_ks);
+
+    let mut lwe_out_ks = vec![P::LweElement::zero(); pbs_info.lwe_n() + 1];
+    lwe_key_switch(
+        &mut lwe_out_ks,
+        &lwe_in_ks,
+        pbs_key.lwe_ksk(),
+        pbs_info.modop_lweq(),
+        pbs_info.lwe_decomposer(),
+    );
+
+    let lwe_qf64 = pbs_info.lwe_q().q_as_f64().unwrap();
+    let br_qf64 = pbs_info.br_q().to_f64().unwrap();
+    lwe_out_ks
+        .iter()
+        .map(|v| mod_switch_odd(v.to_f64().unwrap(), lwe_qf64, br_qf64))
+        .collect()
+}
+
+/// Switches LWE ciphertext `lwe_in` mod Q to `lwe_out` mod Q_ks
+fn mod_down_to_lwe_q<P: PbsInfo>(
+    pbs_info: &P,
+    lwe_in: &[<P::M as Matrix>::MatElement],
+    lwe_out: &mut [P::LweElement],
+) where
+    <P::M as Matrix>::MatElement: ToPrimitive,
+{
+    let rlwe_qf64 = pbs_info.rlwe_q().q_as_f64().unwrap();
+    let lwe_qf64 = pbs_info.lwe_q().q_as_f64().unwrap();
+    izip!(lwe_out.iter_mut(), lwe_in.iter()).for_each(|(o, v)| {
+        *o =
+            P::LweElement::from_f64(((v.to_f64().unwrap() * lwe_qf64) / rlwe_qf64).round()).unwrap()
+    });
+}
+
+/// Returns trivial RLWE ciphertext of `test_vec * X^{g*b}` and LWE secret
+/// indices grouped by discrete log of `a_i`s (see `blind_rotation`), where
+/// (a, b) is key switched LWE ciphertext `lwe_ks` after odd mod switch to
+/// `br_q`.
+///
+/// `tmp` must be of size >= br_q/2 and is used as scratch space.
+fn init_blind_rotation<M: MatrixMut + MatrixEntity, P: PbsInfo<M = M>>(
+    pbs_info: &P,
+    test_vec: &M::R,
+    lwe_ks: &[P::LweElement],
+    tmp: &mut M::R,
+) -> (M, Vec<Vec<usize>>)
+where
+    <M as Matrix>::R: RowMut,
+    M::MatElement: Copy + Zero,
+{
+    let lwe_qf64 = pbs_info.lwe_q().q_as_f64().unwrap();
+    let br_q = pbs_info.br_q();
+    let br_qf64 = br_q.to_f64().unwrap();
+    let rlwe_n = pbs_info.rlwe_n();
+
     // odd moddown Q_ks -> q
     let g_k_dlog_map = pbs_info.g_k_dlog_map();
     let mut g_k_si = vec![vec![]; br_q >> 1];
-    scratch_lwe_vec
-        .as_ref()
-        .iter()
-        .skip(1)
-        .enumerate()
-        .for_each(|(index, v)| {
-            let odd_v = mod_switch_odd(v.to_f64().unwrap(), lwe_qf64, br_qf64);
-            // dlog `k` for `odd_v` is stored as `k` if odd_v = +g^{k}. If odd_v = -g^{k},
-            // then `k` is stored as `q/4 + k`.
-            let k = g_k_dlog_map[odd_v];
-            // assert!(k != 0);
-            g_k_si[k].push(index);
-        });
+    lwe_ks.iter().skip(1).enumerate().for_each(|(index, v)| {
+        let odd_v = mod_switch_odd(v.to_f64().unwrap(), lwe_qf64, br_qf64);
+        // dlog `k` for `odd_v` is stored as `k` if odd_v = +g^{k}. If odd_v = -g^{k},
+        // then `k` is stored as `q/4 + k`.
+        let k = g_k_dlog_map[odd_v];
+        // assert!(k != 0);
+        g_k_si[k].push(index);
+    });
 
     // handle b and set trivial test RLWE
     let g = pbs_info.g() as usize;
-    let g_times_b = (g * mod_switch_odd(
-        scratch_lwe_vec.as_ref()[0].to_f64().unwrap(),
-        lwe_qf64,
-        br_qf64,
-    )) % (br_q);
+    let g_times_b = (g * mod_switch_odd(lwe_ks[0].to_f64().unwrap(), lwe_qf64, br_qf64)) % (br_q);
     // v = (v(X) * X^{g*b}) mod X^{q/2}+1
     let br_qby2 = br_q >> 1;
     let mut gb_monomial_sign = true;
@@ -188,11 +381,10 @@ pub(crate) fn pbs<
             pbs_info.modop_rlweq(),
         );
     } else {
-        // use lwe_in to store the `t = v(X) * X^{g*2} mod X^{q/2}+1` temporarily. This
-        // works because q/2 <= N (where N is lwe_in LWE dimension) always.
+        // use tmp to store the `t = v(X) * X^{g*2} mod X^{q/2}+1` temporarily
         monomial_mul(
             test_vec.as_ref(),
-            &mut lwe_in.as_mut()[..br_qby2],
+            &mut tmp.as_mut()[..br_qby2],
             gb_monomial_exp,
             gb_monomial_sign,
             br_qby2,
@@ -202,7 +394,7 @@ pub(crate) fn pbs<
         // emebed poly `t` in ring X^{q/2}+1 inside the bigger ring X^{N}+1
         let embed_factor = pbs_info.embedding_factor();
         let partb_trivial_rlwe = trivial_rlwe_test_poly.get_row_mut(1);
-        lwe_in.as_ref()[..br_qby2]
+        tmp.as_ref()[..br_qby2]
             .iter()
             .enumerate()
             .for_each(|(index, v)| {
@@ -210,207 +402,259 @@ pub(crate) fn pbs<
             });
     }
 
-    // let now = std::time::Instant::now();
-    // blind rotate
-    blind_rotation(
-        &mut trivial_rlwe_test_poly,
-        scratch_blind_rotate_matrix,
-        pbs_info.g(),
-        pbs_info.w(),
-        br_q,
-        &g_k_si,
-        pbs_info.rlwe_rgsw_decomposer(),
-        pbs_info.auto_decomposer(),
-        pbs_info.nttop_rlweq(),
-        pbs_info.modop_rlweq(),
-        pbs_info,
-        pbs_key,
+    (trivial_rlwe_test_poly, g_k_si)
+}
+
+/// RGSW ciphertext or auto key of blind rotation key with polynomials in
+/// Fourier domain in split representation (see `FftBackendF64`). Rows are
+/// ordered same as in evaluation domain.
+#[cfg_attr(not(feature = "fft"), allow(dead_code))]
+pub(crate) struct FourierCt(Vec<FourierSplitPoly>);
+
+impl FourierCt {
+    /// Transforms `ct` in coefficient domain to Fourier domain
+    #[cfg_attr(not(feature = "fft"), allow(dead_code))]
+    pub(crate) fn new<M: Matrix<MatElement = u64>>(ct: &M, fft_op: &FftBackendF64) -> Self {
+        FourierCt(fft_op.forward_split_rows(ct.as_ref()))
+    }
+}
+
+/// Representation of RGSW ciphertexts and auto keys of blind rotation key.
+/// Representation selects the backend for polynomial products in blind
+/// rotation: NTT for polynomials in evaluation domain with shoup
+/// representation and f64 FFT for polynomials in Fourier domain
+/// (`FourierCt`). Outputs of both backends are equal.
+pub(crate) trait BlindRotationKeyCt<P: PbsInfo>: Sized {
+    /// Blind rotates accumulators `trivial_rlwe_test_polys` with RGSW
+    /// ciphertexts and auto keys of `pbs_key`. See `blind_rotation`.
+    fn blind_rotate<K: PbsKey<RgswCt = Self, AutoKey = Self>>(
+        trivial_rlwe_test_polys: &mut [P::M],
+        scratch_matrix: &mut P::M,
+        gk_to_si: &[Vec<Vec<usize>>],
+        pbs_info: &P,
+        pbs_key: &K,
     );
-    // println!("Blind rotation time: {:?}", now.elapsed());
+}
 
-    // sample extract
-    sample_extract(lwe_in, &trivial_rlwe_test_poly, pbs_info.modop_rlweq(), 0);
+impl<P: PbsInfo, MShoup: WithShoupRepr<M = P::M>> BlindRotationKeyCt<P> for MShoup
+where
+    P::M: MatrixMut,
+    <P::M as Matrix>::R: RowMut,
+    <P::M as Matrix>::MatElement: Copy + Zero,
+{
+    fn blind_rotate<K: PbsKey<RgswCt = Self, AutoKey = Self>>(
+        trivial_rlwe_test_polys: &mut [P::M],
+        scratch_matrix: &mut P::M,
+        gk_to_si: &[Vec<Vec<usize>>],
+        pbs_info: &P,
+        pbs_key: &K,
+    ) {
+        let rlwe_rgsw_decomposer = pbs_info.rlwe_rgsw_decomposer();
+        let auto_decomposer = pbs_info.auto_decomposer();
+        let ntt_op = pbs_info.nttop_rlweq();
+        let mod_op = pbs_info.modop_rlweq();
+        let d_a = rlwe_rgsw_decomposer.a().decomposition_count().0;
+        let d_b = rlwe_rgsw_decomposer.b().decomposition_count().0;
+        let d_auto = auto_decomposer.decomposition_count().0;
+
+        let mut scratch_matrix = RuntimeScratchMutRef::new(scratch_matrix.as_mut());
+        let mut rlwes = trivial_rlwe_test_polys
+            .iter_mut()
+            .map(|p| RlweCiphertextMutRef::new(p.as_mut()))
+            .collect_vec();
+
+        blind_rotation(
+            &mut rlwes,
+            &mut scratch_matrix,
+            pbs_info.w(),
+            pbs_info.br_q(),
+            gk_to_si,
+            |rlwe, scratch_matrix, s_index, is_trivial| {
+                let ct = pbs_key.rgsw_ct_lwe_si(s_index);
+                rlwe_by_rgsw_shoup(
+                    rlwe,
+                    &RgswCiphertextRef::new(ct.as_ref().as_ref(), d_a, d_b),
+                    &RgswCiphertextRef::new(ct.shoup_repr().as_ref(), d_a, d_b),
+                    scratch_matrix,
+                    rlwe_rgsw_decomposer,
+                    ntt_op,
+                    mod_op,
+                    is_trivial,
+                );
+            },
+            |rlwe, scratch_matrix, v, is_trivial| {
+                let (auto_map_index, auto_map_sign) = pbs_info.rlwe_auto_map(v);
+                let auto_key = pbs_key.galois_key_for_auto(v);
+                rlwe_auto_shoup(
+                    rlwe,
+                    &RlweKskRef::new(auto_key.as_ref().as_ref(), d_auto),
+                    &RlweKskRef::new(auto_key.shoup_repr().as_ref(), d_auto),
+                    scratch_matrix,
+                    auto_map_index,
+                    auto_map_sign,
+                    mod_op,
+                    ntt_op,
+                    auto_decomposer,
+                    is_trivial,
+                );
+            },
+        );
+    }
+}
+
+impl<P: PbsInfo> BlindRotationKeyCt<P> for FourierCt
+where
+    P::M: MatrixMut<MatElement = u64>,
+    <P::M as Matrix>::R: RowMut,
+{
+    fn blind_rotate<K: PbsKey<RgswCt = Self, AutoKey = Self>>(
+        trivial_rlwe_test_polys: &mut [P::M],
+        _scratch_matrix: &mut P::M,
+        gk_to_si: &[Vec<Vec<usize>>],
+        pbs_info: &P,
+        pbs_key: &K,
+    ) {
+        let rlwe_rgsw_decomposer = pbs_info.rlwe_rgsw_decomposer();
+        let auto_decomposer = pbs_info.auto_decomposer();
+        let fft_op = pbs_info.fftop_rlweq();
+        let mod_op = pbs_info.modop_rlweq();
+        let d_max = (rlwe_rgsw_decomposer.a().decomposition_count().0)
+            .max(rlwe_rgsw_decomposer.b().decomposition_count().0)
+            .max(auto_decomposer.decomposition_count().0);
+
+        let mut scratch = FourierScratch::new(fft_op, d_max);
+        let mut rlwes = trivial_rlwe_test_polys
+            .iter_mut()
+            .map(|p| RlweCiphertextMutRef::new(p.as_mut()))
+            .collect_vec();
+
+        blind_rotation(
+            &mut rlwes,
+            &mut scratch,
+            pbs_info.w(),
+            pbs_info.br_q(),
+            gk_to_si,
+            |rlwe, scratch, s_index, is_trivial| {
+                rlwe_by_rgsw_fourier(
+                    rlwe,
+                    &pbs_key.rgsw_ct_lwe_si(s_index).0,
+                    scratch,
+                    rlwe_rgsw_decomposer,
+                    fft_op,
+                    is_trivial,
+                );
+            },
+            |rlwe, scratch, v, is_trivial| {
+                let (auto_map_index, auto_map_sign) = pbs_info.rlwe_auto_map(v);
+                rlwe_auto_fourier(
+                    rlwe,
+                    &pbs_key.galois_key_for_auto(v).0,
+                    scratch,
+                    auto_map_index,
+                    auto_map_sign,
+                    mod_op,
+                    fft_op,
+                    auto_decomposer,
+                    is_trivial,
+                );
+            },
+        );
+    }
 }
 
 /// LMKCY+ Blind rotation
 ///
-/// - gk_to_si: Contains LWE secret index `i` in array of secret indices at k^th
-///   index if a_i = g^k if k < q/4 or a_i = -g^k if k > q/4. [g^0, ...,
-///   g^{q/2-1}, -g^0, -g^1, .., -g^{q/2-1}]
-fn blind_rotation<
-    Mmut: MatrixMut,
-    RlweD: RlweDecomposer<Element = Mmut::MatElement>,
-    AutoD: Decomposer<Element = Mmut::MatElement>,
-    NttOp: Ntt<Element = Mmut::MatElement>,
-    ModOp: ArithmeticOps<Element = Mmut::MatElement> + ShoupMatrixFMA<Mmut::R>,
-    MShoup: WithShoupRepr<M = Mmut>,
-    K: PbsKey<RgswCt = MShoup, AutoKey = MShoup>,
-    P: PbsInfo<M = Mmut>,
->(
-    trivial_rlwe_test_poly: &mut Mmut,
-    scratch_matrix: &mut Mmut,
-    _g: isize,
+/// Blind rotates accumulators `rlwes` in lockstep. That is, each step of
+/// blind rotation (RLWE x RGSW products for a discrete log `k` followed by an
+/// automorphism, if any) is applied to all accumulators before moving to the
+/// next step. Hence keys accessed in a step are accessed together for all
+/// accumulators.
+///
+/// Polynomial products are delegated to `rlwe_by_rgsw_si`, which computes
+/// RLWE x RGSW(X^{s_i}) for LWE secret index `i`, and `rlwe_auto`, which
+/// computes automorphism with g^v (-g for v = 0). Both take the scratch space
+/// `scratch` and whether the accumulator is a trivial RLWE ciphertext.
+///
+/// - gk_to_si: For each accumulator, contains LWE secret index `i` in array
+///   of secret indices at k^th index if a_i = g^k if k < q/4 or a_i = -g^k if
+///   k > q/4. [g^0, ..., g^{q/2-1}, -g^0, -g^1, .., -g^{q/2-1}]
+fn blind_rotation<Rlwe, S>(
+    rlwes: &mut [Rlwe],
+    scratch: &mut S,
     w: usize,
     q: usize,
-    gk_to_si: &[Vec<usize>],
-    rlwe_rgsw_decomposer: &RlweD,
-    auto_decomposer: &AutoD,
-    ntt_op: &NttOp,
-    mod_op: &ModOp,
-    parameters: &P,
-    pbs_key: &K,
-) where
-    <Mmut as Matrix>::R: RowMut,
-    Mmut::MatElement: Copy + Zero,
-{
-    let mut is_trivial = true;
-    let mut scratch_matrix = RuntimeScratchMutRef::new(scratch_matrix.as_mut());
-    let mut rlwe = RlweCiphertextMutRef::new(trivial_rlwe_test_poly.as_mut());
-    let d_a = rlwe_rgsw_decomposer.a().decomposition_count().0;
-    let d_b = rlwe_rgsw_decomposer.b().decomposition_count().0;
-    let d_auto = auto_decomposer.decomposition_count().0;
+    gk_to_si: &[Vec<Vec<usize>>],
+    rlwe_by_rgsw_si: impl Fn(&mut Rlwe, &mut S, usize, bool),
+    rlwe_auto: impl Fn(&mut Rlwe, &mut S, usize, bool),
+) {
+    assert!(rlwes.len() == gk_to_si.len());
+
+    let mut is_trivial = vec![true; rlwes.len()];
+
+    // RLWE x RGSW(X^{s_i}) for all `s_indices`
+    let rlwe_by_rgsw_sis =
+        |rlwe: &mut Rlwe, scratch: &mut S, s_indices: &[usize], is_trivial: &mut bool| {
+            s_indices.iter().for_each(|s_index| {
+                rlwe_by_rgsw_si(rlwe, scratch, *s_index, *is_trivial);
+                *is_trivial = false;
+            });
+        };
 
     let q_by_4 = q >> 2;
-    // let mut count = 0;
     // -(g^k)
-    let mut v = 0;
+    let mut v = vec![0; rlwes.len()];
     for i in (1..q_by_4).rev() {
-        // dbg!(q_by_4 + i);
-        let s_indices = &gk_to_si[q_by_4 + i];
-
-        s_indices.iter().for_each(|s_index| {
-            // let new = std::time::Instant::now();
-            let ct = pbs_key.rgsw_ct_lwe_si(*s_index);
-            rlwe_by_rgsw_shoup(
-                &mut rlwe,
-                &RgswCiphertextRef::new(ct.as_ref().as_ref(), d_a, d_b),
-                &RgswCiphertextRef::new(ct.shoup_repr().as_ref(), d_a, d_b),
-                &mut scratch_matrix,
-                rlwe_rgsw_decomposer,
-                ntt_op,
-                mod_op,
-                is_trivial,
-            );
-            is_trivial = false;
-            // println!("Rlwe x Rgsw time: {:?}", new.elapsed());
+        izip!(
+            rlwes.iter_mut(),
+            gk_to_si.iter(),
+            is_trivial.iter_mut(),
+            v.iter_mut()
+        )
+        .for_each(|(rlwe, gk_to_si, is_trivial, v)| {
+            rlwe_by_rgsw_sis(rlwe, scratch, &gk_to_si[q_by_4 + i], is_trivial);
+            *v += 1;
+
+            if gk_to_si[q_by_4 + i - 1].len() != 0 || *v == w || i == 1 {
+                rlwe_auto(rlwe, scratch, *v, *is_trivial);
+                *v = 0;
+            }
         });
-        v += 1;
-
-        if gk_to_si[q_by_4 + i - 1].len() != 0 || v == w || i == 1 {
-            let (auto_map_index, auto_map_sign) = parameters.rlwe_auto_map(v);
-
-            // let now = std::time::Instant::now();
-            let auto_key = pbs_key.galois_key_for_auto(v);
-            rlwe_auto_shoup(
-                &mut rlwe,
-                &RlweKskRef::new(auto_key.as_ref().as_ref(), d_auto),
-                &RlweKskRef::new(auto_key.shoup_repr().as_ref(), d_auto),
-                &mut scratch_matrix,
-                &auto_map_index,
-                &auto_map_sign,
-                mod_op,
-                ntt_op,
-                auto_decomposer,
-                is_trivial,
-            );
-            // println!("Auto time: {:?}", now.elapsed());
-            // count += 1;
-
-            v = 0;
-        }
     }
 
     // -(g^0)
-    {
-        gk_to_si[q_by_4].iter().for_each(|s_index| {
-            let ct = pbs_key.rgsw_ct_lwe_si(*s_index);
-            rlwe_by_rgsw_shoup(
-                &mut rlwe,
-                &RgswCiphertextRef::new(ct.as_ref().as_ref(), d_a, d_b),
-                &RgswCiphertextRef::new(ct.shoup_repr().as_ref(), d_a, d_b),
-                &mut scratch_matrix,
-                rlwe_rgsw_decomposer,
-                ntt_op,
-                mod_op,
-                is_trivial,
-            );
-            is_trivial = false;
-        });
-
-        let (auto_map_index, auto_map_sign) = parameters.rlwe_auto_map(0);
-        let auto_key = pbs_key.galois_key_for_auto(0);
-        rlwe_auto_shoup(
-            &mut rlwe,
-            &RlweKskRef::new(auto_key.as_ref().as_ref(), d_auto),
-            &RlweKskRef::new(auto_key.shoup_repr().as_ref(), d_auto),
-            &mut scratch_matrix,
-            &auto_map_index,
-            &auto_map_sign,
-            mod_op,
-            ntt_op,
-            auto_decomposer,
-            is_trivial,
-        );
-        // count += 1;
-    }
+    izip!(rlwes.iter_mut(), gk_to_si.iter(), is_trivial.iter_mut()).for_each(
+        |(rlwe, gk_to_si, is_trivial)| {
+            rlwe_by_rgsw_sis(rlwe, scratch, &gk_to_si[q_by_4], is_trivial);
+            rlwe_auto(rlwe, scratch, 0, *is_trivial);
+        },
+    );
 
     // +(g^k)
-    let mut v = 0;
+    let mut v = vec![0; rlwes.len()];
     for i in (1..q_by_4).rev() {
-        let s_indices = &gk_to_si[i];
-        s_indices.iter().for_each(|s_index| {
-            let ct = pbs_key.rgsw_ct_lwe_si(*s_index);
-            rlwe_by_rgsw_shoup(
-                &mut rlwe,
-                &RgswCiphertextRef::new(ct.as_ref().as_ref(), d_a, d_b),
-                &RgswCiphertextRef::new(ct.shoup_repr().as_ref(), d_a, d_b),
-                &mut scratch_matrix,
-                rlwe_rgsw_decomposer,
-                ntt_op,
-                mod_op,
-                is_trivial,
-            );
-            is_trivial = false;
+        izip!(
+            rlwes.iter_mut(),
+            gk_to_si.iter(),
+            is_trivial.iter_mut(),
+            v.iter_mut()
+        )
+        .for_each(|(rlwe, gk_to_si, is_trivial, v)| {
+            rlwe_by_rgsw_sis(rlwe, scratch, &gk_to_si[i], is_trivial);
+            *v += 1;
+
+            if gk_to_si[i - 1].len() != 0 || *v == w || i == 1 {
+                rlwe_auto(rlwe, scratch, *v, *is_trivial);
+                *v = 0;
+            }
         });
-        v += 1;
-
-        if gk_to_si[i - 1].len() != 0 || v == w || i == 1 {
-            let (auto_map_index, auto_map_sign) = parameters.rlwe_auto_map(v);
-            let auto_key = pbs_key.galois_key_for_auto(v);
-            rlwe_auto_shoup(
-                &mut rlwe,
-                &RlweKskRef::new(auto_key.as_ref().as_ref(), d_auto),
-                &RlweKskRef::new(auto_key.shoup_repr().as_ref(), d_auto),
-                &mut scratch_matrix,
-                &auto_map_index,
-                &auto_map_sign,
-                mod_op,
-                ntt_op,
-                auto_decomposer,
-                is_trivial,
-            );
-            v = 0;
-
-            // count += 1;
-        }
     }
 
     // +(g^0)
-    gk_to_si[0].iter().for_each(|s_index| {
-        let ct = pbs_key.rgsw_ct_lwe_si(*s_index);
-        rlwe_by_rgsw_shoup(
-            &mut rlwe,
-            &RgswCiphertextRef::new(ct.as_ref().as_ref(), d_a, d_b),
-            &RgswCiphertextRef::new(ct.shoup_repr().as_ref(), d_a, d_b),
-            &mut scratch_matrix,
-            rlwe_rgsw_decomposer,
-            ntt_op,
-            mod_op,
-            is_trivial,
-        );
-        is_trivial = false;
-    });
-    // println!("Auto count: {count}");
+    izip!(rlwes.iter_mut(), gk_to_si.iter(), is_trivial.iter_mut()).for_each(
+        |(rlwe, gk_to_si, is_trivial)| {
+            rlwe_by_rgsw_sis(rlwe, scratch, &gk_to_si[0], is_trivial);
+        },
+    );
 }
 
 fn mod_switch_odd(v: f64, from_q: f64, to_q: f64) -> usize {
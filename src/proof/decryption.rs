@@ -0,0 +1,367 @@
+//! Proofs of correct decryption shares
+//!
+//! Decryption share `d = -<a, s> + e` of LWE ciphertext `(b, a)` is tied to
+//! the public key share `p = a_p * s + e_p` of the party, which all parties
+//! already agree on. Inner product `<a, s>` is not R-linear, hence, unlike
+//! [short_relation](super::short_relation), challenges are bits and the proof
+//! is repeated in parallel. Ring challenges would also reveal `N - 1`
+//! additional noisy inner products of `s`.
+
+use itertools::{izip, Itertools};
+
+use super::{expand_seed, norm_within, sample_mask, to_mod_q, Transcript, ERROR_BOUND};
+use crate::{
+    backend::{ArithmeticOps, GetModulus, Modulus, VectorOps},
+    ntt::Ntt,
+    random::RandomFillUniformInModulus,
+    utils::wipe,
+};
+
+/// No. of parallel repetitions of binary challenge proofs
+const BINARY_CHALLENGE_ROUNDS: usize = 128;
+
+/// Returns bits of binary challenges derived from `seed`
+fn binary_challenges(seed: &[u8; 32]) -> Vec<bool> {
+    expand_seed(seed, BINARY_CHALLENGE_ROUNDS / 8)
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect_vec()
+}
+
+/// Non-interactive proof that decryption share `d = -<a, s> + e` of LWE
+/// ciphertext `(b, a)` is computed with secret `s` of public key share `p = a_p
+/// * s + e_p` with short `e`, `s` and `e_p`.
+///
+/// Inner product `<a, s>` is not R-linear, hence the proof uses
+/// `BINARY_CHALLENGE_ROUNDS` parallel repetitions with challenges in {0, 1}.
+/// Proof has `BINARY_CHALLENGE_ROUNDS * (2N + 1)` i32 responses, i.e. ~2 MB
+/// for ring size N = 2^11.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecryptionShareProof {
+    /// Seed of the challenges
+    challenge_seed: [u8; 32],
+    /// Responses for secret `s` of each round
+    secret_responses: Vec<Vec<i32>>,
+    /// Responses for public key share error `e_p` of each round
+    public_key_error_responses: Vec<Vec<i32>>,
+    /// Responses for decryption share error `e` of each round
+    error_responses: Vec<i32>,
+}
+
+/// Public statement of [DecryptionShareProof]
+pub(crate) struct DecryptionShareStatement<'a> {
+    /// LWE ciphertext (b, a)
+    pub(crate) lwe_ct: &'a [u64],
+    /// Decryption share
+    pub(crate) share: u64,
+    /// Public polynomial `a_p` of public key share in evaluation domain
+    pub(crate) public_key_a_eval: &'a [u64],
+    /// Public key share
+    pub(crate) public_key_share: &'a [u64],
+    /// Bound on secret `s`
+    pub(crate) secret_bound: u64,
+}
+
+impl<'a> DecryptionShareStatement<'a> {
+    fn ring_size(&self) -> usize {
+        self.public_key_share.len()
+    }
+
+    /// Returns bounds on masks of s, e_p and e
+    fn mask_bounds(&self) -> (u64, u64, u64) {
+        let total_coefficients = (BINARY_CHALLENGE_ROUNDS * (2 * self.ring_size() + 1)) as u64;
+        (
+            2 * total_coefficients * self.secret_bound,
+            2 * total_coefficients * ERROR_BOUND,
+            2 * total_coefficients * ERROR_BOUND,
+        )
+    }
+
+    /// Returns (a_p * s, -<a, s>) for each `s` in `secrets`
+    fn evaluate<
+        NttOp: Ntt<Element = u64>,
+        ModOp: ArithmeticOps<Element = u64> + VectorOps<Element = u64> + GetModulus<Element = u64>,
+    >(
+        &self,
+        secrets: &[Vec<i64>],
+        nttop: &NttOp,
+        modop: &ModOp,
+    ) -> Vec<(Vec<u64>, u64)> {
+        let q = modop.modulus();
+        secrets
+            .iter()
+            .map(|s| {
+                let mut s_q = to_mod_q(s, q);
+                let mut inner_product = 0u64;
+                izip!(self.lwe_ct.iter().skip(1), s_q.iter()).for_each(|(a, s)| {
+                    inner_product = modop.add(&inner_product, &modop.mul(a, s));
+                });
+
+                nttop.forward(&mut s_q);
+                modop.elwise_mul_mut(&mut s_q, self.public_key_a_eval);
+                nttop.backward(&mut s_q);
+                (s_q, modop.neg(&inner_product))
+            })
+            .collect_vec()
+    }
+
+    fn challenge_seed<Mo: Modulus<Element = u64>>(
+        &self,
+        label: &[u8],
+        q: &Mo,
+        commitments: &[(Vec<u64>, u64)],
+    ) -> [u8; 32] {
+        let mut transcript = Transcript::new(label);
+        transcript.append_u64s(&[q.q().unwrap_or(0), self.secret_bound, self.share]);
+        transcript.append_u64s(self.lwe_ct);
+        transcript.append_u64s(self.public_key_share);
+        commitments.iter().for_each(|(w_p, w)| {
+            transcript.append_u64s(w_p);
+            transcript.append_u64s(&[*w]);
+        });
+        transcript.finalize()
+    }
+}
+
+/// Proves that decryption share in `statement` is computed with `secret`.
+/// Errors are derived from `secret` and the statement.
+///
+/// Panics if secret or errors exceed their bounds.
+pub(crate) fn prove_decryption_share<
+    NttOp: Ntt<Element = u64>,
+    ModOp: ArithmeticOps<Element = u64> + VectorOps<Element = u64> + GetModulus<Element = u64>,
+    Rng: RandomFillUniformInModulus<[u64], u64>,
+>(
+    label: &[u8],
+    statement: &DecryptionShareStatement,
+    secret: &[i64],
+    nttop: &NttOp,
+    modop: &ModOp,
+    rng: &mut Rng,
+) -> DecryptionShareProof {
+    let ring_size = statement.ring_size();
+    let q = modop.modulus();
+    assert!(secret.len() == ring_size && statement.lwe_ct.len() == ring_size + 1);
+    assert!(norm_within(secret, statement.secret_bound));
+
+    // e_p = p - a_p * s, e = d + <a, s>
+    let (mut e_p, e) = {
+        let (mut a_p_s, neg_a_s) = statement
+            .evaluate(&[secret.to_vec()], nttop, modop)
+            .remove(0);
+        modop.elwise_sub_mut(&mut a_p_s, statement.public_key_share);
+        let e_p = a_p_s.iter().map(|v| -q.map_element_to_i64(v)).collect_vec();
+        let e = modop.sub(&statement.share, &neg_a_s);
+        wipe(&mut a_p_s);
+        (e_p, q.map_element_to_i64(&e))
+    };
+    assert!(norm_within(&e_p, ERROR_BOUND) && e.unsigned_abs() <= ERROR_BOUND);
+
+    let (s_bound, e_p_bound, e_bound) = statement.mask_bounds();
+    assert!(s_bound.max(e_p_bound).max(e_bound) <= i32::MAX as u64);
+
+    let proof = loop {
+        let mut y_s = (0..BINARY_CHALLENGE_ROUNDS)
+            .map(|_| {
+                let mut y = vec![0i64; ring_size];
+                sample_mask(&mut y, s_bound, rng);
+                y
+            })
+            .collect_vec();
+        let mut y_e_p = (0..BINARY_CHALLENGE_ROUNDS)
+            .map(|_| {
+                let mut y = vec![0i64; ring_size];
+                sample_mask(&mut y, e_p_bound, rng);
+                y
+            })
+            .collect_vec();
+        let mut y_e = vec![0i64; BINARY_CHALLENGE_ROUNDS];
+        sample_mask(&mut y_e, e_bound, rng);
+
+        // commitments (a_p y_s + y_e_p, -<a, y_s> + y_e)
+        let mut commitments = statement.evaluate(&y_s, nttop, modop);
+        izip!(commitments.iter_mut(), y_e_p.iter(), y_e.iter()).for_each(
+            |((w_p, w), y_e_p, y_e)| {
+                modop.elwise_add_mut(w_p, &to_mod_q(y_e_p, q));
+                *w = modop.add(w, &q.map_element_from_i64(*y_e));
+            },
+        );
+
+        let challenge_seed = statement.challenge_seed(label, q, &commitments);
+        let challenges = binary_challenges(&challenge_seed);
+
+        // responses z = y + c * witness
+        izip!(
+            challenges.iter(),
+            y_s.iter_mut(),
+            y_e_p.iter_mut(),
+            y_e.iter_mut()
+        )
+        .filter(|(c, ..)| **c)
+        .for_each(|(_, y_s, y_e_p, y_e)| {
+            izip!(y_s.iter_mut(), secret.iter()).for_each(|(y, s)| *y += s);
+            izip!(y_e_p.iter_mut(), e_p.iter()).for_each(|(y, e)| *y += e);
+            *y_e += e;
+        });
+
+        let accept = y_s
+            .iter()
+            .all(|z| norm_within(z, s_bound - statement.secret_bound))
+            && y_e_p
+                .iter()
+                .all(|z| norm_within(z, e_p_bound - ERROR_BOUND))
+            && norm_within(&y_e, e_bound - ERROR_BOUND);
+
+        let to_i32 = |z: &Vec<i64>| z.iter().map(|v| *v as i32).collect_vec();
+        let proof = if accept {
+            Some(DecryptionShareProof {
+                challenge_seed,
+                secret_responses: y_s.iter().map(to_i32).collect_vec(),
+                public_key_error_responses: y_e_p.iter().map(to_i32).collect_vec(),
+                error_responses: to_i32(&y_e),
+            })
+        } else {
+            None
+        };
+        y_s.iter_mut().chain(y_e_p.iter_mut()).for_each(|y| wipe(y));
+        wipe(&mut y_e);
+
+        if let Some(proof) = proof {
+            break proof;
+        }
+    };
+
+    wipe(&mut e_p);
+    proof
+}
+
+/// Verifies `proof` of correct decryption share in `statement`
+pub(crate) fn verify_decryption_share<
+    NttOp: Ntt<Element = u64>,
+    ModOp: ArithmeticOps<Element = u64> + VectorOps<Element = u64> + GetModulus<Element = u64>,
+>(
+    label: &[u8],
+    statement: &DecryptionShareStatement,
+    proof: &DecryptionShareProof,
+    nttop: &NttOp,
+    modop: &ModOp,
+) -> Result<(), String> {
+    let ring_size = statement.ring_size();
+    let q = modop.modulus();
+    if statement.lwe_ct.len() != ring_size + 1
+        || proof.secret_responses.len() != BINARY_CHALLENGE_ROUNDS
+        || proof.public_key_error_responses.len() != BINARY_CHALLENGE_ROUNDS
+        || proof.error_responses.len() != BINARY_CHALLENGE_ROUNDS
+        || proof
+            .secret_responses
+            .iter()
+            .chain(proof.public_key_error_responses.iter())
+            .any(|z| z.len() != ring_size)
+    {
+        return Err("Proof has incorrect dimensions".to_string());
+    }
+
+    let (s_bound, e_p_bound, e_bound) = statement.mask_bounds();
+    let to_i64 = |z: &Vec<i32>| z.iter().map(|v| *v as i64).collect_vec();
+    let z_s = proof.secret_responses.iter().map(to_i64).collect_vec();
+    let z_e_p = proof
+        .public_key_error_responses
+        .iter()
+        .map(to_i64)
+        .collect_vec();
+    let z_e = to_i64(&proof.error_responses);
+    if !(z_s
+        .iter()
+        .all(|z| norm_within(z, s_bound - statement.secret_bound))
+        && z_e_p
+            .iter()
+            .all(|z| norm_within(z, e_p_bound - ERROR_BOUND))
+        && norm_within(&z_e, e_bound - ERROR_BOUND))
+    {
+        return Err("Proof responses exceed bound".to_string());
+    }
+
+    // w' = (a_p z_s + z_e_p - c p, -<a, z_s> + z_e - c d)
+    let challenges = binary_challenges(&proof.challenge_seed);
+    let mut commitments = statement.evaluate(&z_s, nttop, modop);
+    izip!(
+        commitments.iter_mut(),
+        z_e_p.iter(),
+        z_e.iter(),
+        challenges.iter()
+    )
+    .for_each(|((w_p, w), z_e_p, z_e, c)| {
+        modop.elwise_add_mut(w_p, &to_mod_q(z_e_p, q));
+        *w = modop.add(w, &q.map_element_from_i64(*z_e));
+        if *c {
+            modop.elwise_sub_mut(w_p, statement.public_key_share);
+            *w = modop.sub(w, &statement.share);
+        }
+    });
+
+    if statement.challenge_seed(label, q, &commitments) != proof.challenge_seed {
+        return Err("Proof does not verify".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::ModularOpsU64,
+        ntt::NttBackendU64,
+        proof::tests::{short_poly, uniform_poly, N, Q},
+        random::DefaultSecureRng,
+        utils::WithLocal,
+        ModInit, NttInit,
+    };
+
+    #[test]
+    fn decryption_share_proof_works() {
+        let modop = ModularOpsU64::new(Q);
+        let nttop = NttBackendU64::new(&Q, N);
+        let q = modop.modulus();
+
+        let s = short_poly(1);
+        let mut a_p_eval = uniform_poly();
+        nttop.forward(&mut a_p_eval);
+        let public_key_share = {
+            let mut p = to_mod_q(&s, q);
+            nttop.forward(&mut p);
+            modop.elwise_mul_mut(&mut p, &a_p_eval);
+            nttop.backward(&mut p);
+            modop.elwise_add_mut(&mut p, &to_mod_q(&short_poly(ERROR_BOUND as i64), q));
+            p
+        };
+        let mut lwe_ct = vec![0u64];
+        lwe_ct.extend(uniform_poly());
+        let share = {
+            let mut d = q.map_element_from_i64(3);
+            izip!(lwe_ct.iter().skip(1), s.iter()).for_each(|(a, s)| {
+                d = modop.sub(&d, &modop.mul(a, &q.map_element_from_i64(*s)));
+            });
+            d
+        };
+
+        let statement = |share: u64| DecryptionShareStatement {
+            lwe_ct: &lwe_ct,
+            share,
+            public_key_a_eval: &a_p_eval,
+            public_key_share: &public_key_share,
+            secret_bound: 1,
+        };
+        let proof = DefaultSecureRng::with_local_mut(|rng| {
+            prove_decryption_share(b"test", &statement(share), &s, &nttop, &modop, rng)
+        });
+        assert!(
+            verify_decryption_share(b"test", &statement(share), &proof, &nttop, &modop).is_ok()
+        );
+
+        // shifted share does not verify
+        let cheat = (share + Q / 2) % Q;
+        assert!(
+            verify_decryption_share(b"test", &statement(cheat), &proof, &nttop, &modop).is_err()
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Zero-knowledge proofs for multi-party protocols
+//!
+//! - [short_relation]: proofs of short secrets and errors of R-linear relations,
+//!   used for server key shares of the non-interactive protocol.
+//! - [decryption]: proofs of correct decryption shares with respect to public
+//!   key shares.
+//!
+//! Challenges are derived with Fiat-Shamir from a length prefixed SHA-256
+//! transcript. Masks are sampled such that responses do not leak the witness,
+//! otherwise the prover restarts (Lyubashevsky's Fiat-Shamir with aborts).
+
+use itertools::{izip, Itertools};
+use sha2::{Digest, Sha256};
+
+use crate::{backend::Modulus, random::RandomFillUniformInModulus, utils::wipe};
+
+mod decryption;
+#[cfg(feature = "non_interactive_mp")]
+mod short_relation;
+
+pub use decryption::DecryptionShareProof;
+pub(crate) use decryption::{
+    prove_decryption_share, verify_decryption_share, DecryptionShareStatement,
+};
+#[cfg(feature = "non_interactive_mp")]
+pub use short_relation::ShortWitnessProof;
+#[cfg(feature = "non_interactive_mp")]
+pub(crate) use short_relation::{
+    prove_short_relation, verify_short_relation, Coefficient, ShortRelation,
+};
+
+/// Bound on the error sampled from the error distribution
+pub(crate) const ERROR_BOUND: u64 = 40;
+
+/// Transcript of a proof. Each field is length prefixed.
+pub(crate) struct Transcript(Sha256);
+
+impl Transcript {
+    pub(crate) fn new(label: &[u8]) -> Transcript {
+        let mut t = Transcript(Sha256::new());
+        t.append_bytes(b"phantom-zone/proof");
+        t.append_bytes(label);
+        t
+    }
+
+    pub(crate) fn append_bytes(&mut self, bytes: &[u8]) {
+        self.0.update((bytes.len() as u64).to_le_bytes());
+        self.0.update(bytes);
+    }
+
+    pub(crate) fn append_u64s(&mut self, values: &[u64]) {
+        self.append_bytes(&values.iter().flat_map(|v| v.to_le_bytes()).collect_vec());
+    }
+
+    pub(crate) fn finalize(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+/// Expands `seed` to `len` pseudo random bytes with SHA-256 in counter mode.
+///
+/// Challenges must be reproducible by anyone, hence they do not depend on the
+/// PRG selected with features.
+fn expand_seed(seed: &[u8; 32], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + 32);
+    let mut counter = 0u64;
+    while out.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update(counter.to_le_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// Samples `out` uniformly from [-bound, bound]
+fn sample_mask<Rng: RandomFillUniformInModulus<[u64], u64>>(
+    out: &mut [i64],
+    bound: u64,
+    rng: &mut Rng,
+) {
+    let mut v = vec![0u64; out.len()];
+    RandomFillUniformInModulus::random_fill(rng, &(2 * bound + 1), v.as_mut_slice());
+    izip!(out.iter_mut(), v.iter()).for_each(|(o, v)| *o = *v as i64 - bound as i64);
+    wipe(&mut v);
+}
+
+fn to_mod_q<Mo: Modulus<Element = u64>>(v: &[i64], q: &Mo) -> Vec<u64> {
+    v.iter().map(|v| q.map_element_from_i64(*v)).collect_vec()
+}
+
+fn norm_within(v: &[i64], bound: u64) -> bool {
+    v.iter().all(|v| v.unsigned_abs() <= bound)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use itertools::Itertools;
+    use rand::{thread_rng, Rng};
+
+    pub(crate) const Q: u64 = 18014398509404161;
+    pub(crate) const N: usize = 1 << 11;
+
+    pub(crate) fn short_poly(bound: i64) -> Vec<i64> {
+        let mut rng = thread_rng();
+        (0..N).map(|_| rng.gen_range(-bound..=bound)).collect_vec()
+    }
+
+    pub(crate) fn uniform_poly() -> Vec<u64> {
+        let mut rng = thread_rng();
+        (0..N).map(|_| rng.gen_range(0..Q)).collect_vec()
+    }
+}
@@ -0,0 +1,533 @@
+//! Proofs of short secrets and errors
+//!
+//! Proves knowledge of short secret polynomials `w_k` and short errors `e_j`
+//! such that for each row `j` of a relation
+//!
+//!     t_j = \sum_k A_{j, k} * w_k + e_j  (mod q)
+//!
+//! where `A_{j, k}` are public polynomials or scalars and `t_j` is the
+//! public target (for ex, a seeded RLWE ciphertext). The proof is
+//! Lyubashevsky's Fiat-Shamir with aborts: prover samples masks `y`, hashes
+//! `w = \sum A y + y_e` to derive a sparse ternary challenge polynomial `c`
+//! and responds with `z = y + c * w` if `z` does not leak the witness (i.e. is
+//! within the bound), otherwise restarts.
+//!
+//! The proof is relaxed: verification only implies that there exist short
+//! `\bar{w}`, `\bar{e}` and a short `\bar{c}` such that `\bar{c} t_j = \sum_k
+//! A_{j, k} \bar{w}_k + \bar{e}_j`. This is sufficient to detect a party that
+//! uses huge error or a secret that is inconsistent across rows.
+
+use itertools::{izip, Itertools};
+
+use super::{expand_seed, norm_within, sample_mask, to_mod_q, Transcript};
+use crate::{
+    backend::{GetModulus, Modulus, VectorOps},
+    ntt::Ntt,
+    random::RandomFillUniformInModulus,
+    utils::wipe,
+};
+
+/// Returns hamming weight `k` of the sparse ternary challenge polynomial for
+/// `ring_size`, i.e. smallest `k` such that there are at least 2^128
+/// challenges with `k` coefficients set to +/-1.
+pub(crate) fn challenge_weight(ring_size: usize) -> usize {
+    let mut log_challenges = 0f64;
+    let mut k = 0;
+    while log_challenges < 128.0 {
+        // log2(C(n, k+1) * 2^{k+1}) = log2(C(n, k) * 2^k) + log2(2 (n-k)/(k+1))
+        log_challenges += (2.0 * (ring_size - k) as f64 / (k + 1) as f64).log2();
+        k += 1;
+    }
+    k
+}
+
+/// Returns sparse ternary challenge polynomial derived from `seed` as (index,
+/// is_negative) pairs of its `challenge_weight(ring_size)` non-zero
+/// coefficients
+fn sparse_ternary_challenge(seed: &[u8; 32], ring_size: usize) -> Vec<(usize, bool)> {
+    assert!(ring_size.is_power_of_two() && ring_size <= 1 << 16);
+    let weight = challenge_weight(ring_size);
+    let mut challenge = Vec::with_capacity(weight);
+    let mut len = 4 * weight;
+    while challenge.len() < weight {
+        challenge.clear();
+        // Each (index, sign) consumes 3 bytes. Expand more if too many indices
+        // collide.
+        let bytes = expand_seed(seed, 3 * len);
+        for b in bytes.chunks_exact(3) {
+            let index = u16::from_le_bytes([b[0], b[1]]) as usize & (ring_size - 1);
+            if challenge.iter().all(|(i, _)| *i != index) {
+                challenge.push((index, b[2] & 1 == 1));
+                if challenge.len() == weight {
+                    break;
+                }
+            }
+        }
+        len *= 2;
+    }
+    challenge
+}
+
+/// Sets out = out + c * w in Z[X]/(X^N + 1), where `c` is sparse ternary
+fn sparse_negacyclic_mul_add(out: &mut [i64], c: &[(usize, bool)], w: &[i64]) {
+    let ring_size = w.len();
+    c.iter().for_each(|(index, is_negative)| {
+        w.iter().enumerate().for_each(|(i, wi)| {
+            let mut to = i + index;
+            // X^N = -1
+            let mut negate = *is_negative;
+            if to >= ring_size {
+                to -= ring_size;
+                negate = !negate;
+            }
+            if negate {
+                out[to] -= wi;
+            } else {
+                out[to] += wi;
+            }
+        });
+    });
+}
+
+/// Returns polynomial `c` in evaluation domain
+fn sparse_challenge_eval<NttOp: Ntt<Element = u64>, ModOp: GetModulus<Element = u64>>(
+    c: &[(usize, bool)],
+    ring_size: usize,
+    nttop: &NttOp,
+    modop: &ModOp,
+) -> Vec<u64> {
+    let q = modop.modulus();
+    let mut c_eval = vec![0u64; ring_size];
+    c.iter().for_each(|(index, is_negative)| {
+        c_eval[*index] = q.map_element_from_i64(if *is_negative { -1 } else { 1 });
+    });
+    nttop.forward(&mut c_eval);
+    c_eval
+}
+
+/// Coefficient of a secret witness in a row of [ShortRelation]
+pub(crate) enum Coefficient {
+    /// Public polynomial in evaluation domain
+    Poly(Vec<u64>),
+    /// Public scalar
+    Scalar(u64),
+}
+
+/// Row `t = \sum_k A_k * w_k + e` of [ShortRelation]
+pub(crate) struct RelationRow<'a> {
+    /// (index of the witness `k`, A_k)
+    terms: Vec<(usize, Coefficient)>,
+    /// Target `t` in coefficient domain
+    target: &'a [u64],
+}
+
+/// System of R-linear relations over secret witnesses `w_k` with
+/// `||w_k||_\infty <= witness_bounds[k]` and a short error per row with
+/// `||e||_\infty <= error_bound`
+pub(crate) struct ShortRelation<'a> {
+    ring_size: usize,
+    witness_bounds: Vec<u64>,
+    error_bound: u64,
+    rows: Vec<RelationRow<'a>>,
+}
+
+impl<'a> ShortRelation<'a> {
+    pub(crate) fn new(ring_size: usize, witness_bounds: Vec<u64>, error_bound: u64) -> Self {
+        Self {
+            ring_size,
+            witness_bounds,
+            error_bound,
+            rows: vec![],
+        }
+    }
+
+    /// Adds row `target = \sum_{(k, A_k) \in terms} A_k * w_k + e`
+    pub(crate) fn add_row(&mut self, terms: Vec<(usize, Coefficient)>, target: &'a [u64]) {
+        assert!(target.len() == self.ring_size);
+        assert!(terms.iter().all(|(k, _)| *k < self.witness_bounds.len()));
+        self.rows.push(RelationRow { terms, target });
+    }
+
+    /// Returns bound on mask of each witness followed by bound on mask of error
+    /// of each row. Masks are sampled such that responses pass the norm check
+    /// with probability ~e^{-1/2}
+    fn mask_bounds(&self, challenge_weight: usize) -> (Vec<u64>, u64) {
+        let total_coefficients =
+            ((self.witness_bounds.len() + self.rows.len()) * self.ring_size) as u64;
+        let bound = |b: u64| 2 * total_coefficients * challenge_weight as u64 * b;
+        (
+            self.witness_bounds.iter().map(|b| bound(*b)).collect_vec(),
+            bound(self.error_bound),
+        )
+    }
+
+    /// Returns \sum_k A_k * v_k for each row, where `v_k_eval` are polynomials
+    /// `v_k` in evaluation domain. If `c_eval` is provided, subtracts c * t.
+    fn evaluate<NttOp: Ntt<Element = u64>, ModOp: VectorOps<Element = u64>>(
+        &self,
+        v_k_eval: &[Vec<u64>],
+        c_eval: Option<&[u64]>,
+        nttop: &NttOp,
+        modop: &ModOp,
+    ) -> Vec<Vec<u64>> {
+        let mut scratch = vec![0u64; self.ring_size];
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut out = vec![0u64; self.ring_size];
+                row.terms.iter().for_each(|(k, a)| match a {
+                    Coefficient::Poly(a) => modop.elwise_fma_mut(&mut out, a, &v_k_eval[*k]),
+                    Coefficient::Scalar(a) => {
+                        modop.elwise_fma_scalar_mut(&mut out, &v_k_eval[*k], a)
+                    }
+                });
+                if let Some(c_eval) = c_eval {
+                    scratch.copy_from_slice(row.target);
+                    nttop.forward(&mut scratch);
+                    modop.elwise_mul_mut(&mut scratch, c_eval);
+                    modop.elwise_sub_mut(&mut out, &scratch);
+                }
+                nttop.backward(&mut out);
+                out
+            })
+            .collect_vec()
+    }
+
+    /// Returns challenge seed for commitments `w` of each row
+    fn challenge_seed<Mo: Modulus<Element = u64>>(
+        &self,
+        label: &[u8],
+        q: &Mo,
+        w: &[Vec<u64>],
+    ) -> [u8; 32] {
+        let mut transcript = Transcript::new(label);
+        transcript.append_u64s(&[
+            q.q().unwrap_or(0),
+            self.ring_size as u64,
+            self.error_bound,
+            self.rows.len() as u64,
+        ]);
+        transcript.append_u64s(&self.witness_bounds);
+        izip!(self.rows.iter(), w.iter()).for_each(|(row, w)| {
+            transcript.append_u64s(row.target);
+            transcript.append_u64s(w);
+        });
+        transcript.finalize()
+    }
+}
+
+/// Non-interactive proof of knowledge of short witnesses of [ShortRelation]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortWitnessProof {
+    /// Seed of the challenge polynomial
+    challenge_seed: [u8; 32],
+    /// Responses for secret witnesses
+    witness_responses: Vec<Vec<i32>>,
+    /// Responses for error of each row
+    error_responses: Vec<Vec<i32>>,
+}
+
+/// Proves knowledge of `witnesses` of `relation`. Errors of each row are
+/// derived from the witnesses and the targets.
+///
+/// Panics if any witness or error exceeds its bound.
+pub(crate) fn prove_short_relation<
+    NttOp: Ntt<Element = u64>,
+    ModOp: VectorOps<Element = u64> + GetModulus<Element = u64>,
+    Rng: RandomFillUniformInModulus<[u64], u64>,
+>(
+    label: &[u8],
+    relation: &ShortRelation,
+    witnesses: &[Vec<i64>],
+    nttop: &NttOp,
+    modop: &ModOp,
+    rng: &mut Rng,
+) -> ShortWitnessProof {
+    let ring_size = relation.ring_size;
+    let q = modop.modulus();
+    assert!(witnesses.len() == relation.witness_bounds.len());
+    izip!(witnesses.iter(), relation.witness_bounds.iter()).for_each(|(w, bound)| {
+        assert!(w.len() == ring_size);
+        assert!(norm_within(w, *bound), "Witness exceeds bound {bound}");
+    });
+
+    // errors e_j = t_j - \sum A w
+    let mut errors = {
+        let mut w_eval = witnesses
+            .iter()
+            .map(|w| {
+                let mut w = to_mod_q(w, q);
+                nttop.forward(&mut w);
+                w
+            })
+            .collect_vec();
+        let errors = izip!(
+            relation.rows.iter(),
+            relation.evaluate(&w_eval, None, nttop, modop)
+        )
+        .map(|(row, mut aw)| {
+            modop.elwise_sub_mut(&mut aw, row.target);
+            let e = aw.iter().map(|v| -q.map_element_to_i64(v)).collect_vec();
+            wipe(&mut aw);
+            e
+        })
+        .collect_vec();
+        w_eval.iter_mut().for_each(|w| wipe(w));
+        errors.iter().for_each(|e| {
+            assert!(
+                norm_within(e, relation.error_bound),
+                "Error exceeds bound {}",
+                relation.error_bound
+            )
+        });
+        errors
+    };
+
+    let weight = challenge_weight(ring_size);
+    let (witness_mask_bounds, error_mask_bound) = relation.mask_bounds(weight);
+    assert!(
+        error_mask_bound <= i32::MAX as u64
+            && witness_mask_bounds.iter().all(|b| *b <= i32::MAX as u64),
+        "Responses do not fit in i32"
+    );
+
+    let proof = loop {
+        // masks
+        let mut y = witness_mask_bounds
+            .iter()
+            .map(|bound| {
+                let mut y = vec![0i64; ring_size];
+                sample_mask(&mut y, *bound, rng);
+                y
+            })
+            .collect_vec();
+        let mut y_e = relation
+            .rows
+            .iter()
+            .map(|_| {
+                let mut y = vec![0i64; ring_size];
+                sample_mask(&mut y, error_mask_bound, rng);
+                y
+            })
+            .collect_vec();
+
+        // commitments w_j = \sum A y + y_e
+        let mut y_eval = y
+            .iter()
+            .map(|y| {
+                let mut y = to_mod_q(y, q);
+                nttop.forward(&mut y);
+                y
+            })
+            .collect_vec();
+        let mut commitments = relation.evaluate(&y_eval, None, nttop, modop);
+        izip!(commitments.iter_mut(), y_e.iter()).for_each(|(w, y_e)| {
+            modop.elwise_add_mut(w, &to_mod_q(y_e, q));
+        });
+        y_eval.iter_mut().for_each(|y| wipe(y));
+
+        let challenge_seed = relation.challenge_seed(label, q, &commitments);
+        let c = sparse_ternary_challenge(&challenge_seed, ring_size);
+
+        // responses z = y + c * w
+        izip!(y.iter_mut(), witnesses.iter())
+            .for_each(|(y, w)| sparse_negacyclic_mul_add(y, &c, w));
+        izip!(y_e.iter_mut(), errors.iter()).for_each(|(y, e)| sparse_negacyclic_mul_add(y, &c, e));
+
+        let accept = izip!(
+            y.iter(),
+            witness_mask_bounds.iter(),
+            relation.witness_bounds.iter()
+        )
+        .all(|(z, mask_bound, bound)| norm_within(z, mask_bound - weight as u64 * bound))
+            && y_e
+                .iter()
+                .all(|z| norm_within(z, error_mask_bound - weight as u64 * relation.error_bound));
+
+        let proof = if accept {
+            let to_i32 = |z: &Vec<i64>| z.iter().map(|v| *v as i32).collect_vec();
+            Some(ShortWitnessProof {
+                challenge_seed,
+                witness_responses: y.iter().map(to_i32).collect_vec(),
+                error_responses: y_e.iter().map(to_i32).collect_vec(),
+            })
+        } else {
+            None
+        };
+        y.iter_mut().chain(y_e.iter_mut()).for_each(|y| wipe(y));
+
+        if let Some(proof) = proof {
+            break proof;
+        }
+    };
+
+    errors.iter_mut().for_each(|e| wipe(e));
+    proof
+}
+
+/// Verifies `proof` of knowledge of short witnesses of `relation`
+pub(crate) fn verify_short_relation<
+    NttOp: Ntt<Element = u64>,
+    ModOp: VectorOps<Element = u64> + GetModulus<Element = u64>,
+>(
+    label: &[u8],
+    relation: &ShortRelation,
+    proof: &ShortWitnessProof,
+    nttop: &NttOp,
+    modop: &ModOp,
+) -> Result<(), String> {
+    let ring_size = relation.ring_size;
+    let q = modop.modulus();
+    if proof.witness_responses.len() != relation.witness_bounds.len()
+        || proof.error_responses.len() != relation.rows.len()
+        || proof
+            .witness_responses
+            .iter()
+            .chain(proof.error_responses.iter())
+            .any(|z| z.len() != ring_size)
+    {
+        return Err("Proof has incorrect dimensions".to_string());
+    }
+
+    let weight = challenge_weight(ring_size);
+    let (witness_mask_bounds, error_mask_bound) = relation.mask_bounds(weight);
+    let z = proof
+        .witness_responses
+        .iter()
+        .map(|z| z.iter().map(|v| *v as i64).collect_vec())
+        .collect_vec();
+    let z_e = proof
+        .error_responses
+        .iter()
+        .map(|z| z.iter().map(|v| *v as i64).collect_vec())
+        .collect_vec();
+    let within_bounds = izip!(
+        z.iter(),
+        witness_mask_bounds.iter(),
+        relation.witness_bounds.iter()
+    )
+    .all(|(z, mask_bound, bound)| norm_within(z, mask_bound - weight as u64 * bound))
+        && z_e
+            .iter()
+            .all(|z| norm_within(z, error_mask_bound - weight as u64 * relation.error_bound));
+    if !within_bounds {
+        return Err("Proof responses exceed bound".to_string());
+    }
+
+    // w'_j = \sum A z + z_e - c t_j
+    let c = sparse_ternary_challenge(&proof.challenge_seed, ring_size);
+    let c_eval = sparse_challenge_eval(&c, ring_size, nttop, modop);
+    let z_eval = z
+        .iter()
+        .map(|z| {
+            let mut z = to_mod_q(z, q);
+            nttop.forward(&mut z);
+            z
+        })
+        .collect_vec();
+    let mut commitments = relation.evaluate(&z_eval, Some(&c_eval), nttop, modop);
+    izip!(commitments.iter_mut(), z_e.iter()).for_each(|(w, z_e)| {
+        modop.elwise_add_mut(w, &to_mod_q(z_e, q));
+    });
+
+    if relation.challenge_seed(label, q, &commitments) != proof.challenge_seed {
+        return Err("Proof does not verify".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::ModularOpsU64,
+        ntt::NttBackendU64,
+        proof::{
+            tests::{short_poly, uniform_poly, N, Q},
+            ERROR_BOUND,
+        },
+        random::DefaultSecureRng,
+        utils::WithLocal,
+        ModInit, NttInit,
+    };
+
+    #[test]
+    fn challenge_weight_has_128_bits_of_entropy() {
+        assert_eq!(challenge_weight(1 << 11), 14);
+        let seed = [7u8; 32];
+        let c = sparse_ternary_challenge(&seed, N);
+        assert_eq!(c.len(), challenge_weight(N));
+        assert!(c.iter().map(|(i, _)| i).all_unique());
+        assert_eq!(c, sparse_ternary_challenge(&seed, N));
+    }
+
+    #[test]
+    fn short_relation_proof_works() {
+        let modop = ModularOpsU64::new(Q);
+        let nttop = NttBackendU64::new(&Q, N);
+        let q = modop.modulus();
+
+        // t_0 = a_0 s + e, t_1 = a_1 s + beta u + e
+        let s = short_poly(1);
+        let u = short_poly(1);
+        let beta = 1u64 << 20;
+        let a = (0..2)
+            .map(|_| {
+                let mut a = uniform_poly();
+                nttop.forward(&mut a);
+                a
+            })
+            .collect_vec();
+        let mut targets = a
+            .iter()
+            .map(|a| {
+                let mut t = to_mod_q(&s, q);
+                nttop.forward(&mut t);
+                modop.elwise_mul_mut(&mut t, a);
+                nttop.backward(&mut t);
+                modop.elwise_add_mut(&mut t, &to_mod_q(&short_poly(ERROR_BOUND as i64), q));
+                t
+            })
+            .collect_vec();
+        let mut beta_u = to_mod_q(&u, q);
+        modop.elwise_scalar_mul_mut(&mut beta_u, &beta);
+        modop.elwise_add_mut(&mut targets[1], &beta_u);
+
+        fn relation_with_targets<'a>(
+            a: &[Vec<u64>],
+            beta: u64,
+            targets: &'a [Vec<u64>],
+        ) -> ShortRelation<'a> {
+            let mut relation = ShortRelation::new(N, vec![1, 1], ERROR_BOUND);
+            relation.add_row(vec![(0, Coefficient::Poly(a[0].clone()))], &targets[0]);
+            relation.add_row(
+                vec![
+                    (0, Coefficient::Poly(a[1].clone())),
+                    (1, Coefficient::Scalar(beta)),
+                ],
+                &targets[1],
+            );
+            relation
+        }
+
+        let relation = relation_with_targets(&a, beta, &targets);
+        let proof = DefaultSecureRng::with_local_mut(|rng| {
+            prove_short_relation(
+                b"test",
+                &relation,
+                &[s.clone(), u.clone()],
+                &nttop,
+                &modop,
+                rng,
+            )
+        });
+        assert!(verify_short_relation(b"test", &relation, &proof, &nttop, &modop).is_ok());
+        assert!(verify_short_relation(b"other", &relation, &proof, &nttop, &modop).is_err());
+
+        // huge error in one coefficient of a target
+        let mut tampered = targets.clone();
+        tampered[1][3] = (tampered[1][3] + (1 << 40)) % Q;
+        let relation = relation_with_targets(&a, beta, &tampered);
+        assert!(verify_short_relation(b"test", &relation, &proof, &nttop, &modop).is_err());
+    }
+}
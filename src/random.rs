@@ -1,9 +1,9 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, sync::OnceLock};
 
 use itertools::izip;
 use num_traits::{FromPrimitive, PrimInt, Zero};
 use rand::{distributions::Uniform, Rng, RngCore, SeedableRng};
-use rand_chacha::ChaCha8Rng;
+use rand_chacha::{ChaCha12Rng, ChaCha20Rng, ChaCha8Rng};
 use rand_distr::{uniform::SampleUniform, Distribution};
 
 use crate::{backend::Modulus, utils::WithLocal};
@@ -14,6 +14,9 @@ thread_local! {
 
 pub trait NewWithSeed {
     type Seed;
+    /// Generator that expands the seed. Seeded ciphertexts and keys record it
+    /// to detect seeds expanded with a different generator.
+    const ALGORITHM: PrgAlgorithm;
     fn new_with_seed(seed: Self::Seed) -> Self;
 }
 
@@ -40,8 +43,8 @@ pub trait RandomFillGaussian<M>
 where
     M: ?Sized,
 {
-    /// Fill container with random elements sampled from normal distribtuion
-    /// with \mu = 0.0 and \sigma = 3.19.
+    /// Fill container with random elements sampled from normal distribution
+    /// with \mu = 0.0 and \sigma = 3.19 truncated towards 0.
     fn random_fill(&mut self, container: &mut M);
 }
 
@@ -58,24 +61,146 @@ where
     M: ?Sized,
 {
     /// Fill container with gaussian elements sampled from normal distribution
-    /// with \mu = 0.0 and \sigma = 3.19. Elements are converted to signed
-    /// represented in the modulus.
+    /// with \mu = 0.0 and \sigma = 3.19 rounded to nearest integer. Elements are converted
+    /// to signed represented in the modulus.
     fn random_fill(&mut self, modulus: &P, container: &mut M);
 }
 
-pub struct DefaultSecureRng {
-    rng: ChaCha8Rng,
+/// Pseudorandom generator used to expand seeds.
+///
+/// The same generator expands public seeds (common reference string, seeded
+/// ciphertexts and seeded keys) and samples secrets. Parties must use the same
+/// generator to expand each other's seeds, hence seeded ciphertexts record the
+/// generator that expands them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrgAlgorithm {
+    ChaCha8,
+    ChaCha12,
+    ChaCha20,
+    Aes256Ctr,
 }
 
-impl DefaultSecureRng {
-    pub fn new_seeded(seed: <ChaCha8Rng as SeedableRng>::Seed) -> DefaultSecureRng {
-        let rng = ChaCha8Rng::from_seed(seed);
-        DefaultSecureRng { rng }
+/// Pseudorandom generator that can back [SecureRng]
+pub trait Prg: RngCore + SeedableRng<Seed = [u8; 32]> {
+    const ALGORITHM: PrgAlgorithm;
+}
+
+impl Prg for ChaCha8Rng {
+    const ALGORITHM: PrgAlgorithm = PrgAlgorithm::ChaCha8;
+}
+
+impl Prg for ChaCha12Rng {
+    const ALGORITHM: PrgAlgorithm = PrgAlgorithm::ChaCha12;
+}
+
+impl Prg for ChaCha20Rng {
+    const ALGORITHM: PrgAlgorithm = PrgAlgorithm::ChaCha20;
+}
+
+#[cfg(feature = "prg_aes_ctr")]
+mod aes_ctr {
+    use aes::{
+        cipher::{KeyIvInit, StreamCipher},
+        Aes256,
+    };
+    use rand_core::{
+        block::{BlockRng, BlockRngCore},
+        RngCore, SeedableRng,
+    };
+
+    use super::{Prg, PrgAlgorithm};
+
+    pub struct Aes256CtrCore(ctr::Ctr128BE<Aes256>);
+
+    impl BlockRngCore for Aes256CtrCore {
+        type Item = u32;
+        type Results = [u32; 16];
+
+        fn generate(&mut self, results: &mut Self::Results) {
+            let mut bytes = [0u8; 64];
+            self.0.apply_keystream(&mut bytes);
+            results
+                .iter_mut()
+                .zip(bytes.chunks_exact(4))
+                .for_each(|(r, b)| *r = u32::from_le_bytes(b.try_into().unwrap()));
+        }
+    }
+
+    /// AES-256 in counter mode keyed with the seed and IV set to 0
+    pub struct Aes256CtrRng(BlockRng<Aes256CtrCore>);
+
+    impl SeedableRng for Aes256CtrRng {
+        type Seed = [u8; 32];
+        fn from_seed(seed: Self::Seed) -> Self {
+            let core = Aes256CtrCore(ctr::Ctr128BE::<Aes256>::new(
+                &seed.into(),
+                &[0u8; 16].into(),
+            ));
+            Aes256CtrRng(BlockRng::new(core))
+        }
+    }
+
+    impl RngCore for Aes256CtrRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next_u32()
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.0.fill_bytes(dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.0.try_fill_bytes(dest)
+        }
+    }
+
+    impl Prg for Aes256CtrRng {
+        const ALGORITHM: PrgAlgorithm = PrgAlgorithm::Aes256Ctr;
+    }
+}
+
+#[cfg(feature = "prg_aes_ctr")]
+pub use aes_ctr::Aes256CtrRng;
+
+/// Pseudorandom generator backing [DefaultSecureRng]. ChaCha8 unless one of
+/// `prg_chacha12`, `prg_chacha20`, `prg_aes_ctr` features is enabled. If
+/// multiple features are enabled, for ex. by different dependents of the
+/// crate, the strongest generator is selected in order `prg_aes_ctr`,
+/// `prg_chacha20`, `prg_chacha12`.
+#[cfg(not(any(
+    feature = "prg_chacha12",
+    feature = "prg_chacha20",
+    feature = "prg_aes_ctr"
+)))]
+pub type DefaultPrg = ChaCha8Rng;
+#[cfg(all(
+    feature = "prg_chacha12",
+    not(any(feature = "prg_chacha20", feature = "prg_aes_ctr"))
+))]
+pub type DefaultPrg = ChaCha12Rng;
+#[cfg(all(feature = "prg_chacha20", not(feature = "prg_aes_ctr")))]
+pub type DefaultPrg = ChaCha20Rng;
+#[cfg(feature = "prg_aes_ctr")]
+pub type DefaultPrg = Aes256CtrRng;
+
+/// Rng used throughout the library. Generator is selected with features, see
+/// [DefaultPrg].
+pub type DefaultSecureRng = SecureRng<DefaultPrg>;
+
+pub struct SecureRng<R> {
+    rng: R,
+}
+
+impl<R: Prg> SecureRng<R> {
+    pub fn new_seeded(seed: R::Seed) -> SecureRng<R> {
+        let rng = R::from_seed(seed);
+        SecureRng { rng }
     }
 
-    pub fn new() -> DefaultSecureRng {
-        let rng = ChaCha8Rng::from_entropy();
-        DefaultSecureRng { rng }
+    pub fn new() -> SecureRng<R> {
+        let rng = R::from_entropy();
+        SecureRng { rng }
     }
 
     pub fn fill_bytes(&mut self, a: &mut [u8; 32]) {
@@ -83,14 +208,87 @@ impl DefaultSecureRng {
     }
 }
 
-impl NewWithSeed for DefaultSecureRng {
-    type Seed = <ChaCha8Rng as SeedableRng>::Seed;
+/// Standard deviation of the error distribution
+const GAUSSIAN_STD_DEV: f64 = 3.19;
+
+/// No. of entries in the cumulative distribution table of |x|. Probability
+/// mass beyond the last entry is below 2^{-63}, the precision of the table.
+const GAUSSIAN_CDT_SIZE: usize = 40;
+
+/// Cumulative distribution table of |x| where x is an integer obtained from
+/// X \sim N(0, 3.19^2). Entry k equals 2^{63} Pr[|X| < k + offset], that is
+/// offset = 0.5 when X is rounded to nearest integer and offset = 1.0 when X
+/// is truncated towards 0.
+fn normal_cdt(offset: f64) -> [u64; GAUSSIAN_CDT_SIZE] {
+    let density = |x: f64| {
+        (2.0 / (2.0 * std::f64::consts::PI).sqrt() / GAUSSIAN_STD_DEV)
+            * (-(x * x) / (2.0 * GAUSSIAN_STD_DEV * GAUSSIAN_STD_DEV)).exp()
+    };
+    // Pr[a <= |X| < b] with simpson's rule
+    let mass = |a: f64, b: f64| {
+        let steps = 1024;
+        let h = (b - a) / steps as f64;
+        let s = (1..steps)
+            .map(|i| (if i & 1 == 1 { 4.0 } else { 2.0 }) * density(a + i as f64 * h))
+            .sum::<f64>();
+        (density(a) + s + density(b)) * h / 3.0
+    };
+
+    // Entries are calculated from the tail, Pr[|X| >= k + offset], to not lose
+    // precision when Pr[|X| < k + offset] is close to 1. Tail beyond 100 no
+    // longer matters in f64.
+    let mut tail = mass(GAUSSIAN_CDT_SIZE as f64 + offset, 100.0);
+    let mut cdt = [0u64; GAUSSIAN_CDT_SIZE];
+    cdt.iter_mut().enumerate().rev().for_each(|(k, c)| {
+        *c = (1u64 << 63) - (tail * (1u64 << 63) as f64).round() as u64;
+        tail += mass(k as f64 + offset - 1.0, k as f64 + offset);
+    });
+    cdt
+}
+
+/// CDT of errors, X \sim N(0, 3.19^2) rounded to nearest integer
+fn error_cdt() -> &'static [u64; GAUSSIAN_CDT_SIZE] {
+    static CDT: OnceLock<[u64; GAUSSIAN_CDT_SIZE]> = OnceLock::new();
+    CDT.get_or_init(|| normal_cdt(0.5))
+}
+
+/// CDT of secrets sampled from the error distribution, X \sim N(0, 3.19^2)
+/// truncated towards 0
+fn secret_cdt() -> &'static [u64; GAUSSIAN_CDT_SIZE] {
+    static CDT: OnceLock<[u64; GAUSSIAN_CDT_SIZE]> = OnceLock::new();
+    CDT.get_or_init(|| normal_cdt(1.0))
+}
+
+/// Samples x with distribution of |x| given by `cdt` and symmetric sign in
+/// constant time.
+///
+/// Draws 63 bits for |x| and 1 bit for the sign. |x| equals the no. of CDT
+/// entries less than or equal to the random value. All table entries are
+/// compared without branching and the sign is applied arithmetically.
+fn sample_gaussian_ct<R: RngCore>(rng: &mut R, cdt: &[u64; GAUSSIAN_CDT_SIZE]) -> i64 {
+    let r = rng.next_u64();
+    let sign = (r & 1) as i64;
+    let u = r >> 1;
+
+    let mut magnitude = 0u64;
+    cdt.iter().for_each(|c| {
+        // both u and c are <= 2^63, hence the MSB of the difference is set iff u
+        // < c
+        magnitude += 1 ^ (u.wrapping_sub(*c) >> 63);
+    });
+
+    ((magnitude as i64) ^ -sign) + sign
+}
+
+impl<R: Prg> NewWithSeed for SecureRng<R> {
+    type Seed = R::Seed;
+    const ALGORITHM: PrgAlgorithm = R::ALGORITHM;
     fn new_with_seed(seed: Self::Seed) -> Self {
-        DefaultSecureRng::new_seeded(seed)
+        SecureRng::new_seeded(seed)
     }
 }
 
-impl<T, C> RandomFillUniformInModulus<[T], C> for DefaultSecureRng
+impl<T, C, R: RngCore> RandomFillUniformInModulus<[T], C> for SecureRng<R>
 where
     T: PrimInt + SampleUniform,
     C: Modulus<Element = T>,
@@ -109,25 +307,19 @@ where
     }
 }
 
-impl<T, C> RandomFillGaussianInModulus<[T], C> for DefaultSecureRng
+impl<T, C, R: RngCore> RandomFillGaussianInModulus<[T], C> for SecureRng<R>
 where
     T: PrimInt,
     C: Modulus<Element = T>,
 {
     fn random_fill(&mut self, modulus: &C, container: &mut [T]) {
-        izip!(
-            rand_distr::Normal::new(0.0, 3.19f64)
-                .unwrap()
-                .sample_iter(&mut self.rng),
-            container.iter_mut()
-        )
-        .for_each(|(from, to)| {
-            *to = modulus.map_element_from_f64(from);
+        container.iter_mut().for_each(|to| {
+            *to = modulus.map_element_from_i64(sample_gaussian_ct(&mut self.rng, error_cdt()));
         });
     }
 }
 
-impl<T> RandomFill<[T]> for DefaultSecureRng
+impl<T, R: RngCore> RandomFill<[T]> for SecureRng<R>
 where
     T: PrimInt + SampleUniform,
 {
@@ -142,24 +334,18 @@ where
     }
 }
 
-impl<T> RandomFillGaussian<[T]> for DefaultSecureRng
+impl<T, R: RngCore> RandomFillGaussian<[T]> for SecureRng<R>
 where
     T: FromPrimitive,
 {
     fn random_fill(&mut self, container: &mut [T]) {
-        izip!(
-            rand_distr::Normal::new(0.0, 3.19f64)
-                .unwrap()
-                .sample_iter(&mut self.rng),
-            container.iter_mut()
-        )
-        .for_each(|(from, to)| {
-            *to = T::from_f64(from).unwrap();
+        container.iter_mut().for_each(|to| {
+            *to = T::from_i64(sample_gaussian_ct(&mut self.rng, secret_cdt())).unwrap();
         });
     }
 }
 
-impl<T> RandomFill<[T; 32]> for DefaultSecureRng
+impl<T, R: RngCore> RandomFill<[T; 32]> for SecureRng<R>
 where
     T: PrimInt + SampleUniform,
 {
@@ -174,7 +360,7 @@ where
     }
 }
 
-impl<T> RandomElementInModulus<T, T> for DefaultSecureRng
+impl<T, R: RngCore> RandomElementInModulus<T, T> for SecureRng<R>
 where
     T: Zero + SampleUniform,
 {
@@ -183,14 +369,38 @@ where
     }
 }
 
-impl<T, M: Modulus<Element = T>> RandomGaussianElementInModulus<T, M> for DefaultSecureRng {
+impl<T, M: Modulus<Element = T>, R: RngCore> RandomGaussianElementInModulus<T, M> for SecureRng<R> {
     fn random(&mut self, modulus: &M) -> T {
-        modulus.map_element_from_f64(
-            rand_distr::Normal::new(0.0, 3.19f64)
-                .unwrap()
-                .sample(&mut self.rng),
-        )
+        modulus.map_element_from_i64(sample_gaussian_ct(&mut self.rng, error_cdt()))
+    }
+}
+
+/// Runs `f` with the thread local rng replaced by rng seeded with `seed` and
+/// restores the thread local rng afterwards.
+///
+/// All randomness of client key generation, server key share generation,
+/// encryption and decryption share generation on the calling thread is drawn
+/// from the thread local rng. Hence running these within `with_seeded_rng`
+/// reproduces them bit-for-bit, which is useful to produce known-answer test
+/// vectors and to replay failing runs.
+///
+/// **Never use it in production.** Anyone who knows the seed can recover the
+/// secrets generated within `f`.
+pub fn with_seeded_rng<F: FnOnce() -> T, T>(seed: [u8; 32], f: F) -> T {
+    /// Restores the thread local rng, even if `f` panics
+    struct Restore(Option<DefaultSecureRng>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take().unwrap();
+            DEFAULT_RNG.with_borrow_mut(|r| *r = previous);
+        }
     }
+
+    let previous =
+        DEFAULT_RNG.with_borrow_mut(|r| std::mem::replace(r, DefaultSecureRng::new_seeded(seed)));
+    let _restore = Restore(Some(previous));
+    f()
 }
 
 impl WithLocal for DefaultSecureRng {
@@ -215,3 +425,160 @@ impl WithLocal for DefaultSecureRng {
         DEFAULT_RNG.with_borrow_mut(|r| func(r))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+
+    use crate::utils::tests::{dudect, Stats};
+
+    use super::*;
+
+    /// Standard deviation of x where |x| has distribution given by `cdt` and
+    /// sign of x is symmetric
+    fn cdt_std_dev(cdt: &[u64; GAUSSIAN_CDT_SIZE]) -> f64 {
+        let mut prev = 0.0;
+        cdt.iter()
+            .enumerate()
+            .map(|(k, c)| {
+                let p = *c as f64 / (1u64 << 63) as f64;
+                let v = (k * k) as f64 * (p - prev);
+                prev = p;
+                v
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn gaussian_samples_have_correct_std_dev() {
+        // rounding adds variance 1/12
+        let error_std_dev = cdt_std_dev(error_cdt());
+        assert!(
+            (error_std_dev - (GAUSSIAN_STD_DEV.powi(2) + 1.0 / 12.0).sqrt()).abs() < 0.001,
+            "std dev {error_std_dev}"
+        );
+
+        let q = (1u64 << 50) - 27;
+        let mut rng = DefaultSecureRng::new();
+        let mut errors = vec![0u64; 1 << 20];
+        RandomFillGaussianInModulus::random_fill(&mut rng, &q, errors.as_mut_slice());
+        let errors = errors
+            .iter()
+            .map(|v| q.map_element_to_i64(v))
+            .collect::<Vec<_>>();
+        let mut secrets = vec![0i64; 1 << 20];
+        RandomFillGaussian::random_fill(&mut rng, secrets.as_mut_slice());
+
+        for (samples, std_dev) in [
+            (errors, error_std_dev),
+            (secrets, cdt_std_dev(secret_cdt())),
+        ] {
+            let mut stats = Stats::new();
+            stats.add_many_samples(&samples);
+            assert!(stats.mean().abs() < 0.05, "mean {}", stats.mean());
+            assert!(
+                (stats.std_dev() - std_dev).abs() < 0.05,
+                "std dev {} expected {std_dev}",
+                stats.std_dev()
+            );
+            assert!(samples.iter().all(|v| v.abs() < GAUSSIAN_CDT_SIZE as i64));
+
+            // symmetric around 0
+            let positives = samples.iter().filter(|v| **v > 0).count() as f64;
+            let negatives = samples.iter().filter(|v| **v < 0).count() as f64;
+            assert!((positives / negatives - 1.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn secrets_are_truncated_and_errors_are_rounded() {
+        // Pr[|x| = 0] equals Pr[|X| < 1] for truncation and Pr[|X| < 0.5] for
+        // rounding, where X ~ N(0, 3.19^2)
+        let p_zero = |cdt: &[u64; GAUSSIAN_CDT_SIZE]| cdt[0] as f64 / (1u64 << 63) as f64;
+        assert!((p_zero(secret_cdt()) - 0.246084).abs() < 1e-5);
+        assert!((p_zero(error_cdt()) - 0.124550).abs() < 1e-5);
+    }
+
+    #[test]
+    fn gaussian_samples_in_modulus_are_signed() {
+        let q = (1u64 << 50) - 27;
+        let mut rng = DefaultSecureRng::new();
+        let mut samples = vec![0u64; 1 << 12];
+        RandomFillGaussianInModulus::random_fill(&mut rng, &q, samples.as_mut_slice());
+        assert!(samples
+            .iter()
+            .all(|v| *v < GAUSSIAN_CDT_SIZE as u64 || *v > q - GAUSSIAN_CDT_SIZE as u64));
+    }
+
+    fn seeded_expansion_is_deterministic<R: Prg>() -> Vec<u64> {
+        let q = (1u64 << 50) - 27;
+        let expand = |seed| {
+            let mut rng = SecureRng::<R>::new_seeded(seed);
+            let mut out = vec![0u64; 1 << 10];
+            RandomFillUniformInModulus::random_fill(&mut rng, &q, out.as_mut_slice());
+            out
+        };
+
+        let a = expand([7u8; 32]);
+        assert_eq!(a, expand([7u8; 32]));
+        assert_ne!(a, expand([8u8; 32]));
+        assert!(a.iter().all(|v| *v < q));
+        a
+    }
+
+    #[test]
+    fn all_prgs_expand_seeds_deterministically() {
+        let outs = [
+            seeded_expansion_is_deterministic::<ChaCha8Rng>(),
+            seeded_expansion_is_deterministic::<ChaCha12Rng>(),
+            seeded_expansion_is_deterministic::<ChaCha20Rng>(),
+            #[cfg(feature = "prg_aes_ctr")]
+            seeded_expansion_is_deterministic::<Aes256CtrRng>(),
+        ];
+        // different generators expand the same seed differently
+        for i in 0..outs.len() {
+            for j in (i + 1)..outs.len() {
+                assert_ne!(outs[i], outs[j]);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "prg_aes_ctr")]
+    fn aes_ctr_matches_aes_256_test_vector() {
+        // AES-256 encryption of zero block under zero key
+        let expected = [
+            0xdc, 0x95, 0xc0, 0x78, 0xa2, 0x40, 0x89, 0x89, 0xad, 0x48, 0xa2, 0x14, 0x92, 0x84,
+            0x20, 0x87,
+        ];
+        let mut rng = Aes256CtrRng::from_seed([0u8; 32]);
+        let mut out = [0u8; 16];
+        rng.fill_bytes(&mut out);
+        assert_eq!(out, expected);
+    }
+
+    // Run with `cargo test --release ct_ -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn ct_gaussian_sampling() {
+        let q = (1u64 << 50) - 27;
+        let mut seed_rng = DefaultSecureRng::new();
+        dudect(
+            1_000_000,
+            |class| {
+                let mut seed = [0u8; 32];
+                if class == 1 {
+                    RandomFill::<[u8]>::random_fill(&mut seed_rng, seed.as_mut_slice());
+                }
+                DefaultSecureRng::new_seeded(seed)
+            },
+            |mut rng| {
+                let mut out = [0u64; 16];
+                RandomFillGaussianInModulus::random_fill(&mut rng, &q, out.as_mut_slice());
+                black_box(out);
+            },
+        )
+        .assert_constant_time("RandomFillGaussianInModulus");
+    }
+}
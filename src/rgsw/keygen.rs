@@ -5,11 +5,12 @@ use num_traits::{PrimInt, Signed, ToPrimitive, Zero};
 
 use crate::{
     backend::{ArithmeticOps, GetModulus, Modulus, VectorOps},
+    fft::FftBackendF64,
     ntt::Ntt,
     random::{
         RandomElementInModulus, RandomFill, RandomFillGaussianInModulus, RandomFillUniformInModulus,
     },
-    utils::{fill_random_ternary_secret_with_hamming_weight, TryConvertFrom1},
+    utils::{fill_random_ternary_secret_with_hamming_weight, wipe, TryConvertFrom1},
     Matrix, MatrixEntity, MatrixMut, Row, RowEntity, RowMut,
 };
 
@@ -85,21 +86,106 @@ pub(crate) fn secret_key_encrypt_rgsw<
     rng: &mut R,
 ) where
     <Mmut as Matrix>::R: RowMut + RowEntity + TryConvertFrom1<[S], ModOp::M> + Debug,
-    Mmut::MatElement: Copy + Debug,
+    Mmut::MatElement: Copy + Zero + Debug,
+{
+    assert!(m.len() == s.len());
+    let mut s_eval = Mmut::R::try_convert_from(s, mod_op.modulus());
+    ntt_op.forward(s_eval.as_mut());
+
+    _secret_key_encrypt_rgsw::<Mmut, _, _, _, _>(
+        out_rgsw,
+        m,
+        gadget_a,
+        gadget_b,
+        mod_op,
+        |p| {
+            ntt_op.forward(p);
+            mod_op.elwise_mul_mut(p, s_eval.as_ref());
+            ntt_op.backward(p);
+        },
+        p_rng,
+        rng,
+    );
+
+    wipe(s_eval.as_mut());
+}
+
+/// Same as `secret_key_encrypt_rgsw` with products with secret `s` computed
+/// with `fft_op`. Supports native modulus, for which NTT does not exist.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(all(test, feature = "interactive_mp")), allow(dead_code))]
+pub(crate) fn secret_key_encrypt_rgsw_fourier<
+    Mmut: MatrixMut<MatElement = u64> + MatrixEntity,
+    R: RandomFillGaussianInModulus<[u64], ModOp::M> + RandomFillUniformInModulus<[u64], ModOp::M>,
+    PR: RandomFillUniformInModulus<[u64], ModOp::M>,
+    ModOp: VectorOps<Element = u64> + GetModulus<Element = u64>,
+>(
+    out_rgsw: &mut Mmut,
+    m: &[u64],
+    gadget_a: &[u64],
+    gadget_b: &[u64],
+    s: &[i32],
+    mod_op: &ModOp,
+    fft_op: &FftBackendF64,
+    p_rng: &mut PR,
+    rng: &mut R,
+) where
+    <Mmut as Matrix>::R: RowMut + RowEntity + TryConvertFrom1<[i32], ModOp::M>,
+{
+    assert!(m.len() == s.len());
+    let mut s = Mmut::R::try_convert_from(s, mod_op.modulus());
+    let mut scratch = vec![0u64; m.len()];
+
+    _secret_key_encrypt_rgsw::<Mmut, _, _, _, _>(
+        out_rgsw,
+        m,
+        gadget_a,
+        gadget_b,
+        mod_op,
+        |p| {
+            scratch.copy_from_slice(p);
+            fft_op.mul_small(&scratch, s.as_ref(), p);
+        },
+        p_rng,
+        rng,
+    );
+
+    wipe(s.as_mut());
+    wipe(&mut scratch);
+}
+
+/// Encrypts RGSW(m) (see `secret_key_encrypt_rgsw`). `mul_s` sets polynomial
+/// `p` to `p * s`.
+#[allow(clippy::too_many_arguments)]
+fn _secret_key_encrypt_rgsw<
+    Mmut: MatrixMut + MatrixEntity,
+    R: RandomFillGaussianInModulus<[Mmut::MatElement], ModOp::M>
+        + RandomFillUniformInModulus<[Mmut::MatElement], ModOp::M>,
+    PR: RandomFillUniformInModulus<[Mmut::MatElement], ModOp::M>,
+    ModOp: VectorOps<Element = Mmut::MatElement> + GetModulus<Element = Mmut::MatElement>,
+    F: FnMut(&mut [Mmut::MatElement]),
+>(
+    out_rgsw: &mut Mmut,
+    m: &[Mmut::MatElement],
+    gadget_a: &[Mmut::MatElement],
+    gadget_b: &[Mmut::MatElement],
+    mod_op: &ModOp,
+    mut mul_s: F,
+    p_rng: &mut PR,
+    rng: &mut R,
+) where
+    <Mmut as Matrix>::R: RowMut + RowEntity,
+    Mmut::MatElement: Copy + Zero,
 {
     let d_a = gadget_a.len();
     let d_b = gadget_b.len();
     let q = mod_op.modulus();
-    let ring_size = s.len();
+    let ring_size = m.len();
     assert!(out_rgsw.dimension() == (d_a * 2 + d_b, ring_size));
-    assert!(m.as_ref().len() == ring_size);
 
     // RLWE(-sm), RLWE(m)
     let (rlwe_dash_nsm, b_rlwe_dash_m) = out_rgsw.split_at_row_mut(d_a * 2);
 
-    let mut s_eval = Mmut::R::try_convert_from(s, &q);
-    ntt_op.forward(s_eval.as_mut());
-
     let mut scratch_space = Mmut::R::zeros(ring_size);
 
     // RLWE'(-sm)
@@ -111,16 +197,14 @@ pub(crate) fn secret_key_encrypt_rgsw<
     )
     .for_each(|(ai, bi, beta_i)| {
         // Sample a_i
-        RandomFillUniformInModulus::random_fill(rng, &q, ai.as_mut());
+        RandomFillUniformInModulus::random_fill(rng, q, ai.as_mut());
 
         // a_i * s
         scratch_space.as_mut().copy_from_slice(ai.as_ref());
-        ntt_op.forward(scratch_space.as_mut());
-        mod_op.elwise_mul_mut(scratch_space.as_mut(), s_eval.as_ref());
-        ntt_op.backward(scratch_space.as_mut());
+        mul_s(scratch_space.as_mut());
 
         // b_i = e_i + a_i * s
-        RandomFillGaussianInModulus::random_fill(rng, &q, bi.as_mut());
+        RandomFillGaussianInModulus::random_fill(rng, q, bi.as_mut());
         mod_op.elwise_add_mut(bi.as_mut(), scratch_space.as_ref());
 
         // a_i + \beta_i * m
@@ -133,7 +217,7 @@ pub(crate) fn secret_key_encrypt_rgsw<
         // polynomials of part A of RLWE'(m) are sampled from seed
         let mut a = Mmut::zeros(d_b, ring_size);
         a.iter_rows_mut()
-            .for_each(|ai| RandomFillUniformInModulus::random_fill(p_rng, &q, ai.as_mut()));
+            .for_each(|ai| RandomFillUniformInModulus::random_fill(p_rng, q, ai.as_mut()));
         a
     };
 
@@ -144,19 +228,22 @@ pub(crate) fn secret_key_encrypt_rgsw<
     )
     .for_each(|(ai, bi, beta_i)| {
         // ai * s
-        ntt_op.forward(ai.as_mut());
-        mod_op.elwise_mul_mut(ai.as_mut(), s_eval.as_ref());
-        ntt_op.backward(ai.as_mut());
+        mul_s(ai.as_mut());
 
         // beta_i * m
         mod_op.elwise_scalar_mul(scratch_space.as_mut(), m.as_ref(), beta_i);
 
         // Sample e_i
-        RandomFillGaussianInModulus::random_fill(rng, &q, bi.as_mut());
+        RandomFillGaussianInModulus::random_fill(rng, q, bi.as_mut());
         // e_i + beta_i * m + ai*s
         mod_op.elwise_add_mut(bi.as_mut(), scratch_space.as_ref());
         mod_op.elwise_add_mut(bi.as_mut(), ai.as_ref());
     });
+
+    wipe(scratch_space.as_mut());
+    a_rlwe_dash_m
+        .iter_rows_mut()
+        .for_each(|ai| wipe(ai.as_mut()));
 }
 
 /// Returns RGSW(m) encrypted with public key
@@ -185,7 +272,7 @@ pub(crate) fn public_key_encrypt_rgsw<
     rng: &mut R,
 ) where
     <Mmut as Matrix>::R: RowMut + RowEntity + TryConvertFrom1<[i32], ModOp::M>,
-    Mmut::MatElement: Copy,
+    Mmut::MatElement: Copy + Zero,
 {
     let ring_size = public_key.dimension().1;
     let d_a = gadget_a.len();
@@ -243,6 +330,9 @@ pub(crate) fn public_key_encrypt_rgsw<
         // use u_eval as scratch
         mod_op.elwise_scalar_mul(u_eval.as_mut(), m.as_ref(), beta_i);
         mod_op.elwise_add_mut(ai.as_mut(), u_eval.as_ref());
+
+        wipe(&mut u);
+        wipe(u_eval_copy.as_mut());
     });
 
     // RLWE(m)
@@ -282,6 +372,9 @@ pub(crate) fn public_key_encrypt_rgsw<
         // use u_eval as scratch
         mod_op.elwise_scalar_mul(u_eval.as_mut(), m.as_ref(), beta_i);
         mod_op.elwise_add_mut(bi.as_mut(), u_eval.as_ref());
+
+        wipe(&mut u);
+        wipe(u_eval_copy.as_mut());
     });
 }
 
@@ -299,7 +392,8 @@ pub(crate) fn public_key_encrypt_rgsw<
 ///
 /// - neg_from_s: Negative of secret polynomial to key switch from (i.e.
 ///   -from_s)
-/// - to_s: secret polynomial to key switch to.
+/// - mul_to_s: sets polynomial `p` to `p * to_s`, where to_s is secret
+///   polynomial to key switch to.
 /// - gadget_vector: Gadget vector of decomposer used in key switch
 /// - p_rng: Seeded pseudo random generate used to generate `a` polynomials of
 ///   key switching key RLWE ciphertexts
@@ -308,20 +402,20 @@ fn seeded_rlwe_ksk_gen<
     ModOp: ArithmeticOps<Element = Mmut::MatElement>
         + VectorOps<Element = Mmut::MatElement>
         + GetModulus<Element = Mmut::MatElement>,
-    NttOp: Ntt<Element = Mmut::MatElement>,
     R: RandomFillGaussianInModulus<[Mmut::MatElement], ModOp::M>,
     PR: RandomFillUniformInModulus<[Mmut::MatElement], ModOp::M>,
+    F: FnMut(&mut [Mmut::MatElement]),
 >(
     ksk_out: &mut Mmut,
-    neg_from_s: Mmut::R,
-    mut to_s: Mmut::R,
+    mut neg_from_s: Mmut::R,
+    mut mul_to_s: F,
     gadget_vector: &[Mmut::MatElement],
     mod_op: &ModOp,
-    ntt_op: &NttOp,
     p_rng: &mut PR,
     rng: &mut R,
 ) where
     <Mmut as Matrix>::R: RowMut,
+    Mmut::MatElement: Copy + Zero,
 {
     let ring_size = neg_from_s.as_ref().len();
     let d = gadget_vector.len();
@@ -329,8 +423,6 @@ fn seeded_rlwe_ksk_gen<
 
     let q = mod_op.modulus();
 
-    ntt_op.forward(to_s.as_mut());
-
     // RLWE'_{to_s}(-from_s)
     let mut part_a = {
         let mut a = Mmut::zeros(d, ring_size);
@@ -345,9 +437,7 @@ fn seeded_rlwe_ksk_gen<
     )
     .for_each(|(ai, bi, beta_i)| {
         // si * ai
-        ntt_op.forward(ai.as_mut());
-        mod_op.elwise_mul_mut(ai.as_mut(), to_s.as_ref());
-        ntt_op.backward(ai.as_mut());
+        mul_to_s(ai.as_mut());
 
         // ei + to_s*ai
         RandomFillGaussianInModulus::random_fill(rng, &q, bi.as_mut());
@@ -360,6 +450,36 @@ fn seeded_rlwe_ksk_gen<
         // bi = ei + to_s*ai + beta_i*-from_s
         mod_op.elwise_add_mut(bi.as_mut(), ai.as_ref());
     });
+
+    wipe(neg_from_s.as_mut());
+    part_a.iter_rows_mut().for_each(|ai| wipe(ai.as_mut()));
+}
+
+/// Returns -s(X^k) for secret polynomial s(X) mod q
+fn neg_auto_secret<R: RowMut + RowEntity, ModOp: ArithmeticOps<Element = R::Element>>(
+    s: &R,
+    auto_k: isize,
+    mod_op: &ModOp,
+) -> R
+where
+    R::Element: Copy,
+{
+    let ring_size = s.as_ref().len();
+    let (auto_map_index, auto_map_sign) = generate_auto_map(ring_size, auto_k);
+
+    let mut neg_s_auto = R::zeros(ring_size);
+    izip!(s.as_ref(), auto_map_index.iter(), auto_map_sign.iter()).for_each(
+        |(el, to_index, sign)| {
+            // if sign is +ve (true), then negate because we need -s(X) (i.e. do the
+            // opposite than the usual case)
+            if *sign {
+                neg_s_auto.as_mut()[*to_index] = mod_op.neg(el);
+            } else {
+                neg_s_auto.as_mut()[*to_index] = *el;
+            }
+        },
+    );
+    neg_s_auto
 }
 
 /// Returns auto key to send RLWE(m(X)) -> RLWE(m(X^k))
@@ -394,39 +514,73 @@ pub(crate) fn seeded_auto_key_gen<
 ) where
     <Mmut as Matrix>::R: RowMut,
     Mmut::R: TryConvertFrom1<[S], ModOp::M> + RowEntity,
-    Mmut::MatElement: Copy + Sub<Output = Mmut::MatElement>,
+    Mmut::MatElement: Copy + Zero + Sub<Output = Mmut::MatElement>,
 {
-    let ring_size = s.len();
-    let (auto_map_index, auto_map_sign) = generate_auto_map(ring_size, auto_k);
-
-    let q = mod_op.modulus();
-
     // s(X) -> -s(X^k)
-    let s = Mmut::R::try_convert_from(s, q);
-    let mut neg_s_auto = Mmut::R::zeros(s.as_ref().len());
-    izip!(s.as_ref(), auto_map_index.iter(), auto_map_sign.iter()).for_each(
-        |(el, to_index, sign)| {
-            // if sign is +ve (true), then negate because we need -s(X) (i.e. do the
-            // opposite than the usual case)
-            if *sign {
-                neg_s_auto.as_mut()[*to_index] = mod_op.neg(el);
-            } else {
-                neg_s_auto.as_mut()[*to_index] = *el;
-            }
+    let mut s = Mmut::R::try_convert_from(s, mod_op.modulus());
+    let neg_s_auto = neg_auto_secret(&s, auto_k, mod_op);
+
+    // Ksk from -s(X^k) to s(X)
+    ntt_op.forward(s.as_mut());
+    seeded_rlwe_ksk_gen(
+        ksk_out,
+        neg_s_auto,
+        |p: &mut [Mmut::MatElement]| {
+            ntt_op.forward(p);
+            mod_op.elwise_mul_mut(p, s.as_ref());
+            ntt_op.backward(p);
         },
+        gadget_vector,
+        mod_op,
+        p_rng,
+        rng,
     );
 
+    wipe(s.as_mut());
+}
+
+/// Same as `seeded_auto_key_gen` with products with secret `s` computed with
+/// `fft_op`. Supports native modulus, for which NTT does not exist.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(all(test, feature = "interactive_mp")), allow(dead_code))]
+pub(crate) fn seeded_auto_key_gen_fourier<
+    Mmut: MatrixMut<MatElement = u64> + MatrixEntity,
+    ModOp: ArithmeticOps<Element = u64> + VectorOps<Element = u64> + GetModulus<Element = u64>,
+    R: RandomFillGaussianInModulus<[u64], ModOp::M>,
+    PR: RandomFillUniformInModulus<[u64], ModOp::M>,
+>(
+    ksk_out: &mut Mmut,
+    s: &[i32],
+    auto_k: isize,
+    gadget_vector: &[u64],
+    mod_op: &ModOp,
+    fft_op: &FftBackendF64,
+    p_rng: &mut PR,
+    rng: &mut R,
+) where
+    <Mmut as Matrix>::R: RowMut + RowEntity + TryConvertFrom1<[i32], ModOp::M>,
+{
+    // s(X) -> -s(X^k)
+    let mut s = Mmut::R::try_convert_from(s, mod_op.modulus());
+    let neg_s_auto = neg_auto_secret(&s, auto_k, mod_op);
+
     // Ksk from -s(X^k) to s(X)
+    let mut scratch = vec![0u64; s.as_ref().len()];
     seeded_rlwe_ksk_gen(
         ksk_out,
         neg_s_auto,
-        s,
+        |p: &mut [u64]| {
+            scratch.copy_from_slice(p);
+            fft_op.mul_small(&scratch, s.as_ref(), p);
+        },
         gadget_vector,
         mod_op,
-        ntt_op,
         p_rng,
         rng,
     );
+
+    wipe(s.as_mut());
+    wipe(&mut scratch);
 }
 
 /// Returns seeded RLWE(m(X))
@@ -453,6 +607,7 @@ pub(crate) fn seeded_secret_key_encrypt_rlwe<
     rng: &mut R,
 ) where
     Ro: TryConvertFrom1<[S], ModOp::M> + Debug,
+    Ro::Element: Copy + Zero,
 {
     let ring_size = s.len();
     assert!(m.as_ref().len() == ring_size);
@@ -478,6 +633,8 @@ pub(crate) fn seeded_secret_key_encrypt_rlwe<
     RandomFillGaussianInModulus::random_fill(rng, q, b_rlwe_out.as_mut());
     mod_op.elwise_add_mut(b_rlwe_out.as_mut(), m.as_ref());
     mod_op.elwise_add_mut(b_rlwe_out.as_mut(), sa.as_ref());
+
+    wipe(sa.as_mut());
 }
 
 /// Returns RLWE(m(X)) encrypted using public key.
@@ -502,17 +659,18 @@ pub(crate) fn public_key_encrypt_rlwe<
     rng: &mut R,
 ) where
     <Mmut as Matrix>::R: RowMut + TryConvertFrom1<[S], ModOp::M> + RowEntity,
-    M::MatElement: Copy,
-    S: Zero + Signed + Copy,
+    M::MatElement: Copy + Zero,
+    S: Zero + Signed + Copy + From<i8>,
 {
     let ring_size = m.len();
     assert!(rlwe_out.dimension() == (2, ring_size));
 
     let q = mod_op.modulus();
 
-    let mut u = vec![S::zero(); ring_size];
-    fill_random_ternary_secret_with_hamming_weight(u.as_mut(), ring_size >> 1, rng);
-    let mut u = Mmut::R::try_convert_from(&u, q);
+    let mut u_s = vec![S::zero(); ring_size];
+    fill_random_ternary_secret_with_hamming_weight(u_s.as_mut(), ring_size >> 1, rng);
+    let mut u = Mmut::R::try_convert_from(&u_s, q);
+    wipe(&mut u_s);
     ntt_op.forward(u.as_mut());
 
     let mut ua = Mmut::R::zeros(ring_size);
@@ -542,6 +700,10 @@ pub(crate) fn public_key_encrypt_rlwe<
 
     // b*u + e1 + m
     mod_op.elwise_add_mut(rlwe_out.get_row_mut(1), m);
+
+    wipe(u.as_mut());
+    wipe(ua.as_mut());
+    wipe(ub.as_mut());
 }
 
 /// Returns RLWE public key generated using RLWE secret key
@@ -561,6 +723,7 @@ pub(crate) fn rlwe_public_key<
     rng: &mut Rng,
 ) where
     Ro: TryConvertFrom1<[S], ModOp::M>,
+    Ro::Element: Copy + Zero,
 {
     let ring_size = s.len();
     assert!(part_b_out.as_ref().len() == ring_size);
@@ -584,6 +747,8 @@ pub(crate) fn rlwe_public_key<
     // s*a + e
     RandomFillGaussianInModulus::random_fill(rng, &q, part_b_out.as_mut());
     mod_op.elwise_add_mut(part_b_out.as_mut(), sa.as_ref());
+
+    wipe(sa.as_mut());
 }
 
 /// Decrypts ciphertext RLWE(m) and returns noisy m
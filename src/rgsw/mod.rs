@@ -14,11 +14,13 @@ pub(crate) mod tests {
     use crate::{
         backend::{GetModulus, ModInit, ModularOpsU64, Modulus, VectorOps},
         decomposer::{Decomposer, DefaultDecomposer, RlweDecomposer},
+        fft::FftBackendF64,
         ntt::{Ntt, NttBackendU64, NttInit},
-        random::{DefaultSecureRng, NewWithSeed, RandomFillUniformInModulus},
+        random::{DefaultSecureRng, NewWithSeed, PrgAlgorithm, RandomFillUniformInModulus},
         rgsw::{
-            rlwe_auto_scratch_rows, rlwe_auto_shoup, rlwe_by_rgsw_shoup, rlwe_x_rgsw_scratch_rows,
-            RgswCiphertextRef, RlweCiphertextMutRef, RlweKskRef, RuntimeScratchMutRef,
+            rlwe_auto_fourier, rlwe_auto_scratch_rows, rlwe_auto_shoup, rlwe_by_rgsw_fourier,
+            rlwe_by_rgsw_shoup, rlwe_x_rgsw_scratch_rows, FourierScratch, RgswCiphertextRef,
+            RlweCiphertextMutRef, RlweKskRef, RuntimeScratchMutRef,
         },
         utils::{
             fill_random_ternary_secret_with_hamming_weight, generate_prime, negacyclic_mul,
@@ -142,6 +144,8 @@ pub(crate) mod tests {
     {
         pub(crate) data: M,
         seed: S,
+        /// PRG that expands the seed
+        prg: PrgAlgorithm,
         modulus: Mod,
         /// Decomposition for RLWE part A
         d_a: usize,
@@ -154,6 +158,7 @@ pub(crate) mod tests {
             ring_size: usize,
             decomposer: &D,
             seed: S,
+            prg: PrgAlgorithm,
             modulus: Mod,
         ) -> SeededRgswCiphertext<M, S, Mod> {
             SeededRgswCiphertext {
@@ -163,6 +168,7 @@ pub(crate) mod tests {
                     ring_size,
                 ),
                 seed,
+                prg,
                 modulus,
                 d_a: decomposer.a().decomposition_count().0,
                 d_b: decomposer.b().decomposition_count().0,
@@ -178,6 +184,7 @@ pub(crate) mod tests {
             f.debug_struct("SeededRgswCiphertext")
                 .field("data", &self.data)
                 .field("seed", &self.seed)
+                .field("prg", &self.prg)
                 .field("modulus", &self.modulus)
                 .finish()
         }
@@ -215,6 +222,11 @@ pub(crate) mod tests {
             });
 
             // sample A polynomials of RLWE'(m) - RLWE'A(m)
+            assert_eq!(
+                value.prg,
+                R::ALGORITHM,
+                "Seeded RGSW ciphertext was expanded with a different PRG"
+            );
             let mut p_rng = R::new_with_seed(value.seed.clone());
             izip!(data.iter_rows_mut().skip(value.d_a * 2).take(value.d_b * 1))
                 .for_each(|ri| p_rng.random_fill(&value.modulus, ri.as_mut()));
@@ -448,6 +460,7 @@ pub(crate) mod tests {
             ring_size as usize,
             decomposer,
             rgsw_seed,
+            DefaultSecureRng::ALGORITHM,
             q.clone(),
         );
         let mut p_rng = DefaultSecureRng::new_seeded(rgsw_seed);
@@ -629,6 +642,27 @@ pub(crate) mod tests {
             rlwe_in_ct_shoup
         };
 
+        // rlwe x rgsw with FFT
+        let rlwe_in_ct_fourier = {
+            let fft_op = FftBackendF64::new(q, ring_size as usize, logb, 2 * d_rgsw);
+            let mut rgsw_ct_coeff = rgsw_ct.data.clone();
+            rgsw_ct_coeff
+                .iter_mut()
+                .for_each(|r| ntt_op.backward(r.as_mut()));
+            let rgsw_ct_fourier = fft_op.forward_split_rows(&rgsw_ct_coeff);
+
+            let mut rlwe_in_ct_fourier = rlwe_in_ct.data.clone();
+            rlwe_by_rgsw_fourier(
+                &mut RlweCiphertextMutRef::new(rlwe_in_ct_fourier.as_mut()),
+                &rgsw_ct_fourier,
+                &mut FourierScratch::new(&fft_op, d_rgsw),
+                &decomposer,
+                &fft_op,
+                false,
+            );
+            rlwe_in_ct_fourier
+        };
+
         // rlwe x rgsw normal
         {
             rlwe_by_rgsw(
@@ -646,8 +680,9 @@ pub(crate) mod tests {
             );
         }
 
-        // output from both functions must be equal
+        // output from all functions must be equal
         assert_eq!(rlwe_in_ct.data, rlwe_in_ct_shoup);
+        assert_eq!(rlwe_in_ct.data, rlwe_in_ct_fourier);
 
         // Decrypt RLWE(m0m1)
         let mut encoded_m0m1_back = vec![0u64; ring_size as usize];
@@ -685,6 +720,111 @@ pub(crate) mod tests {
         );
     }
 
+    #[test]
+    fn native_rlwe_by_rgsw_works() {
+        let logp = 2;
+        let ring_size = 1 << 10;
+        let p: u64 = 1u64 << logp;
+        let d_rgsw = 3;
+        let logb = 17;
+
+        let mut rng = thread_rng();
+        let s = RlweSecret::random(ring_size >> 1, ring_size);
+        let s_u64 = s.values().iter().map(|v| *v as i64 as u64).collect_vec();
+
+        let fft_op = FftBackendF64::new_native(ring_size, logb, 2 * d_rgsw);
+        // q = 0 for native modulus
+        let decomposer = (
+            DefaultDecomposer::new(0u64, logb, d_rgsw),
+            DefaultDecomposer::new(0u64, logb, d_rgsw),
+        );
+
+        // RLWE(m) = (a, a*s + e + m) mod 2^64
+        let mut encrypt = |m: &[u64], a: &mut [u64], b: &mut [u64]| {
+            a.iter_mut().for_each(|v| *v = rng.gen());
+            fft_op.mul_small(a, &s_u64, b);
+            izip!(b.iter_mut(), m.iter()).for_each(|(b, m)| {
+                let e = rng.gen_range(-8i64..=8) as u64;
+                *b = b.wrapping_add(e).wrapping_add(*m);
+            });
+        };
+
+        let mut m0 = vec![0u64; ring_size];
+        m0.iter_mut()
+            .for_each(|v| *v = thread_rng().gen_range(0..p));
+        let mut m1 = vec![0u64; ring_size];
+        let k = thread_rng().gen_range(0..ring_size);
+        m1[k] = 1;
+
+        // RGSW(m1) = [RLWE'(-sm1), RLWE'(m1)] in Fourier domain
+        let rgsw_ct_fourier = {
+            let mut rgsw_ct = vec![vec![0u64; ring_size]; d_rgsw * 4];
+            let (rlwe_dash_nsm, rlwe_dash_m) = rgsw_ct.split_at_mut(d_rgsw * 2);
+            let (nsm_a, nsm_b) = rlwe_dash_nsm.split_at_mut(d_rgsw);
+            let (m_a, m_b) = rlwe_dash_m.split_at_mut(d_rgsw);
+            let zero = vec![0u64; ring_size];
+            izip!(
+                nsm_a.iter_mut(),
+                nsm_b.iter_mut(),
+                decomposer.a().gadget_vector().iter()
+            )
+            .for_each(|(a, b, beta)| {
+                // RLWE(-s \beta m1) = (a + \beta m1, a*s + e)
+                encrypt(&zero, a, b);
+                izip!(a.iter_mut(), m1.iter())
+                    .for_each(|(a, m)| *a = a.wrapping_add(beta.wrapping_mul(*m)));
+            });
+            izip!(
+                m_a.iter_mut(),
+                m_b.iter_mut(),
+                decomposer.b().gadget_vector().iter()
+            )
+            .for_each(|(a, b, beta)| {
+                let beta_m = m1.iter().map(|m| beta.wrapping_mul(*m)).collect_vec();
+                encrypt(&beta_m, a, b);
+            });
+            fft_op.forward_split_rows(&rgsw_ct)
+        };
+
+        // RLWE(m0)
+        let mut rlwe_ct = vec![vec![0u64; ring_size]; 2];
+        {
+            let encoded_m0 = m0.iter().map(|v| v << (64 - logp)).collect_vec();
+            let (a, b) = rlwe_ct.split_at_mut(1);
+            encrypt(&encoded_m0, &mut a[0], &mut b[0]);
+        }
+
+        // RLWE(m0m1) = RLWE(m0) x RGSW(m1)
+        rlwe_by_rgsw_fourier(
+            &mut RlweCiphertextMutRef::new(rlwe_ct.as_mut()),
+            &rgsw_ct_fourier,
+            &mut FourierScratch::new(&fft_op, d_rgsw),
+            &decomposer,
+            &fft_op,
+            false,
+        );
+
+        // Decrypt RLWE(m0m1) = b - a*s
+        let mut encoded_m0m1_back = vec![0u64; ring_size];
+        fft_op.mul_small(&rlwe_ct[0], &s_u64, &mut encoded_m0m1_back);
+        izip!(encoded_m0m1_back.iter_mut(), rlwe_ct[1].iter())
+            .for_each(|(m, b)| *m = b.wrapping_sub(*m));
+        let m0m1_back = encoded_m0m1_back
+            .iter()
+            .map(|v| (v.wrapping_add(1 << (63 - logp)) >> (64 - logp)) % p)
+            .collect_vec();
+
+        let mul_mod = |v0: &u64, v1: &u64| (v0 * v1) % p;
+        let m0m1 = negacyclic_mul(&m0, &m1, mul_mod, p);
+
+        assert!(
+            m0m1 == m0m1_back,
+            "Expected {:?} \n Got {:?}",
+            m0m1,
+            m0m1_back
+        );
+    }
+
     #[test]
     fn rlwe_auto_works() {
         let logq = 55;
@@ -771,6 +911,30 @@ pub(crate) mod tests {
             rlwe_m_shoup
         };
 
+        // galois auto with FFT
+        let rlwe_m_fourier = {
+            let fft_op = FftBackendF64::new(q, ring_size as usize, logb, d_rgsw);
+            let mut auto_key_coeff = auto_key.data.clone();
+            auto_key_coeff
+                .iter_mut()
+                .for_each(|r| ntt_op.backward(r.as_mut()));
+            let auto_key_fourier = fft_op.forward_split_rows(&auto_key_coeff);
+
+            let mut rlwe_m_fourier = rlwe_m.data.clone();
+            rlwe_auto_fourier(
+                &mut RlweCiphertextMutRef::new(&mut rlwe_m_fourier),
+                &auto_key_fourier,
+                &mut FourierScratch::new(&fft_op, d_rgsw),
+                &auto_map_index,
+                &auto_map_sign,
+                &mod_op,
+                &fft_op,
+                &decomposer,
+                false,
+            );
+            rlwe_m_fourier
+        };
+
         // normal galois auto
         {
             rlwe_auto(
@@ -786,8 +950,9 @@ pub(crate) mod tests {
             );
         }
 
-        // rlwe out from both functions must be same
+        // rlwe out from all functions must be same
         assert_eq!(rlwe_m.data, rlwe_m_shoup);
+        assert_eq!(rlwe_m.data, rlwe_m_fourier);
 
         let rlwe_m_k = rlwe_m;
 
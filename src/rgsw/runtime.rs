@@ -4,6 +4,7 @@ use num_traits::Zero;
 use crate::{
     backend::{ArithmeticOps, GetModulus, ShoupMatrixFMA, VectorOps},
     decomposer::{Decomposer, RlweDecomposer},
+    fft::{Complex64, FftBackendF64, FourierSplitPoly},
     ntt::Ntt,
     parameters::{DecompositionCount, DoubleDecomposerParams, SingleDecomposerParams},
     Matrix, MatrixEntity, MatrixMut, Row, RowEntity, RowMut,
@@ -854,6 +855,222 @@ pub(crate) fn rlwe_by_rgsw_shoup<
     rlwe_in.part_b_mut().copy_from_slice(tmp_rlwe[1].as_mut());
 }
 
+/// Scratch space for RLWE x RGSW product and RLWE automorphism with FFT
+/// backend
+pub(crate) struct FourierScratch {
+    /// Decomposed polynomials in coefficient domain
+    decomposed: Vec<Vec<u64>>,
+    /// A decomposed polynomial in Fourier domain
+    decomposed_fourier: Vec<Complex64>,
+    /// Output RLWE ciphertext in Fourier domain in split representation
+    rlwe: [FourierSplitPoly; 2],
+    /// Temporary polynomial in coefficient domain
+    tmp: Vec<u64>,
+}
+
+impl FourierScratch {
+    /// Returns scratch space for decompositions with at most `d` polynomials
+    pub(crate) fn new(fft_op: &FftBackendF64, d: usize) -> Self {
+        let ring_size = fft_op.ring_size();
+        let split_poly = || vec![vec![Complex64::default(); ring_size >> 1]; fft_op.limbs()];
+        FourierScratch {
+            decomposed: vec![vec![0u64; ring_size]; d],
+            decomposed_fourier: vec![Complex64::default(); ring_size >> 1],
+            rlwe: [split_poly(), split_poly()],
+            tmp: vec![0u64; ring_size],
+        }
+    }
+
+    fn zero_rlwe(&mut self) {
+        self.rlwe
+            .iter_mut()
+            .flatten()
+            .for_each(|r| r.fill(Complex64::default()));
+    }
+}
+
+/// Inplace mutates RLWE(m0) to equal RLWE(m0m1) = RLWE(m0) x RGSW(m1).
+///
+/// Same as `rlwe_by_rgsw` with the difference that polynomial products are
+/// computed with f64 FFT. Output equals output of `rlwe_by_rgsw` as long as
+/// `fft_op` supports decomposition log base of `decomposer` and d_a + d_b
+/// products.
+///
+/// - rgsw_in: RGSW(m1) with polynomials in Fourier domain in split
+///   representation. Polynomials are ordered same as in RGSW(m1) in evaluation
+///   domain.
+pub(crate) fn rlwe_by_rgsw_fourier<Rlwe: RlweCiphertext, D: RlweDecomposer<Element = u64>>(
+    rlwe_in: &mut Rlwe,
+    rgsw_in: &[FourierSplitPoly],
+    scratch: &mut FourierScratch,
+    decomposer: &D,
+    fft_op: &FftBackendF64,
+    is_trivial: bool,
+) where
+    Rlwe::R: RowMut<Element = u64>,
+{
+    let d_a = decomposer.decomposition_count_a().0;
+    let d_b = decomposer.decomposition_count_b().0;
+
+    let (rlwe_dash_nsm, rlwe_dash_m) = rgsw_in.split_at(d_a * 2);
+    let (rlwe_dash_nsm_parta, rlwe_dash_nsm_partb) = rlwe_dash_nsm.split_at(d_a);
+    let (rlwe_dash_m_parta, rlwe_dash_m_partb) = rlwe_dash_m.split_at(d_b);
+
+    scratch.zero_rlwe();
+    let FourierScratch {
+        decomposed,
+        decomposed_fourier,
+        rlwe: [tmp_rlwe_a, tmp_rlwe_b],
+        ..
+    } = scratch;
+
+    // RLWE_in = a_in, b_in; RLWE_out = a_out, b_out
+    if !is_trivial {
+        // a_in = 0 when RLWE_in is trivial RLWE ciphertext
+        // decomp<a_in>
+        decompose_r(rlwe_in.part_a(), &mut decomposed[..d_a], decomposer.a());
+        izip!(
+            decomposed.iter(),
+            rlwe_dash_nsm_parta.iter(),
+            rlwe_dash_nsm_partb.iter()
+        )
+        .for_each(|(r, nsm_a, nsm_b)| {
+            fft_op.forward(r, decomposed_fourier);
+            // a_out += decomp<a_in> \cdot RLWE_A'(-sm)
+            fft_op.fma_split(tmp_rlwe_a, decomposed_fourier, nsm_a);
+            // b_out += decomp<a_in> \cdot RLWE_B'(-sm)
+            fft_op.fma_split(tmp_rlwe_b, decomposed_fourier, nsm_b);
+        });
+    }
+
+    {
+        // decomp<b_in>
+        decompose_r(rlwe_in.part_b(), &mut decomposed[..d_b], decomposer.b());
+        izip!(
+            decomposed.iter(),
+            rlwe_dash_m_parta.iter(),
+            rlwe_dash_m_partb.iter()
+        )
+        .for_each(|(r, m_a, m_b)| {
+            fft_op.forward(r, decomposed_fourier);
+            // a_out += decomp<b_in> \cdot RLWE_A'(m)
+            fft_op.fma_split(tmp_rlwe_a, decomposed_fourier, m_a);
+            // b_out += decomp<b_in> \cdot RLWE_B'(m)
+            fft_op.fma_split(tmp_rlwe_b, decomposed_fourier, m_b);
+        });
+    }
+
+    // transform rlwe_out to coefficient domain
+    fft_op.backward_split(tmp_rlwe_a, rlwe_in.part_a_mut());
+    fft_op.backward_split(tmp_rlwe_b, rlwe_in.part_b_mut());
+}
+
+/// Sends RLWE_{s(X)}(m(X)) -> RLWE_{s(X)}(m{X^k}) where k is some galois
+/// element
+///
+/// Same as `rlwe_auto` with the difference that polynomial products are
+/// computed with f64 FFT and `ksk` has polynomials in Fourier domain in split
+/// representation. Output equals output of `rlwe_auto` as long as `fft_op`
+/// supports decomposition log base of `decomposer` and d products.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn rlwe_auto_fourier<
+    Rlwe: RlweCiphertext,
+    ModOp: ArithmeticOps<Element = u64>,
+    D: Decomposer<Element = u64>,
+>(
+    rlwe_in: &mut Rlwe,
+    ksk: &[FourierSplitPoly],
+    scratch: &mut FourierScratch,
+    auto_map_index: &[usize],
+    auto_map_sign: &[bool],
+    mod_op: &ModOp,
+    fft_op: &FftBackendF64,
+    decomposer: &D,
+    is_trivial: bool,
+) where
+    Rlwe::R: RowMut<Element = u64>,
+{
+    let d = decomposer.decomposition_count().0;
+
+    if !is_trivial {
+        let (ksk_part_a, ksk_part_b) = ksk.split_at(d);
+
+        scratch.zero_rlwe();
+        let FourierScratch {
+            decomposed,
+            decomposed_fourier,
+            rlwe: [tmp_rlwe_a, tmp_rlwe_b],
+            tmp,
+        } = scratch;
+
+        // send a(X) -> a(X^k) and decompose a(X^k)
+        izip!(
+            rlwe_in.part_a(),
+            auto_map_index.iter(),
+            auto_map_sign.iter()
+        )
+        .for_each(|(el_in, to_index, sign)| {
+            let el_out = if !*sign { mod_op.neg(el_in) } else { *el_in };
+
+            decomposer
+                .decompose_iter(&el_out)
+                .enumerate()
+                .for_each(|(index, el)| {
+                    decomposed[index][*to_index] = el;
+                });
+        });
+
+        // a' = decomp<a(X^k)> * RLWE'_A(s(X^k))
+        // b' = decomp<a(X^k)> * RLWE'_B(s(X^k))
+        izip!(
+            decomposed.iter().take(d),
+            ksk_part_a.iter(),
+            ksk_part_b.iter()
+        )
+        .for_each(|(r, ksk_a, ksk_b)| {
+            fft_op.forward(r, decomposed_fourier);
+            fft_op.fma_split(tmp_rlwe_a, decomposed_fourier, ksk_a);
+            fft_op.fma_split(tmp_rlwe_b, decomposed_fourier, ksk_b);
+        });
+
+        // transform RLWE(m^k) to coefficient domain
+        fft_op.backward_split(tmp_rlwe_a, rlwe_in.part_a_mut());
+        fft_op.backward_split(tmp_rlwe_b, tmp);
+
+        // send b(X) -> b(X^k) and then b'(X) += b(X^k)
+        izip!(
+            rlwe_in.part_b(),
+            auto_map_index.iter(),
+            auto_map_sign.iter()
+        )
+        .for_each(|(el_in, to_index, sign)| {
+            if !*sign {
+                tmp[*to_index] = mod_op.sub(&tmp[*to_index], el_in);
+            } else {
+                tmp[*to_index] = mod_op.add(&tmp[*to_index], el_in);
+            }
+        });
+        rlwe_in.part_b_mut().copy_from_slice(tmp);
+    } else {
+        // RLWE is trivial, a(X) is 0.
+        // send b(X) -> b(X^k)
+        let tmp = &mut scratch.tmp;
+        izip!(
+            rlwe_in.part_b(),
+            auto_map_index.iter(),
+            auto_map_sign.iter()
+        )
+        .for_each(|(el_in, to_index, sign)| {
+            if !*sign {
+                tmp[*to_index] = mod_op.neg(el_in);
+            } else {
+                tmp[*to_index] = *el_in;
+            }
+        });
+        rlwe_in.part_b_mut().copy_from_slice(tmp);
+    }
+}
+
 /// Inplace mutates RGSW(m0) to equal RGSW(m0m1) = RGSW(m0)xRGSW(m1)
 ///
 /// RGSW x RGSW product requires multiple RLWE x RGSW products. For example,
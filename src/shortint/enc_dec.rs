@@ -2,10 +2,11 @@ use itertools::Itertools;
 
 use crate::{
     bool::BoolEvaluator,
-    random::{DefaultSecureRng, RandomFillUniformInModulus},
+    random::{DefaultSecureRng, NewWithSeed, PrgAlgorithm, RandomFillUniformInModulus},
     utils::WithLocal,
-    Decryptor, Encryptor, KeySwitchWithId, Matrix, MatrixEntity, MatrixMut, MultiPartyDecryptor,
-    RowMut, SampleExtractor,
+    Decryptor, Encryptor, KeyRefresher, KeySwitchWithId, MalformedDecryptionShare, Matrix,
+    MatrixEntity, MatrixMut, MultiPartyDecryptor, RowMut, SampleExtractor,
+    VerifiableMultiPartyDecryptor,
 };
 
 /// Fhe UInt8
@@ -78,6 +79,11 @@ where
             let ring_size = parameters.rlwe_n().0;
             let rlwe_q = parameters.rlwe_q();
 
+            assert_eq!(
+                value.prg,
+                DefaultSecureRng::ALGORITHM,
+                "Seeded ciphertexts were expanded with a different PRG"
+            );
             let mut prng = DefaultSecureRng::new_seeded(value.seed);
             let rlwes = value
                 .data
@@ -180,6 +186,11 @@ where
             let ring_size = parameters.rlwe_n().0;
             let rlwe_q = parameters.rlwe_q();
 
+            assert_eq!(
+                value.prg,
+                DefaultSecureRng::ALGORITHM,
+                "Seeded ciphertexts were expanded with a different PRG"
+            );
             let mut prng = DefaultSecureRng::new_seeded(value.seed);
             let rlwes = value
                 .data
@@ -235,6 +246,8 @@ pub struct SeededBatchedFheUint8<C, S> {
     seed: S,
     /// Count of FheUint8s packed in vector of RLWE ciphertexts
     count: usize,
+    /// PRG that expands the seed
+    prg: PrgAlgorithm,
 }
 
 impl<K, C, S> Encryptor<[u8], SeededBatchedFheUint8<C, S>> for K
@@ -254,11 +267,18 @@ where
             data: cts,
             seed,
             count: m.len(),
+            prg: DefaultSecureRng::ALGORITHM,
         }
     }
 }
 
 impl<C, S> SeededBatchedFheUint8<C, S> {
+    /// PRG that expands the seed of the ciphertexts. Unseeding panics if it
+    /// differs from PRG selected by this build.
+    pub fn prg(&self) -> PrgAlgorithm {
+        self.prg
+    }
+
     /// Unseed collection of seeded RLWE ciphertexts of `Self` and returns
     /// `NonInteractiveBatchedFheUint8` with collection of unseeded RLWE
     /// ciphertexts.
@@ -297,6 +317,23 @@ impl<C, S> SeededBatchedFheUint8<C, S> {
     }
 }
 
+impl<C, K> KeyRefresher<FheUint8<C>> for K
+where
+    K: KeyRefresher<C>,
+{
+    /// Key switch each bit of `c` to the refreshed key
+    fn refresh(&self, c: &FheUint8<C>) -> FheUint8<C> {
+        assert!(c.data().len() == 8);
+        FheUint8 {
+            data: c
+                .data()
+                .iter()
+                .map(|bit_c| self.refresh(bit_c))
+                .collect_vec(),
+        }
+    }
+}
+
 impl<C, K> MultiPartyDecryptor<u8, FheUint8<C>> for K
 where
     K: MultiPartyDecryptor<bool, C>,
@@ -336,6 +373,80 @@ where
     }
 }
 
+impl<C, K> VerifiableMultiPartyDecryptor<u8, FheUint8<C>> for K
+where
+    K: VerifiableMultiPartyDecryptor<bool, C>,
+    <Self as MultiPartyDecryptor<bool, C>>::DecryptionShare: Clone,
+    <Self as VerifiableMultiPartyDecryptor<bool, C>>::DecryptionShareProof: Clone,
+{
+    type DecryptionShareProof =
+        Vec<<Self as VerifiableMultiPartyDecryptor<bool, C>>::DecryptionShareProof>;
+    type PublicKeyShare = <Self as VerifiableMultiPartyDecryptor<bool, C>>::PublicKeyShare;
+
+    fn gen_decryption_share_with_proof(
+        &self,
+        c: &FheUint8<C>,
+        public_key_share: &Self::PublicKeyShare,
+    ) -> (Self::DecryptionShare, Self::DecryptionShareProof) {
+        assert!(c.data().len() == 8);
+        c.data()
+            .iter()
+            .map(|bit_c| {
+                VerifiableMultiPartyDecryptor::<bool, C>::gen_decryption_share_with_proof(
+                    self,
+                    bit_c,
+                    public_key_share,
+                )
+            })
+            .unzip()
+    }
+
+    fn aggregate_decryption_shares_verified(
+        &self,
+        c: &FheUint8<C>,
+        shares: &[Self::DecryptionShare],
+        proofs: &[Self::DecryptionShareProof],
+        public_key_shares: &[Self::PublicKeyShare],
+    ) -> Result<u8, MalformedDecryptionShare> {
+        if let Some(share_index) = shares
+            .iter()
+            .map(|s| s.len())
+            .chain(proofs.iter().map(|p| p.len()))
+            .position(|len| len != 8)
+        {
+            return Err(MalformedDecryptionShare {
+                share_index: share_index % shares.len().max(1),
+                reason: "Decryption share or proof is not of 8 bits".to_string(),
+            });
+        }
+
+        let mut out = 0u8;
+        for i in 0..8 {
+            // Collect bit i^th decryption share and proof of each party
+            let bit_i_decryption_shares = shares.iter().map(|s| s[i].clone()).collect_vec();
+            let bit_i_proofs = proofs.iter().map(|p| p[i].clone()).collect_vec();
+            let bit_i =
+                VerifiableMultiPartyDecryptor::<bool, C>::aggregate_decryption_shares_verified(
+                    self,
+                    &c.data()[i],
+                    &bit_i_decryption_shares,
+                    &bit_i_proofs,
+                    public_key_shares,
+                )
+                .map_err(|e| MalformedDecryptionShare {
+                    share_index: e.share_index,
+                    reason: format!("bit {i}: {}", e.reason),
+                })?;
+
+            if bit_i {
+                out += 1 << i;
+            }
+        }
+
+        Ok(out)
+    }
+}
+
 impl<C, K> Encryptor<u8, FheUint8<C>> for K
 where
     K: Encryptor<bool, C>,
@@ -5,6 +5,15 @@ pub type FheUint8 = enc_dec::FheUint8<Vec<u64>>;
 
 pub type EncFheUint8 = enc_dec::SeededBatchedFheUint8<Vec<u64>, [u8; 32]>;
 
+/// Batch of FheUint8s packed in RLWE ciphertexts encrypted under the ideal
+/// RLWE secret of the MPC protocol.
+///
+/// In interactive multi-party protocol anyone with access to collective public
+/// key can produce `BatchedFheUint8` with `pk.encrypt(&[u8])`. Individual
+/// FheUint8s are extracted with `extract_at`, `extract_many`, or
+/// `extract_all`.
+pub type BatchedFheUint8 = enc_dec::BatchedFheUint8<Vec<Vec<u64>>>;
+
 use std::cell::RefCell;
 
 use crate::bool::{BoolEvaluator, BooleanGates, FheBool, RuntimeServerKey};
@@ -48,7 +57,7 @@ mod frontend {
                 let is_zero = is_zero(e, denominator.data(), key);
                 DIV_ZERO_ERROR.with_borrow_mut(|before_is_zero| {
                     if before_is_zero.is_none() {
-                        *before_is_zero = Some(FheBool { data: is_zero });
+                        *before_is_zero = Some(FheBool::new(is_zero));
                     } else {
                         e.or_inplace(before_is_zero.as_mut().unwrap().data_mut(), &is_zero, key);
                     }
@@ -148,7 +157,7 @@ mod frontend {
                     let key = RuntimeServerKey::global();
                     let (overflow, _) =
                         arbitrary_bit_adder(e, self.data_mut(), rhs.data(), false, key);
-                    FheBool { data: overflow }
+                    FheBool::new(overflow)
                 })
             }
 
@@ -162,7 +171,7 @@ mod frontend {
                     let key = RuntimeServerKey::global();
                     let (overflow, _) =
                         arbitrary_bit_adder(e, lhs.data_mut(), rhs.data(), false, key);
-                    (lhs, FheBool { data: overflow })
+                    (lhs, FheBool::new(overflow))
                 })
             }
 
@@ -176,7 +185,7 @@ mod frontend {
                     let (out, mut overflow, _) =
                         arbitrary_bit_subtractor(e, self.data(), rhs.data(), key);
                     e.not_inplace(&mut overflow);
-                    (FheUint8 { data: out }, FheBool { data: overflow })
+                    (FheUint8 { data: out }, FheBool::new(overflow))
                 })
             }
 
@@ -218,7 +227,7 @@ mod frontend {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
                     let out = arbitrary_bit_equality(e, self.data(), other.data(), key);
-                    FheBool { data: out }
+                    FheBool::new(out)
                 })
             }
 
@@ -228,7 +237,7 @@ mod frontend {
                     let key = RuntimeServerKey::global();
                     let mut is_equal = arbitrary_bit_equality(e, self.data(), other.data(), key);
                     e.not_inplace(&mut is_equal);
-                    FheBool { data: is_equal }
+                    FheBool::new(is_equal)
                 })
             }
 
@@ -237,7 +246,7 @@ mod frontend {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
                     let out = arbitrary_bit_comparator(e, other.data(), self.data(), key);
-                    FheBool { data: out }
+                    FheBool::new(out)
                 })
             }
 
@@ -246,7 +255,7 @@ mod frontend {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
                     let out = arbitrary_bit_comparator(e, self.data(), other.data(), key);
-                    FheBool { data: out }
+                    FheBool::new(out)
                 })
             }
 
@@ -257,7 +266,7 @@ mod frontend {
                     let mut a_greater_b =
                         arbitrary_bit_comparator(e, self.data(), other.data(), key);
                     e.not_inplace(&mut a_greater_b);
-                    FheBool { data: a_greater_b }
+                    FheBool::new(a_greater_b)
                 })
             }
 
@@ -267,7 +276,7 @@ mod frontend {
                     let key = RuntimeServerKey::global();
                     let mut a_less_b = arbitrary_bit_comparator(e, other.data(), self.data(), key);
                     e.not_inplace(&mut a_less_b);
-                    FheBool { data: a_less_b }
+                    FheBool::new(a_less_b)
                 })
             }
 
@@ -275,6 +284,7 @@ mod frontend {
             pub fn mux(&self, other: &FheUint8, selector: &FheBool) -> FheUint8 {
                 BoolEvaluator::with_local_mut(|e| {
                     let key = RuntimeServerKey::global();
+                    let selector = selector.bootstrapped(e, key);
                     let out = arbitrary_bit_mux(e, selector.data(), self.data(), other.data(), key);
                     FheUint8 { data: out }
                 })
@@ -1,6 +1,6 @@
 use itertools::{izip, Itertools};
 
-use crate::bool::BooleanGates;
+use crate::bool::{BoolGate, BooleanGates};
 
 pub(super) fn half_adder<E: BooleanGates>(
     evaluator: &mut E,
@@ -20,16 +20,14 @@ pub(super) fn full_adder_plain_carry_in<E: BooleanGates>(
     carry_in: bool,
     key: &E::Key,
 ) -> E::Ciphertext {
-    let mut a_and_b = evaluator.and(a, b, key);
-    evaluator.xor_inplace(a, b, key); //a = a ^ b
-    if carry_in {
-        // a_and_b = A & B | ((A^B) & C_in={True})
-        evaluator.or_inplace(&mut a_and_b, &a, key);
+    let carry = if carry_in {
+        // carry = A & B | ((A^B) & C_in={True}) = A | B
+        evaluator.or(a, b, key)
     } else {
-        // a_and_b = A & B | ((A^B) & C_in={False})
-        // a_and_b = A & B
-        // noop
-    }
+        // carry = A & B | ((A^B) & C_in={False}) = A & B
+        evaluator.and(a, b, key)
+    };
+    evaluator.xor_inplace(a, b, key); //a = a ^ b
 
     // In xor if a input is 0, output equals the firt variable. If input is 1 then
     // output equals !(first variable)
@@ -40,7 +38,7 @@ pub(super) fn full_adder_plain_carry_in<E: BooleanGates>(
         // (A^B)^0
         // no-op
     }
-    a_and_b
+    carry
 }
 
 pub(super) fn full_adder<E: BooleanGates>(
@@ -50,12 +48,9 @@ pub(super) fn full_adder<E: BooleanGates>(
     carry_in: &E::Ciphertext,
     key: &E::Key,
 ) -> E::Ciphertext {
-    let mut a_and_b = evaluator.and(a, b, key);
-    evaluator.xor_inplace(a, b, key); //a = a ^ b
-    let a_xor_b_and_c = evaluator.and(&a, carry_in, key);
-    evaluator.or_inplace(&mut a_and_b, &a_xor_b_and_c, key); // a_and_b = A & B | ((A^B) & C_in)
-    evaluator.xor_inplace(a, &carry_in, key);
-    a_and_b
+    let carry = evaluator.maj3(a, b, carry_in, key);
+    evaluator.xor3_inplace(a, b, carry_in, key); //a = a ^ b ^ c_in
+    carry
 }
 
 pub(super) fn arbitrary_bit_adder<E: BooleanGates>(
@@ -111,13 +106,7 @@ pub(super) fn bit_mux<E: BooleanGates>(
     if_false: &E::Ciphertext,
     key: &E::Key,
 ) -> E::Ciphertext {
-    // (s&a) | ((1-s)^b)
-    let not_selector = evaluator.not(&selector);
-
-    let mut s_and_a = evaluator.and(&selector, if_true, key);
-    let s_and_b = evaluator.and(&not_selector, if_false, key);
-    evaluator.or(&mut s_and_a, &s_and_b, key);
-    s_and_a
+    evaluator.mux(&selector, if_true, if_false, key)
 }
 
 pub(super) fn arbitrary_bit_mux<E: BooleanGates>(
@@ -126,18 +115,11 @@ pub(super) fn arbitrary_bit_mux<E: BooleanGates>(
     if_true: &[E::Ciphertext],
     if_false: &[E::Ciphertext],
     key: &E::Key,
-) -> Vec<E::Ciphertext> {
-    // (s&a) | ((1-s)^b)
-    let not_selector = evaluator.not(&selector);
-
-    izip!(if_true.iter(), if_false.iter())
-        .map(|(a, b)| {
-            let mut s_and_a = evaluator.and(&selector, a, key);
-            let s_and_b = evaluator.and(&not_selector, b, key);
-            evaluator.or_inplace(&mut s_and_a, &s_and_b, key);
-            s_and_a
-        })
-        .collect()
+) -> Vec<E::Ciphertext>
+where
+    E::Ciphertext: Clone,
+{
+    evaluator.batch_mux(selector, if_true, if_false, key)
 }
 
 pub(super) fn eight_bit_mul<E: BooleanGates>(
@@ -145,36 +127,50 @@ pub(super) fn eight_bit_mul<E: BooleanGates>(
     a: &[E::Ciphertext],
     b: &[E::Ciphertext],
     key: &E::Key,
-) -> Vec<E::Ciphertext> {
+) -> Vec<E::Ciphertext>
+where
+    E::Ciphertext: Clone,
+{
     assert!(a.len() == 8);
     assert!(b.len() == 8);
+
+    // Partial products a[i - j] & b[j] for 0 <= j <= i < 8 are independent of
+    // each other, hence they are evaluated in a single batch in the order they
+    // are added.
+    let (mut products, b_j): (Vec<_>, Vec<_>) = (0..8)
+        .flat_map(|i| (0..=i).map(move |j| (i, j)))
+        .map(|(i, j)| (a[i - j].clone(), b[j].clone()))
+        .unzip();
+    evaluator.batch_gate(BoolGate::And, &mut products, &b_j, key);
+    let mut products = products.into_iter();
+
     let mut carries = Vec::with_capacity(7);
     let mut out = Vec::with_capacity(8);
 
     for i in 0..8 {
         if i == 0 {
-            let s = evaluator.and(&a[0], &b[0], key);
+            let s = products.next().unwrap();
             out.push(s);
         } else if i == 1 {
-            let mut tmp0 = evaluator.and(&a[1], &b[0], key);
-            let tmp1 = evaluator.and(&a[0], &b[1], key);
+            let mut tmp0 = products.next().unwrap();
+            let tmp1 = products.next().unwrap();
             let carry = half_adder(evaluator, &mut tmp0, &tmp1, key);
             carries.push(carry);
             out.push(tmp0);
         } else {
             let mut sum = {
-                let mut sum = evaluator.and(&a[i], &b[0], key);
-                let tmp = evaluator.and(&a[i - 1], &b[1], key);
+                let mut sum = products.next().unwrap();
+                let tmp = products.next().unwrap();
                 carries[0] = full_adder(evaluator, &mut sum, &tmp, &carries[0], key);
                 sum
             };
 
             for j in 2..i {
-                let tmp = evaluator.and(&a[i - j], &b[j], key);
+                let tmp = products.next().unwrap();
                 carries[j - 1] = full_adder(evaluator, &mut sum, &tmp, &carries[j - 1], key);
             }
 
-            let tmp = evaluator.and(&a[0], &b[i], key);
+            let tmp = products.next().unwrap();
             let carry = half_adder(evaluator, &mut sum, &tmp, key);
             carries.push(carry);
 
@@ -235,17 +231,9 @@ where
             evaluator.and_inplace(&mut carry, &neg_b[j], key);
         }
 
-        let not_carry = evaluator.not(&carry);
         // Choose `remainder` if subtraction has overflown (i.e. carry = false).
         // Otherwise choose `subtractor`.
-        //
-        // mux k^a | !(k)^b, where k is the selector.
-        izip!(remainder.iter_mut(), subtract.iter_mut()).for_each(|(r, s)| {
-            // choose `s` when carry is true, otherwise choose r
-            evaluator.and_inplace(s, &carry, key);
-            evaluator.and_inplace(r, &not_carry, key);
-            evaluator.or_inplace(r, s, key);
-        });
+        remainder = arbitrary_bit_mux(evaluator, &carry, &subtract, &remainder, key);
 
         // Set i^th MSB of quotient to 1 if carry = true, otherwise set it to 0.
         // X&1 | X&0 => X&1 => X
@@ -276,14 +264,18 @@ pub(super) fn arbitrary_bit_equality<E: BooleanGates>(
     a: &[E::Ciphertext],
     b: &[E::Ciphertext],
     key: &E::Key,
-) -> E::Ciphertext {
+) -> E::Ciphertext
+where
+    E::Ciphertext: Clone,
+{
     assert!(a.len() == b.len());
-    let mut out = evaluator.xnor(&a[0], &b[0], key);
-    izip!(a.iter(), b.iter()).skip(1).for_each(|(abit, bbit)| {
-        let e = evaluator.xnor(abit, bbit, key);
-        evaluator.and_inplace(&mut out, &e, key);
+    let mut eq = a.to_vec();
+    evaluator.batch_gate(BoolGate::Xnor, &mut eq, b, key);
+    let (out, rest_eq) = eq.split_at_mut(1);
+    rest_eq.iter().for_each(|e| {
+        evaluator.and_inplace(&mut out[0], e, key);
     });
-    return out;
+    return eq.remove(0);
 }
 
 /// Comparator handle computes comparator result 2ns MSB onwards. It is
@@ -294,29 +286,31 @@ fn _comparator_handler_from_second_msb<E: BooleanGates>(
     a: &[E::Ciphertext],
     b: &[E::Ciphertext],
     mut comp: E::Ciphertext,
-    mut casc: E::Ciphertext,
     key: &E::Key,
-) -> E::Ciphertext {
+) -> E::Ciphertext
+where
+    E::Ciphertext: Clone,
+{
     let n = a.len();
 
-    // handle MSB - 1
-    let mut tmp = evaluator.not(&b[n - 2]);
-    evaluator.and_inplace(&mut tmp, &a[n - 2], key);
-    evaluator.and_inplace(&mut tmp, &casc, key);
-    evaluator.or_inplace(&mut comp, &tmp, key);
-
-    for i in 2..n {
-        // calculate cascading bit
-        let tmp_casc = evaluator.xnor(&a[n - i], &b[n - i], key);
-        evaluator.and_inplace(&mut casc, &tmp_casc, key);
-
-        // calculate computate bit
-        let mut tmp = evaluator.not(&b[n - 1 - i]);
-        evaluator.and_inplace(&mut tmp, &a[n - 1 - i], key);
-        evaluator.and_inplace(&mut tmp, &casc, key);
-        evaluator.or_inplace(&mut comp, &tmp, key);
+    // calculate cascading bits. casc[i] is true if bits i + 1 to MSB of a and b
+    // are equal. XNORs of all bits are independent, hence they are evaluated in a
+    // single batch.
+    let mut casc = a[1..].to_vec();
+    evaluator.batch_gate(BoolGate::Xnor, &mut casc, &b[1..], key);
+    for i in (0..n - 2).rev() {
+        let (lower, upper) = casc.split_at_mut(i + 1);
+        evaluator.and_inplace(&mut lower[i], &upper[0], key);
     }
 
+    // calculate computate bits a[i] & !b[i] & casc[i] of all bits below MSB
+    let mut tmp = b[..n - 1].iter().map(|v| evaluator.not(v)).collect_vec();
+    evaluator.batch_gate(BoolGate::And, &mut tmp, &a[..n - 1], key);
+    evaluator.batch_gate(BoolGate::And, &mut tmp, &casc, key);
+    tmp.iter().rev().for_each(|t| {
+        evaluator.or_inplace(&mut comp, t, key);
+    });
+
     return comp;
 }
 
@@ -326,16 +320,18 @@ pub(super) fn arbitrary_signed_bit_comparator<E: BooleanGates>(
     a: &[E::Ciphertext],
     b: &[E::Ciphertext],
     key: &E::Key,
-) -> E::Ciphertext {
+) -> E::Ciphertext
+where
+    E::Ciphertext: Clone,
+{
     assert!(a.len() == b.len());
     let n = a.len();
 
     // handle MSB
     let mut comp = evaluator.not(&a[n - 1]);
     evaluator.and_inplace(&mut comp, &b[n - 1], key); // comp
-    let casc = evaluator.xnor(&a[n - 1], &b[n - 1], key); // casc
 
-    return _comparator_handler_from_second_msb(evaluator, a, b, comp, casc, key);
+    return _comparator_handler_from_second_msb(evaluator, a, b, comp, key);
 }
 
 pub(super) fn arbitrary_bit_comparator<E: BooleanGates>(
@@ -343,14 +339,16 @@ pub(super) fn arbitrary_bit_comparator<E: BooleanGates>(
     a: &[E::Ciphertext],
     b: &[E::Ciphertext],
     key: &E::Key,
-) -> E::Ciphertext {
+) -> E::Ciphertext
+where
+    E::Ciphertext: Clone,
+{
     assert!(a.len() == b.len());
     let n = a.len();
 
     // handle MSB
     let mut comp = evaluator.not(&b[n - 1]);
     evaluator.and_inplace(&mut comp, &a[n - 1], key);
-    let casc = evaluator.xnor(&a[n - 1], &b[n - 1], key);
 
-    return _comparator_handler_from_second_msb(evaluator, a, b, comp, casc, key);
+    return _comparator_handler_from_second_msb(evaluator, a, b, comp, key);
 }
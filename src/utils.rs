@@ -1,14 +1,9 @@
 use std::{usize, vec};
 
 use itertools::{izip, Itertools};
-use num_traits::{One, PrimInt, Signed};
-
-use crate::{
-    backend::Modulus,
-    decomposer::NumInfo,
-    random::{RandomElementInModulus, RandomFill},
-    Matrix, RowEntity, RowMut,
-};
+use num_traits::{One, PrimInt, Signed, Zero};
+
+use crate::{backend::Modulus, decomposer::NumInfo, random::RandomFill, Matrix, RowEntity, RowMut};
 pub trait WithLocal {
     fn with_local<F, R>(func: F) -> R
     where
@@ -72,41 +67,77 @@ impl ToShoup for Vec<Vec<u64>> {
     }
 }
 
-pub fn fill_random_ternary_secret_with_hamming_weight<
-    T: Signed,
-    R: RandomFill<[u8]> + RandomElementInModulus<usize, usize>,
->(
+/// Fills `out` with a uniformly random ternary secret with exactly
+/// `hamming_weight` non-zero coefficients.
+///
+/// Runs in constant time: the first `hamming_weight` slots are set to -1/1
+/// from random bits and all slots are then shuffled by sorting on random keys
+/// with a bitonic sorting network, which performs the same sequence of memory
+/// accesses irrespective of the secret.
+pub fn fill_random_ternary_secret_with_hamming_weight<T: Signed + From<i8>, R: RandomFill<[u8]>>(
     out: &mut [T],
     hamming_weight: usize,
     rng: &mut R,
 ) {
-    let mut bytes = vec![0u8; hamming_weight.div_ceil(8)];
-    RandomFill::<[u8]>::random_fill(rng, &mut bytes);
-
     let size = out.len();
-    let mut secret_indices = (0..size).into_iter().map(|i| i).collect_vec();
-    let mut bit_index = 0;
-    let mut byte_index = 0;
-    for i in 0..hamming_weight {
-        let s_index = RandomElementInModulus::<usize, usize>::random(rng, &secret_indices.len());
-
-        let curr_bit = (bytes[byte_index] >> bit_index) & 1;
-        if curr_bit == 1 {
-            out[secret_indices[s_index]] = T::one();
-        } else {
-            out[secret_indices[s_index]] = -T::one();
-        }
+    assert!(hamming_weight <= size);
 
-        secret_indices[s_index] = *secret_indices.last().unwrap();
-        secret_indices.truncate(secret_indices.len() - 1);
+    // Padding has key 2^62, which is larger than any random key (< 2^62), hence
+    // padding always ends up at the back after sorting.
+    let padded_size = size.next_power_of_two();
+    let mut bytes = vec![0u8; size * 8 + hamming_weight.div_ceil(8)];
+    RandomFill::<[u8]>::random_fill(rng, &mut bytes);
+    let (key_bytes, sign_bytes) = bytes.split_at(size * 8);
+
+    let mut keys = vec![1u64 << 62; padded_size];
+    izip!(keys.iter_mut(), key_bytes.chunks_exact(8)).for_each(|(k, b)| {
+        *k = u64::from_le_bytes(b.try_into().unwrap()) >> 2;
+    });
+
+    // values are encoded as i8 in u64 to be swapped with masks
+    let mut values = vec![0u64; padded_size];
+    values
+        .iter_mut()
+        .take(hamming_weight)
+        .enumerate()
+        .for_each(|(i, v)| {
+            let bit = ((sign_bytes[i >> 3] >> (i & 7)) & 1) as i8;
+            // bit = 1 maps to 1 and bit = 0 maps to -1
+            *v = ((bit << 1) - 1) as u8 as u64;
+        });
 
-        if bit_index == 7 {
-            bit_index = 0;
-            byte_index += 1;
-        } else {
-            bit_index += 1;
+    let mut k = 2;
+    while k <= padded_size {
+        let mut j = k >> 1;
+        while j > 0 {
+            for i in 0..padded_size {
+                let l = i ^ j;
+                if l > i {
+                    let ascending = ((i & k) == 0) as u64;
+                    // keys are < 2^63, so the MSB of the difference is the comparison
+                    let greater = keys[l].wrapping_sub(keys[i]) >> 63;
+                    let mask = 0u64.wrapping_sub(greater ^ ascending ^ 1);
+
+                    let t = (keys[i] ^ keys[l]) & mask;
+                    keys[i] ^= t;
+                    keys[l] ^= t;
+                    let t = (values[i] ^ values[l]) & mask;
+                    values[i] ^= t;
+                    values[l] ^= t;
+                }
+            }
+            j >>= 1;
         }
+        k <<= 1;
     }
+
+    izip!(out.iter_mut(), values.iter()).for_each(|(o, v)| {
+        *o = T::from(*v as u8 as i8);
+    });
+
+    wipe(&mut keys);
+    wipe(&mut values);
+    wipe(&mut bytes);
 }
 
 // TODO (Jay): this is only a workaround. Add a propoer way to perform primality
@@ -227,6 +258,45 @@ pub(crate) fn puncture_p_rng<S: Default + Copy, R: RandomFill<S>>(
     return out;
 }
 
+/// Requires `Send` if `parallel` feature is enabled. Otherwise implemented by
+/// all types.
+#[cfg(feature = "parallel")]
+pub(crate) trait MaybeSend: Send {}
+#[cfg(feature = "parallel")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+#[cfg(not(feature = "parallel"))]
+pub(crate) trait MaybeSend {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// Requires `Sync` if `parallel` feature is enabled. Otherwise implemented by
+/// all types.
+#[cfg(feature = "parallel")]
+pub(crate) trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+#[cfg(not(feature = "parallel"))]
+pub(crate) trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+/// Overwrites secret or secret-derived values in `v` with zeros.
+///
+/// No-op unless `zeroize` feature is enabled.
+#[allow(unused_variables)]
+pub(crate) fn wipe<T: Zero + Copy>(v: &mut [T]) {
+    #[cfg(feature = "zeroize")]
+    {
+        v.iter_mut().for_each(|el| {
+            // SAFETY: `el` is a valid and aligned reference and `T` is `Copy`,
+            // hence overwriting it without dropping the old value is sound.
+            // Volatile writes are not elided even if `v` is freed right after.
+            unsafe { std::ptr::write_volatile(el, T::zero()) }
+        });
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
 pub(crate) fn log2<T: PrimInt + NumInfo>(v: &T) -> usize {
     if (*v & (*v - T::one())) == T::zero() {
         // value is power of 2
@@ -269,11 +339,15 @@ impl<P: Modulus> TryConvertFrom1<[P::Element], P> for Vec<i64> {
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use std::fmt::Debug;
+    use std::{fmt::Debug, hint::black_box};
 
+    use itertools::{izip, Itertools};
     use num_traits::ToPrimitive;
 
-    use crate::random::DefaultSecureRng;
+    use crate::{
+        backend::Modulus,
+        random::{DefaultSecureRng, RandomFill},
+    };
 
     use super::fill_random_ternary_secret_with_hamming_weight;
 
@@ -354,4 +428,145 @@ pub(crate) mod tests {
             assert_eq!(ring_size >> 1, non_zeros);
         }
     }
+
+    #[test]
+    fn ternary_secret_has_correct_hw_for_any_size() {
+        let mut rng = DefaultSecureRng::new();
+        for size in [1, 3, 100, 500, 1023] {
+            for hw in [0, 1, size >> 1, size] {
+                let mut out = vec![0i32; size];
+                fill_random_ternary_secret_with_hamming_weight(&mut out, hw, &mut rng);
+                assert!(out.iter().all(|v| *v == 0 || *v == 1 || *v == -1));
+                assert_eq!(out.iter().filter(|v| **v != 0).count(), hw);
+            }
+        }
+    }
+
+    /// Outcome of dudect style fixed-vs-random timing test. Timing leaks if
+    /// |t| exceeds [Self::THRESHOLD].
+    pub(crate) struct TimingLeakage {
+        pub(crate) t: f64,
+        pub(crate) measurements: usize,
+    }
+
+    impl TimingLeakage {
+        pub(crate) const THRESHOLD: f64 = 5.0;
+
+        pub(crate) fn assert_constant_time(&self, name: &str) {
+            println!(
+                "{name}: |t| = {:.3} with {} measurements",
+                self.t.abs(),
+                self.measurements
+            );
+            assert!(
+                self.t.abs() < Self::THRESHOLD,
+                "{name} is likely not constant time: |t| = {} >= {}",
+                self.t.abs(),
+                Self::THRESHOLD
+            );
+        }
+    }
+
+    /// Dudect style timing test (https://eprint.iacr.org/2016/1123.pdf).
+    ///
+    /// For each of `measurements` runs flips a coin to choose between the fixed
+    /// (class 0) and the random (class 1) input class. All inputs are prepared
+    /// upfront with `prepare` and then `run` is timed on each input. Measurements
+    /// above the 90th percentile are cropped and Welch's t-statistic of the two
+    /// classes is returned.
+    pub(crate) fn dudect<I, P: FnMut(usize) -> I, F: FnMut(I)>(
+        measurements: usize,
+        mut prepare: P,
+        mut run: F,
+    ) -> TimingLeakage {
+        let mut rng = DefaultSecureRng::new();
+        let mut classes = vec![0u8; measurements];
+        RandomFill::<[u8]>::random_fill(&mut rng, classes.as_mut_slice());
+
+        let classes = classes.iter().map(|c| (*c & 1) as usize).collect_vec();
+        let inputs = classes.iter().map(|c| prepare(*c)).collect_vec();
+
+        let timings = izip!(classes.into_iter(), inputs.into_iter())
+            .map(|(class, input)| {
+                let start = std::time::Instant::now();
+                run(input);
+                (class, start.elapsed().as_nanos() as f64)
+            })
+            .collect_vec();
+
+        let mut sorted = timings.iter().map(|(_, t)| *t).collect_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let crop = sorted[(sorted.len() * 9) / 10];
+
+        let mut stats = [Stats::<f64>::new(), Stats::<f64>::new()];
+        timings.iter().for_each(|(class, t)| {
+            if *t <= crop {
+                stats[*class].add_sample(*t);
+            }
+        });
+
+        let n0 = stats[0].samples.len() as f64;
+        let n1 = stats[1].samples.len() as f64;
+        let t = (stats[0].mean() - stats[1].mean())
+            / (stats[0].variance() / n0 + stats[1].variance() / n1).sqrt();
+        TimingLeakage { t, measurements }
+    }
+
+    // Timing tests are noisy on shared machines, hence ignored by default. Run
+    // them locally with `cargo test --release ct_ -- --ignored --nocapture`.
+
+    #[test]
+    #[ignore]
+    fn ct_ternary_secret_sampling() {
+        let mut seed_rng = DefaultSecureRng::new();
+        dudect(
+            100_000,
+            |class| {
+                let mut seed = [0u8; 32];
+                if class == 1 {
+                    RandomFill::<[u8]>::random_fill(&mut seed_rng, seed.as_mut_slice());
+                }
+                (DefaultSecureRng::new_seeded(seed), vec![0i32; 1 << 9])
+            },
+            |(mut rng, mut out)| {
+                fill_random_ternary_secret_with_hamming_weight(&mut out, 1 << 8, &mut rng);
+                black_box(out);
+            },
+        )
+        .assert_constant_time("fill_random_ternary_secret_with_hamming_weight");
+    }
+
+    #[test]
+    #[ignore]
+    fn ct_map_element_from_i64() {
+        let q = (1u64 << 50) - 27;
+        let mut rng = DefaultSecureRng::new();
+        dudect(
+            1_000_000,
+            |class| {
+                let mut v = [0i64; 64];
+                if class == 1 {
+                    RandomFill::<[i64]>::random_fill(&mut rng, v.as_mut_slice());
+                    v.iter_mut().for_each(|v| *v %= 8);
+                }
+                v
+            },
+            |v| {
+                v.iter().for_each(|v| {
+                    black_box(q.map_element_from_i64(black_box(*v)));
+                });
+            },
+        )
+        .assert_constant_time("Modulus::map_element_from_i64");
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn wipe_zeroes_secret() {
+        let mut rng = DefaultSecureRng::new();
+        let mut s = vec![0i32; 1 << 10];
+        fill_random_ternary_secret_with_hamming_weight(&mut s, 1 << 9, &mut rng);
+        super::wipe(&mut s);
+        assert!(s.iter().all(|v| *v == 0));
+    }
 }